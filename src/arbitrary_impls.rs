@@ -0,0 +1,138 @@
+//! Implementations of `arbitrary::Arbitrary`, for property testing and fuzzing
+//!
+//! Only available with the `arbitrary` feature. Generated values always satisfy the crate's
+//! invariants (fractions have non-zero denominators, a Day's blocks are in order and don't
+//! overlap), so properties can be tested without filtering out impossible inputs.
+use arbitrary::{Arbitrary, Result, Unstructured};
+use chrono::prelude::*;
+use food::engine::{Block, Day};
+use food::*;
+use num_rational::*;
+
+/// How deeply recipes may nest within generated foods
+const MAX_DEPTH: u32 = 2;
+
+/// Every unit, for choosing from
+const UNITS: [Unit; 12] = [
+    Unit::Milligram,
+    Unit::Gram,
+    Unit::Kilogram,
+    Unit::Ounce,
+    Unit::Pound,
+    Unit::Milliliter,
+    Unit::Liter,
+    Unit::Cup,
+    Unit::Tablespoon,
+    Unit::Teaspoon,
+    Unit::Kilocalorie,
+    Unit::Count,
+];
+
+/// Generates a small rational with a non-zero denominator
+fn arbitrary_rational(u: &mut Unstructured) -> Result<Rational32> {
+    let numerator = u.int_in_range(-1000..=1000)?;
+    let denominator = u.int_in_range(1..=64)?;
+    Ok(Rational32::new(numerator, denominator))
+}
+
+/// Generates a small, non-negative, number of minutes
+fn arbitrary_minutes(u: &mut Unstructured) -> Result<Rational32> {
+    let numerator = u.int_in_range(0..=240)?;
+    let denominator = u.int_in_range(1..=4)?;
+    Ok(Rational32::new(numerator, denominator))
+}
+
+fn arbitrary_istring(u: &mut Unstructured) -> Result<IString> {
+    let mut string = IString::new(&String::arbitrary(u)?);
+    for _ in 0..u.int_in_range(0..=2)? {
+        let lang = *u.choose(&["en_US", "fr_FR", "ja_JP", "ar_EG"])?;
+        string.set_value_for(lang, &String::arbitrary(u)?);
+    }
+    Ok(string)
+}
+
+fn arbitrary_food(u: &mut Unstructured, depth: u32) -> Result<Food> {
+    let choices = if depth < MAX_DEPTH { 3 } else { 2 };
+    Ok(match u.choose_index(choices)? {
+        0 => Food::new_from_raw_food(RawFood::new(
+            arbitrary_istring(u)?,
+            Amount::arbitrary(u)?,
+            Nutrition::new(),
+        )),
+        1 => {
+            let mut meal = ExternalMeal::new(arbitrary_istring(u)?);
+            if bool::arbitrary(u)? {
+                meal.set_calories(arbitrary_rational(u)?);
+            }
+            if bool::arbitrary(u)? {
+                meal.set_cost(arbitrary_rational(u)?);
+            }
+            Food::new_from_external(meal)
+        }
+        _ => Food::new_from_recipe(arbitrary_recipe(u, depth + 1)?),
+    })
+}
+
+fn arbitrary_recipe(u: &mut Unstructured, depth: u32) -> Result<Recipe> {
+    let mut builder = RecipeBuilder::new(&String::arbitrary(u)?);
+    builder
+        .set_serving_size(*u.choose(&UNITS)?, arbitrary_rational(u)?)
+        .set_servings(Rational32::new(
+            u.int_in_range(1..=12)?,
+            u.int_in_range(1..=4)?,
+        ));
+    for _ in 0..u.int_in_range(0..=4)? {
+        let food = arbitrary_food(u, depth)?;
+        builder.add_food(food, *u.choose(&UNITS)?, arbitrary_rational(u)?);
+    }
+    for _ in 0..u.int_in_range(0..=4)? {
+        builder.add_step(Step::new(&String::arbitrary(u)?, arbitrary_minutes(u)?));
+    }
+    Ok(builder.build_with_defaults())
+}
+
+impl<'a> Arbitrary<'a> for Amount {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Amount::new(*u.choose(&UNITS)?, arbitrary_rational(u)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Food {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_food(u, 0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Recipe {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_recipe(u, 0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Day {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let date = NaiveDate::from_num_days_from_ce_opt(u.int_in_range(730_000..=760_000)?)
+            .unwrap_or_default();
+        let mut day = Day::new(date);
+        // Walk forward through the day, in whole minutes, so blocks never overlap
+        let mut minute: u32 = u.int_in_range(0..=600)?;
+        for _ in 0..u.int_in_range(0..=5)? {
+            let length = u.int_in_range(5..=120)?;
+            if minute + length >= 24 * 60 {
+                break;
+            }
+            let start = NaiveTime::from_num_seconds_from_midnight_opt(minute * 60, 0);
+            let end = NaiveTime::from_num_seconds_from_midnight_opt((minute + length) * 60, 0);
+            if let (Some(start), Some(end)) = (start, end) {
+                let food = if bool::arbitrary(u)? {
+                    Some(arbitrary_food(u, 0)?)
+                } else {
+                    None
+                };
+                day.add_block(Block::new(start, end, food));
+            }
+            minute += length + u.int_in_range(0..=120)?;
+        }
+        Ok(day)
+    }
+}
@@ -0,0 +1,524 @@
+//! This module contains the configuration shared by the calendar (ICS/CalDAV) exporters
+//!
+//! Calendar apps have no idea what a breakfast is, so the exporters rely on the tags attached
+//! to each Block to decide how an event should look. A `CalendarStyles` maps those tags to a
+//! colour and a category, which are emitted as the RFC 7986 `COLOR` and RFC 5545 `CATEGORIES`
+//! properties of the event.
+//!
+//! Tags also select the reminders attached to an event. A `ReminderSettings` maps tags to
+//! reminder offsets, which are emitted as VALARM components.
+//!
+//! `MealPlan::to_ical` puts these together, producing an .ics file with an event for every
+//! Block with food, ready to import into Google or Apple calendars.
+use super::localized;
+use chrono::prelude::*;
+use chrono::Duration;
+use food::engine::{Block, MealPlan};
+use settings::Settings;
+use std::collections::BTreeMap;
+
+/// How events for a particular tag should look in a calendar app
+///
+/// The colour should be a CSS3 colour name ("teal", "orange"), as that is all RFC 7986 allows.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TagStyle {
+    colour: String,
+    category: String,
+}
+
+impl TagStyle {
+    /// Creates a new TagStyle from a colour name and a category
+    pub fn new(colour: &str, category: &str) -> TagStyle {
+        TagStyle {
+            colour: colour.to_string(),
+            category: category.to_string(),
+        }
+    }
+
+    /// Returns the CSS3 colour name of the style
+    pub fn get_colour(&self) -> &str {
+        &self.colour
+    }
+
+    /// Returns the calendar category of the style
+    pub fn get_category(&self) -> &str {
+        &self.category
+    }
+}
+
+/// Maps Block tags to the way their events should look
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use time_for_food::export::calendar::*;
+/// use time_for_food::food::engine::Block;
+///
+/// # fn main() {
+/// let mut styles = CalendarStyles::meal_defaults();
+/// styles.set_style("guests", TagStyle::new("teal", "Guests, family"));
+///
+/// let mut block = Block::new(NaiveTime::from_hms(18, 0, 0), NaiveTime::from_hms(19, 0, 0), None);
+/// block.add_tag("untracked");
+/// block.add_tag("dinner");
+/// block.add_tag("guests");
+/// // Every styled tag is a category, but the first decides the colour
+/// assert_eq!(
+///     styles.ical_properties(&block),
+///     ["CATEGORIES:Dinner,Guests\\, family", "COLOR:firebrick"]
+/// );
+///
+/// // Blocks with no styled tags aren't styled at all
+/// let plain = Block::new(NaiveTime::from_hms(7, 0, 0), NaiveTime::from_hms(7, 30, 0), None);
+/// assert!(styles.ical_properties(&plain).is_empty());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CalendarStyles {
+    styles: BTreeMap<String, TagStyle>,
+}
+
+impl CalendarStyles {
+    /// Creates a new, empty, CalendarStyles
+    pub fn new() -> CalendarStyles {
+        CalendarStyles {
+            styles: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a CalendarStyles with distinct styles for the common meal tags
+    ///
+    /// Covers "breakfast", "lunch", "dinner", "snack", and "prep".
+    pub fn meal_defaults() -> CalendarStyles {
+        let mut styles = CalendarStyles::new();
+        styles.set_style("breakfast", TagStyle::new("gold", "Breakfast"));
+        styles.set_style("lunch", TagStyle::new("forestgreen", "Lunch"));
+        styles.set_style("dinner", TagStyle::new("firebrick", "Dinner"));
+        styles.set_style("snack", TagStyle::new("orchid", "Snack"));
+        styles.set_style("prep", TagStyle::new("steelblue", "Meal prep"));
+        styles
+    }
+
+    /// Sets the style for a tag, overwriting the existing style if there is one
+    pub fn set_style(&mut self, tag: &str, style: TagStyle) {
+        self.styles.insert(tag.to_string(), style);
+    }
+
+    /// Returns the style for a tag, if there is one
+    pub fn get_style(&self, tag: &str) -> Option<&TagStyle> {
+        self.styles.get(tag)
+    }
+
+    /// Returns the style that decides the colour of a Block's event
+    ///
+    /// This is the style of the first of the Block's tags that has one.
+    pub fn style_for(&self, block: &Block) -> Option<&TagStyle> {
+        block
+            .get_tags()
+            .iter()
+            .filter_map(|x| self.get_style(x))
+            .next()
+    }
+
+    /// Returns the categories of a Block's event, one for every styled tag, in tag order
+    pub fn categories_for(&self, block: &Block) -> Vec<&str> {
+        block
+            .get_tags()
+            .iter()
+            .filter_map(|x| self.get_style(x))
+            .map(|x| x.get_category())
+            .collect()
+    }
+
+    /// Returns the iCalendar property lines styling a Block's event
+    ///
+    /// These are unfolded content lines, without the trailing CRLF, for the exporters to
+    /// insert into the VEVENT.
+    pub fn ical_properties(&self, block: &Block) -> Vec<String> {
+        let mut properties = Vec::new();
+        let categories = self.categories_for(block);
+        if !categories.is_empty() {
+            let escaped: Vec<String> = categories.iter().map(|x| escape_text(x)).collect();
+            properties.push(format!("CATEGORIES:{}", escaped.join(",")));
+        }
+        if let Some(style) = self.style_for(block) {
+            properties.push(format!("COLOR:{}", style.get_colour()));
+        }
+        properties
+    }
+}
+
+/// The point in a Block a reminder is measured from
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ReminderAnchor {
+    /// The start of the Block, when cooking begins
+    CookStart,
+    /// The end of the Block, when the food is ready to eat
+    EatTime,
+}
+
+/// A single reminder, some number of minutes before a point in a Block
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Reminder {
+    anchor: ReminderAnchor,
+    minutes_before: u32,
+}
+
+impl Reminder {
+    /// Creates a new Reminder, some number of minutes before the anchor
+    ///
+    /// Zero minutes alerts exactly at the anchor.
+    pub fn new(anchor: ReminderAnchor, minutes_before: u32) -> Reminder {
+        Reminder {
+            anchor,
+            minutes_before,
+        }
+    }
+
+    /// Returns the point in the Block the reminder is measured from
+    pub fn get_anchor(&self) -> ReminderAnchor {
+        self.anchor
+    }
+
+    /// Returns how many minutes before the anchor the reminder goes off
+    pub fn get_minutes_before(&self) -> u32 {
+        self.minutes_before
+    }
+
+    /// Renders the reminder as the lines of a VALARM component
+    pub fn to_valarm(&self, description: &str) -> Vec<String> {
+        let related = match self.anchor {
+            ReminderAnchor::CookStart => "START",
+            ReminderAnchor::EatTime => "END",
+        };
+        let trigger = if self.minutes_before == 0 {
+            "PT0S".to_string()
+        } else {
+            format!("-PT{}M", self.minutes_before)
+        };
+        vec![
+            "BEGIN:VALARM".to_string(),
+            "ACTION:DISPLAY".to_string(),
+            format!("DESCRIPTION:{}", escape_text(description)),
+            format!("TRIGGER;RELATED={}:{}", related, trigger),
+            "END:VALARM".to_string(),
+        ]
+    }
+}
+
+/// Maps Block tags to the reminders their events should carry
+///
+/// Blocks with none of the configured tags get the default reminders.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use time_for_food::export::calendar::*;
+/// use time_for_food::food::engine::Block;
+///
+/// # fn main() {
+/// let mut reminders = ReminderSettings::new();
+/// reminders.add_reminder("prep", Reminder::new(ReminderAnchor::CookStart, 15));
+/// reminders.add_default_reminder(Reminder::new(ReminderAnchor::EatTime, 0));
+///
+/// let time = |h| NaiveTime::from_hms(h, 0, 0);
+/// let mut prep = Block::new(time(16), time(18), None);
+/// prep.add_tag("prep");
+/// assert_eq!(
+///     reminders.valarms_for(&prep, "Start the stew"),
+///     [
+///         "BEGIN:VALARM",
+///         "ACTION:DISPLAY",
+///         "DESCRIPTION:Start the stew",
+///         "TRIGGER;RELATED=START:-PT15M",
+///         "END:VALARM",
+///     ]
+/// );
+///
+/// // Without a configured tag, the default reminder goes off as the food is ready
+/// let dinner = Block::new(time(18), time(19), None);
+/// let alarms = reminders.valarms_for(&dinner, "Dinner");
+/// assert_eq!(alarms[3], "TRIGGER;RELATED=END:PT0S");
+/// assert_eq!(alarms.len(), 5);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReminderSettings {
+    per_tag: BTreeMap<String, Vec<Reminder>>,
+    default: Vec<Reminder>,
+}
+
+impl ReminderSettings {
+    /// Creates a new ReminderSettings, with no reminders at all
+    pub fn new() -> ReminderSettings {
+        ReminderSettings {
+            per_tag: BTreeMap::new(),
+            default: Vec::new(),
+        }
+    }
+
+    /// Adds a reminder for Blocks with the given tag
+    pub fn add_reminder(&mut self, tag: &str, reminder: Reminder) {
+        self.per_tag
+            .entry(tag.to_string())
+            .or_default()
+            .push(reminder);
+    }
+
+    /// Adds a reminder for Blocks with none of the configured tags
+    pub fn add_default_reminder(&mut self, reminder: Reminder) {
+        self.default.push(reminder);
+    }
+
+    /// Returns the reminders for a Block
+    ///
+    /// Collects the reminders of every configured tag the Block has, without duplicates,
+    /// falling back to the defaults when the Block has no configured tags.
+    pub fn reminders_for(&self, block: &Block) -> Vec<Reminder> {
+        let mut reminders: Vec<Reminder> = Vec::new();
+        let mut matched = false;
+        for tag in block.get_tags() {
+            if let Some(tag_reminders) = self.per_tag.get(tag) {
+                matched = true;
+                for reminder in tag_reminders {
+                    if !reminders.contains(reminder) {
+                        reminders.push(*reminder);
+                    }
+                }
+            }
+        }
+        if matched {
+            reminders
+        } else {
+            self.default.clone()
+        }
+    }
+
+    /// Returns the VALARM components for a Block's event, as unfolded content lines
+    pub fn valarms_for(&self, block: &Block, description: &str) -> Vec<String> {
+        self.reminders_for(block)
+            .iter()
+            .flat_map(|x| x.to_valarm(description))
+            .collect()
+    }
+}
+
+/// Escapes a value for use as iCalendar TEXT
+pub fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Folds an iCalendar content line into lines of at most 75 octets, as RFC 5545 requires
+///
+/// Continuation lines start with a single space, and lines are never broken in the middle of
+/// a character. The result has no trailing CRLF.
+pub fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            length = 1;
+        }
+        out.push(c);
+        length += c.len_utf8();
+    }
+    out
+}
+
+/// The options used when exporting a MealPlan as an iCalendar file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IcalOptions {
+    lang: String,
+    timezone: Option<FixedOffset>,
+    styles: CalendarStyles,
+    reminders: ReminderSettings,
+}
+
+impl IcalOptions {
+    /// Creates a new set of options, naming foods in the given language, with floating local
+    /// times, and no styles or reminders
+    pub fn new(lang: &str) -> IcalOptions {
+        IcalOptions {
+            lang: lang.to_string(),
+            timezone: None,
+            styles: CalendarStyles::new(),
+            reminders: ReminderSettings::new(),
+        }
+    }
+
+    /// Creates a new set of options using a household's language and timezone
+    pub fn from_settings(settings: &Settings) -> IcalOptions {
+        let mut options = IcalOptions::new(settings.get_language());
+        options.set_timezone(Some(settings.get_timezone()));
+        options
+    }
+
+    /// Returns the language foods are named in
+    pub fn get_lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Returns the timezone Block times are in, or None if they are floating local times
+    pub fn get_timezone(&self) -> Option<FixedOffset> {
+        self.timezone
+    }
+
+    /// Sets the timezone Block times are in
+    ///
+    /// With a timezone, events are written in UTC. Without one, they are written as floating
+    /// local times, which calendar apps show at the same clock time wherever they are.
+    pub fn set_timezone(&mut self, timezone: Option<FixedOffset>) -> &mut Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Returns the styles applied to events
+    pub fn get_styles(&self) -> &CalendarStyles {
+        &self.styles
+    }
+
+    /// Sets the styles applied to events
+    pub fn set_styles(&mut self, styles: CalendarStyles) -> &mut Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Returns the reminders attached to events
+    pub fn get_reminders(&self) -> &ReminderSettings {
+        &self.reminders
+    }
+
+    /// Sets the reminders attached to events
+    pub fn set_reminders(&mut self, reminders: ReminderSettings) -> &mut Self {
+        self.reminders = reminders;
+        self
+    }
+
+    /// Formats a date and time in the options' timezone as an iCalendar DATE-TIME
+    fn format_time(&self, time: NaiveDateTime) -> String {
+        match self.timezone {
+            Some(offset) => {
+                let utc = time - Duration::seconds(i64::from(offset.local_minus_utc()));
+                utc.format("%Y%m%dT%H%M%SZ").to_string()
+            }
+            None => time.format("%Y%m%dT%H%M%S").to_string(),
+        }
+    }
+}
+
+impl MealPlan {
+    /// Exports the plan as an iCalendar file, with an event for every Block with food
+    ///
+    /// Each event is named after its food, and runs from the start of the Block, when cooking
+    /// begins, to its end, when the food is ready. Blocks ending before they start are taken
+    /// to run past midnight. The anchor is used as the DTSTAMP of every event, so exporting
+    /// the same plan at the same anchor always gives the same file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::export::calendar::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut recipe = RecipeBuilder::new("lasagne");
+    /// recipe.add_name("en_US", "Lasagne");
+    /// let lasagne = Food::new_from_recipe(recipe.build_with_defaults());
+    ///
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut day = Day::new(date);
+    /// let time = |h| NaiveTime::from_hms(h, 0, 0);
+    /// day.add_block(Block::new(time(17), time(18), Some(lasagne)));
+    /// day.add_block(Block::new(time(12), time(13), None));
+    /// let mut plan = MealPlan::new();
+    /// plan.add_day(day);
+    ///
+    /// let mut options = IcalOptions::new("en_US");
+    /// options.set_timezone(Some(FixedOffset::east(3600)));
+    /// let anchor = Utc.ymd(2024, 1, 1).and_hms(9, 0, 0);
+    /// let ics = plan.to_ical(anchor, &options);
+    ///
+    /// assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    /// assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    /// assert!(ics.contains("SUMMARY:Lasagne\r\n"));
+    /// assert!(ics.contains("DTSTART:20240108T160000Z\r\n"));
+    /// assert!(ics.contains("DTEND:20240108T170000Z\r\n"));
+    /// assert!(ics.contains("DTSTAMP:20240101T090000Z\r\n"));
+    /// # }
+    /// ```
+    pub fn to_ical(&self, date_anchor: DateTime<Utc>, options: &IcalOptions) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//time-for-food//MealPlan//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+        let stamp = date_anchor.format("%Y%m%dT%H%M%SZ").to_string();
+        for day in self.get_days() {
+            let date = day.get_date();
+            for block in day.get_blocks() {
+                let food = match block.get_food() {
+                    Some(x) => x,
+                    None => continue,
+                };
+                let start = date.and_time(*block.get_start());
+                let mut end = date.and_time(*block.get_end());
+                if end < start {
+                    end += Duration::days(1);
+                }
+                let name = localized(food.get_name(), &options.lang);
+                lines.push("BEGIN:VEVENT".to_string());
+                lines.push(format!(
+                    "UID:{}-{}-{}@time-for-food",
+                    date.format("%Y%m%d"),
+                    block.get_start().format("%H%M%S"),
+                    food.get_name().get_short_code()
+                ));
+                lines.push(format!("DTSTAMP:{}", stamp));
+                lines.push(format!("DTSTART:{}", options.format_time(start)));
+                lines.push(format!("DTEND:{}", options.format_time(end)));
+                lines.push(format!("SUMMARY:{}", escape_text(&name)));
+                let notes: Vec<String> = block
+                    .get_notes()
+                    .iter()
+                    .map(|x| localized(x, &options.lang))
+                    .collect();
+                if !notes.is_empty() {
+                    lines.push(format!("DESCRIPTION:{}", escape_text(&notes.join("\n"))));
+                }
+                lines.extend(options.styles.ical_properties(block));
+                lines.extend(options.reminders.valarms_for(block, &name));
+                lines.push("END:VEVENT".to_string());
+            }
+        }
+        lines.push("END:VCALENDAR".to_string());
+        let mut out = String::new();
+        for line in lines {
+            out.push_str(&fold_line(&line));
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
@@ -0,0 +1,294 @@
+//! This module contains the LaTeX exporter, for producing print-ready cookbooks
+//!
+//! Export happens in two stages. A Recipe is first flattened into a `PrintRecipe`, a
+//! structured print model with everything already rendered into a single language. The print
+//! model is then laid out as LaTeX, cookbook style, with the ingredients in a narrow column
+//! beside the steps. Applications wanting some other print layout can stop at the print model.
+use super::{format_minutes, format_rational, localized};
+use food::appliance::Appliances;
+use food::*;
+use format::{Formatter, FractionStyle};
+use num_rational::*;
+use settings::Settings;
+
+/// The headings and labels used when laying out a recipe
+///
+/// Defaults to English, replace them to print in another language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrintLabels {
+    pub ingredients: String,
+    pub steps: String,
+    pub servings: String,
+    pub total_time: String,
+    /// Printed beside steps that need nobody, such as resting dough
+    pub hands_off: String,
+}
+
+impl Default for PrintLabels {
+    fn default() -> PrintLabels {
+        PrintLabels {
+            ingredients: "Ingredients".to_string(),
+            steps: "Method".to_string(),
+            servings: "Serves".to_string(),
+            total_time: "Total time".to_string(),
+            hands_off: "hands-off".to_string(),
+        }
+    }
+}
+
+/// A step, rendered for print
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrintStep {
+    pub text: String,
+    /// The time the step takes, already formatted, None if the step has no time
+    pub time: Option<String>,
+    /// The oven temperature, already formatted, None if the step doesn't use the oven
+    pub temperature: Option<String>,
+    /// True if the step needs nobody, so it can be told apart from a step keeping the cook
+    /// busy for the same time
+    pub hands_off: bool,
+}
+
+/// A recipe flattened into everything needed to print it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrintRecipe {
+    pub title: String,
+    /// How many servings the recipe makes, already formatted
+    pub yields: String,
+    /// The total time the recipe takes, already formatted
+    pub total_time: String,
+    /// One line per ingredient, such as "2 onions"
+    pub ingredients: Vec<String>,
+    pub steps: Vec<PrintStep>,
+}
+
+impl PrintRecipe {
+    /// Builds the print model for a recipe in the given language
+    pub fn from_recipe(recipe: &Recipe, lang: &str) -> PrintRecipe {
+        PrintRecipe::build(recipe, &Formatter::new(lang), &Appliances::new())
+    }
+
+    /// Builds the print model for a recipe in the household's language, with ingredient
+    /// amounts in the household's units and rounded according to the household's rounding
+    /// policy, and times and oven temperatures adjusted for the household's appliances
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::export::latex::*;
+    /// use time_for_food::food::*;
+    /// use time_for_food::settings::*;
+    ///
+    /// # fn main() {
+    /// let gram = Amount::new(Unit::Gram, Rational32::from_integer(1));
+    /// let flour = RawFood::new(IString::new("flour"), gram, Nutrition::new());
+    /// let bread = RecipeBuilder::new("bread")
+    ///     .add_food(Food::new_from_raw_food(flour), Unit::Kilogram, Rational32::from_integer(1))
+    ///     .build_with_defaults();
+    /// let print = PrintRecipe::from_recipe(&bread, "en_US");
+    /// assert_eq!(print.ingredients, vec!["1 kg flour".to_string()]);
+    ///
+    /// let mut settings = Settings::new();
+    /// settings
+    ///     .set_unit_system(UnitSystem::UsCustomary)
+    ///     .set_rounding(RoundingPolicy::Nearest(4));
+    /// let print = PrintRecipe::from_recipe_with_settings(&bread, &settings);
+    /// assert_eq!(print.ingredients, vec!["2 1/4 lb flour".to_string()]);
+    /// # }
+    /// ```
+    pub fn from_recipe_with_settings(recipe: &Recipe, settings: &Settings) -> PrintRecipe {
+        PrintRecipe::build(
+            recipe,
+            &Formatter::from_settings(settings),
+            settings.get_appliances(),
+        )
+    }
+
+    fn build(recipe: &Recipe, formatter: &Formatter, appliances: &Appliances) -> PrintRecipe {
+        let lang = formatter.get_lang();
+        // Unicode fractions need extra packages to typeset, so amounts are written in ASCII
+        let mut formatter = formatter.clone();
+        formatter.set_fraction_style(FractionStyle::Ascii);
+        let ingredients = recipe
+            .get_foods()
+            .iter()
+            .map(|&(ref food, amount)| {
+                format!(
+                    "{} {}",
+                    formatter.format_amount(&amount),
+                    localized(food.get_name(), lang)
+                )
+            })
+            .collect();
+        let steps = recipe
+            .get_steps()
+            .iter()
+            .map(|step| {
+                let time = appliances.step_time(step);
+                PrintStep {
+                    text: localized(step.get_text(), lang),
+                    time: if time == Rational32::from_integer(0) {
+                        None
+                    } else {
+                        Some(format_minutes(time))
+                    },
+                    temperature: appliances
+                        .oven_temperature(step)
+                        .map(|x| format!("{} °C", x)),
+                    hands_off: step.get_attention() == Attention::Passive,
+                }
+            })
+            .collect();
+
+        PrintRecipe {
+            title: localized(recipe.get_name(), lang),
+            yields: format_rational(recipe.get_servings()),
+            total_time: format_minutes(appliances.recipe_time(recipe)),
+            ingredients,
+            steps,
+        }
+    }
+
+    /// Lays the recipe out as a LaTeX fragment
+    ///
+    /// The fragment only relies on the standard LaTeX environments, so it can be dropped
+    /// into any document. See `latex_document` for producing a complete cookbook.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::export::latex::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut simmer = Step::new("simmer", Rational32::from_integer(40));
+    /// simmer.set_attention(Attention::Passive);
+    /// let stew = RecipeBuilder::new("stew")
+    ///     .add_step(Step::new("chop", Rational32::from_integer(40)))
+    ///     .add_step(simmer)
+    ///     .build_with_defaults();
+    ///
+    /// let latex = PrintRecipe::from_recipe(&stew, "en_US").to_latex(&PrintLabels::default());
+    /// assert!(latex.contains("\\item chop \\hfill \\textit{40 min}"));
+    /// assert!(latex.contains("\\item simmer \\hfill \\textit{40 min, hands-off}"));
+    /// # }
+    /// ```
+    pub fn to_latex(&self, labels: &PrintLabels) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("\\section*{{{}}}\n", escape(&self.title)));
+        out.push_str(&format!(
+            "\\textit{{{} {} \\quad {}: {}}}\n\n",
+            escape(&labels.servings),
+            escape(&self.yields),
+            escape(&labels.total_time),
+            escape(&self.total_time)
+        ));
+
+        // Ingredients column
+        out.push_str("\\noindent\n\\begin{minipage}[t]{0.3\\textwidth}\n");
+        out.push_str(&format!(
+            "\\subsection*{{{}}}\n",
+            escape(&labels.ingredients)
+        ));
+        if !self.ingredients.is_empty() {
+            out.push_str("\\begin{itemize}\n");
+            for ingredient in &self.ingredients {
+                out.push_str(&format!("\\item {}\n", escape(ingredient)));
+            }
+            out.push_str("\\end{itemize}\n");
+        }
+        out.push_str("\\end{minipage}\\hfill\n");
+
+        // Steps column
+        out.push_str("\\begin{minipage}[t]{0.65\\textwidth}\n");
+        out.push_str(&format!("\\subsection*{{{}}}\n", escape(&labels.steps)));
+        if !self.steps.is_empty() {
+            out.push_str("\\begin{enumerate}\n");
+            for step in &self.steps {
+                let hands_off = if step.hands_off {
+                    Some(&labels.hands_off)
+                } else {
+                    None
+                };
+                let aside: Vec<&str> = step
+                    .temperature
+                    .iter()
+                    .chain(step.time.iter())
+                    .chain(hands_off)
+                    .map(|x| x.as_str())
+                    .collect();
+                if aside.is_empty() {
+                    out.push_str(&format!("\\item {}\n", escape(&step.text)));
+                } else {
+                    out.push_str(&format!(
+                        "\\item {} \\hfill \\textit{{{}}}\n",
+                        escape(&step.text),
+                        escape(&aside.join(", "))
+                    ));
+                }
+            }
+            out.push_str("\\end{enumerate}\n");
+        }
+        out.push_str("\\end{minipage}\n");
+        out
+    }
+}
+
+/// Lays out a complete LaTeX document, one recipe per page
+pub fn latex_document(recipes: &[PrintRecipe], labels: &PrintLabels) -> String {
+    let mut out = String::new();
+    out.push_str("\\documentclass{article}\n");
+    out.push_str("\\usepackage[utf8]{inputenc}\n");
+    out.push_str("\\begin{document}\n");
+    for (i, recipe) in recipes.iter().enumerate() {
+        if i > 0 {
+            out.push_str("\\newpage\n");
+        }
+        out.push_str(&recipe.to_latex(labels));
+    }
+    out.push_str("\\end{document}\n");
+    out
+}
+
+/// Escapes the characters LaTeX treats specially
+///
+/// Everything taken from a recipe is escaped when it is laid out, so recipe names can use
+/// these characters freely.
+///
+/// # Examples
+///
+/// ```
+/// extern crate time_for_food;
+/// use time_for_food::export::latex::*;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// assert_eq!(escape("50% rye_sourdough"), "50\\% rye\\_sourdough");
+///
+/// let recipe = RecipeBuilder::new("mac & cheese, 100% cheddar_mix").build_with_defaults();
+/// let latex = PrintRecipe::from_recipe(&recipe, "en").to_latex(&PrintLabels::default());
+/// assert!(latex.contains("\\section*{mac \\& cheese, 100\\% cheddar\\_mix}"));
+/// # }
+/// ```
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,292 @@
+//! This module contains the menu exporter, for producing a weekly menu to stick on the fridge
+//!
+//! Like the LaTeX exporter, export happens in two stages. A MealPlan is first flattened into a
+//! `Menu`, with each day's meals already rendered into a single language, and the menu is then
+//! laid out as Markdown or HTML. Free time (Blocks without food) is left off the menu.
+//!
+//! Names and notes keep their writing direction, so right-to-left names are marked with `dir`
+//! in HTML and wrapped in unicode isolates in Markdown.
+use super::localized_text;
+use food::engine::*;
+use food::script::RenderedText;
+use settings::Settings;
+
+/// A single meal on the menu
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MenuMeal {
+    /// When the food is ready to eat, formatted as "18:30"
+    pub time: String,
+    pub name: RenderedText,
+    pub short_code: String,
+    /// The block's notes, such as "eat before soccer practice"
+    pub notes: Vec<RenderedText>,
+}
+
+/// A single day on the menu
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MenuDay {
+    /// The heading for the day, such as "Monday 8 January"
+    pub heading: String,
+    pub meals: Vec<MenuMeal>,
+}
+
+/// A meal plan flattened into everything needed to print a menu
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Menu {
+    pub days: Vec<MenuDay>,
+}
+
+impl Menu {
+    /// Builds the menu for a meal plan in the given language
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::export::menu::Menu;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut name = IString::new("pizza");
+    /// name.set_value_for("en_US", "Pizza");
+    /// let food = Food::new_from_external(ExternalMeal::new(name));
+    ///
+    /// let mut day = Day::new(NaiveDate::from_ymd(2024, 1, 8));
+    /// day.add_block(Block::new(
+    ///     NaiveTime::from_hms(18, 0, 0),
+    ///     NaiveTime::from_hms(18, 30, 0),
+    ///     Some(food),
+    /// ));
+    /// let mut plan = MealPlan::new();
+    /// plan.add_day(day);
+    ///
+    /// let menu = Menu::from_plan(&plan, "en_US");
+    /// assert_eq!(
+    ///     menu.to_markdown(None),
+    ///     "## Monday 8 January\n\n- 18:30 Pizza (`pizza`)\n\n"
+    /// );
+    ///
+    /// // Notes are listed under their meal
+    /// let block = plan
+    ///     .get_mut_day(NaiveDate::from_ymd(2024, 1, 8))
+    ///     .unwrap()
+    ///     .get_mut_block(NaiveTime::from_hms(18, 0, 0))
+    ///     .unwrap();
+    /// block.add_note(IString::new("guests: grandparents"));
+    /// let menu = Menu::from_plan(&plan, "en_US");
+    /// assert_eq!(menu.days[0].meals[0].notes[0].get_text(), "guests: grandparents");
+    /// assert!(menu
+    ///     .to_markdown(None)
+    ///     .contains("- 18:30 Pizza (`pizza`)\n  - guests: grandparents\n"));
+    /// assert!(menu
+    ///     .to_html(None)
+    ///     .contains("<ul>\n<li dir=\"ltr\">guests: grandparents</li>\n</ul>"));
+    /// # }
+    /// ```
+    pub fn from_plan(plan: &MealPlan, lang: &str) -> Menu {
+        let days = plan
+            .get_days()
+            .iter()
+            .map(|day| MenuDay {
+                heading: day.get_date().format("%A %-d %B").to_string(),
+                meals: day
+                    .get_blocks()
+                    .iter()
+                    .filter_map(|block| {
+                        block.get_food().map(|food| MenuMeal {
+                            time: block.get_end().format("%H:%M").to_string(),
+                            name: localized_text(food.get_name(), lang),
+                            short_code: food.get_name().get_short_code().to_string(),
+                            notes: block
+                                .get_notes()
+                                .iter()
+                                .map(|x| localized_text(x, lang))
+                                .collect(),
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+        Menu { days }
+    }
+
+    /// Builds the menu for a meal plan in the household's language
+    pub fn from_plan_with_settings(plan: &MealPlan, settings: &Settings) -> Menu {
+        Menu::from_plan(plan, settings.get_language())
+    }
+
+    /// Lays the menu out as Markdown, one section per day
+    ///
+    /// If a link base is given, each meal links to the link base followed by its short code,
+    /// otherwise the short code is shown after the meal. Notes are listed under their meal.
+    /// Names and notes are escaped, so they are never read as Markdown, and right-to-left ones
+    /// are wrapped in unicode isolates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::export::menu::Menu;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut name = IString::new("salad");
+    /// name.set_value_for("en_US", "Salad | *extra* [large]");
+    /// name.set_value_for("he", "סלט");
+    /// let food = Food::new_from_external(ExternalMeal::new(name));
+    /// let time = |h| NaiveTime::from_hms(h, 0, 0);
+    /// let mut plan = MealPlan::new();
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// plan.insert_block(date, Block::new(time(12), time(13), Some(food)));
+    ///
+    /// let markdown = Menu::from_plan(&plan, "en_US").to_markdown(None);
+    /// assert!(markdown.contains("- 13:00 Salad \\| \\*extra\\* \\[large\\] (`salad`)"));
+    ///
+    /// let markdown = Menu::from_plan(&plan, "he").to_markdown(None);
+    /// assert!(markdown.contains("- 13:00 \u{2067}סלט\u{2069} (`salad`)"));
+    /// # }
+    /// ```
+    pub fn to_markdown(&self, link_base: Option<&str>) -> String {
+        let mut out = String::new();
+        for day in &self.days {
+            out.push_str(&format!("## {}\n\n", day.heading));
+            for meal in &day.meals {
+                let name = escape_markdown(&meal.name.to_isolated());
+                match link_base {
+                    Some(base) => out.push_str(&format!(
+                        "- {} [{}]({}{})\n",
+                        meal.time, name, base, meal.short_code
+                    )),
+                    None => out.push_str(&format!(
+                        "- {} {} (`{}`)\n",
+                        meal.time, name, meal.short_code
+                    )),
+                }
+                for note in &meal.notes {
+                    out.push_str(&format!("  - {}\n", escape_markdown(&note.to_isolated())));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Lays the menu out as an HTML fragment, one section per day
+    ///
+    /// Links and notes work as they do for `to_markdown`, with names and notes marked with the
+    /// direction they are written in. The fragment carries no styling, so it can be dropped
+    /// into any page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::export::menu::Menu;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut name = IString::new("hummus");
+    /// name.set_value_for("ar", "حمص");
+    /// let food = Food::new_from_external(ExternalMeal::new(name));
+    /// let time = |h| NaiveTime::from_hms(h, 0, 0);
+    /// let mut plan = MealPlan::new();
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// plan.insert_block(date, Block::new(time(12), time(13), Some(food)));
+    ///
+    /// let menu = Menu::from_plan(&plan, "ar");
+    /// assert!(menu
+    ///     .to_html(None)
+    ///     .contains("<li><time>13:00</time> <bdi dir=\"rtl\">حمص</bdi> <code>hummus</code>"));
+    /// assert!(menu
+    ///     .to_html(Some("/recipes/"))
+    ///     .contains("<a href=\"/recipes/hummus\" dir=\"rtl\">حمص</a>"));
+    /// # }
+    /// ```
+    pub fn to_html(&self, link_base: Option<&str>) -> String {
+        let mut out = String::new();
+        for day in &self.days {
+            out.push_str("<section>\n");
+            out.push_str(&format!("<h2>{}</h2>\n", escape(&day.heading)));
+            out.push_str("<ul>\n");
+            for meal in &day.meals {
+                let dir = meal.name.get_direction().html_dir();
+                let name = match link_base {
+                    Some(base) => format!(
+                        "<a href=\"{}{}\" dir=\"{}\">{}</a>",
+                        escape(base),
+                        escape(&meal.short_code),
+                        dir,
+                        escape(meal.name.get_text())
+                    ),
+                    None => format!(
+                        "<bdi dir=\"{}\">{}</bdi> <code>{}</code>",
+                        dir,
+                        escape(meal.name.get_text()),
+                        escape(&meal.short_code)
+                    ),
+                };
+                out.push_str(&format!("<li><time>{}</time> {}", escape(&meal.time), name));
+                if !meal.notes.is_empty() {
+                    out.push_str("\n<ul>\n");
+                    for note in &meal.notes {
+                        out.push_str(&format!(
+                            "<li dir=\"{}\">{}</li>\n",
+                            note.get_direction().html_dir(),
+                            escape(note.get_text())
+                        ));
+                    }
+                    out.push_str("</ul>\n");
+                }
+                out.push_str("</li>\n");
+            }
+            out.push_str("</ul>\n");
+            out.push_str("</section>\n");
+        }
+        out
+    }
+}
+
+/// Escapes the characters Markdown treats specially, so the text is always read as is
+///
+/// # Examples
+///
+/// ```
+/// use time_for_food::export::menu::escape_markdown;
+///
+/// assert_eq!(escape_markdown("fish & chips | *large*"), "fish & chips \\| \\*large\\*");
+/// ```
+pub fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if let '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#' = c {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escapes the characters HTML treats specially
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
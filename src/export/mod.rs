@@ -0,0 +1,76 @@
+//! This module contains the exporters, which render the crate's data structures into
+//! formats meant for people (or other programs) to consume
+pub mod calendar;
+pub mod latex;
+pub mod menu;
+pub mod packet;
+pub mod shopping;
+use food::script::{RenderedText, TextDirection};
+use food::*;
+use num_rational::*;
+
+/// Returns the value of an IString in the given language, falling back to the default
+/// language, and then to the short code
+///
+/// Exporters should never produce empty names just because a translation is missing.
+pub fn localized(string: &IString, lang: &str) -> String {
+    string
+        .get_value(lang)
+        .or_else(|| string.get_value(string.get_default()))
+        .unwrap_or_else(|| string.get_short_code())
+        .to_string()
+}
+
+/// Returns the value of an IString in the given language, falling back as `localized` does,
+/// along with the script and direction of the language the value came from
+///
+/// Short codes are taken to be left-to-right.
+pub fn localized_text(string: &IString, lang: &str) -> RenderedText {
+    let found = [lang, string.get_default()]
+        .iter()
+        .find_map(|&x| string.get_value(x).map(|value| (x, value)));
+    match found {
+        Some((found, value)) => {
+            let script = string
+                .get_script(found)
+                .and_then(|x| x.get_script())
+                .map(|x| x.to_string());
+            RenderedText::new(
+                value.to_string(),
+                found,
+                script,
+                string.get_direction(found),
+            )
+        }
+        None => RenderedText::new(
+            string.get_short_code().to_string(),
+            lang,
+            None,
+            TextDirection::LeftToRight,
+        ),
+    }
+}
+
+/// Formats a rational as a mixed number, such as "1 1/2" or "3/4"
+pub fn format_rational(value: Rational32) -> String {
+    let whole = value.trunc().to_integer();
+    let fraction = value.fract();
+    if fraction == Rational32::from_integer(0) {
+        whole.to_string()
+    } else if whole == 0 {
+        fraction.to_string()
+    } else {
+        // The fraction carries the sign for negative values, so only show it once
+        let fraction = if fraction < Rational32::from_integer(0) {
+            -fraction
+        } else {
+            fraction
+        };
+        format!("{} {}", whole, fraction)
+    }
+}
+
+/// Formats a number of minutes for display, such as "45 min"
+pub fn format_minutes(minutes: Rational32) -> String {
+    format!("{} min", format_rational(minutes))
+}
@@ -0,0 +1,338 @@
+//! This module contains the weekly packet exporter, producing everything needed to cook a
+//! week of meals as a single document
+//!
+//! A packet is the weekly menu, the shopping list for the whole plan, and a cook schedule for
+//! each day, listing when each step of each recipe has to start for the meal to be ready on
+//! time. Like the menu, a MealPlan is first flattened into a `WeeklyPacket`, with everything
+//! rendered into a single language, and the packet is then laid out as Markdown or HTML.
+use super::menu::{escape, escape_markdown, Menu};
+use super::shopping::{shopping_html, shopping_markdown};
+use super::{format_minutes, localized};
+use chrono::prelude::*;
+use chrono::Duration;
+use food::appliance::Appliances;
+use food::engine::*;
+use food::*;
+use format::Formatter;
+use num_rational::*;
+use settings::Settings;
+use shopping::{ShoppingList, StoreLayout};
+
+/// The headings and labels used when laying out a packet
+///
+/// Defaults to English, replace them to print in another language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PacketLabels {
+    /// The title of the HTML document
+    pub title: String,
+    pub menu: String,
+    pub shopping: String,
+    pub schedule: String,
+    /// The heading for shopping items without a category
+    pub other: String,
+    /// Printed beside steps that need nobody, such as resting dough
+    pub hands_off: String,
+}
+
+impl Default for PacketLabels {
+    fn default() -> PacketLabels {
+        PacketLabels {
+            title: "Weekly plan".to_string(),
+            menu: "Menu".to_string(),
+            shopping: "Shopping list".to_string(),
+            schedule: "Cook schedule".to_string(),
+            other: "Other".to_string(),
+            hands_off: "hands-off".to_string(),
+        }
+    }
+}
+
+/// A single step in a day's cook schedule
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScheduleStep {
+    /// When the step starts, formatted as "17:45"
+    pub start: String,
+    /// How long the step takes, already formatted
+    pub time: String,
+    /// The name of the food the step is for
+    pub food: String,
+    pub text: String,
+    /// True if the step needs nobody
+    pub hands_off: bool,
+    /// The notes of the food's block, only given with the food's first step
+    pub notes: Vec<String>,
+}
+
+/// A single day's cook schedule
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScheduleDay {
+    /// The heading for the day, such as "Monday 8 January"
+    pub heading: String,
+    /// The steps of every recipe on the day, in the order they start
+    pub steps: Vec<ScheduleStep>,
+}
+
+/// A meal plan flattened into everything needed to print a weekly packet
+#[derive(Clone, PartialEq, Eq)]
+pub struct WeeklyPacket {
+    /// Writes the amounts on the shopping list, in the packet's language
+    pub formatter: Formatter,
+    pub menu: Menu,
+    pub shopping: ShoppingList,
+    /// The cook schedule for each day with a recipe on it
+    pub schedule: Vec<ScheduleDay>,
+}
+
+impl WeeklyPacket {
+    /// Builds the packet for a meal plan in the given language
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::export::packet::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut bake = Step::new("bake", Rational32::from_integer(45));
+    /// bake.set_attention(Attention::Passive);
+    /// let lasagne = RecipeBuilder::new("lasagne")
+    ///     .add_step(Step::new("assemble", Rational32::from_integer(30)))
+    ///     .add_step(bake)
+    ///     .build_with_defaults();
+    /// let mut plan = MealPlan::new();
+    /// plan.insert_block(
+    ///     NaiveDate::from_ymd(2024, 1, 8),
+    ///     Block::new(
+    ///         NaiveTime::from_hms(17, 45, 0),
+    ///         NaiveTime::from_hms(19, 0, 0),
+    ///         Some(Food::new_from_recipe(lasagne)),
+    ///     ),
+    /// );
+    ///
+    /// let packet = WeeklyPacket::from_plan(&plan, "en_US");
+    /// let markdown = packet.to_markdown(&PacketLabels::default(), None);
+    /// assert!(markdown.starts_with("# Menu\n\n## Monday 8 January\n\n- 19:00 lasagne"));
+    /// assert!(markdown.contains(
+    ///     "# Cook schedule\n\n## Monday 8 January\n\n\
+    ///      - 17:45 lasagne: assemble (30 min)\n\
+    ///      - 18:15 lasagne: bake (45 min, hands-off)\n"
+    /// ));
+    /// # }
+    /// ```
+    pub fn from_plan(plan: &MealPlan, lang: &str) -> WeeklyPacket {
+        WeeklyPacket {
+            formatter: Formatter::new(lang),
+            menu: Menu::from_plan(plan, lang),
+            shopping: plan.shopping_list(&Settings::default()),
+            schedule: schedule(plan, lang, &Appliances::new()),
+        }
+    }
+
+    /// Builds the packet for a meal plan in the household's language, with the shopping list
+    /// using the household's conversions and written in the household's units, and step times
+    /// adjusted for the household's appliances
+    pub fn from_plan_with_settings(plan: &MealPlan, settings: &Settings) -> WeeklyPacket {
+        let lang = settings.get_language();
+        WeeklyPacket {
+            formatter: Formatter::from_settings(settings),
+            menu: Menu::from_plan(plan, lang),
+            shopping: plan.shopping_list(settings),
+            schedule: schedule(plan, lang, settings.get_appliances()),
+        }
+    }
+
+    /// Lays the packet out as a Markdown document, with the menu, the shopping list, and the
+    /// cook schedule in turn
+    ///
+    /// Given a store layout, the shopping list is in the order the items are found walking
+    /// that store, see `shopping_markdown`. Block notes are listed under their meal on the
+    /// menu, and beside the recipe's first step in the cook schedule.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::export::packet::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let stew = RecipeBuilder::new("stew")
+    ///     .add_step(Step::new("simmer", Rational32::from_integer(60)))
+    ///     .build_with_defaults();
+    /// let time = |h| NaiveTime::from_hms(h, 0, 0);
+    /// let mut block = Block::new(time(17), time(18), Some(Food::new_from_recipe(stew)));
+    /// block.add_note(IString::new("guests: grandparents"));
+    /// let mut plan = MealPlan::new();
+    /// plan.insert_block(NaiveDate::from_ymd(2024, 1, 8), block);
+    ///
+    /// let packet = WeeklyPacket::from_plan(&plan, "en_US");
+    /// let labels = PacketLabels::default();
+    /// let markdown = packet.to_markdown(&labels, None);
+    /// assert!(markdown.contains("- 18:00 stew (`stew`)\n  - guests: grandparents\n"));
+    /// assert!(markdown.contains("- 17:00 stew: simmer (60 min)\n  - guests: grandparents\n"));
+    /// let html = packet.to_html(&labels, None);
+    /// assert!(html.contains("<li dir=\"ltr\">guests: grandparents</li>"));
+    /// assert!(html.contains("stew: simmer (60 min)<br><small>guests: grandparents</small>"));
+    /// # }
+    /// ```
+    pub fn to_markdown(&self, labels: &PacketLabels, layout: Option<&StoreLayout>) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("# {}\n\n", labels.menu));
+        out.push_str(&self.menu.to_markdown(None));
+        out.push_str(&format!("# {}\n\n", labels.shopping));
+        out.push_str(&shopping_markdown(
+            &self.shopping,
+            layout,
+            &self.formatter,
+            &labels.other,
+        ));
+        out.push_str(&format!("\n# {}\n\n", labels.schedule));
+        for day in &self.schedule {
+            out.push_str(&format!("## {}\n\n", day.heading));
+            for step in &day.steps {
+                out.push_str(&format!(
+                    "- {} {}: {} ({})\n",
+                    step.start,
+                    step.food,
+                    step.text,
+                    step_details(step, labels)
+                ));
+                for note in &step.notes {
+                    out.push_str(&format!("  - {}\n", escape_markdown(note)));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Lays the packet out as a complete HTML document, with the menu, the shopping list, and
+    /// the cook schedule in turn
+    ///
+    /// The document carries no styling, so it prints plainly and can be restyled freely.
+    pub fn to_html(&self, labels: &PacketLabels, layout: Option<&StoreLayout>) -> String {
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n");
+        out.push_str(&format!(
+            "<html lang=\"{}\">\n",
+            escape(&html_lang(self.formatter.get_lang()))
+        ));
+        out.push_str("<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str(&format!("<title>{}</title>\n", escape(&labels.title)));
+        out.push_str("</head>\n<body>\n");
+        out.push_str(&format!("<h1>{}</h1>\n", escape(&labels.menu)));
+        out.push_str(&self.menu.to_html(None));
+        out.push_str(&format!("<h1>{}</h1>\n", escape(&labels.shopping)));
+        out.push_str(&shopping_html(
+            &self.shopping,
+            layout,
+            &self.formatter,
+            &labels.other,
+        ));
+        out.push_str(&format!("<h1>{}</h1>\n", escape(&labels.schedule)));
+        for day in &self.schedule {
+            out.push_str("<section>\n");
+            out.push_str(&format!("<h2>{}</h2>\n", escape(&day.heading)));
+            out.push_str("<ol>\n");
+            for step in &day.steps {
+                out.push_str(&format!(
+                    "<li><time>{}</time> {}: {} ({})",
+                    escape(&step.start),
+                    escape(&step.food),
+                    escape(&step.text),
+                    escape(&step_details(step, labels))
+                ));
+                for note in &step.notes {
+                    out.push_str(&format!("<br><small>{}</small>", escape(note)));
+                }
+                out.push_str("</li>\n");
+            }
+            out.push_str("</ol>\n");
+            out.push_str("</section>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+/// Returns the time a step takes, marked if it is hands-off
+fn step_details(step: &ScheduleStep, labels: &PacketLabels) -> String {
+    if step.hands_off {
+        format!("{}, {}", step.time, labels.hands_off)
+    } else {
+        step.time.clone()
+    }
+}
+
+/// Converts a language code like "en_US" to the form HTML expects, "en-US"
+fn html_lang(lang: &str) -> String {
+    lang.replace('_', "-")
+}
+
+/// Builds the cook schedule for every day of the plan with a recipe on it
+///
+/// Each recipe's steps are worked backward from the end of its block, when it is served, the
+/// same way as `scheduler::step_times`, but with the times adjusted for the appliances.
+fn schedule(plan: &MealPlan, lang: &str, appliances: &Appliances) -> Vec<ScheduleDay> {
+    plan.get_days()
+        .iter()
+        .filter_map(|day| {
+            let mut steps: Vec<(NaiveTime, ScheduleStep)> = Vec::new();
+            for block in day.get_blocks() {
+                let recipe = match block.get_food() {
+                    Some(Food::Recipe(recipe)) => recipe,
+                    _ => continue,
+                };
+                let food = localized(recipe.get_name(), lang);
+                let mut end = *block.get_end();
+                let mut recipe_steps = Vec::new();
+                for step in recipe.get_steps().iter().rev() {
+                    let time = appliances.step_time(step);
+                    let seconds = (time * Rational32::from_integer(60)).to_integer();
+                    let start = end - Duration::seconds(i64::from(seconds));
+                    recipe_steps.push((
+                        start,
+                        ScheduleStep {
+                            start: start.format("%H:%M").to_string(),
+                            time: format_minutes(time),
+                            food: food.clone(),
+                            text: localized(step.get_text(), lang),
+                            hands_off: step.get_attention() == Attention::Passive,
+                            notes: Vec::new(),
+                        },
+                    ));
+                    end = start;
+                }
+                recipe_steps.reverse();
+                if let Some(first) = recipe_steps.first_mut() {
+                    first.1.notes = block
+                        .get_notes()
+                        .iter()
+                        .map(|x| localized(x, lang))
+                        .collect();
+                }
+                steps.extend(recipe_steps);
+            }
+            if steps.is_empty() {
+                return None;
+            }
+            // Stable, so steps starting together stay in the order of their meals
+            steps.sort_by_key(|x| x.0);
+            Some(ScheduleDay {
+                heading: day.get_date().format("%A %-d %B").to_string(),
+                steps: steps.into_iter().map(|x| x.1).collect(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,121 @@
+//! This module contains the shopping list exporter, for producing a list to take to the store
+use super::localized;
+use super::menu::escape;
+use format::Formatter;
+use shopping::*;
+
+/// Returns the items in the order they are listed, see `shopping_markdown`
+fn ordered<'a>(list: &'a ShoppingList, layout: Option<&StoreLayout>) -> Vec<&'a ShoppingItem> {
+    match layout {
+        Some(layout) => list.sorted_for(layout),
+        None => {
+            let mut items = list.sorted();
+            items.sort_by(|a, b| match (a.get_category(), b.get_category()) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => ::std::cmp::Ordering::Less,
+                (None, Some(_)) => ::std::cmp::Ordering::Greater,
+                (None, None) => ::std::cmp::Ordering::Equal,
+            });
+            items
+        }
+    }
+}
+
+/// Lays a shopping list out as a Markdown task list, grouped under category headings
+///
+/// Checked off items are shown ticked. Names are in the formatter's language, and amounts are
+/// written with their units by `Formatter::format_amount`.
+///
+/// Given a store layout, the items are in the order they are found walking that store,
+/// otherwise they are sorted by short code. Items without a category are listed last, under
+/// the `other` heading.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::export::shopping::shopping_markdown;
+/// use time_for_food::food::*;
+/// use time_for_food::format::Formatter;
+/// use time_for_food::shopping::*;
+///
+/// # fn main() {
+/// let mut list = ShoppingList::new();
+/// let mut milk = ShoppingItem::new(
+///     IString::new("milk"),
+///     Amount::new(Unit::Liter, Rational32::from_integer(2)),
+/// );
+/// milk.set_category(Some("dairy"));
+/// list.add_item(milk);
+///
+/// assert_eq!(
+///     shopping_markdown(&list, None, &Formatter::new("en_US"), "Other"),
+///     "### dairy\n\n- [ ] 2 L milk\n"
+/// );
+/// # }
+/// ```
+pub fn shopping_markdown(
+    list: &ShoppingList,
+    layout: Option<&StoreLayout>,
+    formatter: &Formatter,
+    other: &str,
+) -> String {
+    let items = ordered(list, layout);
+    let mut out = String::new();
+    let mut heading: Option<&str> = None;
+    for (i, item) in items.iter().enumerate() {
+        let category = item.get_category().unwrap_or(other);
+        if i == 0 || heading != Some(category) {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.push_str(&format!("### {}\n\n", category));
+            heading = Some(category);
+        }
+        out.push_str(&format!(
+            "- [{}] {} {}\n",
+            if item.is_checked() { "x" } else { " " },
+            formatter.format_amount(&item.get_amount()),
+            localized(item.get_name(), formatter.get_lang())
+        ));
+    }
+    out
+}
+
+/// Lays a shopping list out as an HTML fragment, one list per category
+///
+/// Items are ordered and grouped as they are by `shopping_markdown`, with checked off items
+/// shown as ticked checkboxes. The fragment carries no styling, so it can be dropped into any
+/// page.
+pub fn shopping_html(
+    list: &ShoppingList,
+    layout: Option<&StoreLayout>,
+    formatter: &Formatter,
+    other: &str,
+) -> String {
+    let items = ordered(list, layout);
+    let mut out = String::new();
+    let mut heading: Option<&str> = None;
+    for (i, item) in items.iter().enumerate() {
+        let category = item.get_category().unwrap_or(other);
+        if i == 0 || heading != Some(category) {
+            if i > 0 {
+                out.push_str("</ul>\n");
+            }
+            out.push_str(&format!("<h3>{}</h3>\n<ul>\n", escape(category)));
+            heading = Some(category);
+        }
+        out.push_str(&format!(
+            "<li><input type=\"checkbox\"{}> {} {}</li>\n",
+            if item.is_checked() { " checked" } else { "" },
+            escape(&formatter.format_amount(&item.get_amount())),
+            escape(&localized(item.get_name(), formatter.get_lang()))
+        ));
+    }
+    if !items.is_empty() {
+        out.push_str("</ul>\n");
+    }
+    out
+}
@@ -0,0 +1,398 @@
+//! This module contains appliance profiles, describing how a household's kitchen differs from
+//! the one a recipe was written for
+//!
+//! Profiles are keyed by the same equipment short codes used on steps. A profile can scale the
+//! time of any step using the equipment (a slow oven, a fast induction hob), and can swap the
+//! equipment for another (cooking "large-pot" steps in a pressure cooker).
+//!
+//! Adjustment rules change every oven step instead, whatever equipment it uses, such as
+//! lowering temperatures for a convection oven, or adding time at high altitude.
+//!
+//! Profiles can also record how much a piece of equipment holds, as a multiple of the
+//! quantities recipes are written for, so scaling a recipe up can be checked for steps that
+//! won't fit, such as six batches of batter in a single mixer bowl.
+use food::*;
+use std::collections::BTreeMap;
+
+/// How a household's version of a piece of equipment behaves
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ApplianceProfile {
+    /// Steps using the equipment take this many times as long
+    multiplier: Fraction,
+    /// The equipment used in its place, if any
+    substitute: Option<String>,
+    /// The largest multiple of a recipe's quantities the equipment holds, if known
+    #[serde(default)]
+    capacity: Option<Fraction>,
+}
+
+impl ApplianceProfile {
+    /// Creates a profile scaling step times by the given multiplier
+    pub fn new(multiplier: Rational32) -> ApplianceProfile {
+        ApplianceProfile {
+            multiplier: Fraction::from_rational(multiplier),
+            substitute: None,
+            capacity: None,
+        }
+    }
+
+    /// Creates a profile replacing the equipment with another, which takes the given
+    /// multiple of the time
+    pub fn substitute(short_code: &str, multiplier: Rational32) -> ApplianceProfile {
+        ApplianceProfile {
+            multiplier: Fraction::from_rational(multiplier),
+            substitute: Some(short_code.to_string()),
+            capacity: None,
+        }
+    }
+
+    /// Returns the multiple of the written time steps using the equipment take
+    pub fn get_multiplier(&self) -> Rational32 {
+        self.multiplier.to_rational()
+    }
+
+    /// Returns the short code of the equipment used in its place, if any
+    pub fn get_substitute(&self) -> Option<&str> {
+        self.substitute.as_deref()
+    }
+
+    /// Returns the largest multiple of a recipe's quantities the equipment holds, if known
+    pub fn get_capacity(&self) -> Option<Rational32> {
+        self.capacity.map(|x| x.to_rational())
+    }
+
+    /// Sets the largest multiple of a recipe's quantities the equipment holds, such as 2 for
+    /// a pan that fits a double batch, or None if it isn't known
+    pub fn set_capacity(&mut self, capacity: Option<Rational32>) -> &mut Self {
+        self.capacity = capacity.map(Fraction::from_rational);
+        self
+    }
+}
+
+/// Why a step of a scaled recipe likely won't scale as written
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScalingIssue {
+    /// The equipment holds less than the scaled quantities, so the step needs batches or a
+    /// bigger piece of equipment
+    OverCapacity {
+        equipment: String,
+        capacity: Rational32,
+    },
+    /// The equipment's capacity isn't known, and the recipe is scaled past the threshold
+    UnknownCapacity { equipment: String },
+}
+
+/// A warning about a step of a scaled recipe
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScalingWarning {
+    step: usize,
+    factor: Rational32,
+    issue: ScalingIssue,
+}
+
+impl ScalingWarning {
+    /// Returns the index of the step the warning is about
+    pub fn get_step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns how many times the recipe's quantities were scaled
+    pub fn get_factor(&self) -> Rational32 {
+        self.factor
+    }
+
+    /// Returns why the step likely won't scale
+    pub fn get_issue(&self) -> &ScalingIssue {
+        &self.issue
+    }
+}
+
+/// A change made to every step with an oven temperature
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AdjustmentRule {
+    /// Change to the oven temperature, in degrees Celsius
+    temperature_change: i32,
+    /// Minutes added to the step
+    extra_minutes: Fraction,
+}
+
+impl AdjustmentRule {
+    /// Creates a rule changing oven temperatures by the given number of degrees Celsius, and
+    /// adding the given number of minutes to every oven step
+    pub fn new(temperature_change: i32, extra_minutes: Rational32) -> AdjustmentRule {
+        AdjustmentRule {
+            temperature_change,
+            extra_minutes: Fraction::from_rational(extra_minutes),
+        }
+    }
+
+    /// The usual adjustment for a convection (fan) oven, 15 °C cooler
+    pub fn convection() -> AdjustmentRule {
+        AdjustmentRule::new(-15, Rational32::from_integer(0))
+    }
+
+    /// Adds the given number of minutes to every oven step, as needed at high altitude
+    pub fn altitude(extra_minutes: Rational32) -> AdjustmentRule {
+        AdjustmentRule::new(0, extra_minutes)
+    }
+
+    /// Returns the change to oven temperatures, in degrees Celsius
+    pub fn get_temperature_change(&self) -> i32 {
+        self.temperature_change
+    }
+
+    /// Returns the minutes added to every oven step
+    pub fn get_extra_minutes(&self) -> Rational32 {
+        self.extra_minutes.to_rational()
+    }
+}
+
+/// A household's appliance profiles, keyed by equipment short code, and adjustment rules
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Appliances {
+    profiles: BTreeMap<String, ApplianceProfile>,
+    #[serde(default)]
+    rules: Vec<AdjustmentRule>,
+}
+
+impl Appliances {
+    /// Creates an empty set of profiles, where everything behaves as written
+    pub fn new() -> Appliances {
+        Appliances {
+            profiles: BTreeMap::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Sets the profile for a piece of equipment, replacing any existing profile
+    pub fn set_profile(&mut self, equipment: &str, profile: ApplianceProfile) -> &mut Self {
+        self.profiles.insert(equipment.to_string(), profile);
+        self
+    }
+
+    /// Returns the profile for a piece of equipment, if there is one
+    pub fn get_profile(&self, equipment: &str) -> Option<&ApplianceProfile> {
+        self.profiles.get(equipment)
+    }
+
+    /// Removes the profile for a piece of equipment, returning it
+    pub fn remove_profile(&mut self, equipment: &str) -> Option<ApplianceProfile> {
+        self.profiles.remove(equipment)
+    }
+
+    /// Adds a rule applied to every oven step
+    pub fn add_rule(&mut self, rule: AdjustmentRule) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Returns the rules applied to every oven step, in the order they were added
+    pub fn get_rules(&self) -> &[AdjustmentRule] {
+        &self.rules
+    }
+
+    /// Removes the rule at the given index, returning it
+    pub fn remove_rule(&mut self, index: usize) -> Option<AdjustmentRule> {
+        if index < self.rules.len() {
+            Some(self.rules.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the oven temperature, in degrees Celsius, a step uses in this kitchen, after
+    /// the adjustment rules
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::appliance::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut bake = Step::new("bake-bread", Rational32::from_integer(30));
+    /// bake.set_oven_temperature(Some(220));
+    ///
+    /// let mut appliances = Appliances::new();
+    /// appliances
+    ///     .add_rule(AdjustmentRule::convection())
+    ///     .add_rule(AdjustmentRule::altitude(Rational32::from_integer(5)));
+    ///
+    /// assert_eq!(appliances.oven_temperature(&bake), Some(205));
+    /// assert_eq!(appliances.step_time(&bake), Rational32::from_integer(35));
+    /// # }
+    /// ```
+    pub fn oven_temperature(&self, step: &Step) -> Option<u32> {
+        step.get_oven_temperature().map(|temperature| {
+            let change: i32 = self.rules.iter().map(|x| x.temperature_change).sum();
+            ::std::cmp::max(temperature as i32 + change, 0) as u32
+        })
+    }
+
+    /// Returns the equipment a step actually uses in this kitchen, after substitutions
+    pub fn equipment_for<'a>(&'a self, step: &'a Step) -> Vec<&'a str> {
+        step.get_equipment()
+            .iter()
+            .map(|x| {
+                self.get_profile(x)
+                    .and_then(|y| y.get_substitute())
+                    .unwrap_or(x)
+            })
+            .collect()
+    }
+
+    /// Returns how long a step takes in this kitchen
+    ///
+    /// When a step uses several profiled pieces of equipment, the slowest one decides. Oven
+    /// steps then have the adjustment rules' extra minutes added.
+    pub fn step_time(&self, step: &Step) -> Rational32 {
+        let multiplier = step
+            .get_equipment()
+            .iter()
+            .filter_map(|x| self.get_profile(x))
+            .map(|x| x.get_multiplier())
+            .max();
+        let time = match multiplier {
+            Some(multiplier) => step.get_time() * multiplier,
+            None => step.get_time(),
+        };
+        if step.get_oven_temperature().is_some() {
+            self.rules
+                .iter()
+                .fold(time, |total, x| total + x.get_extra_minutes())
+        } else {
+            time
+        }
+    }
+
+    /// Returns how long a recipe takes in this kitchen
+    ///
+    /// The recipe's time is adjusted by however much its steps speed up or slow down, so any
+    /// time the recipe states beyond its steps is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::appliance::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut simmer = Step::new("simmer-beans", Rational32::from_integer(90));
+    /// simmer.add_equipment("large-pot");
+    /// let beans = RecipeBuilder::new("beans")
+    ///     .add_step(Step::new("rinse-beans", Rational32::from_integer(5)))
+    ///     .add_step(simmer)
+    ///     .build_with_defaults();
+    ///
+    /// let mut appliances = Appliances::new();
+    /// appliances.set_profile(
+    ///     "large-pot",
+    ///     ApplianceProfile::substitute("pressure-cooker", Rational32::new(1, 3)),
+    /// );
+    ///
+    /// assert_eq!(appliances.recipe_time(&beans), Rational32::from_integer(35));
+    /// # }
+    /// ```
+    pub fn recipe_time(&self, recipe: &Recipe) -> Rational32 {
+        let change = recipe
+            .get_steps()
+            .iter()
+            .fold(Rational32::from_integer(0), |total, x| {
+                total + self.step_time(x) - x.get_time()
+            });
+        recipe.get_time() + change
+    }
+
+    /// Checks whether the steps of a recipe will still work when it is scaled to the given
+    /// number of servings
+    ///
+    /// Every piece of equipment a step uses in this kitchen is compared against its capacity,
+    /// and warned about if the scaled quantities won't fit. Equipment with no known capacity
+    /// is only warned about once the recipe is scaled past the threshold, as a single pan or
+    /// mixer bowl likely won't hold that much. Recipes making no servings can't be scaled, so
+    /// are never warned about.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::appliance::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut mix = Step::new("mix-batter", Rational32::from_integer(5));
+    /// mix.add_equipment("stand-mixer");
+    /// let mut fry = Step::new("fry-pancakes", Rational32::from_integer(20));
+    /// fry.add_equipment("frying-pan");
+    /// let pancakes = RecipeBuilder::new("pancakes")
+    ///     .set_servings(Rational32::from_integer(4))
+    ///     .add_step(mix)
+    ///     .add_step(fry)
+    ///     .build_with_defaults();
+    ///
+    /// let mut mixer = ApplianceProfile::new(Rational32::from_integer(1));
+    /// mixer.set_capacity(Some(Rational32::from_integer(3)));
+    /// let mut appliances = Appliances::new();
+    /// appliances.set_profile("stand-mixer", mixer);
+    ///
+    /// let six = Rational32::from_integer(6);
+    /// // Doubling fits in the mixer, and is well under the threshold for the pan
+    /// assert!(appliances.check_scaling(&pancakes, Rational32::from_integer(8), six).is_empty());
+    ///
+    /// // Seven times over needs batches in the mixer, and likely more than one pan
+    /// let warnings = appliances.check_scaling(&pancakes, Rational32::from_integer(28), six);
+    /// assert_eq!(warnings.len(), 2);
+    /// assert_eq!(
+    ///     warnings[0].get_issue(),
+    ///     &ScalingIssue::OverCapacity {
+    ///         equipment: "stand-mixer".to_string(),
+    ///         capacity: Rational32::from_integer(3),
+    ///     }
+    /// );
+    /// assert_eq!(warnings[1].get_step(), 1);
+    /// assert_eq!(warnings[1].get_factor(), Rational32::from_integer(7));
+    /// # }
+    /// ```
+    pub fn check_scaling(
+        &self,
+        recipe: &Recipe,
+        target_servings: Rational32,
+        threshold: Rational32,
+    ) -> Vec<ScalingWarning> {
+        let servings = recipe.get_servings();
+        if servings == Rational32::from_integer(0) {
+            return Vec::new();
+        }
+        let factor = target_servings / servings;
+        let mut warnings = Vec::new();
+        for (index, step) in recipe.get_steps().iter().enumerate() {
+            for equipment in self.equipment_for(step) {
+                let capacity = self.get_profile(equipment).and_then(|x| x.get_capacity());
+                let issue = match capacity {
+                    Some(capacity) if factor > capacity => ScalingIssue::OverCapacity {
+                        equipment: equipment.to_string(),
+                        capacity,
+                    },
+                    None if factor > threshold => ScalingIssue::UnknownCapacity {
+                        equipment: equipment.to_string(),
+                    },
+                    _ => continue,
+                };
+                warnings.push(ScalingWarning {
+                    step: index,
+                    factor,
+                    issue,
+                });
+            }
+        }
+        warnings
+    }
+}
@@ -0,0 +1,965 @@
+//! This module contains the constraints a household places on its meal plans
+//!
+//! Constraints are either hard, and a plan breaking them is unacceptable, or soft, and a plan
+//! breaking them is merely penalized. Anything choosing recipes should discard plans that
+//! break hard constraints, and prefer plans with the lowest total penalty.
+//!
+//! Objectives describe what makes one acceptable plan better than another, and are scored the
+//! same way as penalties, lower is better.
+//!
+//! Most constraints look at a day as a whole, but some look at each of its meals, the blocks
+//! with food, and a few, like keeping the same recipe from coming up too often, also look back
+//! at the days before it in the plan. Meals are measured by the nutrition of a single serving
+//! of their food, and nutrients a food's nutrition doesn't record count as none at all.
+//!
+//! A `Preset` is a named set of constraints for a stretch of recovery or illness, such as soft
+//! foods only, that can be switched on for a range of dates.
+use chrono::prelude::*;
+use chrono::Duration;
+use food::engine::{self, Day, MealPlan};
+use food::goal::{GoalSchedule, GoalScoring};
+use food::score::{ScoreFormula, ServingScore};
+use food::{Food, Nutrient, Unit};
+use num_rational::*;
+
+/// How strictly a constraint is enforced
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Strength {
+    /// Plans breaking the constraint are unacceptable
+    Hard,
+    /// Plans breaking the constraint are penalized by the given weight per unit of excess
+    Soft { weight: u32 },
+}
+
+/// A rule a meal plan should follow
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Constraint {
+    /// Caps the minutes of active cooking on each day, optionally only on the given weekdays
+    /// ("no more than 40 minutes on weekdays")
+    MaxActiveMinutes {
+        minutes: u32,
+        weekdays: Vec<Weekday>,
+        strength: Strength,
+    },
+    /// Requires at least the given amount of a nutrient, in the nutrient's own unit, in every
+    /// meal ("at least 25 g of protein each meal")
+    MinNutrientPerMeal {
+        nutrient: Nutrient,
+        amount: u32,
+        strength: Strength,
+    },
+    /// Caps the percentage of each day's total of a nutrient eaten in meals starting at or
+    /// after the given time ("less than 30% of carbohydrates after 18:00")
+    MaxShareAfter {
+        nutrient: Nutrient,
+        after: NaiveTime,
+        percent: u32,
+        strength: Strength,
+    },
+    /// Caps the amount of a nutrient, in the nutrient's own unit, eaten each day
+    MaxNutrientPerDay {
+        nutrient: Nutrient,
+        amount: u32,
+        strength: Strength,
+    },
+    /// Requires at least the given amount of a nutrient, in the nutrient's own unit, each day
+    MinNutrientPerDay {
+        nutrient: Nutrient,
+        amount: u32,
+        strength: Strength,
+    },
+    /// Caps a score, such as points or carbohydrate exchanges, totalled over each day
+    MaxScorePerDay {
+        formula: ScoreFormula,
+        amount: u32,
+        strength: Strength,
+    },
+    /// Caps a score, such as points or carbohydrate exchanges, in every meal
+    MaxScorePerMeal {
+        formula: ScoreFormula,
+        amount: u32,
+        strength: Strength,
+    },
+    /// Requires every meal to be a food with the given tag ("soft foods only")
+    RequireTag { tag: String, strength: Strength },
+    /// Forbids meals made with the given ingredient, named as in `Food::contains_ingredient`
+    /// ("no dairy")
+    ExcludeIngredient {
+        ingredient: String,
+        strength: Strength,
+    },
+    /// Requires at least the given millilitres of beverages to be drunk each day
+    MinFluidPerDay {
+        milliliters: u32,
+        strength: Strength,
+    },
+    /// Requires at least the given number of days between meals of the same food, by short
+    /// code, or of any food if none is given ("no spaghetti more than once every 4 days").
+    /// Every meal repeated too soon counts as one unit of excess.
+    MinDaysBetweenRepeats {
+        food: Option<String>,
+        days: u32,
+        strength: Strength,
+    },
+    /// Requires every meal to be ready to eat within a daily window, such as 12:00–20:00 for
+    /// intermittent fasting. Every meal ready outside the window counts as one unit of excess.
+    EatingWindow {
+        window: engine::EatingWindow,
+        strength: Strength,
+    },
+    /// Only applies the inner constraint between the start and end dates, inclusive
+    Between {
+        start: NaiveDate,
+        end: NaiveDate,
+        constraint: Box<Constraint>,
+    },
+}
+
+/// A temporary ban on an ingredient, between two dates inclusive, such as no dairy during an
+/// elimination diet
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use num_rational::Rational32;
+/// use time_for_food::food::constraint::*;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let milk = RawFoodBuilder::new("milk")
+///     .add_tag("dairy")
+///     .set_serving_size(Unit::Milliliter, Rational32::from_integer(250))
+///     .set_nutrition(Nutrition::new())
+///     .build_raw_food()
+///     .unwrap();
+/// let porridge = RecipeBuilder::new("porridge")
+///     .add_food(Food::new_from_raw_food(milk), Unit::Milliliter, Rational32::from_integer(200))
+///     .build_with_defaults();
+/// let porridge = Food::new_from_recipe(porridge);
+///
+/// let no_dairy = Exclusion::new(
+///     "dairy",
+///     NaiveDate::from_ymd(2024, 2, 1),
+///     NaiveDate::from_ymd(2024, 2, 29),
+/// );
+/// assert!(no_dairy.excludes(NaiveDate::from_ymd(2024, 2, 14), &porridge));
+/// assert!(!no_dairy.excludes(NaiveDate::from_ymd(2024, 3, 1), &porridge));
+///
+/// // The planner rules the porridge out for February only
+/// let constraint = no_dairy.constraint(Strength::Hard);
+/// assert!(!constraint.permits(NaiveDate::from_ymd(2024, 2, 14), &porridge));
+/// assert!(constraint.permits(NaiveDate::from_ymd(2024, 3, 1), &porridge));
+/// # }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Exclusion {
+    ingredient: String,
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+impl Exclusion {
+    /// Creates a new exclusion of an ingredient, named as in `Food::contains_ingredient`,
+    /// between the start and end dates, inclusive
+    pub fn new(ingredient: &str, start: NaiveDate, end: NaiveDate) -> Exclusion {
+        Exclusion {
+            ingredient: ingredient.to_string(),
+            start,
+            end,
+        }
+    }
+
+    /// Returns the excluded ingredient
+    pub fn get_ingredient(&self) -> &str {
+        &self.ingredient
+    }
+
+    /// Returns the first day of the exclusion
+    pub fn get_start(&self) -> NaiveDate {
+        self.start
+    }
+
+    /// Returns the last day of the exclusion
+    pub fn get_end(&self) -> NaiveDate {
+        self.end
+    }
+
+    /// Returns true if the exclusion is in effect on the given date
+    pub fn applies_to(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// Returns true if eating the food on the given date would break the exclusion
+    pub fn excludes(&self, date: NaiveDate, food: &Food) -> bool {
+        self.applies_to(date) && food.contains_ingredient(&self.ingredient)
+    }
+
+    /// Returns the exclusion as a constraint for the planner
+    pub fn constraint(&self, strength: Strength) -> Constraint {
+        Constraint::ExcludeIngredient {
+            ingredient: self.ingredient.clone(),
+            strength,
+        }
+        .between(self.start, self.end)
+    }
+}
+
+/// Describes a constraint broken on a particular day
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    date: NaiveDate,
+    constraint: Constraint,
+    excess: Rational32,
+    meals: Vec<NaiveTime>,
+}
+
+impl Violation {
+    /// Returns the date the constraint was broken on
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the constraint that was broken
+    pub fn get_constraint(&self) -> &Constraint {
+        &self.constraint
+    }
+
+    /// Returns how far past the constraint the day went, in the constraint's own units
+    ///
+    /// For constraints on each meal, this is the total across every meal that broke it.
+    pub fn get_excess(&self) -> Rational32 {
+        self.excess
+    }
+
+    /// Returns the start times of the meals that broke the constraint, empty for constraints
+    /// on whole days
+    pub fn get_meals(&self) -> &[NaiveTime] {
+        &self.meals
+    }
+
+    /// Returns true if the broken constraint is a hard constraint
+    pub fn is_hard(&self) -> bool {
+        self.constraint.get_strength() == Strength::Hard
+    }
+
+    /// Returns the penalty for the violation, zero for hard constraints
+    pub fn get_penalty(&self) -> Rational32 {
+        match self.constraint.get_strength() {
+            Strength::Hard => Rational32::from_integer(0),
+            Strength::Soft { weight } => self.excess * Rational32::from_integer(weight as i32),
+        }
+    }
+}
+
+impl Constraint {
+    /// Caps the minutes of active cooking on every day
+    pub fn max_active_minutes(minutes: u32, strength: Strength) -> Constraint {
+        Constraint::MaxActiveMinutes {
+            minutes,
+            weekdays: Vec::new(),
+            strength,
+        }
+    }
+
+    /// Caps the minutes of active cooking on the given weekdays only
+    pub fn max_active_minutes_on(
+        minutes: u32,
+        weekdays: &[Weekday],
+        strength: Strength,
+    ) -> Constraint {
+        Constraint::MaxActiveMinutes {
+            minutes,
+            weekdays: weekdays.to_vec(),
+            strength,
+        }
+    }
+
+    /// Requires at least the given amount of a nutrient in every meal
+    pub fn min_nutrient_per_meal(
+        nutrient: Nutrient,
+        amount: u32,
+        strength: Strength,
+    ) -> Constraint {
+        Constraint::MinNutrientPerMeal {
+            nutrient,
+            amount,
+            strength,
+        }
+    }
+
+    /// Caps the percentage of each day's nutrient eaten in meals starting at or after a time
+    pub fn max_share_after(
+        nutrient: Nutrient,
+        after: NaiveTime,
+        percent: u32,
+        strength: Strength,
+    ) -> Constraint {
+        Constraint::MaxShareAfter {
+            nutrient,
+            after,
+            percent,
+            strength,
+        }
+    }
+
+    /// Requires at least the given number of days between meals of the same food, by short
+    /// code, or between repeats of any food if none is given
+    ///
+    /// With one day, a food can't be eaten twice on the same day, with two, not on the next
+    /// day either, and so on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let spaghetti = RecipeBuilder::new("spaghetti").build_with_defaults();
+    /// let spaghetti = Food::new_from_recipe(spaghetti);
+    /// let mut plan = MealPlan::new();
+    /// for day in 8..11 {
+    ///     let block = Block::new(
+    ///         NaiveTime::from_hms(18, 0, 0),
+    ///         NaiveTime::from_hms(19, 0, 0),
+    ///         Some(spaghetti.clone()),
+    ///     );
+    ///     plan.insert_block(NaiveDate::from_ymd(2024, 1, day), block);
+    /// }
+    ///
+    /// let variety = Constraint::min_days_between_repeats(Some("spaghetti"), 2, Strength::Hard);
+    /// let violations = check_plan(&plan, &[variety]);
+    /// // The second and third nights both follow a night of spaghetti
+    /// let dates: Vec<NaiveDate> = violations.iter().map(|x| x.get_date()).collect();
+    /// assert_eq!(dates, [NaiveDate::from_ymd(2024, 1, 9), NaiveDate::from_ymd(2024, 1, 10)]);
+    /// assert_eq!(violations[0].get_meals(), [NaiveTime::from_hms(18, 0, 0)]);
+    /// # }
+    /// ```
+    pub fn min_days_between_repeats(
+        food: Option<&str>,
+        days: u32,
+        strength: Strength,
+    ) -> Constraint {
+        Constraint::MinDaysBetweenRepeats {
+            food: food.map(|x| x.to_string()),
+            days,
+            strength,
+        }
+    }
+
+    /// Only applies the constraint between the start and end dates, inclusive
+    pub fn between(self, start: NaiveDate, end: NaiveDate) -> Constraint {
+        Constraint::Between {
+            start,
+            end,
+            constraint: Box::new(self),
+        }
+    }
+
+    /// Returns how strictly the constraint is enforced
+    pub fn get_strength(&self) -> Strength {
+        match *self {
+            Constraint::MaxActiveMinutes { strength, .. }
+            | Constraint::MinNutrientPerMeal { strength, .. }
+            | Constraint::MaxShareAfter { strength, .. }
+            | Constraint::MaxNutrientPerDay { strength, .. }
+            | Constraint::MinNutrientPerDay { strength, .. }
+            | Constraint::MaxScorePerDay { strength, .. }
+            | Constraint::MaxScorePerMeal { strength, .. }
+            | Constraint::RequireTag { strength, .. }
+            | Constraint::ExcludeIngredient { strength, .. }
+            | Constraint::MinFluidPerDay { strength, .. }
+            | Constraint::MinDaysBetweenRepeats { strength, .. }
+            | Constraint::EatingWindow { strength, .. } => strength,
+            Constraint::Between { ref constraint, .. } => constraint.get_strength(),
+        }
+    }
+
+    /// Returns true if the constraint applies on the given date
+    pub fn applies_to(&self, date: NaiveDate) -> bool {
+        match *self {
+            Constraint::MaxActiveMinutes { ref weekdays, .. } => {
+                weekdays.is_empty() || weekdays.contains(&date.weekday())
+            }
+            Constraint::Between {
+                start,
+                end,
+                ref constraint,
+            } => start <= date && date <= end && constraint.applies_to(date),
+            _ => true,
+        }
+    }
+
+    /// Returns true unless the food, as a meal on the given date, would break a hard
+    /// constraint all by itself
+    ///
+    /// Lets anything choosing recipes rule out foods for a day before trying them.
+    pub fn permits(&self, date: NaiveDate, food: &Food) -> bool {
+        if self.get_strength() != Strength::Hard || !self.applies_to(date) {
+            return true;
+        }
+        match *self {
+            Constraint::RequireTag { ref tag, .. } => food.has_tag(tag),
+            Constraint::ExcludeIngredient { ref ingredient, .. } => {
+                !food.contains_ingredient(ingredient)
+            }
+            Constraint::MinNutrientPerMeal {
+                nutrient, amount, ..
+            } => {
+                let x = food.get_nutrition().get_nutrient(nutrient);
+                x.unwrap_or_else(|| Rational32::from_integer(0))
+                    >= Rational32::from_integer(amount as i32)
+            }
+            Constraint::MaxScorePerMeal {
+                ref formula,
+                amount,
+                ..
+            }
+            | Constraint::MaxScorePerDay {
+                ref formula,
+                amount,
+                ..
+            } => formula.score_food(food) <= Rational32::from_integer(amount as i32),
+            Constraint::Between { ref constraint, .. } => constraint.permits(date, food),
+            _ => true,
+        }
+    }
+
+    /// Returns true unless the food, as a meal ready to eat at the given time on the given
+    /// date, would break a hard constraint all by itself
+    ///
+    /// As `permits`, but also rules out meals ready outside a hard eating window.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::optimizer::Optimizer;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let time = |h| NaiveTime::from_hms(h, 0, 0);
+    /// let fasting = Constraint::EatingWindow {
+    ///     window: EatingWindow::new(time(12), time(20)),
+    ///     strength: Strength::Hard,
+    /// };
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let salad = Food::new_from_recipe(RecipeBuilder::new("salad").build_with_defaults());
+    /// assert!(!fasting.permits_at(date, time(9), &salad));
+    /// assert!(fasting.permits_at(date, time(13), &salad));
+    ///
+    /// // The optimizer leaves breakfast free, rather than break the fast
+    /// let mut template = MealPlan::new();
+    /// template.insert_block(date, Block::new(time(8), time(9), None));
+    /// template.insert_block(date, Block::new(time(12), time(13), None));
+    /// let mut optimizer = Optimizer::new(1);
+    /// optimizer.add_constraint(fasting);
+    /// let plan = optimizer.optimize(&template, &[salad]).unwrap().into_plan();
+    /// assert!(!plan.blocks_between(date, time(8), time(9))[0].has_food());
+    /// assert!(plan.blocks_between(date, time(12), time(13))[0].has_food());
+    /// # }
+    /// ```
+    pub fn permits_at(&self, date: NaiveDate, ready: NaiveTime, food: &Food) -> bool {
+        if self.get_strength() != Strength::Hard || !self.applies_to(date) {
+            return true;
+        }
+        match *self {
+            Constraint::EatingWindow { ref window, .. } => window.contains(ready),
+            Constraint::Between { ref constraint, .. } => constraint.permits_at(date, ready, food),
+            _ => self.permits(date, food),
+        }
+    }
+
+    /// Checks a single day against the constraint
+    ///
+    /// The day is checked on its own, so constraints looking back at earlier days only see
+    /// the day itself, see `check_day_in` for checking a day of a plan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let lasagne = RecipeBuilder::new("lasagne")
+    ///     .add_step(Step::new("assemble", Rational32::from_integer(50)))
+    ///     .build_with_defaults();
+    /// // 2024-01-08 is a Monday
+    /// let mut day = Day::new(NaiveDate::from_ymd(2024, 1, 8));
+    /// day.add_block(Block::new(
+    ///     NaiveTime::from_hms(17, 0, 0),
+    ///     NaiveTime::from_hms(18, 0, 0),
+    ///     Some(Food::new_from_recipe(lasagne)),
+    /// ));
+    ///
+    /// let weekdays = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+    /// let cap = Constraint::max_active_minutes_on(40, &weekdays, Strength::Soft { weight: 2 });
+    /// let violation = cap.check_day(&day).unwrap();
+    /// assert_eq!(violation.get_excess(), Rational32::from_integer(10));
+    /// assert_eq!(violation.get_penalty(), Rational32::from_integer(20));
+    /// # }
+    /// ```
+    ///
+    /// Constraints on meals look at each block with food:
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let meal = |name: &str, protein: i32, carbohydrates: i32| {
+    ///     let mut nutrition = Nutrition::new();
+    ///     nutrition
+    ///         .set_protein(Amount::new(Unit::Gram, Rational32::from_integer(protein)))
+    ///         .set_carbohydrates(Amount::new(Unit::Gram, Rational32::from_integer(carbohydrates)));
+    ///     Food::new_from_recipe(
+    ///         RecipeBuilder::new(name)
+    ///             .set_nutrition(nutrition)
+    ///             .build_with_defaults(),
+    ///     )
+    /// };
+    /// let time = |h| NaiveTime::from_hms(h, 0, 0);
+    /// let mut day = Day::new(NaiveDate::from_ymd(2024, 1, 8));
+    /// day.add_block(Block::new(time(8), time(9), Some(meal("toast", 10, 40))));
+    /// day.add_block(Block::new(time(19), time(20), Some(meal("steak", 40, 60))));
+    ///
+    /// // Breakfast is 15 g of protein short
+    /// let protein = Constraint::min_nutrient_per_meal(Nutrient::Protein, 25, Strength::Hard);
+    /// let violation = protein.check_day(&day).unwrap();
+    /// assert_eq!(violation.get_meals(), [time(8)]);
+    /// assert_eq!(violation.get_excess(), Rational32::from_integer(15));
+    ///
+    /// // 60% of the day's carbohydrates come after 18:00
+    /// let late = Constraint::max_share_after(Nutrient::Carbohydrates, time(18), 30, Strength::Hard);
+    /// assert_eq!(late.check_day(&day).unwrap().get_excess(), Rational32::from_integer(30));
+    /// # }
+    /// ```
+    pub fn check_day(&self, day: &Day) -> Option<Violation> {
+        self.check(day, &[])
+    }
+
+    /// Checks a single day of a plan against the constraint, looking back at the days before
+    /// it in the plan
+    pub fn check_day_in(&self, plan: &MealPlan, day: &Day) -> Option<Violation> {
+        let first = plan
+            .get_days()
+            .first()
+            .map_or(day.get_date(), |x| x.get_date());
+        self.check(day, plan.days_between(first, day.get_date()))
+    }
+
+    /// Checks a single day against the constraint, given the days before it, in date order
+    fn check(&self, day: &Day, earlier: &[Day]) -> Option<Violation> {
+        if !self.applies_to(day.get_date()) {
+            return None;
+        }
+        let zero = Rational32::from_integer(0);
+        // A single serving of each meal's nutrient, alongside when the meal starts
+        let meals = |nutrient: Nutrient| -> Vec<(NaiveTime, Rational32)> {
+            day.get_blocks()
+                .iter()
+                .filter_map(|x| x.get_food().map(|food| (*x.get_start(), food)))
+                .map(|(start, food)| {
+                    let amount = food.get_nutrition().get_nutrient(nutrient);
+                    (start, amount.unwrap_or(zero))
+                })
+                .collect()
+        };
+        let mut offending = Vec::new();
+        let excess = match *self {
+            Constraint::MaxActiveMinutes { minutes, .. } => {
+                let active = day
+                    .get_blocks()
+                    .iter()
+                    .filter_map(|x| x.get_food())
+                    .fold(Rational32::from_integer(0), |total, x| {
+                        total + x.get_active_time()
+                    });
+                active - Rational32::from_integer(minutes as i32)
+            }
+            Constraint::MinNutrientPerMeal {
+                nutrient, amount, ..
+            } => {
+                let amount = Rational32::from_integer(amount as i32);
+                let mut shortfall = zero;
+                for (start, x) in meals(nutrient) {
+                    if x < amount {
+                        offending.push(start);
+                        shortfall += amount - x;
+                    }
+                }
+                shortfall
+            }
+            Constraint::MaxShareAfter {
+                nutrient,
+                after,
+                percent,
+                ..
+            } => {
+                let meals = meals(nutrient);
+                let total = meals.iter().fold(zero, |total, x| total + x.1);
+                let late: Vec<&(NaiveTime, Rational32)> =
+                    meals.iter().filter(|x| x.0 >= after).collect();
+                let late_total = late.iter().fold(zero, |total, x| total + x.1);
+                if total > zero {
+                    let share = late_total / total * Rational32::from_integer(100);
+                    let excess = share - Rational32::from_integer(percent as i32);
+                    if excess > zero {
+                        offending.extend(late.iter().filter(|x| x.1 > zero).map(|x| x.0));
+                    }
+                    excess
+                } else {
+                    zero
+                }
+            }
+            Constraint::MaxNutrientPerDay {
+                nutrient, amount, ..
+            } => {
+                let total = meals(nutrient).iter().fold(zero, |total, x| total + x.1);
+                total - Rational32::from_integer(amount as i32)
+            }
+            Constraint::MinNutrientPerDay {
+                nutrient, amount, ..
+            } => {
+                let total = meals(nutrient).iter().fold(zero, |total, x| total + x.1);
+                Rational32::from_integer(amount as i32) - total
+            }
+            Constraint::MaxScorePerDay {
+                ref formula,
+                amount,
+                ..
+            } => {
+                let total = day
+                    .get_blocks()
+                    .iter()
+                    .filter_map(|x| x.get_food())
+                    .fold(zero, |total, x| total + formula.score_food(x));
+                total - Rational32::from_integer(amount as i32)
+            }
+            Constraint::MaxScorePerMeal {
+                ref formula,
+                amount,
+                ..
+            } => {
+                let amount = Rational32::from_integer(amount as i32);
+                let mut excess = zero;
+                for block in day.get_blocks() {
+                    if let Some(food) = block.get_food() {
+                        let score = formula.score_food(food);
+                        if score > amount {
+                            offending.push(*block.get_start());
+                            excess += score - amount;
+                        }
+                    }
+                }
+                excess
+            }
+            Constraint::RequireTag { ref tag, .. } => {
+                for block in day.get_blocks() {
+                    if block.get_food().is_some_and(|x| !x.has_tag(tag)) {
+                        offending.push(*block.get_start());
+                    }
+                }
+                Rational32::from_integer(offending.len() as i32)
+            }
+            Constraint::ExcludeIngredient { ref ingredient, .. } => {
+                for block in day.get_blocks() {
+                    if block
+                        .get_food()
+                        .is_some_and(|x| x.contains_ingredient(ingredient))
+                    {
+                        offending.push(*block.get_start());
+                    }
+                }
+                Rational32::from_integer(offending.len() as i32)
+            }
+            Constraint::MinFluidPerDay { milliliters, .. } => {
+                let drunk = day
+                    .get_blocks()
+                    .iter()
+                    .flat_map(|x| x.get_beverages())
+                    .filter_map(|x| x.get_volume().convert_to(Unit::Milliliter).ok())
+                    .fold(zero, |total, x| total + x.get_amount());
+                Rational32::from_integer(milliliters as i32) - drunk
+            }
+            Constraint::MinDaysBetweenRepeats { ref food, days, .. } => {
+                let counts = |x: &Food| match *food {
+                    Some(ref code) => x.get_name().get_short_code() == code,
+                    None => true,
+                };
+                // Only the days within the window count, any before it are far enough apart
+                let after = day.get_date() - Duration::days(i64::from(days));
+                let mut seen: Vec<&str> = earlier
+                    .iter()
+                    .filter(|x| x.get_date() > after)
+                    .flat_map(|x| x.get_blocks())
+                    .filter_map(|x| x.get_food())
+                    .filter(|x| counts(x))
+                    .map(|x| x.get_name().get_short_code())
+                    .collect();
+                for block in day.get_blocks() {
+                    let code = match block.get_food() {
+                        Some(x) if days > 0 && counts(x) => x.get_name().get_short_code(),
+                        _ => continue,
+                    };
+                    if seen.contains(&code) {
+                        offending.push(*block.get_start());
+                    } else {
+                        seen.push(code);
+                    }
+                }
+                Rational32::from_integer(offending.len() as i32)
+            }
+            Constraint::EatingWindow { ref window, .. } => {
+                for block in day.get_blocks() {
+                    if !window.permits(block) {
+                        offending.push(*block.get_start());
+                    }
+                }
+                Rational32::from_integer(offending.len() as i32)
+            }
+            Constraint::Between { ref constraint, .. } => {
+                // Report the violation against this constraint, rather than the inner one
+                return constraint.check(day, earlier).map(|x| Violation {
+                    constraint: self.clone(),
+                    ..x
+                });
+            }
+        };
+        if excess > Rational32::from_integer(0) {
+            Some(Violation {
+                date: day.get_date(),
+                constraint: self.clone(),
+                excess,
+                meals: offending,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A named set of constraints for a stretch of recovery or illness
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Preset {
+    /// Every meal must be a food tagged "soft"
+    SoftFoods,
+    /// Prefers days with no more than 10 g of fiber
+    LowFiber,
+    /// Prefers days with at least 2.5 L of beverages
+    HighFluid,
+}
+
+impl Preset {
+    /// Returns the constraints making up the preset
+    ///
+    /// Soft foods are a hard constraint, so foods that aren't soft are never chosen. Fiber and
+    /// fluids are soft constraints, so plans keeping to them are only preferred.
+    pub fn constraints(self) -> Vec<Constraint> {
+        match self {
+            Preset::SoftFoods => vec![Constraint::RequireTag {
+                tag: "soft".to_string(),
+                strength: Strength::Hard,
+            }],
+            Preset::LowFiber => vec![Constraint::MaxNutrientPerDay {
+                nutrient: Nutrient::Fiber,
+                amount: 10,
+                strength: Strength::Soft { weight: 10 },
+            }],
+            Preset::HighFluid => vec![Constraint::MinFluidPerDay {
+                milliliters: 2500,
+                strength: Strength::Soft { weight: 1 },
+            }],
+        }
+    }
+
+    /// Returns the constraints making up the preset, only applying between the start and end
+    /// dates, inclusive
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = |name: &str| {
+    ///     RecipeBuilder::new(name)
+    ///         .add_step(Step::new("cook", Rational32::from_integer(20)))
+    ///         .build_with_defaults()
+    /// };
+    /// let mut soup = recipe("soup");
+    /// soup.add_tag("soft");
+    /// let crisps = Food::new_from_recipe(recipe("crisps"));
+    /// let soup = Food::new_from_recipe(soup);
+    ///
+    /// // Recovering from having a tooth out
+    /// let start = NaiveDate::from_ymd(2024, 1, 8);
+    /// let end = NaiveDate::from_ymd(2024, 1, 10);
+    /// let constraints = Preset::SoftFoods.between(start, end);
+    ///
+    /// let during = NaiveDate::from_ymd(2024, 1, 9);
+    /// assert!(constraints.iter().all(|x| x.permits(during, &soup)));
+    /// assert!(!constraints.iter().all(|x| x.permits(during, &crisps)));
+    /// let after = NaiveDate::from_ymd(2024, 1, 11);
+    /// assert!(constraints.iter().all(|x| x.permits(after, &crisps)));
+    /// # }
+    /// ```
+    pub fn between(self, start: NaiveDate, end: NaiveDate) -> Vec<Constraint> {
+        self.constraints()
+            .into_iter()
+            .map(|x| x.between(start, end))
+            .collect()
+    }
+}
+
+/// Checks every day of a plan against every constraint
+///
+/// The violations are in date order, and can be inspected to explain why a plan was rejected.
+pub fn check_plan(plan: &MealPlan, constraints: &[Constraint]) -> Vec<Violation> {
+    let days = plan.get_days();
+    (0..days.len())
+        .flat_map(|i| {
+            constraints
+                .iter()
+                .filter_map(move |x| x.check(&days[i], &days[..i]))
+        })
+        .collect()
+}
+
+/// Returns true if the plan breaks none of the hard constraints
+pub fn is_feasible(plan: &MealPlan, constraints: &[Constraint]) -> bool {
+    !check_plan(plan, constraints).iter().any(|x| x.is_hard())
+}
+
+/// Returns the total penalty of the plan's soft constraint violations
+pub fn penalty(plan: &MealPlan, constraints: &[Constraint]) -> Rational32 {
+    check_plan(plan, constraints)
+        .iter()
+        .fold(Rational32::from_integer(0), |total, x| {
+            total + x.get_penalty()
+        })
+}
+
+/// Something to minimize when choosing between acceptable plans
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Objective {
+    /// Minimize the number of distinct pieces of equipment (pots, pans, bowls) used across
+    /// each day's meals, so there is less washing up. Scores the given weight per piece.
+    MinimizeDishes { weight: u32 },
+    /// Get each day's nutrition as close to the goal period in effect on it as possible, a
+    /// single serving of each meal counting. Scores the given weight times how far the day
+    /// misses its period, see `GoalSchedule::score_day`.
+    MeetNutritionGoals {
+        goals: GoalSchedule,
+        scoring: GoalScoring,
+        weight: u32,
+    },
+}
+
+impl Objective {
+    /// Scores a single day, lower is better
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut boil = Step::new("boil-pasta", Rational32::from_integer(10));
+    /// boil.add_equipment("large-pot");
+    /// let mut sauce = Step::new("make-sauce", Rational32::from_integer(10));
+    /// sauce.add_equipment("frying-pan");
+    /// let pasta = RecipeBuilder::new("pasta").add_step(boil).add_step(sauce).build_with_defaults();
+    ///
+    /// let mut soup = Step::new("simmer-soup", Rational32::from_integer(30));
+    /// soup.add_equipment("large-pot");
+    /// let soup = RecipeBuilder::new("soup").add_step(soup).build_with_defaults();
+    ///
+    /// let mut day = Day::new(NaiveDate::from_ymd(2024, 1, 8));
+    /// day.add_block(Block::new(
+    ///     NaiveTime::from_hms(11, 30, 0),
+    ///     NaiveTime::from_hms(12, 0, 0),
+    ///     Some(Food::new_from_recipe(soup)),
+    /// ));
+    /// day.add_block(Block::new(
+    ///     NaiveTime::from_hms(17, 30, 0),
+    ///     NaiveTime::from_hms(18, 0, 0),
+    ///     Some(Food::new_from_recipe(pasta)),
+    /// ));
+    ///
+    /// // The large pot is shared, so only two distinct dishes are used
+    /// let objective = Objective::MinimizeDishes { weight: 1 };
+    /// assert_eq!(objective.score_day(&day), Rational32::from_integer(2));
+    /// # }
+    /// ```
+    pub fn score_day(&self, day: &Day) -> Rational32 {
+        match *self {
+            Objective::MeetNutritionGoals {
+                ref goals,
+                scoring,
+                weight,
+            } => goals.score_day(day, scoring) * weight as i32,
+            Objective::MinimizeDishes { weight } => {
+                let mut equipment: Vec<&str> = Vec::new();
+                for food in day.get_blocks().iter().filter_map(|x| x.get_food()) {
+                    if let Food::Recipe(ref recipe) = *food {
+                        equipment.extend(recipe.get_equipment());
+                    }
+                }
+                equipment.sort();
+                equipment.dedup();
+                Rational32::from_integer(equipment.len() as i32 * weight as i32)
+            }
+        }
+    }
+}
+
+/// Returns the total score of a plan against every objective, lower is better
+pub fn score(plan: &MealPlan, objectives: &[Objective]) -> Rational32 {
+    plan.get_days()
+        .iter()
+        .flat_map(|day| objectives.iter().map(move |x| x.score_day(day)))
+        .fold(Rational32::from_integer(0), |total, x| total + x)
+}
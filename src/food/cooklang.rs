@@ -0,0 +1,475 @@
+//! This module contains the Cooklang reader and writer
+//!
+//! Cooklang is a plain text recipe markup, where ingredients, cookware, and timers are marked
+//! up right in the text of the steps:
+//!
+//! ```text
+//! >> servings: 2
+//!
+//! Whisk the @eggs{3} and @milk{50%ml} in a #bowl.
+//!
+//! Cook in a hot #frying pan{} for ~{3%minutes}.
+//! ```
+//!
+//! Each paragraph is a step, taking as long as the timers in it. Ingredients become the
+//! recipe's foods, in the order they appear, as placeholder raw foods with empty nutrition,
+//! and cookware becomes the step's equipment. The markup is dropped from the step text, leaving
+//! the names and the timers' durations behind. Amounts in units the crate doesn't measure,
+//! such as cloves, are kept as a count.
+//!
+//! Recipes don't tie their foods to their steps, so when writing Cooklang each food is marked
+//! up at its first mention in the steps after the food before it, and foods that are never
+//! mentioned are listed at the end of the last step. Cooklang has no notion of language, so
+//! text is read and written in English, falling back to the default language when writing.
+use export::localized;
+use food::*;
+use num_rational::*;
+use parse::duration::{format_iso_duration, unit_minutes};
+use parse::ingredient::normalize_unit;
+use parse::{checked_add, checked_mul, parse_duration, parse_number, to_short_code, ParseError};
+
+/// The language Cooklang text is read and written in
+const LANG: &str = "en";
+
+/// The text of a step with its markup removed, and what the markup held
+struct MarkedStep {
+    text: String,
+    minutes: Rational32,
+    ingredients: Vec<(String, Amount)>,
+    cookware: Vec<String>,
+}
+
+/// Parses a quantity, such as "1 1/2" or "200", adding up mixed numbers
+fn parse_quantity(text: &str) -> Result<Rational32, ParseError> {
+    let mut total = Rational32::from_integer(0);
+    let mut tokens = text.split_whitespace().peekable();
+    if tokens.peek().is_none() {
+        return Err(ParseError::Empty);
+    }
+    for token in tokens {
+        total = checked_add(total, parse_number(token)?)
+            .ok_or_else(|| ParseError::InvalidNumber(text.to_string()))?;
+    }
+    Ok(total)
+}
+
+/// Reads an amount written as "200%g", or "200 g" when `%` isn't used
+///
+/// Empty and unreadable amounts, such as "some", are a count of one.
+fn parse_amount(text: &str) -> Amount {
+    let text = text.trim();
+    let (quantity, unit) = match text.find('%') {
+        Some(i) => (&text[..i], text[i + 1..].trim()),
+        None => match text.rfind(|c: char| c.is_whitespace()) {
+            Some(i) if !text[i + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                (&text[..i], text[i + 1..].trim())
+            }
+            _ => (text, ""),
+        },
+    };
+    let unit = normalize_unit(unit)
+        .and_then(Unit::from_symbol)
+        .or_else(|| Unit::from_symbol(unit))
+        .unwrap_or(Unit::Count);
+    let quantity = parse_quantity(quantity).unwrap_or_else(|_| Rational32::from_integer(1));
+    Amount::new(unit, quantity)
+}
+
+/// Returns true for the characters making up a single word name
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Splits a component, the text after its `@`, `#`, or `~`, into its name, the text in its
+/// braces if it has any, and the length of the component
+///
+/// A name runs to the braces if they come before the next component, otherwise it is a single
+/// word.
+fn split_component(rest: &str) -> Result<(&str, Option<&str>, usize), ParseError> {
+    let next = rest.find(['@', '#', '~']);
+    match rest.find('{') {
+        Some(open) if next.is_none_or(|x| open < x) => {
+            let close = match rest[open..].find('}') {
+                Some(x) => open + x,
+                None => return Err(ParseError::Unrecognized(rest.to_string())),
+            };
+            Ok((rest[..open].trim(), Some(&rest[open + 1..close]), close + 1))
+        }
+        _ => {
+            let end = rest.find(|c| !is_word(c)).unwrap_or(rest.len());
+            Ok((&rest[..end], None, end))
+        }
+    }
+}
+
+/// Removes the markup from a paragraph, collecting what it held
+fn parse_step(paragraph: &str) -> Result<MarkedStep, ParseError> {
+    let mut step = MarkedStep {
+        text: String::new(),
+        minutes: Rational32::from_integer(0),
+        ingredients: Vec::new(),
+        cookware: Vec::new(),
+    };
+    let mut rest = paragraph;
+    while let Some(start) = rest.find(['@', '#', '~']) {
+        step.text.push_str(&rest[..start]);
+        let marker = &rest[start..start + 1];
+        let (name, braces, length) = split_component(&rest[start + 1..])?;
+        if name.is_empty() && (braces.is_none() || marker != "~") {
+            // Not a component after all, just the character
+            step.text.push_str(marker);
+            rest = &rest[start + 1..];
+            continue;
+        }
+        match marker {
+            "@" => {
+                step.text.push_str(name);
+                step.ingredients
+                    .push((name.to_string(), parse_amount(braces.unwrap_or(""))));
+            }
+            "#" => {
+                step.text.push_str(name);
+                step.cookware.push(name.to_string());
+            }
+            _ => {
+                let braces = braces.unwrap_or("");
+                let (quantity, unit) = match braces.find('%') {
+                    Some(i) => (braces[..i].trim(), braces[i + 1..].trim()),
+                    None => return Err(ParseError::Unrecognized(braces.to_string())),
+                };
+                let per_unit = unit_minutes(&unit.to_lowercase())
+                    .ok_or_else(|| ParseError::Unrecognized(unit.to_string()))?;
+                let invalid = || ParseError::InvalidNumber(quantity.to_string());
+                let minutes =
+                    checked_mul(parse_quantity(quantity)?, per_unit).ok_or_else(invalid)?;
+                step.minutes = checked_add(step.minutes, minutes).ok_or_else(invalid)?;
+                step.text.push_str(&format!("{} {}", quantity, unit));
+            }
+        }
+        rest = &rest[start + 1 + length..];
+    }
+    step.text.push_str(rest);
+    Ok(step)
+}
+
+/// Removes `[- block comments -]` from the text
+fn strip_block_comments(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = text;
+    while let Some(open) = rest.find("[-") {
+        stripped.push_str(&rest[..open]);
+        rest = match rest[open..].find("-]") {
+            Some(close) => &rest[open + close + 2..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// Applies a single metadata entry to the builder
+fn apply_metadata(builder: &mut RecipeBuilder, key: &str, value: &str) -> Result<(), ParseError> {
+    let key = key.trim().to_lowercase().replace(['_', '-'], " ");
+    let value = value.trim();
+    match key.as_str() {
+        "servings" | "serves" | "yield" => {
+            // Scalable recipes list several servings, "2|4|8", the first is the base
+            let first = value.split('|').next().unwrap_or("");
+            let number = first.split_whitespace().next().unwrap_or("");
+            builder.set_servings(parse_number(number)?);
+        }
+        "serving size" => {
+            let amount = parse_amount(value);
+            builder.set_serving_size(amount.get_unit(), amount.get_amount());
+        }
+        "time" | "total time" | "duration" => {
+            builder.set_time(parse_duration(value)?);
+        }
+        "prep time" => {
+            builder.set_prep_time(parse_duration(value)?);
+        }
+        "cook time" => {
+            builder.set_cook_time(parse_duration(value)?);
+        }
+        "tags" => {
+            for tag in value
+                .trim_matches(|c| c == '[' || c == ']')
+                .split(',')
+                .map(to_short_code)
+                .filter(|x| !x.is_empty())
+            {
+                builder.add_tag(&tag);
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Finds the first whole word match of the needle in the text at or after `from`, skipping
+/// any ranges already taken
+fn find_word(
+    text: &str,
+    needle: &str,
+    from: usize,
+    taken: &[(usize, usize, String)],
+) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    let mut start = from;
+    while let Some(found) = text[start..].find(needle).map(|x| x + start) {
+        let end = found + needle.len();
+        let before = text[..found]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_word(c));
+        let after = text[end..].chars().next().is_none_or(|c| !is_word(c));
+        let free = taken.iter().all(|&(x, y, _)| end <= x || y <= found);
+        if before && after && free {
+            return Some(found);
+        }
+        start = found + text[found..].chars().next().map_or(1, |c| c.len_utf8());
+    }
+    None
+}
+
+/// Writes an amount as the inside of an ingredient's braces
+fn format_amount(amount: &Amount) -> String {
+    match amount.get_unit() {
+        Unit::Count => amount.get_amount().to_string(),
+        unit => format!("{}%{}", amount.get_amount(), unit.get_symbol()),
+    }
+}
+
+impl Recipe {
+    /// Reads a recipe written in Cooklang
+    ///
+    /// The recipe is named by its `title` metadata, or "untitled" without one, as Cooklang
+    /// files are usually named after their recipe instead. The `servings`, `serving size`,
+    /// `time`, `prep time`, `cook time`, and `tags` metadata are read, either as `>> key:
+    /// value` lines or as front matter, and anything else is ignored. Without a time, the
+    /// recipe takes as long as its steps.
+    ///
+    /// Fails on unclosed braces, and on timers or metadata that can't be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let text = "\
+    /// >> title: Scrambled Eggs
+    /// >> servings: 2
+    ///
+    /// Whisk the @eggs{3} and @milk{50%ml} in a #bowl.
+    ///
+    /// Cook in a hot #frying pan{} for ~{3%minutes}, stirring. -- not too long!
+    /// ";
+    /// let recipe = Recipe::from_cooklang(text).unwrap();
+    /// assert_eq!(recipe.get_name().get_short_code(), "scrambled-eggs");
+    /// assert_eq!(recipe.get_servings(), Rational32::from_integer(2));
+    /// assert_eq!(recipe.get_time(), Rational32::from_integer(3));
+    ///
+    /// let (milk, amount) = &recipe.get_foods()[1];
+    /// assert_eq!(milk.get_name().get_short_code(), "milk");
+    /// assert_eq!(amount.get_unit(), Unit::Milliliter);
+    /// assert_eq!(amount.get_amount(), Rational32::from_integer(50));
+    ///
+    /// let cook = &recipe.get_steps()[1];
+    /// assert_eq!(
+    ///     cook.get_text().get_value("en"),
+    ///     Some("Cook in a hot frying pan for 3 minutes, stirring.")
+    /// );
+    /// assert_eq!(cook.get_equipment(), ["frying-pan"]);
+    ///
+    /// // Writing the recipe back out keeps its ingredients
+    /// let again = Recipe::from_cooklang(&recipe.to_cooklang()).unwrap();
+    /// assert_eq!(again.get_foods().len(), 2);
+    /// assert!(again.get_foods()[1].1 == *amount);
+    /// assert_eq!(again.get_time(), recipe.get_time());
+    /// # }
+    /// ```
+    pub fn from_cooklang(text: &str) -> Result<Recipe, ParseError> {
+        let text = strip_block_comments(text);
+        let mut metadata: Vec<(String, String)> = Vec::new();
+        let mut paragraphs: Vec<Vec<&str>> = Vec::new();
+        let mut current = Vec::new();
+        let mut lines = text.lines().peekable();
+
+        // Front matter, between a pair of "---" lines at the very start
+        if lines.peek().map(|x| x.trim()) == Some("---") {
+            lines.next();
+            for line in lines.by_ref() {
+                if line.trim() == "---" {
+                    break;
+                }
+                if let Some(colon) = line.find(':') {
+                    metadata.push((line[..colon].to_string(), line[colon + 1..].to_string()));
+                }
+            }
+        }
+
+        for line in lines {
+            let line = match line.find("--") {
+                Some(i) => &line[..i],
+                None => line,
+            }
+            .trim();
+            if let Some(entry) = line.strip_prefix(">>") {
+                if let Some(colon) = entry.find(':') {
+                    metadata.push((entry[..colon].to_string(), entry[colon + 1..].to_string()));
+                }
+            } else if line.is_empty() || line.starts_with('=') {
+                // A blank line or a section heading ends the step
+                if !current.is_empty() {
+                    paragraphs.push(current);
+                    current = Vec::new();
+                }
+            } else if !line.starts_with('>') {
+                current.push(line);
+            }
+        }
+        if !current.is_empty() {
+            paragraphs.push(current);
+        }
+
+        let title = metadata
+            .iter()
+            .find(|x| x.0.trim().eq_ignore_ascii_case("title"))
+            .map(|x| x.1.trim().to_string());
+        let mut builder = match title {
+            Some(ref title) if !title.is_empty() => {
+                let mut builder = RecipeBuilder::new(&to_short_code(title));
+                builder.add_name(LANG, title);
+                builder
+            }
+            _ => RecipeBuilder::new("untitled"),
+        };
+        for (key, value) in &metadata {
+            apply_metadata(&mut builder, key, value)?;
+        }
+
+        for (index, paragraph) in paragraphs.iter().enumerate() {
+            let marked = parse_step(&paragraph.join(" "))?;
+            for (name, amount) in marked.ingredients {
+                let mut food_name = IString::new(&to_short_code(&name));
+                food_name.set_value_for(LANG, &name);
+                let serving = Amount::new(amount.get_unit(), Rational32::from_integer(1));
+                let food =
+                    Food::new_from_raw_food(RawFood::new(food_name, serving, Nutrition::new()));
+                builder.add_food(food, amount.get_unit(), amount.get_amount());
+            }
+            let mut step = Step::new(&format!("step-{}", index + 1), marked.minutes);
+            step.get_mut_text().set_value_for(LANG, marked.text.trim());
+            for cookware in &marked.cookware {
+                step.add_equipment(&to_short_code(cookware));
+            }
+            builder.add_step(step);
+        }
+        Ok(builder.build_with_defaults())
+    }
+
+    /// Writes the recipe as Cooklang
+    ///
+    /// The name, servings, serving size, and times are written as metadata, along with the
+    /// tags, and each step is a paragraph. Times are written as ISO 8601 durations, so they
+    /// survive exactly. See the module documentation for how foods are placed in the steps.
+    pub fn to_cooklang(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(">> title: {}\n", localized(self.get_name(), LANG)));
+        out.push_str(&format!(">> servings: {}\n", self.get_servings()));
+        out.push_str(&format!(
+            ">> serving size: {}\n",
+            format_amount(&self.get_serving_size())
+        ));
+        out.push_str(&format!(
+            ">> time: {}\n",
+            format_iso_duration(self.get_time())
+        ));
+        if let Some(x) = self.get_prep_time() {
+            out.push_str(&format!(">> prep time: {}\n", format_iso_duration(x)));
+        }
+        if let Some(x) = self.get_cook_time() {
+            out.push_str(&format!(">> cook time: {}\n", format_iso_duration(x)));
+        }
+        if !self.get_tags().is_empty() {
+            out.push_str(&format!(">> tags: {}\n", self.get_tags().join(", ")));
+        }
+
+        let mut texts: Vec<String> = self
+            .get_steps()
+            .iter()
+            .map(|x| localized(x.get_text(), LANG).replace('\n', " "))
+            .collect();
+        if texts.is_empty() {
+            texts.push(String::new());
+        }
+        // The markup to insert into each step, as the byte range it replaces and the markup
+        let mut marks: Vec<Vec<(usize, usize, String)>> = vec![Vec::new(); texts.len()];
+        let mut unplaced = Vec::new();
+        let mut cursor = (0, 0);
+        for (food, amount) in self.get_foods() {
+            let name = localized(food.get_name(), LANG);
+            let markup = format!("@{}{{{}}}", name, format_amount(amount));
+            let found = (cursor.0..texts.len()).find_map(|i| {
+                let from = if i == cursor.0 { cursor.1 } else { 0 };
+                find_word(&texts[i], &name, from, &marks[i]).map(|x| (i, x))
+            });
+            match found {
+                Some((i, start)) => {
+                    let end = start + name.len();
+                    marks[i].push((start, end, markup));
+                    cursor = (i, end);
+                }
+                None => unplaced.push(markup),
+            }
+        }
+        for (i, step) in self.get_steps().iter().enumerate() {
+            for equipment in step.get_equipment() {
+                let name = equipment.replace('-', " ");
+                if let Some(start) = find_word(&texts[i], &name, 0, &marks[i]) {
+                    marks[i].push((start, start + name.len(), format!("#{}{{}}", name)));
+                }
+            }
+            let time = step.get_time();
+            if time > Rational32::from_integer(0) {
+                let timer = format!("~{{{}%minutes}}", time);
+                match find_word(&texts[i], &format!("{} minutes", time), 0, &marks[i]) {
+                    Some(start) => {
+                        let end = start + format!("{} minutes", time).len();
+                        marks[i].push((start, end, timer));
+                    }
+                    None => marks[i].push((texts[i].len(), texts[i].len(), format!(" {}", timer))),
+                }
+            }
+        }
+        if let Some(last) = marks.last_mut() {
+            let end = texts[texts.len() - 1].len();
+            for markup in unplaced {
+                last.push((end, end, format!(" {}", markup)));
+            }
+        }
+
+        for (text, mut marks) in texts.into_iter().zip(marks) {
+            // Stable, so markup appended at the same place keeps its order
+            marks.sort_by_key(|x| x.0);
+            let mut paragraph = String::new();
+            let mut at = 0;
+            for (start, end, markup) in marks {
+                paragraph.push_str(&text[at..start]);
+                paragraph.push_str(&markup);
+                at = end;
+            }
+            paragraph.push_str(&text[at..]);
+            out.push('\n');
+            out.push_str(paragraph.trim());
+            out.push('\n');
+        }
+        out
+    }
+}
@@ -0,0 +1,163 @@
+//! This module contains dietary restrictions and allergens
+//!
+//! Raw foods are labelled with the diets they suit (`DietTag`) and the allergens they contain
+//! (`Allergen`). Recipes aren't labelled by hand, instead they suit a diet only if every one of
+//! their ingredients does, and contain every allergen any of their ingredients do, so a recipe
+//! is never labelled vegan because someone forgot to untick it after adding butter.
+//!
+//! A `DietProfile` is what one person can eat, the diets they keep and the allergens they
+//! avoid, and `Food::satisfies` checks a food against it.
+use std::collections::BTreeSet;
+
+/// A diet a food is suitable for
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DietTag {
+    Vegan,
+    Vegetarian,
+    Pescatarian,
+    GlutenFree,
+    DairyFree,
+    Halal,
+    Kosher,
+}
+
+/// An allergen a food may contain, the fourteen major allergens labelled in the EU and UK
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Allergen {
+    /// Cereals containing gluten, such as wheat, rye, and barley
+    Gluten,
+    Crustaceans,
+    Eggs,
+    Fish,
+    Peanuts,
+    Soybeans,
+    Milk,
+    /// Tree nuts, such as almonds, hazelnuts, and walnuts
+    TreeNuts,
+    Celery,
+    Mustard,
+    Sesame,
+    /// Sulphur dioxide and sulphites
+    Sulphites,
+    Lupin,
+    Molluscs,
+}
+
+/// What one person can eat, the diets they keep and the allergens they avoid
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::diet::*;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let raw = |name: &str, tags: &[DietTag], allergens: &[Allergen]| {
+///     let mut builder = RawFoodBuilder::new(name);
+///     builder
+///         .set_serving_size(Unit::Gram, Rational32::from_integer(100))
+///         .set_nutrition(Nutrition::new());
+///     for &tag in tags {
+///         builder.add_diet_tag(tag);
+///     }
+///     for &allergen in allergens {
+///         builder.add_allergen(allergen);
+///     }
+///     Food::new_from_raw_food(builder.build_raw_food().unwrap())
+/// };
+/// use DietTag::*;
+/// let pasta = raw("pasta", &[Vegan, Vegetarian], &[Allergen::Gluten]);
+/// let tomatoes = raw("tomatoes", &[Vegan, Vegetarian, GlutenFree], &[]);
+/// let parmesan = raw("parmesan", &[Vegetarian, GlutenFree], &[Allergen::Milk]);
+///
+/// let grams = |n| Rational32::from_integer(n);
+/// let dinner = RecipeBuilder::new("pasta-al-pomodoro")
+///     .add_food(pasta, Unit::Gram, grams(200))
+///     .add_food(tomatoes, Unit::Gram, grams(400))
+///     .add_food(parmesan, Unit::Gram, grams(30))
+///     .build_with_defaults();
+/// let dinner = Food::new_from_recipe(dinner);
+///
+/// // Only the diets every ingredient suits, and every allergen any ingredient contains
+/// assert_eq!(dinner.get_diet_tags().into_iter().collect::<Vec<_>>(), [DietTag::Vegetarian]);
+/// assert_eq!(dinner.get_allergens().map(|x| x.len()), Some(2));
+///
+/// let mut vegetarian = DietProfile::new();
+/// vegetarian.add_diet(DietTag::Vegetarian);
+/// assert!(dinner.satisfies(&vegetarian));
+/// let mut coeliac = DietProfile::new();
+/// coeliac.avoid_allergen(Allergen::Gluten);
+/// assert!(!dinner.satisfies(&coeliac));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DietProfile {
+    #[serde(default)]
+    diets: BTreeSet<DietTag>,
+    #[serde(default)]
+    allergens: BTreeSet<Allergen>,
+}
+
+impl DietProfile {
+    /// Creates a new profile, able to eat anything
+    pub fn new() -> DietProfile {
+        DietProfile {
+            diets: BTreeSet::new(),
+            allergens: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the diets kept, foods must suit every one of them
+    pub fn get_diets(&self) -> &BTreeSet<DietTag> {
+        &self.diets
+    }
+
+    /// Adds a diet to keep
+    pub fn add_diet(&mut self, diet: DietTag) -> &mut Self {
+        self.diets.insert(diet);
+        self
+    }
+
+    /// Stops keeping a diet, returning true if it was kept
+    pub fn remove_diet(&mut self, diet: DietTag) -> bool {
+        self.diets.remove(&diet)
+    }
+
+    /// Returns the allergens avoided, foods must contain none of them
+    pub fn get_allergens(&self) -> &BTreeSet<Allergen> {
+        &self.allergens
+    }
+
+    /// Adds an allergen to avoid
+    pub fn avoid_allergen(&mut self, allergen: Allergen) -> &mut Self {
+        self.allergens.insert(allergen);
+        self
+    }
+
+    /// Stops avoiding an allergen, returning true if it was avoided
+    pub fn remove_allergen(&mut self, allergen: Allergen) -> bool {
+        self.allergens.remove(&allergen)
+    }
+
+    /// Returns true if the profile keeps no diets and avoids no allergens
+    pub fn is_unrestricted(&self) -> bool {
+        self.diets.is_empty() && self.allergens.is_empty()
+    }
+
+    /// Returns true if a food suiting the given diets and containing the given allergens can
+    /// be eaten
+    ///
+    /// Allergens given as None aren't known, so the food is only permitted if no allergens are
+    /// avoided.
+    pub fn permits(
+        &self,
+        diets: &BTreeSet<DietTag>,
+        allergens: Option<&BTreeSet<Allergen>>,
+    ) -> bool {
+        self.diets.is_subset(diets)
+            && allergens.map_or(self.allergens.is_empty(), |x| self.allergens.is_disjoint(x))
+    }
+}
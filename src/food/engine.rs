@@ -1,22 +1,50 @@
 //! This module contains the code that implements meal planning
 use chrono::prelude::*;
+use food::optimizer::Optimizer;
 use food::*;
+use household::Household;
+use settings::Settings;
+use shopping::{ShoppingItem, ShoppingList};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use store::FoodId;
 
 /// Describes a block of time
 ///
 /// Blocks consist of a start time, an end time, and an optional food occupying the block.
 ///
 /// NaiveTime is used as our goal is to produce a timezone agnostic schedule.
+///
+/// Blocks can also carry free-text notes ("eat before soccer practice"), for context that
+/// isn't captured by the food itself, and tags ("breakfast", "prep") that exporters use to
+/// style the block.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Block {
     start: NaiveTime,
     end: NaiveTime,
-    food: Option<Food>,
+    /// Boxed, so splitting and moving blocks around doesn't copy whole recipes
+    food: Option<Box<Food>>,
+    #[serde(default)]
+    notes: Vec<IString>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    beverages: Vec<Beverage>,
 }
 
 impl Block {
     /// Constructs a new block from its raw components
     pub fn new(start: NaiveTime, end: NaiveTime, food: Option<Food>) -> Block {
-        Block { start, end, food }
+        Block {
+            start,
+            end,
+            food: food.map(Box::new),
+            notes: Vec::new(),
+            tags: Vec::new(),
+            beverages: Vec::new(),
+        }
     }
 
     pub fn get_start(&self) -> &NaiveTime {
@@ -27,6 +55,18 @@ impl Block {
         &self.end
     }
 
+    /// Returns true if the two blocks share any time
+    ///
+    /// Blocks that merely touch, one ending as the other starts, don't overlap.
+    pub fn overlaps(&self, other: &Block) -> bool {
+        self.overlaps_range(other.start, other.end)
+    }
+
+    /// Returns true if the block shares any time with the range from start to end
+    pub fn overlaps_range(&self, start: NaiveTime, end: NaiveTime) -> bool {
+        self.start < end && start < self.end
+    }
+
     /// Returns true if this Block has a food attached to it, returns false otherwise
     pub fn has_food(&self) -> bool {
         self.food.is_some()
@@ -34,12 +74,124 @@ impl Block {
 
     /// Returns the food attached to this Block, as an optional refrence
     pub fn get_food(&self) -> Option<&Food> {
-        self.food.as_ref()
+        self.food.as_deref()
     }
 
     /// Adds the food to the block, overwriting the current value if it exists
     pub fn add_food(&mut self, food: Food) {
-        self.food = Some(food)
+        self.food = Some(Box::new(food))
+    }
+
+    /// Removes the food from the block, leaving it as free time, and returns it
+    pub fn remove_food(&mut self) -> Option<Food> {
+        self.food.take().map(|x| *x)
+    }
+
+    /// Switches the planned recipe to another method, such as the slow cooker on a busy day
+    ///
+    /// Returns false, changing nothing, if the block has no recipe or the recipe has no such
+    /// method. The block's times are left alone, so check the recipe's new time still fits.
+    pub fn select_method(&mut self, method: &str) -> bool {
+        match self.food.as_deref_mut() {
+            Some(Food::Recipe(recipe)) => recipe.select_method(method),
+            _ => false,
+        }
+    }
+
+    /// Returns the notes attached to this Block
+    pub fn get_notes(&self) -> &[IString] {
+        &self.notes
+    }
+
+    /// Attaches a note to this Block
+    ///
+    /// Notes are kept when the Block is serialized, along with every translation of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate serde_json;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let time = |h| NaiveTime::from_hms(h, 0, 0);
+    /// let mut block = Block::new(time(17), time(18), None);
+    /// let mut note = IString::new("practice");
+    /// note.set_value_for("en_US", "eat before soccer practice");
+    /// note.set_value_for("fr_FR", "manger avant l'entraînement de foot");
+    /// block.add_note(note);
+    /// block.add_note(IString::new("guests: grandparents"));
+    ///
+    /// let json = serde_json::to_string(&block).unwrap();
+    /// let back: Block = serde_json::from_str(&json).unwrap();
+    /// assert!(back == block);
+    /// assert_eq!(back.get_notes().len(), 2);
+    /// assert_eq!(
+    ///     back.get_notes()[0].get_value("fr_FR"),
+    ///     Some("manger avant l'entraînement de foot")
+    /// );
+    /// # }
+    /// ```
+    pub fn add_note(&mut self, note: IString) {
+        self.notes.push(note);
+    }
+
+    /// Removes the note at the given index
+    ///
+    /// # Returns
+    /// The removed note, or None if there was no note at that index
+    pub fn remove_note(&mut self, index: usize) -> Option<IString> {
+        if index < self.notes.len() {
+            Some(self.notes.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the beverages planned to be drunk during this Block
+    pub fn get_beverages(&self) -> &[Beverage] {
+        &self.beverages
+    }
+
+    /// Attaches a beverage to this Block
+    pub fn add_beverage(&mut self, beverage: Beverage) {
+        self.beverages.push(beverage);
+    }
+
+    /// Returns the tags attached to this Block
+    pub fn get_tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns true if this Block has the given tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|x| x == tag)
+    }
+
+    /// Tags this Block, doing nothing if it already has the tag
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.has_tag(tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    /// Removes a tag from this Block, returning true if the tag was present
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|x| x != tag);
+        before != self.tags.len()
+    }
+
+    /// Returns which meal this Block is, going by the first of its tags that names one
+    pub fn get_meal_kind(&self) -> Option<MealKind> {
+        self.tags
+            .iter()
+            .filter_map(|x| MealKind::from_tag(x))
+            .next()
     }
 
     /// Attempts to split the block into two blocks, placing the food at the start
@@ -53,7 +205,9 @@ impl Block {
     /// The second block is optional, as there will not be a second block if there is no existing
     /// food and the amount of time avaible is exactly consumed by the given food.
     ///
-    /// Will place the existing food in the second block, if it exists.
+    /// Will place the existing food in the second block, if it exists. Notes follow the
+    /// existing food, if there is no existing food they stay with the first block, and beverages
+    /// do the same. Tags are copied to both blocks.
     pub fn split_at_start(&self, food: &Food) -> SplitBlock {
         // First, calcuate the end time that would result from making this food
         let food_end: NaiveTime;
@@ -71,7 +225,7 @@ impl Block {
             SplitBlock::Failure(food_end)
         } else {
             // Capture a copy of the existing food to put into the new second block
-            let existing_food = self.food.clone();
+            let existing_food = self.get_food().cloned();
             // Capture a copy of the new food to put into the new first block
             let new_food = food.clone();
 
@@ -83,14 +237,159 @@ impl Block {
             // is no existing food
             if middle == self.end && existing_food.is_some() {
                 // In this case, we basically just create a copy of the block
-                SplitBlock::Replace(Block::new(self.start, middle, Some(new_food)))
+                let mut block = Block::new(self.start, middle, Some(new_food));
+                block.notes = self.notes.clone();
+                block.tags = self.tags.clone();
+                block.beverages = self.beverages.clone();
+                SplitBlock::Replace(block)
             } else {
-                let first_block = Block::new(self.start, middle, Some(new_food));
-                let second_block = Block::new(middle, self.end, existing_food);
+                let mut first_block = Block::new(self.start, middle, Some(new_food));
+                let mut second_block = Block::new(middle, self.end, existing_food);
+                first_block.tags = self.tags.clone();
+                second_block.tags = self.tags.clone();
+                if second_block.has_food() {
+                    second_block.notes = self.notes.clone();
+                    second_block.beverages = self.beverages.clone();
+                } else {
+                    first_block.notes = self.notes.clone();
+                    first_block.beverages = self.beverages.clone();
+                }
                 SplitBlock::Split(first_block, second_block)
             }
         }
     }
+
+    /// Attempts to split this block so the given food starts at the given time
+    ///
+    /// Any existing food keeps its place at the start of the block, so the new food can't
+    /// start until the existing food is done. The block is cut into up to three pieces: the
+    /// time before the new food, holding the existing food if there is one, the new food, and
+    /// the free time after it. Pieces with no time in them are left out.
+    ///
+    /// If the food doesn't fit, returns a Failure with the time the block would have to reach:
+    /// its end if the food would run past the end of the block, otherwise the start it would
+    /// need to leave room for the food. Notes, beverages, and tags are handed out as in
+    /// `split_at_start`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let stir_fry = RecipeBuilder::new("stir-fry")
+    ///     .add_step(Step::new("fry", Rational32::from_integer(20)))
+    ///     .build_with_defaults();
+    /// let stir_fry = Food::new_from_recipe(stir_fry);
+    /// let evening = Block::new(NaiveTime::from_hms(17, 0, 0), NaiveTime::from_hms(20, 0, 0), None);
+    ///
+    /// match evening.split_at(NaiveTime::from_hms(18, 10, 0), &stir_fry) {
+    ///     SplitBlock::SplitTwice(before, food, after) => {
+    ///         assert_eq!(*before.get_end(), NaiveTime::from_hms(18, 10, 0));
+    ///         assert_eq!(*food.get_end(), NaiveTime::from_hms(18, 30, 0));
+    ///         assert!(!after.has_food());
+    ///     }
+    ///     _ => panic!("the stir fry should land in the middle of the evening"),
+    /// }
+    ///
+    /// // Starting at 19:50 would run 10 minutes past the end of the block
+    /// match evening.split_at(NaiveTime::from_hms(19, 50, 0), &stir_fry) {
+    ///     SplitBlock::Failure(end) => assert_eq!(end, NaiveTime::from_hms(20, 10, 0)),
+    ///     _ => panic!("the stir fry shouldn't fit"),
+    /// }
+    /// # }
+    /// ```
+    pub fn split_at(&self, time: NaiveTime, food: &Food) -> SplitBlock {
+        let existing = self
+            .food
+            .as_ref()
+            .map(|x| x.get_duration())
+            .unwrap_or_else(Duration::zero);
+        let duration = food.get_duration();
+        // Work in durations from the start of the block, so nothing wraps around midnight
+        let offset = time - self.start;
+        let length = self.end - self.start;
+        if offset < existing {
+            return SplitBlock::Failure(time - existing);
+        }
+        if offset + duration > length {
+            return SplitBlock::Failure(self.end + (offset + duration - length));
+        }
+        let food_end = time + duration;
+
+        let mut before = Block::new(self.start, time, self.get_food().cloned());
+        let mut middle = Block::new(time, food_end, Some(food.clone()));
+        let mut after = Block::new(food_end, self.end, None);
+        before.tags = self.tags.clone();
+        middle.tags = self.tags.clone();
+        after.tags = self.tags.clone();
+        if before.has_food() {
+            before.notes = self.notes.clone();
+            before.beverages = self.beverages.clone();
+        } else {
+            middle.notes = self.notes.clone();
+            middle.beverages = self.beverages.clone();
+        }
+
+        let mut pieces: Vec<Block> = vec![before, middle, after]
+            .into_iter()
+            .filter(|x| x.start < x.end)
+            .collect();
+        match pieces.len() {
+            1 => SplitBlock::Replace(pieces.remove(0)),
+            2 => {
+                let second = pieces.remove(1);
+                SplitBlock::Split(pieces.remove(0), second)
+            }
+            _ => {
+                let third = pieces.remove(2);
+                let second = pieces.remove(1);
+                SplitBlock::SplitTwice(pieces.remove(0), second, third)
+            }
+        }
+    }
+
+    /// Attempts to split this block so the given food finishes as the block ends
+    ///
+    /// Useful for having a meal ready at a set time, rather than as early as possible. Works
+    /// like `split_at`, with the food starting as late as it can.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let roast = RecipeBuilder::new("roast")
+    ///     .add_step(Step::new("roast", Rational32::from_integer(90)))
+    ///     .build_with_defaults();
+    /// // Dinner is at 18:30
+    /// let afternoon = Block::new(NaiveTime::from_hms(14, 0, 0), NaiveTime::from_hms(18, 30, 0), None);
+    ///
+    /// match afternoon.split_at_end(&Food::new_from_recipe(roast)) {
+    ///     SplitBlock::Split(free, roast) => {
+    ///         assert!(!free.has_food());
+    ///         assert_eq!(*roast.get_start(), NaiveTime::from_hms(17, 0, 0));
+    ///     }
+    ///     _ => panic!("the roast should fit"),
+    /// }
+    /// # }
+    /// ```
+    pub fn split_at_end(&self, food: &Food) -> SplitBlock {
+        self.split_at(self.end - food.get_duration(), food)
+    }
 }
 
 /// Intermediate data structure used when splitting blocks
@@ -99,7 +398,1289 @@ pub enum SplitBlock {
     Replace(Block),
     /// Indicates that the block was split into the two provided blocks
     Split(Block, Block),
+    /// Indicates that the food landed in the middle of the block, which was split into the time
+    /// before it, the food, and the free time after it
+    SplitTwice(Block, Block, Block),
     /// Block was unable to be split, Includes the end time the block would have to have in order
-    /// to be split
+    /// to be split. For `split_at` and `split_at_end`, this is the start time instead when the
+    /// food would start too early.
     Failure(NaiveTime),
 }
+
+/// A pet's meal, planned at a time of day
+///
+/// Feedings are kept apart from a Day's blocks, so they can share times with the household's
+/// own meals, and aren't counted towards the household's nutrition.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Feeding {
+    pet: String,
+    time: NaiveTime,
+    food: Food,
+    /// How many of the food's servings the pet is given
+    servings: Fraction,
+}
+
+impl Feeding {
+    /// Constructs a feeding of the given number of servings of a food
+    pub fn new(pet: &str, time: NaiveTime, food: Food, servings: Rational32) -> Feeding {
+        Feeding {
+            pet: pet.to_string(),
+            time,
+            food,
+            servings: Fraction::from_rational(servings),
+        }
+    }
+
+    /// Returns the name of the pet being fed
+    pub fn get_pet(&self) -> &str {
+        &self.pet
+    }
+
+    /// Returns the time of the feeding
+    pub fn get_time(&self) -> NaiveTime {
+        self.time
+    }
+
+    /// Returns the food the pet is given
+    pub fn get_food(&self) -> &Food {
+        &self.food
+    }
+
+    /// Returns how many of the food's servings the pet is given
+    pub fn get_servings(&self) -> Rational32 {
+        self.servings.to_rational()
+    }
+}
+
+/// A single date's worth of Blocks
+///
+/// Blocks within a Day never overlap, and are kept ordered by start time. Pets' feedings and
+/// the household's supplements are kept separately, also ordered by time.
+///
+/// Deserializing a Day checks the same, putting everything in order and refusing overlapping
+/// blocks.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_json;
+/// extern crate time_for_food;
+/// use time_for_food::food::engine::*;
+///
+/// # fn main() {
+/// let json = r#"{
+///     "date": "2024-01-08",
+///     "blocks": [
+///         {"start": "18:00:00", "end": "19:00:00", "food": null},
+///         {"start": "12:00:00", "end": "13:00:00", "food": null}
+///     ]
+/// }"#;
+/// let day: Day = serde_json::from_str(json).unwrap();
+/// assert!(day.get_blocks()[0].get_start() < day.get_blocks()[1].get_start());
+///
+/// let overlapping = r#"{
+///     "date": "2024-01-08",
+///     "blocks": [
+///         {"start": "12:00:00", "end": "13:00:00", "food": null},
+///         {"start": "12:30:00", "end": "13:30:00", "food": null}
+///     ]
+/// }"#;
+/// assert!(serde_json::from_str::<Day>(overlapping).is_err());
+/// # }
+/// ```
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "DayData")]
+pub struct Day {
+    date: NaiveDate,
+    blocks: Vec<Block>,
+    #[serde(default)]
+    feedings: Vec<Feeding>,
+    #[serde(default)]
+    supplements: Vec<Supplement>,
+}
+
+/// A Day as it is serialized, before it is checked
+#[derive(Deserialize)]
+struct DayData {
+    date: NaiveDate,
+    blocks: Vec<Block>,
+    #[serde(default)]
+    feedings: Vec<Feeding>,
+    #[serde(default)]
+    supplements: Vec<Supplement>,
+}
+
+impl TryFrom<DayData> for Day {
+    type Error = PlanError;
+
+    fn try_from(data: DayData) -> Result<Day, PlanError> {
+        let mut day = Day::new(data.date);
+        for block in data.blocks {
+            if !day.add_block(block) {
+                return Err(PlanError::OverlappingBlocks(data.date));
+            }
+        }
+        for feeding in data.feedings {
+            day.add_feeding(feeding);
+        }
+        for supplement in data.supplements {
+            day.add_supplement(supplement);
+        }
+        Ok(day)
+    }
+}
+
+/// Describes why a serialized Day or MealPlan was refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanError {
+    /// Some of the blocks on the date overlap each other
+    OverlappingBlocks(NaiveDate),
+    /// The date has more than one Day, in the plan or in its trash
+    DuplicateDay(NaiveDate),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PlanError::OverlappingBlocks(date) => write!(f, "blocks overlap on {}", date),
+            PlanError::DuplicateDay(date) => write!(f, "more than one day for {}", date),
+        }
+    }
+}
+
+impl Error for PlanError {}
+
+impl Day {
+    /// Constructs a new Day with no blocks
+    pub fn new(date: NaiveDate) -> Day {
+        Day {
+            date,
+            blocks: Vec::new(),
+            feedings: Vec::new(),
+            supplements: Vec::new(),
+        }
+    }
+
+    /// Returns the date this Day covers
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the blocks of this Day, ordered by start time
+    pub fn get_blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Returns a mutable refrence to the block starting at the given time, if there is one
+    pub fn get_mut_block(&mut self, start: NaiveTime) -> Option<&mut Block> {
+        self.blocks.iter_mut().find(|x| x.start == start)
+    }
+
+    /// Adds a block to this Day, keeping the blocks ordered by start time
+    ///
+    /// # Returns
+    /// False, without adding it, if the block overlaps one already in the Day
+    pub fn add_block(&mut self, block: Block) -> bool {
+        if self.blocks.iter().any(|x| x.overlaps(&block)) {
+            return false;
+        }
+        let index = self
+            .blocks
+            .iter()
+            .position(|x| x.start > block.start)
+            .unwrap_or(self.blocks.len());
+        self.blocks.insert(index, block);
+        true
+    }
+
+    /// Removes the block starting at the given time, returning it
+    pub fn remove_block(&mut self, start: NaiveTime) -> Option<Block> {
+        let index = self.blocks.iter().position(|x| x.start == start)?;
+        Some(self.blocks.remove(index))
+    }
+
+    /// Returns the blocks sharing any time with the range from start to end, ordered by start
+    /// time
+    pub fn blocks_between(&self, start: NaiveTime, end: NaiveTime) -> Vec<&Block> {
+        self.blocks
+            .iter()
+            .filter(|x| x.overlaps_range(start, end))
+            .collect()
+    }
+
+    /// Returns true if no block shares any time with the range from start to end
+    pub fn is_free(&self, start: NaiveTime, end: NaiveTime) -> bool {
+        !self.blocks.iter().any(|x| x.overlaps_range(start, end))
+    }
+
+    /// Returns the pets' feedings, ordered by time
+    pub fn get_feedings(&self) -> &[Feeding] {
+        &self.feedings
+    }
+
+    /// Adds a feeding, keeping the feedings ordered by time
+    pub fn add_feeding(&mut self, feeding: Feeding) {
+        let index = self.feedings.partition_point(|x| x.time <= feeding.time);
+        self.feedings.insert(index, feeding);
+    }
+
+    /// Removes every feeding for the given pet, returning how many were removed
+    pub fn remove_feedings(&mut self, pet: &str) -> usize {
+        let before = self.feedings.len();
+        self.feedings.retain(|x| x.pet != pet);
+        before - self.feedings.len()
+    }
+
+    /// Returns the supplements taken during the day, ordered by time
+    pub fn get_supplements(&self) -> &[Supplement] {
+        &self.supplements
+    }
+
+    /// Adds a supplement, keeping the supplements ordered by time
+    pub fn add_supplement(&mut self, supplement: Supplement) {
+        let index = self
+            .supplements
+            .partition_point(|x| x.time <= supplement.time);
+        self.supplements.insert(index, supplement);
+    }
+
+    /// Removes every dose of the supplement with the given short code, returning how many were
+    /// removed
+    pub fn remove_supplements(&mut self, short_code: &str) -> usize {
+        let before = self.supplements.len();
+        self.supplements
+            .retain(|x| x.name.get_short_code() != short_code);
+        before - self.supplements.len()
+    }
+
+    /// Checks every supplement's timing against the day's meals, see `Supplement::validate`
+    ///
+    /// # Returns
+    /// Each supplement breaking any of its rules, along with the rules it breaks
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let mut plan = MealPlan::new();
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let soup = Food::new_from_recipe(RecipeBuilder::new("soup").build_with_defaults());
+    /// plan.insert_block(date, Block::new(time(12, 0), time(12, 30), Some(soup)));
+    ///
+    /// let mut iron = Supplement::new(IString::new("iron"), time(18, 0));
+    /// iron.add_rule(TimingRule::WithFood {
+    ///     tag: None,
+    ///     minutes: 30,
+    /// });
+    /// plan.add_supplement(date, iron);
+    ///
+    /// let day = plan.get_day(date).unwrap();
+    /// let problems = day.check_supplements();
+    /// assert_eq!(problems.len(), 1);
+    /// assert_eq!(problems[0].0.get_name().get_short_code(), "iron");
+    /// # }
+    /// ```
+    pub fn check_supplements(&self) -> Vec<(&Supplement, Vec<TimingViolation>)> {
+        self.supplements
+            .iter()
+            .map(|x| (x, x.validate(&self.blocks)))
+            .filter(|x| !x.1.is_empty())
+            .collect()
+    }
+}
+
+/// Identifies a meal slot in a plan, the block starting at a time on a date
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SlotId {
+    date: NaiveDate,
+    start: NaiveTime,
+}
+
+impl SlotId {
+    /// Identifies the block starting at the given time on the given date
+    pub fn new(date: NaiveDate, start: NaiveTime) -> SlotId {
+        SlotId { date, start }
+    }
+
+    /// Returns the date of the slot
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the start time of the slot
+    pub fn get_start(&self) -> NaiveTime {
+        self.start
+    }
+}
+
+/// What changed when some of a plan's slots were regenerated
+///
+/// Only the regenerated slots are looked at, so the change in nutrition and shopping can be
+/// worked out without going over the whole plan again.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct PlanDelta {
+    removed: Vec<(SlotId, Food)>,
+    added: Vec<(SlotId, Food)>,
+}
+
+impl PlanDelta {
+    /// Returns the foods taken out of the plan, with the slots they were in
+    pub fn get_removed(&self) -> &[(SlotId, Food)] {
+        &self.removed
+    }
+
+    /// Returns the foods put into the plan, with the slots they are in
+    pub fn get_added(&self) -> &[(SlotId, Food)] {
+        &self.added
+    }
+
+    /// Returns true if no slot changed
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+
+    /// Returns the total nutrition of the foods taken out of the plan
+    pub fn removed_nutrition(&self) -> Nutrition {
+        let mut total = Nutrition::new();
+        for (_, food) in &self.removed {
+            total.add(food.get_nutrition());
+        }
+        total
+    }
+
+    /// Returns the total nutrition of the foods put into the plan
+    pub fn added_nutrition(&self) -> Nutrition {
+        let mut total = Nutrition::new();
+        for (_, food) in &self.added {
+            total.add(food.get_nutrition());
+        }
+        total
+    }
+
+    /// Returns the ingredients no longer needed, broken down as by `Food::decompose`
+    pub fn removed_ingredients(&self, settings: &Settings) -> Vec<(Food, Amount)> {
+        self.removed
+            .iter()
+            .flat_map(|x| x.1.decompose(settings))
+            .collect()
+    }
+
+    /// Returns the ingredients newly needed, broken down as by `Food::decompose`
+    pub fn added_ingredients(&self, settings: &Settings) -> Vec<(Food, Amount)> {
+        self.added
+            .iter()
+            .flat_map(|x| x.1.decompose(settings))
+            .collect()
+    }
+}
+
+/// A plan covering any number of Days
+///
+/// Days removed from the plan go to the plan's trash, where they can be restored until the
+/// trash is purged.
+///
+/// Deserializing a MealPlan puts its days in order by date, and refuses more than one Day for
+/// the same date.
+///
+/// # Examples
+///
+/// ```
+/// extern crate serde_json;
+/// extern crate time_for_food;
+/// use time_for_food::food::engine::*;
+///
+/// # fn main() {
+/// let day = |date: &str| format!(r#"{{"date": "{}", "blocks": []}}"#, date);
+/// let json = format!(r#"{{"days": [{}, {}]}}"#, day("2024-01-09"), day("2024-01-08"));
+/// let plan: MealPlan = serde_json::from_str(&json).unwrap();
+/// assert!(plan.get_days()[0].get_date() < plan.get_days()[1].get_date());
+///
+/// let json = format!(r#"{{"days": [{}, {}]}}"#, day("2024-01-08"), day("2024-01-08"));
+/// assert!(serde_json::from_str::<MealPlan>(&json).is_err());
+/// # }
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "MealPlanData")]
+pub struct MealPlan {
+    days: Vec<Day>,
+    /// Days removed from the plan, but not yet purged, ordered by date
+    #[serde(default)]
+    trash: Vec<Day>,
+}
+
+/// A MealPlan as it is serialized, before it is checked
+#[derive(Deserialize)]
+struct MealPlanData {
+    days: Vec<Day>,
+    #[serde(default)]
+    trash: Vec<Day>,
+}
+
+impl TryFrom<MealPlanData> for MealPlan {
+    type Error = PlanError;
+
+    fn try_from(data: MealPlanData) -> Result<MealPlan, PlanError> {
+        // Sorts the days by date, refusing any date given twice
+        let ordered = |mut days: Vec<Day>| {
+            days.sort_by_key(|x| x.date);
+            match days.windows(2).find(|x| x[0].date == x[1].date) {
+                Some(x) => Err(PlanError::DuplicateDay(x[0].date)),
+                None => Ok(days),
+            }
+        };
+        Ok(MealPlan {
+            days: ordered(data.days)?,
+            trash: ordered(data.trash)?,
+        })
+    }
+}
+
+impl MealPlan {
+    /// Constructs a new, empty, MealPlan
+    pub fn new() -> MealPlan {
+        MealPlan {
+            days: Vec::new(),
+            trash: Vec::new(),
+        }
+    }
+
+    /// Returns the days of the plan, ordered by date
+    pub fn get_days(&self) -> &[Day] {
+        &self.days
+    }
+
+    /// Returns the Day for the given date, if the plan covers it
+    pub fn get_day(&self, date: NaiveDate) -> Option<&Day> {
+        self.days.iter().find(|x| x.date == date)
+    }
+
+    /// Returns a mutable refrence to the Day for the given date, if the plan covers it
+    pub fn get_mut_day(&mut self, date: NaiveDate) -> Option<&mut Day> {
+        self.days.iter_mut().find(|x| x.date == date)
+    }
+
+    /// Returns the days from start up to, but not including, end, ordered by date
+    pub fn days_between(&self, start: NaiveDate, end: NaiveDate) -> &[Day] {
+        let first = self.days.partition_point(|x| x.date < start);
+        let last = self.days.partition_point(|x| x.date < end);
+        if first < last {
+            &self.days[first..last]
+        } else {
+            &[]
+        }
+    }
+
+    /// Removes the Day for the given date from the plan permanently, returning it
+    ///
+    /// See `trash_day` to keep the Day restorable.
+    pub fn remove_day(&mut self, date: NaiveDate) -> Option<Day> {
+        let index = self.days.binary_search_by_key(&date, |x| x.date).ok()?;
+        Some(self.days.remove(index))
+    }
+
+    /// Adds a block on the given date, adding a Day for the date if the plan doesn't cover it
+    ///
+    /// # Returns
+    /// False, without adding it, if the block overlaps one already planned that day
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::food::engine::*;
+    ///
+    /// # fn main() {
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let mut plan = MealPlan::new();
+    ///
+    /// assert!(plan.insert_block(date, Block::new(time(12, 0), time(12, 30), None)));
+    /// assert!(plan.insert_block(date, Block::new(time(18, 0), time(19, 0), None)));
+    /// // Overlaps lunch
+    /// assert!(!plan.insert_block(date, Block::new(time(12, 15), time(13, 0), None)));
+    ///
+    /// assert_eq!(plan.blocks_between(date, time(11, 0), time(14, 0)).len(), 1);
+    /// assert!(plan.remove_block(date, time(12, 0)).is_some());
+    /// assert!(plan.blocks_between(date, time(11, 0), time(14, 0)).is_empty());
+    /// # }
+    /// ```
+    pub fn insert_block(&mut self, date: NaiveDate, block: Block) -> bool {
+        let index = match self.days.binary_search_by_key(&date, |x| x.date) {
+            Ok(index) => index,
+            Err(index) => {
+                self.days.insert(index, Day::new(date));
+                index
+            }
+        };
+        let added = self.days[index].add_block(block);
+        if !added && self.days[index].blocks.is_empty() {
+            // Don't leave behind a Day created just for the rejected block
+            self.days.remove(index);
+        }
+        added
+    }
+
+    /// Adds a supplement taken on the given date, adding a Day for the date if the plan doesn't
+    /// cover it
+    pub fn add_supplement(&mut self, date: NaiveDate, supplement: Supplement) {
+        let index = match self.days.binary_search_by_key(&date, |x| x.date) {
+            Ok(index) => index,
+            Err(index) => {
+                self.days.insert(index, Day::new(date));
+                index
+            }
+        };
+        self.days[index].add_supplement(supplement);
+    }
+
+    /// Removes the block starting at the given time on the given date, returning it
+    ///
+    /// The Day itself stays in the plan, even if it has no blocks left.
+    pub fn remove_block(&mut self, date: NaiveDate, start: NaiveTime) -> Option<Block> {
+        self.get_mut_day(date)?.remove_block(start)
+    }
+
+    /// Returns the blocks on the given date sharing any time with the range from start to
+    /// end, ordered by start time
+    pub fn blocks_between(&self, date: NaiveDate, start: NaiveTime, end: NaiveTime) -> Vec<&Block> {
+        match self.get_day(date) {
+            Some(day) => day.blocks_between(start, end),
+            None => Vec::new(),
+        }
+    }
+
+    /// Adds a Day to the plan, keeping the days ordered by date
+    ///
+    /// Replaces the existing Day for the same date, if there is one.
+    pub fn add_day(&mut self, day: Day) {
+        match self.days.binary_search_by_key(&day.date, |x| x.date) {
+            Ok(index) => self.days[index] = day,
+            Err(index) => self.days.insert(index, day),
+        }
+    }
+
+    /// Moves the Day for the given date to the trash, returning false if the plan doesn't
+    /// cover the date
+    ///
+    /// Replaces any Day for the same date already in the trash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::food::engine::*;
+    ///
+    /// # fn main() {
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut plan = MealPlan::new();
+    /// plan.add_day(Day::new(date));
+    ///
+    /// assert!(plan.trash_day(date));
+    /// assert!(plan.get_day(date).is_none());
+    /// assert!(plan.restore_day(date));
+    /// assert!(plan.get_day(date).is_some());
+    /// # }
+    /// ```
+    pub fn trash_day(&mut self, date: NaiveDate) -> bool {
+        match self.days.binary_search_by_key(&date, |x| x.date) {
+            Ok(index) => {
+                let day = self.days.remove(index);
+                match self.trash.binary_search_by_key(&date, |x| x.date) {
+                    Ok(index) => self.trash[index] = day,
+                    Err(index) => self.trash.insert(index, day),
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Moves the Day for the given date back out of the trash
+    ///
+    /// # Returns
+    /// False, without changing anything, if the trash has no Day for the date, or the plan
+    /// has gained a new Day for it since
+    pub fn restore_day(&mut self, date: NaiveDate) -> bool {
+        if self.get_day(date).is_some() {
+            return false;
+        }
+        match self.trash.binary_search_by_key(&date, |x| x.date) {
+            Ok(index) => {
+                let day = self.trash.remove(index);
+                self.add_day(day);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the days in the trash, ordered by date
+    pub fn get_trash(&self) -> &[Day] {
+        &self.trash
+    }
+
+    /// Permanently deletes every Day in the trash, returning how many were deleted
+    pub fn purge_trash(&mut self) -> usize {
+        let count = self.trash.len();
+        self.trash.clear();
+        count
+    }
+
+    /// Chooses new foods for the given slots with the optimizer, holding the rest of the plan
+    /// fixed, and returns what changed
+    ///
+    /// The foods in the given slots are cleared and the optimizer fills just those slots, so
+    /// constraints and objectives are still judged against the whole plan. Slots that don't
+    /// start a block in the plan are ignored. If the optimizer can't find an acceptable plan,
+    /// the plan is left as it was and None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::optimizer::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = |name: &str| {
+    ///     Food::new_from_recipe(
+    ///         RecipeBuilder::new(name)
+    ///             .add_step(Step::new("cook", Rational32::from_integer(20)))
+    ///             .build_with_defaults(),
+    ///     )
+    /// };
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let lunch = NaiveTime::from_hms(12, 0, 0);
+    /// let dinner = NaiveTime::from_hms(18, 0, 0);
+    /// let mut plan = MealPlan::new();
+    /// plan.insert_block(date, Block::new(lunch, NaiveTime::from_hms(13, 0, 0), Some(recipe("soup"))));
+    /// plan.insert_block(date, Block::new(dinner, NaiveTime::from_hms(19, 0, 0), Some(recipe("soup"))));
+    ///
+    /// // Swap dinner for something else, keeping lunch
+    /// let delta = plan
+    ///     .regenerate(&[SlotId::new(date, dinner)], &Optimizer::new(1), &[recipe("tacos")])
+    ///     .unwrap();
+    /// assert_eq!(delta.get_removed()[0].1.get_name().get_short_code(), "soup");
+    /// assert_eq!(delta.get_added()[0].1.get_name().get_short_code(), "tacos");
+    /// let day = plan.get_day(date).unwrap();
+    /// assert_eq!(day.get_blocks()[0].get_food().unwrap().get_name().get_short_code(), "soup");
+    /// # }
+    /// ```
+    pub fn regenerate(
+        &mut self,
+        slots: &[SlotId],
+        optimizer: &Optimizer,
+        recipes: &[Food],
+    ) -> Option<PlanDelta> {
+        let mut template = self.clone();
+        let mut removed = Vec::new();
+        for slot in slots {
+            let block = template
+                .get_mut_day(slot.date)
+                .and_then(|x| x.get_mut_block(slot.start));
+            if let Some(food) = block.and_then(|x| x.remove_food()) {
+                removed.push((*slot, food));
+            }
+        }
+
+        let plan = optimizer
+            .optimize_slots(&template, recipes, slots)?
+            .into_plan();
+        let added = slots
+            .iter()
+            .filter_map(|slot| {
+                plan.get_day(slot.date)?
+                    .get_blocks()
+                    .iter()
+                    .find(|x| x.start == slot.start)?
+                    .get_food()
+                    .map(|x| (*slot, x.clone()))
+            })
+            .collect();
+        *self = plan;
+        Some(PlanDelta { removed, added })
+    }
+
+    /// Returns everything that needs buying to make the food in the plan
+    ///
+    /// See `shopping_list_between` for how the list is put together.
+    pub fn shopping_list(&self, settings: &Settings) -> ShoppingList {
+        shopping_list_for(&self.days, settings)
+    }
+
+    /// Returns everything that needs buying to make the food planned from start up to, but not
+    /// including, end
+    ///
+    /// Every planned recipe is made once, and broken down into raw ingredients as by
+    /// `Food::decompose`, and every planned raw food is a serving. Pets' feedings are included.
+    /// Amounts of the same ingredient are merged across recipes, in the unit the ingredient
+    /// was first seen in, so long as the household's unit sizes and densities can convert
+    /// between them. Eating out and beverages aren't
+    /// shopped for. The list is sorted by short code, and items take their category from the
+    /// raw food, see `ShoppingList::group_by_category`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    /// use time_for_food::settings::Settings;
+    ///
+    /// # fn main() {
+    /// let raw = |name: &str, unit| {
+    ///     Food::new_from_raw_food(
+    ///         RawFoodBuilder::new(name)
+    ///             .set_serving_size(unit, Rational32::from_integer(1))
+    ///             .set_nutrition(Nutrition::new())
+    ///             .build_raw_food()
+    ///             .unwrap(),
+    ///     )
+    /// };
+    /// let pancakes = RecipeBuilder::new("pancakes")
+    ///     .add_food(raw("milk", Unit::Cup), Unit::Cup, Rational32::from_integer(1))
+    ///     .add_food(raw("flour", Unit::Gram), Unit::Gram, Rational32::from_integer(150))
+    ///     .build_with_defaults();
+    /// let porridge = RecipeBuilder::new("porridge")
+    ///     .add_food(raw("milk", Unit::Cup), Unit::Cup, Rational32::new(1, 2))
+    ///     .build_with_defaults();
+    ///
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut plan = MealPlan::new();
+    /// let breakfast = |food: Recipe| {
+    ///     Block::new(
+    ///         NaiveTime::from_hms(7, 0, 0),
+    ///         NaiveTime::from_hms(8, 0, 0),
+    ///         Some(Food::new_from_recipe(food)),
+    ///     )
+    /// };
+    /// plan.insert_block(date, breakfast(pancakes));
+    /// plan.insert_block(date.succ(), breakfast(porridge));
+    ///
+    /// let list = plan.shopping_list(&Settings::new());
+    /// let items: Vec<(&str, Rational32)> = list
+    ///     .get_items()
+    ///     .iter()
+    ///     .map(|x| (x.get_name().get_short_code(), x.get_amount().get_amount()))
+    ///     .collect();
+    /// assert_eq!(
+    ///     items,
+    ///     vec![
+    ///         ("flour", Rational32::from_integer(150)),
+    ///         ("milk", Rational32::new(3, 2)),
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    pub fn shopping_list_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        settings: &Settings,
+    ) -> ShoppingList {
+        shopping_list_for(self.days_between(start, end), settings)
+    }
+
+    /// Tags every block with food that isn't already tagged with a meal, returning how many
+    /// blocks were tagged
+    ///
+    /// Blocks are classified by their end time, when the food is ready to eat.
+    pub fn label_meals(&mut self, windows: &MealWindows) -> usize {
+        let mut count = 0;
+        for block in self.days.iter_mut().flat_map(|x| x.blocks.iter_mut()) {
+            if block.has_food() && block.get_meal_kind().is_none() {
+                block.add_tag(windows.classify(block.end).get_tag());
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Makes the child variant of every planned recipe that has one, if the household has
+    /// young children, returning how many blocks changed
+    ///
+    /// Each variant is made from the same block as the adult version, see
+    /// `Recipe::with_child_variant`. The block's times are left alone, so check the recipe's
+    /// new time still fits.
+    pub fn add_child_variants(&mut self, household: &Household) -> usize {
+        if !household.has_young_children() {
+            return 0;
+        }
+        let mut count = 0;
+        for block in self.days.iter_mut().flat_map(|x| x.blocks.iter_mut()) {
+            let adapted = match block.get_food() {
+                Some(Food::Recipe(recipe)) => recipe.with_child_variant(),
+                _ => None,
+            };
+            if let Some(recipe) = adapted {
+                block.add_food(Food::Recipe(recipe));
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// Builds the shopping list for the given days, see `MealPlan::shopping_list_between`
+fn shopping_list_for(days: &[Day], settings: &Settings) -> ShoppingList {
+    let conversions = settings.get_conversions();
+    let mut needed: Vec<(Food, Amount)> = Vec::new();
+    let mut add = |food: Food, amount: Amount| {
+        if let Food::External(_) = food {
+            return;
+        }
+        let id = FoodId::of(&food);
+        let existing = needed.iter_mut().find(|(x, y)| {
+            x.get_name().get_short_code() == id.get_short_code()
+                && conversions.convert_food(&id, &amount, y.get_unit()).is_ok()
+        });
+        let convert = |total: &Amount| conversions.convert_food(&id, &amount, total.get_unit());
+        match existing.map(|(_, total)| (convert(total), total)) {
+            Some((Ok(x), total)) => total.set_amount(total.get_amount() + x.get_amount()),
+            _ => needed.push((food, amount)),
+        }
+    };
+    for day in days {
+        for food in day.blocks.iter().filter_map(|x| x.get_food()) {
+            for (ingredient, amount) in food.decompose(settings) {
+                add(ingredient, amount);
+            }
+        }
+        for feeding in &day.feedings {
+            for (ingredient, amount) in feeding.food.decompose(settings) {
+                let servings = feeding.get_servings();
+                add(
+                    ingredient,
+                    Amount::new(amount.get_unit(), amount.get_amount() * servings),
+                );
+            }
+        }
+    }
+    needed.sort_by(|a, b| {
+        a.0.get_name()
+            .get_short_code()
+            .cmp(b.0.get_name().get_short_code())
+    });
+
+    let mut list = ShoppingList::new();
+    for (food, amount) in needed {
+        let mut item = ShoppingItem::new(food.get_name().clone(), amount);
+        if let Food::RawFood(ref raw) = food {
+            item.set_category(raw.get_category());
+        }
+        list.add_item(item);
+    }
+    list
+}
+
+/// A daily window of time in which food may be eaten, such as 12:00–20:00 for intermittent
+/// fasting
+///
+/// Treated as a hard constraint, food blocks must finish (that is, the food must be ready to
+/// eat) within the window. Cooking may start before the window opens. Windows that cross
+/// midnight, such as 20:00–02:00, are supported. See `Scheduler::set_eating_window` and
+/// `Constraint::EatingWindow` to keep the scheduler and the optimizer to a window.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EatingWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl EatingWindow {
+    /// Constructs a new eating window from its opening and closing times
+    pub fn new(start: NaiveTime, end: NaiveTime) -> EatingWindow {
+        EatingWindow { start, end }
+    }
+
+    /// Returns the time the window opens
+    pub fn get_start(&self) -> &NaiveTime {
+        &self.start
+    }
+
+    /// Returns the time the window closes
+    pub fn get_end(&self) -> &NaiveTime {
+        &self.end
+    }
+
+    /// Returns true if the given time falls within the window, inclusive of both ends
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time <= self.end
+        } else {
+            // The window wraps around midnight
+            time >= self.start || time <= self.end
+        }
+    }
+
+    /// Returns true if the food in the block would be ready to eat within the window
+    ///
+    /// Blocks without food are always permitted, they are just free time.
+    pub fn permits(&self, block: &Block) -> bool {
+        !block.has_food() || self.contains(block.end)
+    }
+}
+
+/// Which meal of the day some food is
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MealKind {
+    Breakfast,
+    Lunch,
+    Dinner,
+    /// Anything eaten outside of the main meals
+    Snack,
+}
+
+impl MealKind {
+    /// Returns the Block tag used for the meal, such as "breakfast"
+    pub fn get_tag(self) -> &'static str {
+        match self {
+            MealKind::Breakfast => "breakfast",
+            MealKind::Lunch => "lunch",
+            MealKind::Dinner => "dinner",
+            MealKind::Snack => "snack",
+        }
+    }
+
+    /// Returns the meal a Block tag names, if it names one
+    pub fn from_tag(tag: &str) -> Option<MealKind> {
+        match tag {
+            "breakfast" => Some(MealKind::Breakfast),
+            "lunch" => Some(MealKind::Lunch),
+            "dinner" => Some(MealKind::Dinner),
+            "snack" => Some(MealKind::Snack),
+            _ => None,
+        }
+    }
+}
+
+/// The times of day each meal is eaten in, used to label food that wasn't labelled
+///
+/// Food is classified by the time it is eaten, checking breakfast, then lunch, then dinner,
+/// and anything outside every window is a snack.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MealWindows {
+    windows: BTreeMap<MealKind, EatingWindow>,
+}
+
+impl Default for MealWindows {
+    /// Breakfast from 05:00 to 10:30, lunch from 11:00 to 14:30, and dinner from 17:00 to 21:30
+    fn default() -> MealWindows {
+        let mut windows = MealWindows {
+            windows: BTreeMap::new(),
+        };
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        windows
+            .set_window(
+                MealKind::Breakfast,
+                EatingWindow::new(time(5, 0), time(10, 30)),
+            )
+            .set_window(
+                MealKind::Lunch,
+                EatingWindow::new(time(11, 0), time(14, 30)),
+            )
+            .set_window(
+                MealKind::Dinner,
+                EatingWindow::new(time(17, 0), time(21, 30)),
+            );
+        windows
+    }
+}
+
+impl MealWindows {
+    /// Creates the default windows
+    pub fn new() -> MealWindows {
+        MealWindows::default()
+    }
+
+    /// Returns the window a meal is eaten in, if it has one
+    pub fn get_window(&self, kind: MealKind) -> Option<&EatingWindow> {
+        self.windows.get(&kind)
+    }
+
+    /// Sets the window a meal is eaten in, replacing any existing window
+    ///
+    /// Snacks can be given a window too, though anything outside every window is still a
+    /// snack.
+    pub fn set_window(&mut self, kind: MealKind, window: EatingWindow) -> &mut Self {
+        self.windows.insert(kind, window);
+        self
+    }
+
+    /// Removes the window a meal is eaten in, returning it
+    pub fn remove_window(&mut self, kind: MealKind) -> Option<EatingWindow> {
+        self.windows.remove(&kind)
+    }
+
+    /// Returns which meal food eaten at the given time is
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::food::engine::*;
+    ///
+    /// # fn main() {
+    /// let windows = MealWindows::new();
+    /// assert_eq!(windows.classify(NaiveTime::from_hms(7, 30, 0)), MealKind::Breakfast);
+    /// assert_eq!(windows.classify(NaiveTime::from_hms(18, 15, 0)), MealKind::Dinner);
+    /// assert_eq!(windows.classify(NaiveTime::from_hms(15, 30, 0)), MealKind::Snack);
+    /// # }
+    /// ```
+    pub fn classify(&self, time: NaiveTime) -> MealKind {
+        self.windows
+            .iter()
+            .find(|(_, window)| window.contains(time))
+            .map_or(MealKind::Snack, |(kind, _)| *kind)
+    }
+}
+
+/// A rule describing when a supplement or medication may be taken, relative to meals
+///
+/// Times are compared around the clock, so a supplement taken at 23:30 is 45 minutes from a
+/// meal eaten at 00:15.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TimingRule {
+    /// Must be taken within the given number of minutes of eating, optionally only with a
+    /// meal carrying the given Block tag ("take iron with lunch")
+    WithFood { tag: Option<String>, minutes: u32 },
+    /// No food may be eaten within the given number of minutes either side
+    EmptyStomach { minutes: u32 },
+    /// No food containing the given ingredient may be eaten within the given number of minutes
+    /// either side ("no dairy within 2 h of this medication"), see `Food::contains_ingredient`
+    AwayFrom { ingredient: String, minutes: u32 },
+}
+
+impl TimingRule {
+    /// Returns true if eating the food at the given time breaks the rule, for a supplement
+    /// taken at `taken`
+    ///
+    /// WithFood rules are never broken by eating.
+    fn forbids(&self, taken: NaiveTime, food: &Food, eaten: NaiveTime) -> bool {
+        match *self {
+            TimingRule::WithFood { .. } => false,
+            TimingRule::EmptyStomach { minutes } => {
+                minutes_apart(taken, eaten) < i64::from(minutes)
+            }
+            TimingRule::AwayFrom {
+                ref ingredient,
+                minutes,
+            } => {
+                food.contains_ingredient(ingredient)
+                    && minutes_apart(taken, eaten) < i64::from(minutes)
+            }
+        }
+    }
+}
+
+/// A non-food item taken on a schedule, such as a supplement or medication
+///
+/// Supplements are kept on a Day alongside its blocks, see `Day::check_supplements`, and the
+/// Scheduler won't place foods where eating them would break a supplement's rules, see
+/// `Scheduler::add_supplement`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Supplement {
+    name: IString,
+    time: NaiveTime,
+    rules: Vec<TimingRule>,
+}
+
+/// Describes how a supplement's timing broke one of its rules
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimingViolation {
+    /// A WithFood rule had no matching meal close enough
+    NoFoodNearby(TimingRule),
+    /// Food was eaten too close to the supplement, includes the time the food was eaten
+    FoodTooClose(TimingRule, NaiveTime),
+}
+
+impl Supplement {
+    /// Constructs a new Supplement, taken at the given time, with no rules
+    pub fn new(name: IString, time: NaiveTime) -> Supplement {
+        Supplement {
+            name,
+            time,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the supplement
+    pub fn get_name(&self) -> &IString {
+        &self.name
+    }
+
+    /// Returns the time the supplement is taken at
+    pub fn get_time(&self) -> &NaiveTime {
+        &self.time
+    }
+
+    /// Sets the time the supplement is taken at
+    pub fn set_time(&mut self, time: NaiveTime) {
+        self.time = time;
+    }
+
+    /// Adds a timing rule to the supplement
+    pub fn add_rule(&mut self, rule: TimingRule) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Returns the timing rules for the supplement
+    pub fn get_rules(&self) -> &[TimingRule] {
+        &self.rules
+    }
+
+    /// Returns true if eating the food at the given time would break one of the supplement's
+    /// rules
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let milk = RawFoodBuilder::new("milk")
+    ///     .add_tag("dairy")
+    ///     .set_serving_size(Unit::Milliliter, Rational32::from_integer(250))
+    ///     .set_nutrition(Nutrition::new())
+    ///     .build_raw_food()
+    ///     .unwrap();
+    /// let ml = Rational32::from_integer(200);
+    /// let porridge = RecipeBuilder::new("porridge")
+    ///     .add_food(Food::new_from_raw_food(milk), Unit::Milliliter, ml)
+    ///     .build_with_defaults();
+    /// let porridge = Food::new_from_recipe(porridge);
+    /// let toast = Food::new_from_recipe(RecipeBuilder::new("toast").build_with_defaults());
+    ///
+    /// // No dairy within two hours of the antibiotic
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let mut antibiotic = Supplement::new(IString::new("doxycycline"), time(8, 0));
+    /// antibiotic.add_rule(TimingRule::AwayFrom {
+    ///     ingredient: "dairy".to_string(),
+    ///     minutes: 120,
+    /// });
+    /// assert!(antibiotic.forbids(&porridge, time(9, 0)));
+    /// assert!(!antibiotic.forbids(&porridge, time(10, 0)));
+    /// assert!(!antibiotic.forbids(&toast, time(9, 0)));
+    /// # }
+    /// ```
+    pub fn forbids(&self, food: &Food, eaten: NaiveTime) -> bool {
+        self.rules.iter().any(|x| x.forbids(self.time, food, eaten))
+    }
+
+    /// Checks the supplement's timing against the meals in the given blocks
+    ///
+    /// A meal is considered to be eaten at the end of its block.
+    ///
+    /// # Returns
+    /// Every rule the supplement breaks, empty if the timing is fine
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let food = |name: &str| {
+    ///     let recipe = RecipeBuilder::new(name).build_with_defaults();
+    ///     Some(Food::new_from_recipe(recipe))
+    /// };
+    /// let mut lunch = Block::new(time(12, 0), time(12, 30), food("soup"));
+    /// lunch.add_tag("lunch");
+    /// let breakfast = Block::new(time(7, 0), time(7, 30), food("porridge"));
+    /// let blocks = [breakfast, lunch];
+    ///
+    /// // Taken with lunch
+    /// let mut iron = Supplement::new(IString::new("iron"), time(12, 15));
+    /// let with_lunch = TimingRule::WithFood {
+    ///     tag: Some("lunch".to_string()),
+    ///     minutes: 30,
+    /// };
+    /// iron.add_rule(with_lunch.clone());
+    /// assert!(iron.validate(&blocks).is_empty());
+    /// iron.set_time(time(8, 0));
+    /// assert_eq!(iron.validate(&blocks), vec![TimingViolation::NoFoodNearby(with_lunch)]);
+    ///
+    /// // Taken an hour away from any food, around the clock
+    /// let mut thyroid = Supplement::new(IString::new("levothyroxine"), time(6, 0));
+    /// let empty = TimingRule::EmptyStomach { minutes: 60 };
+    /// thyroid.add_rule(empty.clone());
+    /// assert!(thyroid.validate(&blocks).is_empty());
+    /// thyroid.set_time(time(7, 0));
+    /// assert_eq!(
+    ///     thyroid.validate(&blocks),
+    ///     vec![TimingViolation::FoodTooClose(empty.clone(), time(7, 30))]
+    /// );
+    /// let snack = [Block::new(time(0, 0), time(0, 15), food("toast"))];
+    /// thyroid.set_time(time(23, 30));
+    /// assert_eq!(
+    ///     thyroid.validate(&snack),
+    ///     vec![TimingViolation::FoodTooClose(empty, time(0, 15))]
+    /// );
+    /// # }
+    /// ```
+    pub fn validate(&self, blocks: &[Block]) -> Vec<TimingViolation> {
+        let meals: Vec<(&Food, &Block)> = blocks
+            .iter()
+            .filter_map(|x| x.get_food().map(|food| (food, x)))
+            .collect();
+        let mut violations = Vec::new();
+        for rule in &self.rules {
+            if let TimingRule::WithFood { ref tag, minutes } = *rule {
+                let satisfied = meals.iter().any(|&(_, block)| {
+                    let tagged = tag.as_ref().is_none_or(|x| block.has_tag(x));
+                    tagged && minutes_apart(self.time, block.end) <= i64::from(minutes)
+                });
+                if !satisfied {
+                    violations.push(TimingViolation::NoFoodNearby(rule.clone()));
+                }
+                continue;
+            }
+            for &(food, block) in &meals {
+                if rule.forbids(self.time, food, block.end) {
+                    violations.push(TimingViolation::FoodTooClose(rule.clone(), block.end));
+                }
+            }
+        }
+        violations
+    }
+}
+
+/// The number of whole minutes between two times, ignoring which comes first, and going the
+/// short way around midnight
+fn minutes_apart(a: NaiveTime, b: NaiveTime) -> i64 {
+    let minutes = (a - b).num_minutes().abs();
+    minutes.min(24 * 60 - minutes)
+}
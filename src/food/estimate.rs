@@ -0,0 +1,265 @@
+//! This module contains heuristics for estimating how long steps take
+//!
+//! Imported recipes frequently have step text, but no durations. The estimates produced here
+//! are only ever proposals, nothing in this module modifies a Step, it is up to the user to
+//! confirm (or correct) a proposal before applying it with `Step::set_time`.
+//!
+//! The heuristics only understand English text.
+use food::*;
+use parse::duration::unit_minutes;
+use parse::{checked_add, parse_duration};
+use warning::{Warning, WithWarnings};
+
+/// Words per minute used to compute reading time
+const READING_SPEED: i32 = 200;
+
+/// Cooking verbs, and the number of minutes they are assumed to take when the text doesn't
+/// say otherwise
+const VERB_TIMES: &[(&str, i32)] = &[
+    ("preheat", 10),
+    ("chop", 5),
+    ("dice", 5),
+    ("mince", 5),
+    ("slice", 5),
+    ("grate", 3),
+    ("peel", 3),
+    ("mix", 2),
+    ("stir", 2),
+    ("whisk", 2),
+    ("combine", 2),
+    ("fold", 2),
+    ("knead", 10),
+    ("boil", 10),
+    ("simmer", 20),
+    ("fry", 8),
+    ("saute", 8),
+    ("sauté", 8),
+    ("bake", 30),
+    ("roast", 40),
+    ("grill", 15),
+    ("marinate", 60),
+    ("chill", 60),
+    ("refrigerate", 60),
+    ("rest", 10),
+    ("season", 1),
+    ("garnish", 1),
+    ("serve", 1),
+];
+
+/// How much effort a step looks like it requires
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Complexity {
+    /// A single short action
+    Simple,
+    /// A few actions, or a moderate amount of text
+    Moderate,
+    /// Many actions, or a long block of instructions
+    Involved,
+}
+
+/// Where a proposed step time came from, from most to least trustworthy
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EstimateBasis {
+    /// The step text stated the time outright ("simmer for 20 minutes")
+    StatedInText,
+    /// The time was built up from the cooking verbs found in the text
+    Verbs(Vec<String>),
+    /// Nothing recognizable was found, so the time was derived from the length of the text
+    WordCount,
+}
+
+/// A proposed time for a step, for the user to confirm
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StepEstimate {
+    time: Rational32,
+    reading_time: Rational32,
+    complexity: Complexity,
+    basis: EstimateBasis,
+}
+
+impl StepEstimate {
+    /// Returns the proposed time for the step, in miniutes
+    pub fn get_time(&self) -> Rational32 {
+        self.time
+    }
+
+    /// Returns the proposed time for the step, warning unless the text stated it outright
+    pub fn get_time_with_warnings(&self) -> WithWarnings<Rational32> {
+        let mut time = WithWarnings::new(self.time);
+        if self.basis != EstimateBasis::StatedInText {
+            time.add_warning(Warning::EstimatedTime(self.basis.clone()));
+        }
+        time
+    }
+
+    /// Returns the time it takes to read the step text, in miniutes
+    pub fn get_reading_time(&self) -> Rational32 {
+        self.reading_time
+    }
+
+    /// Returns how complex the step looks
+    pub fn get_complexity(&self) -> Complexity {
+        self.complexity
+    }
+
+    /// Returns what the proposed time was based on
+    pub fn get_basis(&self) -> &EstimateBasis {
+        &self.basis
+    }
+}
+
+/// Proposes a time for a step, based on its text in the given language
+///
+/// # Returns
+/// None if the step has no text in the given language, otherwise Some(StepEstimate)
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+/// use time_for_food::food::estimate::*;
+///
+/// # fn main() {
+/// let mut step = Step::new("simmer-sauce", Rational32::from_integer(0));
+/// step.get_mut_text().set_value_for("en_US", "Simmer the sauce for 20 minutes.");
+///
+/// let estimate = estimate_step(&step, "en_US").unwrap();
+/// assert_eq!(estimate.get_time(), Rational32::from_integer(20));
+/// assert_eq!(estimate.get_basis(), &EstimateBasis::StatedInText);
+/// # }
+/// ```
+pub fn estimate_step(step: &Step, lang: &str) -> Option<StepEstimate> {
+    step.get_text().get_value(lang).map(estimate_text)
+}
+
+/// Proposes times for every step of a recipe that doesn't already have one
+///
+/// # Returns
+/// A list of (step index, estimate) pairs, skipping steps that already have a non-zero time
+/// and steps with no text in the given language.
+pub fn estimate_missing_steps(recipe: &Recipe, lang: &str) -> Vec<(usize, StepEstimate)> {
+    recipe
+        .get_steps()
+        .iter()
+        .enumerate()
+        .filter(|&(_, step)| step.get_time() == Rational32::from_integer(0))
+        .filter_map(|(i, step)| estimate_step(step, lang).map(|estimate| (i, estimate)))
+        .collect()
+}
+
+/// Proposes a time for a bit of step text
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::estimate::*;
+///
+/// # fn main() {
+/// let estimate = estimate_text("Simmer 20 minutes, then rest 5 minutes");
+/// assert_eq!(estimate.get_time(), Rational32::from_integer(25));
+///
+/// // Stated times too long to add up are ignored, and the verbs estimated from instead
+/// let estimate = estimate_text("Simmer 2000000000 minutes then rest 2000000000 minutes");
+/// assert_ne!(estimate.get_basis(), &EstimateBasis::StatedInText);
+/// # }
+/// ```
+pub fn estimate_text(text: &str) -> StepEstimate {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect();
+    let word_count = words.len() as i32;
+    let reading_time = Rational32::new(word_count, READING_SPEED);
+
+    // Pick out all the cooking verbs
+    let mut verbs = Vec::new();
+    let mut verb_time = 0;
+    for word in &words {
+        if let Some(&(verb, minutes)) = VERB_TIMES.iter().find(|&&(verb, _)| is_form_of(word, verb))
+        {
+            verbs.push(verb.to_string());
+            verb_time += minutes;
+        }
+    }
+
+    let complexity = if verbs.len() <= 1 && word_count <= 15 {
+        Complexity::Simple
+    } else if verbs.len() <= 3 && word_count <= 40 {
+        Complexity::Moderate
+    } else {
+        Complexity::Involved
+    };
+
+    let (time, basis) = if let Some(stated) = stated_time(&words) {
+        (stated, EstimateBasis::StatedInText)
+    } else if !verbs.is_empty() {
+        (
+            Rational32::from_integer(verb_time),
+            EstimateBasis::Verbs(verbs),
+        )
+    } else {
+        // A minute for every ten words, but never less than a minute
+        let minutes = (word_count + 9) / 10;
+        (
+            Rational32::from_integer(minutes.max(1)),
+            EstimateBasis::WordCount,
+        )
+    };
+
+    StepEstimate {
+        time,
+        reading_time,
+        complexity,
+        basis,
+    }
+}
+
+/// Checks to see if a word is one of the simple inflections of a verb
+///
+/// Handles "stir", "stirs", "stirring", "stirred", "bake", "baking", and the like.
+fn is_form_of(word: &str, verb: &str) -> bool {
+    if word == verb {
+        return true;
+    }
+    let stem = verb.trim_end_matches('e');
+    if word.len() <= stem.len() || !word.starts_with(stem) {
+        return false;
+    }
+    let rest = &word[stem.len()..];
+    // Allow a doubled final consonant, as in "stirring" or "chopped"
+    let rest = match (rest.chars().next(), stem.chars().last()) {
+        (Some(a), Some(b)) if a == b => &rest[a.len_utf8()..],
+        _ => rest,
+    };
+    ["s", "es", "ed", "d", "ing"].contains(&rest)
+}
+
+/// Searches the words of a step for a stated time, such as "20 minutes" or "1 hour"
+///
+/// Ranges ("10-15 minutes") resolve to their upper bound, as it is better for a schedule to
+/// overestimate. Multiple stated times are added together, and None is returned if their
+/// total overflows.
+fn stated_time(words: &[String]) -> Option<Rational32> {
+    let mut total: Option<Rational32> = None;
+    for (i, word) in words.iter().enumerate().skip(1) {
+        if unit_minutes(word).is_none() {
+            continue;
+        }
+        let phrase = format!("{} {}", words[i - 1], word);
+        if let Ok(minutes) = parse_duration(&phrase) {
+            let so_far = total.unwrap_or_else(|| Rational32::from_integer(0));
+            total = Some(checked_add(so_far, minutes)?);
+        }
+    }
+    total
+}
@@ -0,0 +1,782 @@
+//! This module contains nutrition goals that change over time
+//!
+//! A `NutritionTarget` is a daily range for a single nutrient. Targets are grouped into
+//! `GoalPeriod`s, phases such as eight weeks of cutting followed by maintenance, and a
+//! `GoalSchedule` holds the phases in order. Each phase runs from its start date until the day
+//! before the next phase starts, or until its own end date if that comes first, so reports and
+//! plans switch targets on the boundary dates without any bookkeeping by the caller.
+//!
+//! Besides targets for the nutrients every Nutrition has room for, such as a calorie range, a
+//! period can set daily minimums for micronutrients, by short code. Given to the optimizer as
+//! an `Objective::MeetNutritionGoals`, a schedule picks the recipes that bring each day
+//! closest to its period.
+//!
+//! Days are measured the same way as constraints measure them: plans by a single serving of
+//! each meal, logs by the servings actually eaten, and unrecorded nutrients count as none.
+use chrono::prelude::*;
+use food::constraint::{Constraint, Strength};
+use food::engine::{Day, MealPlan};
+use food::{Amount, Food, Fraction, Nutrient, Nutrition, Unit};
+use log::FoodLog;
+use num_rational::*;
+use store::{FoodId, FoodStore};
+
+/// A daily range for a single nutrient, in the nutrient's own unit
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NutritionTarget {
+    nutrient: Nutrient,
+    #[serde(default)]
+    min: Option<u32>,
+    #[serde(default)]
+    max: Option<u32>,
+}
+
+impl NutritionTarget {
+    /// Creates a new target for the nutrient, without any bounds
+    pub fn new(nutrient: Nutrient) -> NutritionTarget {
+        NutritionTarget {
+            nutrient,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Creates a new target for the nutrient, between the given bounds inclusive
+    pub fn between(nutrient: Nutrient, min: u32, max: u32) -> NutritionTarget {
+        NutritionTarget {
+            nutrient,
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    /// Returns the nutrient the target is for
+    pub fn get_nutrient(&self) -> Nutrient {
+        self.nutrient
+    }
+
+    /// Returns the least of the nutrient to eat each day, if there is a lower bound
+    pub fn get_min(&self) -> Option<u32> {
+        self.min
+    }
+
+    /// Sets the least of the nutrient to eat each day
+    pub fn set_min(&mut self, min: u32) -> &mut Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Returns the most of the nutrient to eat each day, if there is an upper bound
+    pub fn get_max(&self) -> Option<u32> {
+        self.max
+    }
+
+    /// Sets the most of the nutrient to eat each day
+    pub fn set_max(&mut self, max: u32) -> &mut Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Returns how far a day's total falls outside the target, negative if under the lower
+    /// bound, positive if over the upper bound, and zero within it
+    pub fn deviation(&self, total: Rational32) -> Rational32 {
+        let zero = Rational32::from_integer(0);
+        let min = self.min.map(|x| Rational32::from_integer(x as i32));
+        let max = self.max.map(|x| Rational32::from_integer(x as i32));
+        match (min, max) {
+            (Some(min), _) if total < min => total - min,
+            (_, Some(max)) if total > max => total - max,
+            _ => zero,
+        }
+    }
+
+    /// Returns the constraints keeping each day of a plan within the target
+    pub fn constraints(&self, strength: Strength) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+        if let Some(amount) = self.min {
+            constraints.push(Constraint::MinNutrientPerDay {
+                nutrient: self.nutrient,
+                amount,
+                strength,
+            });
+        }
+        if let Some(amount) = self.max {
+            constraints.push(Constraint::MaxNutrientPerDay {
+                nutrient: self.nutrient,
+                amount,
+                strength,
+            });
+        }
+        constraints
+    }
+}
+
+/// A phase of a goal schedule, with the targets and micronutrient minimums that apply during
+/// it
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use num_rational::Rational32;
+/// use time_for_food::food::constraint::*;
+/// use time_for_food::food::engine::*;
+/// use time_for_food::food::goal::*;
+/// use time_for_food::food::optimizer::*;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let recipe = |name: &str, minutes: i32, calories: i32| {
+///     let mut nutrition = Nutrition::new();
+///     nutrition.set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(calories)));
+///     Food::new_from_recipe(
+///         RecipeBuilder::new(name)
+///             .add_step(Step::new("cook", Rational32::from_integer(minutes)))
+///             .set_nutrition(nutrition)
+///             .build_with_defaults(),
+///     )
+/// };
+/// let recipes = [recipe("salad", 15, 400), recipe("curry", 45, 900), recipe("burger", 90, 1300)];
+///
+/// // An hour for lunch, and two for dinner
+/// let date = NaiveDate::from_ymd(2024, 1, 8);
+/// let time = |h| NaiveTime::from_hms(h, 0, 0);
+/// let mut template = MealPlan::new();
+/// template.insert_block(date, Block::new(time(12), time(13), None));
+/// template.insert_block(date, Block::new(time(17), time(19), None));
+///
+/// let mut maintenance = GoalPeriod::new("maintenance", date);
+/// maintenance.set_calories(1800, 2200);
+/// let mut goals = GoalSchedule::new();
+/// goals.add_period(maintenance);
+/// let mut optimizer = Optimizer::new(5);
+/// optimizer.set_candidates(50).add_objective(Objective::MeetNutritionGoals {
+///     goals: goals.clone(),
+///     scoring: GoalScoring::Relative,
+///     weight: 1,
+/// });
+/// let best = optimizer.optimize(&template, &recipes).unwrap();
+///
+/// // The burger doesn't fit in lunch, so lunch is a curry to reach the calories
+/// let day = best.get_plan().get_day(date).unwrap();
+/// assert_eq!(day.get_blocks()[0].get_food().unwrap().get_name().get_short_code(), "curry");
+/// assert!(goals.report(best.get_plan())[0].is_met());
+/// # }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GoalPeriod {
+    name: String,
+    start: NaiveDate,
+    #[serde(default)]
+    end: Option<NaiveDate>,
+    #[serde(default)]
+    targets: Vec<NutritionTarget>,
+    /// Daily minimums of micronutrients, by short code, stored apart as Amount has no Debug
+    #[serde(default)]
+    micronutrients: Vec<(String, Unit, Fraction)>,
+}
+
+impl GoalPeriod {
+    /// Creates a new period, without any targets, starting on the given date
+    pub fn new(name: &str, start: NaiveDate) -> GoalPeriod {
+        GoalPeriod {
+            name: name.to_string(),
+            start,
+            end: None,
+            targets: Vec::new(),
+            micronutrients: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the period
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the first day of the period
+    pub fn get_start(&self) -> NaiveDate {
+        self.start
+    }
+
+    /// Returns the last day of the period, if it ends before the next period starts
+    pub fn get_end(&self) -> Option<NaiveDate> {
+        self.end
+    }
+
+    /// Sets the last day of the period, inclusive
+    ///
+    /// Days after it, and before the next period, have no targets at all.
+    pub fn set_end(&mut self, end: NaiveDate) -> &mut Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Returns the targets that apply during the period
+    pub fn get_targets(&self) -> &[NutritionTarget] {
+        &self.targets
+    }
+
+    /// Adds a target, replacing any existing target for the same nutrient
+    pub fn add_target(&mut self, target: NutritionTarget) -> &mut Self {
+        self.targets.retain(|x| x.nutrient != target.nutrient);
+        self.targets.push(target);
+        self
+    }
+
+    /// Returns the target for the nutrient, if the period has one
+    pub fn get_target(&self, nutrient: Nutrient) -> Option<&NutritionTarget> {
+        self.targets.iter().find(|x| x.nutrient == nutrient)
+    }
+
+    /// Sets the range of calories to eat each day, in kilocalories
+    pub fn set_calories(&mut self, min: u32, max: u32) -> &mut Self {
+        self.add_target(NutritionTarget::between(Nutrient::Calories, min, max))
+    }
+
+    /// Sets the least of a micronutrient, by short code, to eat each day, replacing any
+    /// existing minimum for it
+    pub fn add_micronutrient_minimum(&mut self, short_code: &str, minimum: Amount) -> &mut Self {
+        self.micronutrients.retain(|x| x.0 != short_code);
+        self.micronutrients.push((
+            short_code.to_string(),
+            minimum.get_unit(),
+            Fraction::from_rational(minimum.get_amount()),
+        ));
+        self
+    }
+
+    /// Returns the micronutrient minimums, by short code, in the order they were added
+    pub fn get_micronutrient_minimums(&self) -> Vec<(&str, Amount)> {
+        self.micronutrients
+            .iter()
+            .map(|(code, unit, min)| (&**code, Amount::new(*unit, min.to_rational())))
+            .collect()
+    }
+
+    /// Measures a single day's nutrition against the period, targets first, then
+    /// micronutrient minimums
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::goal::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut eaten = Nutrition::new();
+    /// eaten
+    ///     .set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(1800)))
+    ///     .set_protein(Amount::new(Unit::Gram, Rational32::from_integer(80)))
+    ///     .set_micronutrient("iron", Amount::new(Unit::Milligram, Rational32::from_integer(12)));
+    ///
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut period = GoalPeriod::new("maintenance", date);
+    /// let iron = Amount::new(Unit::Milligram, Rational32::from_integer(18));
+    /// period
+    ///     .set_calories(1800, 2000)
+    ///     .add_target(NutritionTarget::between(Nutrient::Protein, 50, 70))
+    ///     .add_micronutrient_minimum("iron", iron);
+    ///
+    /// let progress = period.measure(date, &eaten);
+    /// assert!(progress[0].is_met());
+    /// assert_eq!(progress[1].get_surplus(), Rational32::from_integer(10));
+    /// assert_eq!(progress[2].get_nutrient(), &NutrientKey::Micronutrient("iron".to_string()));
+    /// assert_eq!(progress[2].get_deficit(), Rational32::from_integer(6));
+    /// # }
+    /// ```
+    pub fn measure(&self, date: NaiveDate, nutrition: &Nutrition) -> Vec<TargetProgress> {
+        let zero = Rational32::from_integer(0);
+        let whole = |x: Option<u32>| x.map(|x| Rational32::from_integer(x as i32));
+        let mut progress: Vec<TargetProgress> = self
+            .targets
+            .iter()
+            .map(|target| TargetProgress {
+                date,
+                period: self.name.clone(),
+                nutrient: NutrientKey::Nutrient(target.nutrient),
+                total: nutrition.get_nutrient(target.nutrient).unwrap_or(zero),
+                min: whole(target.min),
+                max: whole(target.max),
+                untrusted: Vec::new(),
+            })
+            .collect();
+        for (short_code, unit, minimum) in &self.micronutrients {
+            // Micronutrients recorded in a unit that can't be compared count as none
+            let total = nutrition
+                .get_micronutrient(short_code)
+                .and_then(|x| x.convert_to(*unit).ok())
+                .map(|x| x.get_amount())
+                .unwrap_or(zero);
+            progress.push(TargetProgress {
+                date,
+                period: self.name.clone(),
+                nutrient: NutrientKey::Micronutrient(short_code.clone()),
+                total,
+                min: Some(minimum.to_rational()),
+                max: None,
+                untrusted: Vec::new(),
+            });
+        }
+        progress
+    }
+}
+
+/// Identifies what a `TargetProgress` measures
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NutrientKey {
+    /// One of the nutrients every Nutrition has room for, in the nutrient's own unit
+    Nutrient(Nutrient),
+    /// A micronutrient, by short code, in the unit of its minimum
+    Micronutrient(String),
+}
+
+/// How missing a nutrition goal is scored, lower is better
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GoalScoring {
+    /// The amount each nutrient is over or under by, in the nutrient's own unit
+    ///
+    /// Nutrients measured in large numbers, like calories, dominate the score.
+    Absolute,
+    /// The amount each nutrient is over or under by, as a percentage of the bound it crossed,
+    /// so being 10% short on protein counts the same as being 10% over on calories
+    Relative,
+}
+
+/// How a single day measured up against one of its targets, or one of its micronutrient
+/// minimums
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TargetProgress {
+    date: NaiveDate,
+    period: String,
+    nutrient: NutrientKey,
+    total: Rational32,
+    min: Option<Rational32>,
+    max: Option<Rational32>,
+    untrusted: Vec<String>,
+}
+
+impl TargetProgress {
+    /// Returns the day measured
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the name of the period in effect on the day
+    pub fn get_period(&self) -> &str {
+        &self.period
+    }
+
+    /// Returns what was measured
+    pub fn get_nutrient(&self) -> &NutrientKey {
+        &self.nutrient
+    }
+
+    /// Returns the day's total, in the nutrient's own unit, or for micronutrients the unit of
+    /// the minimum
+    pub fn get_total(&self) -> Rational32 {
+        self.total
+    }
+
+    /// Returns the least to eat, if there is a lower bound
+    pub fn get_min(&self) -> Option<Rational32> {
+        self.min
+    }
+
+    /// Returns the most to eat, if there is an upper bound
+    pub fn get_max(&self) -> Option<Rational32> {
+        self.max
+    }
+
+    /// Returns how far the day fell outside the target, negative if under the lower bound,
+    /// positive if over the upper bound, and zero within it
+    pub fn get_deviation(&self) -> Rational32 {
+        self.get_surplus() - self.get_deficit()
+    }
+
+    /// Returns how far the total falls short of the minimum, zero if it doesn't
+    pub fn get_deficit(&self) -> Rational32 {
+        match self.min {
+            Some(min) if self.total < min => min - self.total,
+            _ => Rational32::from_integer(0),
+        }
+    }
+
+    /// Returns how far the total goes over the maximum, zero if it doesn't
+    pub fn get_surplus(&self) -> Rational32 {
+        match self.max {
+            Some(max) if self.total > max => self.total - max,
+            _ => Rational32::from_integer(0),
+        }
+    }
+
+    /// Returns the short codes of the day's foods left out of the total because their
+    /// nutrition isn't trusted, see `GoalSchedule::report_trusted`
+    pub fn get_untrusted(&self) -> &[String] {
+        &self.untrusted
+    }
+
+    /// Returns true if the day was within the target
+    pub fn is_met(&self) -> bool {
+        self.get_deviation() == Rational32::from_integer(0)
+    }
+
+    /// Scores how far the total is from the target, zero if it is met
+    pub fn score(&self, scoring: GoalScoring) -> Rational32 {
+        let zero = Rational32::from_integer(0);
+        let relative = |miss: Rational32, bound: Option<Rational32>| match bound {
+            Some(bound) if bound > zero => miss * 100 / bound,
+            _ => miss,
+        };
+        match scoring {
+            GoalScoring::Absolute => self.get_deficit() + self.get_surplus(),
+            GoalScoring::Relative => {
+                relative(self.get_deficit(), self.min) + relative(self.get_surplus(), self.max)
+            }
+        }
+    }
+}
+
+/// Nutrition goals that change over time, as a sequence of dated periods
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use time_for_food::food::goal::*;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let start = NaiveDate::from_ymd(2024, 1, 1);
+/// let mut cutting = GoalPeriod::new("cutting", start);
+/// cutting.add_target(NutritionTarget::between(Nutrient::Calories, 1800, 2000));
+/// let mut maintenance = GoalPeriod::new("maintenance", start + chrono::Duration::weeks(8));
+/// maintenance.add_target(NutritionTarget::between(Nutrient::Calories, 2300, 2500));
+///
+/// let mut schedule = GoalSchedule::new();
+/// schedule.add_period(maintenance).add_period(cutting);
+///
+/// let last_cut = NaiveDate::from_ymd(2024, 2, 25);
+/// assert_eq!(schedule.period_on(last_cut).unwrap().get_name(), "cutting");
+/// let first_maintained = NaiveDate::from_ymd(2024, 2, 26);
+/// assert_eq!(schedule.period_on(first_maintained).unwrap().get_name(), "maintenance");
+/// assert!(schedule.period_on(NaiveDate::from_ymd(2023, 12, 31)).is_none());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GoalSchedule {
+    /// Sorted by start date, without two periods starting on the same day
+    periods: Vec<GoalPeriod>,
+}
+
+impl GoalSchedule {
+    /// Creates a new, empty, schedule
+    pub fn new() -> GoalSchedule {
+        GoalSchedule::default()
+    }
+
+    /// Returns the periods, sorted by start date
+    pub fn get_periods(&self) -> &[GoalPeriod] {
+        &self.periods
+    }
+
+    /// Adds a period, replacing any period starting on the same day
+    pub fn add_period(&mut self, period: GoalPeriod) -> &mut Self {
+        match self
+            .periods
+            .binary_search_by(|x| x.start.cmp(&period.start))
+        {
+            Ok(index) => self.periods[index] = period,
+            Err(index) => self.periods.insert(index, period),
+        }
+        self
+    }
+
+    /// Removes the period starting on the given date, returning it
+    pub fn remove_period(&mut self, start: NaiveDate) -> Option<GoalPeriod> {
+        match self.periods.binary_search_by(|x| x.start.cmp(&start)) {
+            Ok(index) => Some(self.periods.remove(index)),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns the first and last days the period at the index is in effect, the last day
+    /// being None if it runs on forever
+    fn effective_range(&self, index: usize) -> (NaiveDate, Option<NaiveDate>) {
+        let period = &self.periods[index];
+        let next = self.periods.get(index + 1).and_then(|x| x.start.pred_opt());
+        let end = match (period.end, next) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        };
+        (period.start, end)
+    }
+
+    /// Returns the period in effect on the given date, if any
+    pub fn period_on(&self, date: NaiveDate) -> Option<&GoalPeriod> {
+        let index = match self.periods.binary_search_by(|x| x.start.cmp(&date)) {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+        match self.effective_range(index) {
+            (_, Some(end)) if date > end => None,
+            _ => Some(&self.periods[index]),
+        }
+    }
+
+    /// Returns the targets in effect on the given date, empty if no period is in effect
+    pub fn targets_on(&self, date: NaiveDate) -> &[NutritionTarget] {
+        self.period_on(date).map_or(&[], |x| x.get_targets())
+    }
+
+    /// Returns constraints keeping each day of a plan within the targets in effect on it
+    ///
+    /// Every target becomes one or two constraints, only applying while its period is in
+    /// effect, so they can be handed straight to the optimizer.
+    pub fn constraints(&self, strength: Strength) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+        for (index, period) in self.periods.iter().enumerate() {
+            let (start, end) = self.effective_range(index);
+            let end = end.unwrap_or(NaiveDate::MAX);
+            for target in &period.targets {
+                constraints.extend(
+                    target
+                        .constraints(strength)
+                        .into_iter()
+                        .map(|x| x.between(start, end)),
+                );
+            }
+        }
+        constraints
+    }
+
+    /// Measures every day of the plan against the period in effect on it, in date order
+    ///
+    /// Each meal counts as a single serving of its food. See `GoalPeriod::measure`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::goal::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut nutrition = Nutrition::new();
+    /// nutrition.set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(2100)));
+    /// let stew = RecipeBuilder::new("stew")
+    ///     .set_nutrition(nutrition)
+    ///     .build_with_defaults();
+    /// let mut plan = MealPlan::new();
+    /// for day in 1..3 {
+    ///     plan.insert_block(
+    ///         NaiveDate::from_ymd(2024, 1, day),
+    ///         Block::new(
+    ///             NaiveTime::from_hms(18, 0, 0),
+    ///             NaiveTime::from_hms(19, 0, 0),
+    ///             Some(Food::new_from_recipe(stew.clone())),
+    ///         ),
+    ///     );
+    /// }
+    ///
+    /// let mut cutting = GoalPeriod::new("cutting", NaiveDate::from_ymd(2024, 1, 1));
+    /// cutting.add_target(NutritionTarget::between(Nutrient::Calories, 1800, 2000));
+    /// let mut maintenance = GoalPeriod::new("maintenance", NaiveDate::from_ymd(2024, 1, 2));
+    /// maintenance.add_target(NutritionTarget::between(Nutrient::Calories, 2000, 2200));
+    /// let mut schedule = GoalSchedule::new();
+    /// schedule.add_period(cutting).add_period(maintenance);
+    ///
+    /// let report = schedule.report(&plan);
+    /// assert_eq!(report[0].get_deviation(), Rational32::from_integer(100));
+    /// assert!(report[1].is_met());
+    /// # }
+    /// ```
+    pub fn report(&self, plan: &MealPlan) -> Vec<TargetProgress> {
+        let mut progress = Vec::new();
+        for day in plan.get_days() {
+            if let Some(period) = self.period_on(day.get_date()) {
+                progress.extend(period.measure(day.get_date(), &day_nutrition(day)));
+            }
+        }
+        progress
+    }
+
+    /// Measures every day of the plan like `report`, but leaves out foods whose nutrition the
+    /// store doesn't trust, listing them on each of the day's progress instead
+    ///
+    /// Foods the store doesn't have are counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::goal::*;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::*;
+    ///
+    /// # fn main() {
+    /// let mut nutrition = Nutrition::new();
+    /// nutrition.set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(1900)));
+    /// let stew = Food::new_from_recipe(
+    ///     RecipeBuilder::new("stew")
+    ///         .set_nutrition(nutrition)
+    ///         .build_with_defaults(),
+    /// );
+    /// // Quickly captured, with no nutrition filled in yet
+    /// let snack = Food::new_from_recipe(RecipeBuilder::new("snack").build_with_defaults());
+    /// let mut store = FoodStore::new();
+    /// store.insert_with_status(stew.clone(), FoodStatus::Verified);
+    /// store.insert(snack.clone());
+    ///
+    /// let date = NaiveDate::from_ymd(2024, 1, 1);
+    /// let time = |h| NaiveTime::from_hms(h, 0, 0);
+    /// let mut plan = MealPlan::new();
+    /// plan.insert_block(date, Block::new(time(12), time(13), Some(snack)));
+    /// plan.insert_block(date, Block::new(time(18), time(19), Some(stew)));
+    ///
+    /// let mut cutting = GoalPeriod::new("cutting", date);
+    /// cutting.set_calories(1800, 2000);
+    /// let mut schedule = GoalSchedule::new();
+    /// schedule.add_period(cutting);
+    ///
+    /// let report = schedule.report_trusted(&plan, &store);
+    /// assert_eq!(report[0].get_total(), Rational32::from_integer(1900));
+    /// assert_eq!(report[0].get_untrusted(), ["snack".to_string()]);
+    /// # }
+    /// ```
+    pub fn report_trusted(&self, plan: &MealPlan, store: &FoodStore) -> Vec<TargetProgress> {
+        let mut progress = Vec::new();
+        for day in plan.get_days() {
+            let period = match self.period_on(day.get_date()) {
+                Some(x) => x,
+                None => continue,
+            };
+            let mut total = Nutrition::new();
+            let mut untrusted = Vec::new();
+            for food in day.get_blocks().iter().filter_map(|x| x.get_food()) {
+                if is_trusted(store, food) {
+                    total.add(food.get_nutrition());
+                } else {
+                    untrusted.push(food.get_name().get_short_code().to_string());
+                }
+            }
+            progress.extend(flagged(period.measure(day.get_date(), &total), &untrusted));
+        }
+        progress
+    }
+
+    /// Measures every day of the log against the period in effect on it, in date order
+    ///
+    /// Each entry counts for the servings actually eaten.
+    pub fn report_log(&self, log: &FoodLog) -> Vec<TargetProgress> {
+        let mut dates: Vec<NaiveDate> = log.get_entries().iter().map(|x| x.get_date()).collect();
+        dates.sort();
+        dates.dedup();
+        let mut progress = Vec::new();
+        for date in dates {
+            let period = match self.period_on(date) {
+                Some(x) => x,
+                None => continue,
+            };
+            let mut total = Nutrition::new();
+            for entry in log.entries_on(date) {
+                total.add(
+                    &entry
+                        .get_food()
+                        .get_nutrition()
+                        .scaled(entry.get_servings()),
+                );
+            }
+            progress.extend(period.measure(date, &total));
+        }
+        progress
+    }
+
+    /// Measures every day of the log like `report_log`, but leaves out foods whose nutrition
+    /// the store doesn't trust, listing them on each of the day's progress instead
+    ///
+    /// Foods the store doesn't have are counted.
+    pub fn report_log_trusted(&self, log: &FoodLog, store: &FoodStore) -> Vec<TargetProgress> {
+        let mut dates: Vec<NaiveDate> = log.get_entries().iter().map(|x| x.get_date()).collect();
+        dates.sort();
+        dates.dedup();
+        let mut progress = Vec::new();
+        for date in dates {
+            let period = match self.period_on(date) {
+                Some(x) => x,
+                None => continue,
+            };
+            let mut total = Nutrition::new();
+            let mut untrusted = Vec::new();
+            for entry in log.entries_on(date) {
+                let food = entry.get_food();
+                if is_trusted(store, food) {
+                    total.add(&food.get_nutrition().scaled(entry.get_servings()));
+                } else {
+                    untrusted.push(food.get_name().get_short_code().to_string());
+                }
+            }
+            progress.extend(flagged(period.measure(date, &total), &untrusted));
+        }
+        progress
+    }
+
+    /// Scores a single day against the period in effect on it, zero if there is none or every
+    /// target is met
+    ///
+    /// Each meal counts as a single serving of its food.
+    pub fn score_day(&self, day: &Day, scoring: GoalScoring) -> Rational32 {
+        let zero = Rational32::from_integer(0);
+        match self.period_on(day.get_date()) {
+            Some(period) => period
+                .measure(day.get_date(), &day_nutrition(day))
+                .iter()
+                .fold(zero, |total, x| total + x.score(scoring)),
+            None => zero,
+        }
+    }
+}
+
+/// Returns true unless the store has the food with nutrition that isn't fit for reports
+fn is_trusted(store: &FoodStore, food: &Food) -> bool {
+    store
+        .get_status(&FoodId::of(food))
+        .is_none_or(|x| x.has_trusted_nutrition())
+}
+
+/// Marks each of a day's progress with the foods left out of it
+fn flagged(mut progress: Vec<TargetProgress>, untrusted: &[String]) -> Vec<TargetProgress> {
+    for x in &mut progress {
+        x.untrusted = untrusted.to_vec();
+    }
+    progress
+}
+
+/// Totals the nutrition of a single serving of every meal on a day
+fn day_nutrition(day: &Day) -> Nutrition {
+    let mut total = Nutrition::new();
+    for food in day.get_blocks().iter().filter_map(|x| x.get_food()) {
+        total.add(food.get_nutrition());
+    }
+    total
+}
@@ -0,0 +1,216 @@
+//! This module contains helpers for working out nutrition targets from body metrics
+//!
+//! Applications shouldn't each have to carry their own copy of the energy formulas, so the
+//! common ones live here. Energy needs are estimated from a person's resting energy, by the
+//! Mifflin-St Jeor or revised Harris-Benedict equation, scaled by how active they are, and then
+//! split into macronutrient ranges using the Acceptable Macronutrient Distribution Ranges.
+//!
+//! Everything here is an estimate for healthy adults, and only ever a starting point, the
+//! targets produced are ordinary `NutritionTarget`s that can be adjusted like any other.
+use food::goal::NutritionTarget;
+use food::{Fraction, Nutrient};
+use num_rational::*;
+
+/// The sex used by the energy equations
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Sex {
+    Female,
+    Male,
+}
+
+/// How active a person is day to day, beyond resting
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ActivityLevel {
+    /// Little or no exercise
+    Sedentary,
+    /// Light exercise one to three days a week
+    Light,
+    /// Moderate exercise three to five days a week
+    Moderate,
+    /// Hard exercise six or seven days a week
+    Active,
+    /// Hard exercise and a physical job, or training twice a day
+    VeryActive,
+}
+
+impl ActivityLevel {
+    /// Returns the factor resting energy is multiplied by to give daily energy needs
+    pub fn get_factor(self) -> Rational32 {
+        match self {
+            ActivityLevel::Sedentary => Rational32::new(6, 5),
+            ActivityLevel::Light => Rational32::new(11, 8),
+            ActivityLevel::Moderate => Rational32::new(31, 20),
+            ActivityLevel::Active => Rational32::new(69, 40),
+            ActivityLevel::VeryActive => Rational32::new(19, 10),
+        }
+    }
+}
+
+/// An equation estimating resting energy needs
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EnergyFormula {
+    /// Mifflin-St Jeor (1990), the usual choice
+    MifflinStJeor,
+    /// Harris-Benedict, as revised by Roza and Shizgal (1984)
+    HarrisBenedict,
+}
+
+/// The body metrics nutrition targets are worked out from
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BodyMetrics {
+    sex: Sex,
+    age_years: u32,
+    weight: Fraction,
+    height: Fraction,
+}
+
+impl BodyMetrics {
+    /// Creates a new set of metrics, from an age in years, a weight in kilograms, and a height
+    /// in centimeters
+    pub fn new(
+        sex: Sex,
+        age_years: u32,
+        weight_kg: Rational32,
+        height_cm: Rational32,
+    ) -> BodyMetrics {
+        BodyMetrics {
+            sex,
+            age_years,
+            weight: Fraction::from_rational(weight_kg),
+            height: Fraction::from_rational(height_cm),
+        }
+    }
+
+    /// Returns the sex used by the energy equations
+    pub fn get_sex(&self) -> Sex {
+        self.sex
+    }
+
+    /// Returns the age, in years
+    pub fn get_age_years(&self) -> u32 {
+        self.age_years
+    }
+
+    /// Returns the weight, in kilograms
+    pub fn get_weight_kg(&self) -> Rational32 {
+        self.weight.to_rational()
+    }
+
+    /// Returns the height, in centimeters
+    pub fn get_height_cm(&self) -> Rational32 {
+        self.height.to_rational()
+    }
+
+    /// Estimates the energy burned at rest each day, in kilocalories
+    pub fn resting_energy(&self, formula: EnergyFormula) -> Rational32 {
+        let weight = self.get_weight_kg();
+        let height = self.get_height_cm();
+        let age = Rational32::from_integer(self.age_years as i32);
+        // Coefficients for weight, height, and age, and the constant
+        let (w, h, a, c) = match (formula, self.sex) {
+            (EnergyFormula::MifflinStJeor, Sex::Male) => (
+                Rational32::from_integer(10),
+                Rational32::new(25, 4),
+                Rational32::from_integer(5),
+                Rational32::from_integer(5),
+            ),
+            (EnergyFormula::MifflinStJeor, Sex::Female) => (
+                Rational32::from_integer(10),
+                Rational32::new(25, 4),
+                Rational32::from_integer(5),
+                Rational32::from_integer(-161),
+            ),
+            (EnergyFormula::HarrisBenedict, Sex::Male) => (
+                Rational32::new(13397, 1000),
+                Rational32::new(4799, 1000),
+                Rational32::new(5677, 1000),
+                Rational32::new(88362, 1000),
+            ),
+            (EnergyFormula::HarrisBenedict, Sex::Female) => (
+                Rational32::new(9247, 1000),
+                Rational32::new(3098, 1000),
+                Rational32::new(433, 100),
+                Rational32::new(447593, 1000),
+            ),
+        };
+        w * weight + h * height - a * age + c
+    }
+
+    /// Estimates the energy needed each day to keep weight steady, in kilocalories
+    pub fn daily_energy(&self, formula: EnergyFormula, activity: ActivityLevel) -> Rational32 {
+        self.resting_energy(formula) * activity.get_factor()
+    }
+
+    /// Works out daily nutrition targets
+    ///
+    /// The calorie target is the daily energy needs plus the adjustment, negative to lose
+    /// weight and positive to gain it, give or take 5%. Protein, fat, and carbohydrates get the
+    /// ranges of that many calories recommended for adults: 10-35%, 20-35%, and 45-65%. Fiber
+    /// gets a minimum of 14 g per 1000 kcal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::metrics::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let metrics = BodyMetrics::new(
+    ///     Sex::Female,
+    ///     30,
+    ///     Rational32::from_integer(60),
+    ///     Rational32::from_integer(165),
+    /// );
+    /// // 600 + 1031.25 - 150 - 161
+    /// let resting = metrics.resting_energy(EnergyFormula::MifflinStJeor);
+    /// assert_eq!(resting, Rational32::new(5281, 4));
+    ///
+    /// let targets = metrics.targets(
+    ///     EnergyFormula::MifflinStJeor,
+    ///     ActivityLevel::Sedentary,
+    ///     -300,
+    /// );
+    /// let calories = &targets[0];
+    /// assert_eq!(calories.get_nutrient(), Nutrient::Calories);
+    /// // 1320.25 * 1.2 - 300 = 1284.3, give or take 5%
+    /// assert_eq!(calories.get_min(), Some(1220));
+    /// assert_eq!(calories.get_max(), Some(1349));
+    /// # }
+    /// ```
+    pub fn targets(
+        &self,
+        formula: EnergyFormula,
+        activity: ActivityLevel,
+        adjustment: i32,
+    ) -> Vec<NutritionTarget> {
+        let calories = self.daily_energy(formula, activity) + Rational32::from_integer(adjustment);
+        let whole = |x: Rational32| -> u32 {
+            let x = x.round().to_integer();
+            if x < 0 {
+                0
+            } else {
+                x as u32
+            }
+        };
+        // Grams of a nutrient providing the given percentage of the calories
+        let grams = |percent: i32, per_gram: i32| {
+            whole(calories * Rational32::new(percent, 100 * per_gram))
+        };
+        let mut fiber = NutritionTarget::new(Nutrient::Fiber);
+        fiber.set_min(whole(calories * Rational32::new(14, 1000)));
+        vec![
+            NutritionTarget::between(
+                Nutrient::Calories,
+                whole(calories * Rational32::new(95, 100)),
+                whole(calories * Rational32::new(105, 100)),
+            ),
+            NutritionTarget::between(Nutrient::Protein, grams(10, 4), grams(35, 4)),
+            NutritionTarget::between(Nutrient::Fat, grams(20, 9), grams(35, 9)),
+            NutritionTarget::between(Nutrient::Carbohydrates, grams(45, 4), grams(65, 4)),
+            fiber,
+        ]
+    }
+}
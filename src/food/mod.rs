@@ -1,20 +1,41 @@
 //! This module contains dumb data structures describing real-world foods
+pub mod appliance;
+pub mod constraint;
+pub mod cooklang;
+pub mod diet;
 pub mod engine;
-use chrono::Duration;
+pub mod estimate;
+pub mod goal;
+pub mod metrics;
+pub mod optimizer;
+pub mod plural;
+pub mod scheduler;
+pub mod score;
+pub mod script;
+pub mod thermal;
+use self::diet::{Allergen, DietProfile, DietTag};
+use self::plural::PluralCategory;
+use self::script::{RenderedText, ScriptHint, TextDirection};
+use chrono::{DateTime, Duration, Utc};
 use num_rational::*;
-use std::collections::HashMap;
+use settings::Settings;
+use std::collections::{BTreeMap, BTreeSet};
+use store::FoodId;
+use units::ConversionTable;
 
 /// Describes a specific, real world food
 ///
 /// Contains directions on how to prepare the food, as well as how much
 /// of the food is made.
 ///
-/// A food can either be a Recipe (composite of multiple foods) or
-/// a RawFood (single ingredient food intended as the atomic building blocks of recipes)
+/// A food can either be a Recipe (composite of multiple foods),
+/// a RawFood (single ingredient food intended as the atomic building blocks of recipes),
+/// or an ExternalMeal (a placeholder for food someone else made, such as a restaurant meal)
 #[derive(Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Food {
     Recipe(Recipe),
     RawFood(RawFood),
+    External(ExternalMeal),
 }
 
 impl Food {
@@ -26,12 +47,128 @@ impl Food {
     pub fn new_from_recipe(food: Recipe) -> Food {
         Food::Recipe(food)
     }
+    /// Converts an ExternalMeal into a Food
+    pub fn new_from_external(food: ExternalMeal) -> Food {
+        Food::External(food)
+    }
 
     /// Returns the name of the Food
     pub fn get_name(&self) -> &IString {
         match *self {
             Food::RawFood(ref x) => &x.name,
             Food::Recipe(ref x) => &x.name,
+            Food::External(ref x) => &x.name,
+        }
+    }
+
+    /// Returns the tags attached to the food
+    ///
+    /// ExternalMeals have no tags, so always return an empty slice.
+    pub fn get_tags(&self) -> &[String] {
+        match *self {
+            Food::RawFood(ref x) => x.get_tags(),
+            Food::Recipe(ref x) => x.get_tags(),
+            Food::External(_) => &[],
+        }
+    }
+
+    /// Returns true if the food has the given tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.get_tags().iter().any(|x| x == tag)
+    }
+
+    /// Returns the diets the food suits, see `Recipe::get_diet_tags` for recipes
+    ///
+    /// Nothing is known about what goes into an ExternalMeal, so it suits no diets.
+    pub fn get_diet_tags(&self) -> BTreeSet<DietTag> {
+        match *self {
+            Food::RawFood(ref x) => x.get_diet_tags().clone(),
+            Food::Recipe(ref x) => x.get_diet_tags(),
+            Food::External(_) => BTreeSet::new(),
+        }
+    }
+
+    /// Returns the allergens the food contains, see `Recipe::get_allergens` for recipes, or
+    /// None if they aren't known
+    ///
+    /// Nothing is known about what goes into an ExternalMeal, so its allergens are never known.
+    pub fn get_allergens(&self) -> Option<BTreeSet<Allergen>> {
+        match *self {
+            Food::RawFood(ref x) => Some(x.get_allergens().clone()),
+            Food::Recipe(ref x) => x.get_allergens(),
+            Food::External(_) => None,
+        }
+    }
+
+    /// Returns true if someone with the given profile can eat the food, it suits all of their
+    /// diets and contains none of the allergens they avoid
+    ///
+    /// Foods with unknown allergens are ruled out for anyone avoiding an allergen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate time_for_food;
+    /// use time_for_food::food::diet::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let takeaway = Food::new_from_external(ExternalMeal::new(IString::new("takeaway")));
+    /// assert!(takeaway.satisfies(&DietProfile::new()));
+    ///
+    /// let mut nut_allergy = DietProfile::new();
+    /// nut_allergy.avoid_allergen(Allergen::TreeNuts);
+    /// assert!(!takeaway.satisfies(&nut_allergy));
+    /// # }
+    /// ```
+    pub fn satisfies(&self, profile: &DietProfile) -> bool {
+        profile.permits(&self.get_diet_tags(), self.get_allergens().as_ref())
+    }
+
+    /// Returns true if the food is, or is made with, the given ingredient
+    ///
+    /// An ingredient is named by a short code, the short code of a generic food, or a tag, so
+    /// "dairy" matches anything tagged "dairy". Recipes are searched recursively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let butter = RawFoodBuilder::new("butter")
+    ///     .add_tag("dairy")
+    ///     .set_serving_size(Unit::Gram, Rational32::from_integer(14))
+    ///     .set_nutrition(Nutrition::new())
+    ///     .build_raw_food()
+    ///     .unwrap();
+    /// let pastry = RecipeBuilder::new("pastry")
+    ///     .add_food(Food::new_from_raw_food(butter), Unit::Gram, Rational32::from_integer(150))
+    ///     .build_with_defaults();
+    /// let pie = RecipeBuilder::new("pie")
+    ///     .add_food(Food::new_from_recipe(pastry), Unit::Gram, Rational32::from_integer(200))
+    ///     .build_with_defaults();
+    ///
+    /// let pie = Food::new_from_recipe(pie);
+    /// assert!(pie.contains_ingredient("dairy"));
+    /// assert!(pie.contains_ingredient("butter"));
+    /// assert!(!pie.contains_ingredient("eggs"));
+    /// # }
+    /// ```
+    pub fn contains_ingredient(&self, ingredient: &str) -> bool {
+        if self.get_name().get_short_code() == ingredient || self.has_tag(ingredient) {
+            return true;
+        }
+        match *self {
+            Food::RawFood(ref x) => x.get_generic() == Some(ingredient),
+            Food::Recipe(ref x) => x
+                .foods
+                .iter()
+                .any(|(food, _)| food.contains_ingredient(ingredient)),
+            Food::External(_) => false,
         }
     }
 
@@ -40,26 +177,252 @@ impl Food {
         match *self {
             Food::RawFood(ref x) => &x.nutrition,
             Food::Recipe(ref x) => &x.nutrition,
+            Food::External(ref x) => &x.nutrition,
         }
     }
 
-    /// Decomposes a food into a list of ingredients
+    /// Returns where the nutritional value of the food came from
     ///
-    /// TODO: Implement
-    pub fn decompose(&self) -> Vec<Food> {
-        Vec::new()
+    /// A recipe is only as trustworthy as its least trustworthy part, so for recipes this is
+    /// the weakest source among the recipe itself and all of its component foods. External
+    /// meals are always estimates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut flour = RawFood::new(
+    ///     IString::new("flour"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(30)),
+    ///     Nutrition::new(),
+    /// );
+    /// flour.set_nutrition_source(NutritionSource::Usda);
+    /// let mut jam = RawFood::new(
+    ///     IString::new("jam"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(20)),
+    ///     Nutrition::new(),
+    /// );
+    /// jam.set_nutrition_source(NutritionSource::LabelScan);
+    ///
+    /// let mut tart = RecipeBuilder::new("jam-tart")
+    ///     .add_food(Food::new_from_raw_food(flour), Unit::Gram, Rational32::from_integer(200))
+    ///     .add_food(Food::new_from_raw_food(jam), Unit::Gram, Rational32::from_integer(40))
+    ///     .build_with_defaults();
+    /// tart.set_nutrition_source(NutritionSource::Usda);
+    ///
+    /// let tart = Food::new_from_recipe(tart);
+    /// assert_eq!(tart.get_nutrition_source(), NutritionSource::LabelScan);
+    /// # }
+    /// ```
+    pub fn get_nutrition_source(&self) -> NutritionSource {
+        match *self {
+            Food::RawFood(ref x) => x.nutrition_source,
+            Food::Recipe(ref x) => x
+                .foods
+                .iter()
+                .map(|(food, _)| food.get_nutrition_source())
+                .fold(x.nutrition_source, ::std::cmp::min),
+            Food::External(_) => NutritionSource::Estimated,
+        }
+    }
+
+    /// Decomposes a food into a flat list of ingredients, and how much of each is needed
+    ///
+    /// Recipes are broken down recursively, with each component recipe's ingredients scaled
+    /// to the amount of it used, until only raw foods and external meals remain. Ingredients
+    /// used in several places are merged into one entry, as long as their amounts can be
+    /// converted to the same unit. A component recipe that can't be scaled, as it makes no
+    /// servings or has an empty serving size, is kept whole.
+    ///
+    /// A raw food decomposes to a serving of itself, and an external meal to one of itself.
+    ///
+    /// Amounts are converted with the household's unit sizes and densities, so a component
+    /// recipe used by volume but measured by mass can still be scaled, and ingredients used by
+    /// both volume and mass are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::settings::Settings;
+    /// use time_for_food::store::FoodId;
+    ///
+    /// # fn main() {
+    /// let settings = Settings::new();
+    /// let butter = Food::new_from_raw_food(RawFood::new(
+    ///     IString::new("butter"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(14)),
+    ///     Nutrition::new(),
+    /// ));
+    /// let flour = Food::new_from_raw_food(RawFood::new(
+    ///     IString::new("flour"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(30)),
+    ///     Nutrition::new(),
+    /// ));
+    ///
+    /// // Makes 400 g of pastry, in four 100 g servings
+    /// let pastry = RecipeBuilder::new("pastry")
+    ///     .set_serving_size(Unit::Gram, Rational32::from_integer(100))
+    ///     .set_servings(Rational32::from_integer(4))
+    ///     .add_food(flour, Unit::Gram, Rational32::from_integer(250))
+    ///     .add_food(butter.clone(), Unit::Gram, Rational32::from_integer(150))
+    ///     .build_with_defaults();
+    ///
+    /// let pie = RecipeBuilder::new("pie")
+    ///     .add_food(Food::new_from_recipe(pastry), Unit::Gram, Rational32::from_integer(200))
+    ///     .add_food(butter, Unit::Kilogram, Rational32::new(1, 100))
+    ///     .build_with_defaults();
+    ///
+    /// let ingredients = Food::new_from_recipe(pie).decompose(&settings);
+    /// let amounts: Vec<(&str, Rational32)> = ingredients
+    ///     .iter()
+    ///     .map(|(food, amount)| (food.get_name().get_short_code(), amount.get_amount()))
+    ///     .collect();
+    /// // Half the pastry, plus the 10 g of butter used directly
+    /// assert_eq!(
+    ///     amounts,
+    ///     vec![
+    ///         ("flour", Rational32::from_integer(125)),
+    ///         ("butter", Rational32::from_integer(85)),
+    ///     ]
+    /// );
+    ///
+    /// // Flour used by the cup only merges with flour by the gram once its density is known
+    /// let flour = RawFoodBuilder::new("flour")
+    ///     .set_serving_size(Unit::Gram, Rational32::from_integer(30))
+    ///     .set_nutrition(Nutrition::new())
+    ///     .build_raw_food()
+    ///     .unwrap();
+    /// let flour = Food::new_from_raw_food(flour);
+    /// let bread = RecipeBuilder::new("bread")
+    ///     .add_food(flour.clone(), Unit::Gram, Rational32::from_integer(100))
+    ///     .add_food(flour, Unit::Cup, Rational32::from_integer(1))
+    ///     .build_with_defaults();
+    /// let bread = Food::new_from_recipe(bread);
+    /// assert_eq!(bread.decompose(&settings).len(), 2);
+    ///
+    /// let mut household = Settings::new();
+    /// household
+    ///     .get_mut_conversions()
+    ///     .set_unit_size(Unit::Cup, Amount::new(Unit::Milliliter, Rational32::from_integer(250)))
+    ///     .unwrap()
+    ///     .get_mut_densities()
+    ///     .set_density(FoodId::new("flour"), Rational32::new(12, 25));
+    /// let ingredients = bread.decompose(&household);
+    /// assert_eq!(ingredients.len(), 1);
+    /// assert_eq!(ingredients[0].1.get_amount(), Rational32::from_integer(220));
+    /// # }
+    /// ```
+    pub fn decompose(&self, settings: &Settings) -> Vec<(Food, Amount)> {
+        let conversions = settings.get_conversions();
+        let mut ingredients: Vec<(Food, Amount)> = Vec::new();
+        match *self {
+            Food::RawFood(ref x) => ingredients.push((self.clone(), x.serving_size)),
+            Food::External(_) => ingredients.push((
+                self.clone(),
+                Amount::new(Unit::Count, Rational32::from_integer(1)),
+            )),
+            Food::Recipe(ref x) => {
+                x.decompose_into(Rational32::from_integer(1), conversions, &mut ingredients)
+            }
+        }
+        ingredients
+    }
+
+    /// Converts every serving size and ingredient amount in one unit to another, including
+    /// those of component recipes, returning how many amounts were converted
+    ///
+    /// Amounts are converted with the household's unit sizes. Nutritional information is left
+    /// alone, as each nutrient is measured in its own unit. On error nothing is changed.
+    pub fn convert_units(
+        &mut self,
+        from: Unit,
+        to: Unit,
+        settings: &Settings,
+    ) -> Result<usize, ConversionError> {
+        let mut converted = self.clone();
+        let count = converted.convert_units_in_place(from, to, settings.get_conversions())?;
+        *self = converted;
+        Ok(count)
+    }
+
+    fn convert_units_in_place(
+        &mut self,
+        from: Unit,
+        to: Unit,
+        conversions: &ConversionTable,
+    ) -> Result<usize, ConversionError> {
+        let convert = |amount: &mut Amount| -> Result<usize, ConversionError> {
+            if amount.get_unit() == from {
+                *amount = amount.convert_with(to, conversions)?;
+                Ok(1)
+            } else {
+                Ok(0)
+            }
+        };
+        let mut count = 0;
+        match *self {
+            Food::RawFood(ref mut x) => count += convert(&mut x.serving_size)?,
+            Food::Recipe(ref mut x) => {
+                count += convert(&mut x.serving_size)?;
+                for (food, amount) in &mut x.foods {
+                    count += convert(amount)?;
+                    count += food.convert_units_in_place(from, to, conversions)?;
+                }
+            }
+            Food::External(_) => (),
+        }
+        Ok(count)
+    }
+
+    /// Returns how many servings of this food an amount of it is
+    ///
+    /// Amounts in a unit incompatible with the serving size, such as a count of a food
+    /// measured in grams, are taken as a number of servings. Returns None if the food has an
+    /// empty serving size.
+    fn servings_in(&self, amount: Amount, conversions: &ConversionTable) -> Option<Rational32> {
+        let serving_size = match *self {
+            Food::RawFood(ref x) => x.serving_size,
+            Food::Recipe(ref x) => x.serving_size,
+            Food::External(_) => return Some(amount.get_amount()),
+        };
+        if serving_size.get_amount() == Rational32::from_integer(0) {
+            return None;
+        }
+        match conversions.convert_food(&FoodId::of(self), &amount, serving_size.get_unit()) {
+            Ok(x) => Some(x.get_amount() / serving_size.get_amount()),
+            Err(_) => Some(amount.get_amount()),
+        }
     }
 
     /// Return the time as fractional miniutes
     ///
-    /// Always just returns 0 for a RawFood
+    /// Always just returns 0 for a RawFood or an ExternalMeal
     pub fn get_time(&self) -> Rational32 {
         match self {
-            Food::RawFood(_) => Rational32::from_integer(0),
+            Food::RawFood(_) | Food::External(_) => Rational32::from_integer(0),
             Food::Recipe(x) => x.get_time(),
         }
     }
 
+    /// Returns how many minutes of the food's time need the cook's attention
+    ///
+    /// Always just returns 0 for a RawFood or an ExternalMeal
+    pub fn get_active_time(&self) -> Rational32 {
+        match self {
+            Food::RawFood(_) | Food::External(_) => Rational32::from_integer(0),
+            Food::Recipe(x) => x.get_active_time(),
+        }
+    }
+
     /// Time the recipe takes, in seconds
     pub fn get_duration(&self) -> Duration {
         // Get the time it takes to make the recipe, and convert it to seconds
@@ -75,15 +438,15 @@ impl Food {
 ///
 /// Upstream actually supports serde 1.0, but only in 0.2, which isn't on crates.io yet
 /// so until then we are stuck with this abomination.
-#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-struct Fraction {
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct Fraction {
     numerator: i32,
     denominator: i32,
 }
 
 impl Fraction {
     /// Rewraps a Rational32 as a Fraction
-    fn from_rational(ratio: Rational32) -> Fraction {
+    pub(crate) fn from_rational(ratio: Rational32) -> Fraction {
         Fraction {
             numerator: *ratio.numer(),
             denominator: *ratio.denom(),
@@ -91,14 +454,184 @@ impl Fraction {
     }
 
     /// Rewraps a Fraction as a Rational32
-    fn to_rational(&self) -> Rational32 {
+    pub(crate) fn to_rational(self) -> Rational32 {
         Rational32::new(self.numerator, self.denominator)
     }
 }
 
-/// Stub type, will be replaced by its own module later
-#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Unit;
+/// What a unit measures, units can only be compared with others of the same dimension
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dimension {
+    Mass,
+    Volume,
+    /// Energy, used for food energy in nutritional information
+    Energy,
+    /// Whole things, such as eggs or servings
+    Count,
+}
+
+/// A unit of measure
+///
+/// Volumes are US customary measures where they differ, so a cup is 240 ml, a tablespoon 15
+/// ml, and a teaspoon 5 ml, as used on nutrition labels.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    Milligram,
+    Gram,
+    Kilogram,
+    Ounce,
+    Pound,
+    Milliliter,
+    Liter,
+    Cup,
+    Tablespoon,
+    Teaspoon,
+    /// Kilocalories, the "calories" of nutrition labels
+    Kilocalorie,
+    /// A dimensionless count of things
+    Count,
+}
+
+impl Unit {
+    /// Returns what the unit measures
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate time_for_food;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// assert_eq!(Unit::Cup.get_dimension(), Dimension::Volume);
+    /// assert!(Unit::Ounce.is_compatible(Unit::Kilogram));
+    /// assert!(!Unit::Gram.is_compatible(Unit::Tablespoon));
+    /// # }
+    /// ```
+    pub fn get_dimension(self) -> Dimension {
+        match self {
+            Unit::Milligram | Unit::Gram | Unit::Kilogram | Unit::Ounce | Unit::Pound => {
+                Dimension::Mass
+            }
+            Unit::Milliliter | Unit::Liter | Unit::Cup | Unit::Tablespoon | Unit::Teaspoon => {
+                Dimension::Volume
+            }
+            Unit::Kilocalorie => Dimension::Energy,
+            Unit::Count => Dimension::Count,
+        }
+    }
+
+    /// Returns true if amounts in the two units measure the same thing
+    pub fn is_compatible(self, other: Unit) -> bool {
+        self.get_dimension() == other.get_dimension()
+    }
+
+    /// Returns the unit's abbreviation, empty for counts
+    ///
+    /// These are the same names the ingredient parser normalizes units to.
+    pub fn get_symbol(self) -> &'static str {
+        match self {
+            Unit::Milligram => "mg",
+            Unit::Gram => "g",
+            Unit::Kilogram => "kg",
+            Unit::Ounce => "oz",
+            Unit::Pound => "lb",
+            Unit::Milliliter => "ml",
+            Unit::Liter => "l",
+            Unit::Cup => "cup",
+            Unit::Tablespoon => "tbsp",
+            Unit::Teaspoon => "tsp",
+            Unit::Kilocalorie => "kcal",
+            Unit::Count => "",
+        }
+    }
+
+    /// Returns the size of the unit in its dimension's base unit, grams for mass, milliliters
+    /// for volume, and the unit itself otherwise
+    ///
+    /// Pounds and ounces are the international avoirdupois definitions, exactly.
+    pub(crate) fn base_factor(self) -> Rational64 {
+        match self {
+            Unit::Milligram => Rational64::new(1, 1000),
+            Unit::Gram => Rational64::from_integer(1),
+            Unit::Kilogram => Rational64::from_integer(1000),
+            Unit::Ounce => Rational64::new(45_359_237, 1_600_000),
+            Unit::Pound => Rational64::new(45_359_237, 100_000),
+            Unit::Milliliter => Rational64::from_integer(1),
+            Unit::Liter => Rational64::from_integer(1000),
+            Unit::Cup => Rational64::from_integer(240),
+            Unit::Tablespoon => Rational64::from_integer(15),
+            Unit::Teaspoon => Rational64::from_integer(5),
+            Unit::Kilocalorie | Unit::Count => Rational64::from_integer(1),
+        }
+    }
+
+    /// Looks a unit up by its abbreviation, see `get_symbol`
+    pub fn from_symbol(symbol: &str) -> Option<Unit> {
+        match symbol {
+            "mg" => Some(Unit::Milligram),
+            "g" => Some(Unit::Gram),
+            "kg" => Some(Unit::Kilogram),
+            "oz" => Some(Unit::Ounce),
+            "lb" => Some(Unit::Pound),
+            "ml" => Some(Unit::Milliliter),
+            "l" => Some(Unit::Liter),
+            "cup" => Some(Unit::Cup),
+            "tbsp" => Some(Unit::Tablespoon),
+            "tsp" => Some(Unit::Teaspoon),
+            "kcal" => Some(Unit::Kilocalorie),
+            "" => Some(Unit::Count),
+            _ => None,
+        }
+    }
+}
+
+/// Describes why an amount couldn't be converted to another unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConversionError {
+    /// The units measure different things, such as grams and cups
+    Incompatible { from: Unit, to: Unit },
+    /// The converted amount is too large, or too finely divided, to represent
+    Overflow,
+    /// Converting between volume and mass needs the food's density, which isn't known
+    UnknownDensity,
+}
+
+impl ::std::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            ConversionError::Incompatible { from, to } => write!(
+                f,
+                "can't convert {:?} ({:?}) to {:?} ({:?})",
+                from,
+                from.get_dimension(),
+                to,
+                to.get_dimension()
+            ),
+            ConversionError::Overflow => write!(f, "converted amount is out of range"),
+            ConversionError::UnknownDensity => write!(f, "the food's density isn't known"),
+        }
+    }
+}
+
+impl ::std::error::Error for ConversionError {}
+
+/// Reads a unit, treating a missing one as a count
+///
+/// Files written before units were real stored every unit as null.
+fn unit_or_count<'de, D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Unit, D::Error> {
+    let unit: Option<Unit> = ::serde::Deserialize::deserialize(deserializer)?;
+    Ok(unit.unwrap_or(Unit::Count))
+}
+
+/// Reads a Nutrition, treating a missing one as empty
+///
+/// Files written before nutrition was tracked stored every Nutrition as null.
+fn nutrition_or_empty<'de, D: ::serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Nutrition, D::Error> {
+    let nutrition: Option<Nutrition> = ::serde::Deserialize::deserialize(deserializer)?;
+    Ok(nutrition.unwrap_or_default())
+}
 
 /// A fractional ammount combined with a unit.
 ///
@@ -106,6 +639,7 @@ pub struct Unit;
 /// This is to allow easy serailization/deserializeation.
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Amount {
+    #[serde(deserialize_with = "unit_or_count")]
     unit: Unit,
     amount: Fraction,
 }
@@ -123,7 +657,7 @@ impl Amount {
 
     /// Returns the unit poriton of the Ammount
     pub fn get_unit(&self) -> Unit {
-        // Unit is Clone
+        // Unit is Copy
         self.unit
     }
 
@@ -145,6 +679,94 @@ impl Amount {
     pub fn set_amount(&mut self, ratio: Rational32) {
         self.amount = Fraction::from_rational(ratio);
     }
+
+    /// Converts the Amount to another unit of the same dimension, exactly
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let milk = Amount::new(Unit::Cup, Rational32::new(1, 2));
+    /// let tablespoons = milk.convert_to(Unit::Tablespoon).unwrap();
+    /// assert_eq!(tablespoons.get_amount(), Rational32::from_integer(8));
+    ///
+    /// let butter = Amount::new(Unit::Pound, Rational32::new(1, 4));
+    /// assert_eq!(
+    ///     butter.convert_to(Unit::Ounce).unwrap().get_amount(),
+    ///     Rational32::from_integer(4)
+    /// );
+    ///
+    /// assert_eq!(
+    ///     butter.convert_to(Unit::Cup).err(),
+    ///     Some(ConversionError::Incompatible {
+    ///         from: Unit::Pound,
+    ///         to: Unit::Cup
+    ///     })
+    /// );
+    /// # }
+    /// ```
+    pub fn convert_to(&self, target: Unit) -> Result<Amount, ConversionError> {
+        self.convert_with(target, &ConversionTable::new())
+    }
+
+    /// Converts the Amount to another unit of the same dimension, using a household's unit
+    /// sizes where they override the standard ones
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::units::ConversionTable;
+    ///
+    /// # fn main() {
+    /// // A metric cup
+    /// let mut conversions = ConversionTable::new();
+    /// conversions
+    ///     .set_unit_size(Unit::Cup, Amount::new(Unit::Milliliter, Rational32::from_integer(250)))
+    ///     .unwrap();
+    ///
+    /// let stock = Amount::new(Unit::Cup, Rational32::from_integer(2));
+    /// let milliliters = stock.convert_with(Unit::Milliliter, &conversions).unwrap();
+    /// assert_eq!(milliliters.get_amount(), Rational32::from_integer(500));
+    /// # }
+    /// ```
+    pub fn convert_with(
+        &self,
+        target: Unit,
+        conversions: &ConversionTable,
+    ) -> Result<Amount, ConversionError> {
+        if !self.unit.is_compatible(target) {
+            return Err(ConversionError::Incompatible {
+                from: self.unit,
+                to: target,
+            });
+        }
+        // Worked in 64 bits, as the exact factors for pounds and ounces are large. The factor
+        // is reduced before the amount is scaled by it, so the product always fits.
+        let factor = conversions.base_factor(self.unit) / conversions.base_factor(target);
+        let amount = self.get_amount();
+        let converted =
+            Rational64::new(i64::from(*amount.numer()), i64::from(*amount.denom())) * factor;
+        let narrow = |x: i64| {
+            if x < i64::from(i32::MIN) || x > i64::from(i32::MAX) {
+                Err(ConversionError::Overflow)
+            } else {
+                Ok(x as i32)
+            }
+        };
+        Ok(Amount::new(
+            target,
+            Rational32::new(narrow(*converted.numer())?, narrow(*converted.denom())?),
+        ))
+    }
 }
 
 /// IStrings are stored as a dictonary mapping lang-code to
@@ -176,11 +798,67 @@ impl Amount {
 /// assert_eq!(is.get_value("fr_FR"), Some("Bonjour monde!"));
 /// assert_eq!(is.get_value("en_UK"), None);
 /// ```
+///
+/// # Plural forms
+///
+/// Each language may additionally store a set of plural forms, keyed by CLDR plural category.
+/// These are selected with `get_plural_value`/`render_plural`, according to the plural rules
+/// of the language in question.
+///
+/// # Script and direction
+///
+/// A ScriptHint may be stored per language, recording the script and direction the value is
+/// written in. When no hint is stored, the direction is guessed from the language code.
+///
+/// # Serialization
+///
+/// Values are kept in language code order, so an IString always serializes the same way,
+/// keeping saved files diff friendly.
+///
+/// ```
+/// extern crate serde_json;
+/// extern crate time_for_food;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let mut first = IString::new("bread");
+/// first.set_value_for("fr_FR", "pain");
+/// first.set_value_for("en_US", "bread");
+/// first.set_value_for("de_DE", "Brot");
+/// let mut second = IString::new("bread");
+/// second.set_value_for("de_DE", "Brot");
+/// second.set_value_for("en_US", "bread");
+/// second.set_value_for("fr_FR", "pain");
+///
+/// let json = serde_json::to_string(&first).unwrap();
+/// assert_eq!(json, serde_json::to_string(&first).unwrap());
+/// assert_eq!(json, serde_json::to_string(&second).unwrap());
+/// let de = json.find("de_DE").unwrap();
+/// let en = json.find("en_US").unwrap();
+/// let fr = json.find("fr_FR").unwrap();
+/// assert!(de < en && en < fr);
+/// # }
+/// ```
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct IString {
     short_code: String,
-    names: HashMap<String, String>,
+    names: BTreeMap<String, String>,
     default: String,
+    /// Plural forms of the value, per language
+    #[serde(default)]
+    plurals: BTreeMap<String, BTreeMap<PluralCategory, String>>,
+    /// Script and direction hints, per language
+    #[serde(default)]
+    scripts: BTreeMap<String, ScriptHint>,
+    /// When each language's value was last set, for languages where it is known
+    #[serde(default)]
+    updated: BTreeMap<String, DateTime<Utc>>,
+    /// Other values kept for a language after a merge, alongside the value itself
+    #[serde(default)]
+    variants: BTreeMap<String, Vec<String>>,
+    /// Abbreviated values for narrow displays, per language
+    #[serde(default)]
+    short: BTreeMap<String, String>,
 }
 
 impl IString {
@@ -188,8 +866,13 @@ impl IString {
     pub fn new(short_code: &str) -> IString {
         IString {
             short_code: short_code.to_string(),
-            names: HashMap::new(),
+            names: BTreeMap::new(),
             default: String::new(),
+            plurals: BTreeMap::new(),
+            scripts: BTreeMap::new(),
+            updated: BTreeMap::new(),
+            variants: BTreeMap::new(),
+            short: BTreeMap::new(),
         }
     }
 
@@ -199,6 +882,144 @@ impl IString {
         self.names.insert(lang.to_string(), value.to_string());
     }
 
+    /// Sets the value for a language, recording when it was set
+    ///
+    /// The time is used to pick the newer value when merging, see `MergeStrategy::PreferNewer`.
+    pub fn set_value_at(&mut self, lang: &str, value: &str, time: DateTime<Utc>) {
+        self.set_value_for(lang, value);
+        self.updated.insert(lang.to_string(), time);
+    }
+
+    /// Returns when the value for a language was last set, if it was recorded
+    pub fn get_updated(&self, lang: &str) -> Option<DateTime<Utc>> {
+        self.updated.get(lang).cloned()
+    }
+
+    /// Returns the other values kept for a language after merges, see `MergeStrategy::KeepBoth`
+    pub fn get_variants(&self, lang: &str) -> &[String] {
+        self.variants.get(lang).map_or(&[], |x| x.as_slice())
+    }
+
+    /// Merges another IString, such as the same food's name from another device, into this one
+    ///
+    /// Languages only the other IString has are copied over, along with their plural forms,
+    /// script hints, abbreviations, and variants. Where both have a different value for the same language,
+    /// the strategy picks which is kept. The default language is only taken from the other
+    /// IString if this one has none.
+    ///
+    /// # Returns
+    /// The conflicts found, in language code order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut local = IString::new("aubergine");
+    /// local.set_value_at("en_GB", "Aubergine", Utc.ymd(2024, 1, 8).and_hms(9, 0, 0));
+    /// let mut remote = IString::new("aubergine");
+    /// remote.set_value_at("en_GB", "Eggplant", Utc.ymd(2024, 1, 9).and_hms(9, 0, 0));
+    /// remote.set_value_for("fr_FR", "Aubergine");
+    ///
+    /// let mut newer = local.clone();
+    /// let conflicts = newer.merge(&remote, MergeStrategy::PreferNewer);
+    /// assert_eq!(conflicts.len(), 1);
+    /// assert_eq!(newer.get_value("en_GB"), Some("Eggplant"));
+    /// assert_eq!(newer.get_value("fr_FR"), Some("Aubergine"));
+    ///
+    /// let mut both = local.clone();
+    /// both.merge(&remote, MergeStrategy::KeepBoth);
+    /// assert_eq!(both.get_value("en_GB"), Some("Aubergine"));
+    /// assert_eq!(both.get_variants("en_GB"), &["Eggplant".to_string()]);
+    /// # }
+    /// ```
+    pub fn merge(&mut self, other: &IString, strategy: MergeStrategy) -> Vec<MergeConflict> {
+        self.merge_by(other, |x| strategy.resolve(x))
+    }
+
+    /// Merges another IString into this one, as `merge` does, with conflicts resolved by the
+    /// given function
+    pub fn merge_by<F: FnMut(&MergeConflict) -> Resolution>(
+        &mut self,
+        other: &IString,
+        mut resolve: F,
+    ) -> Vec<MergeConflict> {
+        let mut conflicts = Vec::new();
+        for (lang, remote) in &other.names {
+            let local = match self.names.get(lang) {
+                Some(x) => x.clone(),
+                None => {
+                    self.names.insert(lang.clone(), remote.clone());
+                    if let Some(time) = other.updated.get(lang) {
+                        self.updated.insert(lang.clone(), *time);
+                    }
+                    continue;
+                }
+            };
+            if &local == remote {
+                continue;
+            }
+            let conflict = MergeConflict {
+                lang: lang.clone(),
+                local,
+                remote: remote.clone(),
+                local_updated: self.get_updated(lang),
+                remote_updated: other.get_updated(lang),
+            };
+            match resolve(&conflict) {
+                Resolution::Local => (),
+                Resolution::Remote => {
+                    self.names.insert(lang.clone(), remote.clone());
+                    match conflict.remote_updated {
+                        Some(time) => self.updated.insert(lang.clone(), time),
+                        None => self.updated.remove(lang),
+                    };
+                }
+                Resolution::Both => self.add_variant(lang, remote),
+            }
+            conflicts.push(conflict);
+        }
+        for (lang, variants) in &other.variants {
+            for variant in variants {
+                self.add_variant(lang, variant);
+            }
+        }
+        for (lang, forms) in &other.plurals {
+            self.plurals
+                .entry(lang.clone())
+                .or_insert_with(|| forms.clone());
+        }
+        for (lang, short) in &other.short {
+            self.short
+                .entry(lang.clone())
+                .or_insert_with(|| short.clone());
+        }
+        for (lang, hint) in &other.scripts {
+            self.scripts
+                .entry(lang.clone())
+                .or_insert_with(|| hint.clone());
+        }
+        if self.default.is_empty() {
+            self.default = other.default.clone();
+        }
+        conflicts
+    }
+
+    /// Keeps another value for a language, unless it is the value itself or already kept
+    fn add_variant(&mut self, lang: &str, value: &str) {
+        if self.names.get(lang).map(|x| x.as_str()) == Some(value) {
+            return;
+        }
+        let variants = self.variants.entry(lang.to_string()).or_default();
+        if !variants.iter().any(|x| x == value) {
+            variants.push(value.to_string());
+        }
+    }
+
     /// Gets the default language for this string
     pub fn get_default(&self) -> &str {
         &*self.default
@@ -224,67 +1045,1118 @@ impl IString {
         }
     }
 
+    /// Sets an abbreviated value for a language, such as "tbsp" for "tablespoon", for use on
+    /// narrow displays
+    pub fn set_short_value_for(&mut self, lang: &str, value: &str) {
+        self.short.insert(lang.to_string(), value.to_string());
+    }
+
+    /// Returns the abbreviated value for a language, if it has one
+    pub fn get_short_value(&self, lang: &str) -> Option<&str> {
+        self.short.get(lang).map(|x| x.as_str())
+    }
+
+    /// Returns the value for a language suited to a display of the given width
+    ///
+    /// Narrow displays get the abbreviated value where there is one, and the full value
+    /// otherwise. Returns None if there is no full value for the language.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use time_for_food::food::*;
+    ///
+    /// let mut unit = IString::new("tablespoon");
+    /// unit.set_value_for("en_US", "tablespoon");
+    /// unit.set_short_value_for("en_US", "tbsp");
+    ///
+    /// let value = |width| unit.get_value_for_width("en_US", width);
+    /// assert_eq!(value(DisplayWidth::Full), Some("tablespoon"));
+    /// assert_eq!(value(DisplayWidth::Narrow), Some("tbsp"));
+    /// assert_eq!(value(DisplayWidth::MaxChars(12)), Some("tablespoon"));
+    /// assert_eq!(value(DisplayWidth::MaxChars(6)), Some("tbsp"));
+    /// ```
+    pub fn get_value_for_width(&self, lang: &str, width: DisplayWidth) -> Option<&str> {
+        let full = self.get_value(lang)?;
+        let fits = match width {
+            DisplayWidth::Full => true,
+            DisplayWidth::Narrow => false,
+            DisplayWidth::MaxChars(max) => full.chars().count() <= max,
+        };
+        if fits {
+            Some(full)
+        } else {
+            Some(self.get_short_value(lang).unwrap_or(full))
+        }
+    }
+
     /// Returns the shortcode name for this IString
     pub fn get_short_code(&self) -> &str {
         &*self.short_code
     }
-}
 
-/// A step in making a recipe
-///
-/// Knows its text (encoded with an IString), and how long it takes to complete
-/// (encoded as a Rational32 describing miniutes)
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Step {
-    text: IString,
-    time: Fraction,
-}
+    /// Renders the value for the specified language, filling in any placeholders
+    ///
+    /// Placeholders are written inside the stored value as `{name}`, and are filled from
+    /// the matching entry in `args`. A placeholder may also refer to an argument by its
+    /// position, as `{0}`, `{1}`, etc. Since every language stores its own template,
+    /// each language is free to put the placeholders in whatever order its grammar needs.
+    ///
+    /// Literal braces can be written as `{{` and `}}`. Placeholders with no matching
+    /// argument are left in the output untouched, so missing data is visible rather than
+    /// silently dropped.
+    ///
+    /// # Returns
+    /// None if the language could not be found, otherwise Some(String) with the rendered value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use time_for_food::food::*;
+    ///
+    /// let mut is = IString::new("amount-of-food");
+    /// is.set_value_for("en_US", "{amount} of {food}");
+    /// is.set_value_for("ja_JP", "{food}を{amount}");
+    ///
+    /// let args = [("amount", "2 cups"), ("food", "rice")];
+    /// assert_eq!(is.render("en_US", &args), Some("2 cups of rice".to_string()));
+    /// assert_eq!(is.render("ja_JP", &args), Some("riceを2 cups".to_string()));
+    /// ```
+    pub fn render(&self, lang: &str, args: &[(&str, &str)]) -> Option<String> {
+        self.get_value(lang)
+            .map(|template| fill_placeholders(template, args))
+    }
 
-impl Step {
-    /// Makes a new empty step, using a short-code name
-    /// and the time to completion
-    pub fn new(short_code: &str, time: Rational32) -> Step {
-        Step {
-            text: IString::new(short_code),
-            time: Fraction::from_rational(time),
-        }
+    /// Renders the value for the default language
+    ///
+    /// See `render` for the placeholder syntax.
+    pub fn render_default(&self, args: &[(&str, &str)]) -> Option<String> {
+        self.render(self.get_default(), args)
     }
 
-    /// Return an immutable refrence to the raw IString for the text
-    pub fn get_text(&self) -> &IString {
-        &self.text
+    /// Sets the plural form of the IString used for a given category in a given language,
+    /// creating it if it does not exist.
+    pub fn set_plural_value_for(&mut self, lang: &str, category: PluralCategory, value: &str) {
+        self.plurals
+            .entry(lang.to_string())
+            .or_default()
+            .insert(category, value.to_string());
     }
 
-    /// Returns a mutable refrence to the raw IString
+    /// Gets the value of the IString appropriate for describing `count` things in the specified
+    /// language.
     ///
-    /// For now, use this to add translations
-    pub fn get_mut_text(&mut self) -> &mut IString {
-        &mut self.text
+    /// # Returns
+    /// The form for the plural category `count` falls into. If the language has no such form,
+    /// falls back to its `Other` form, and then to the plain value of the language. Returns None
+    /// only when none of those exist.
+    pub fn get_plural_value(&self, lang: &str, count: Rational32) -> Option<&str> {
+        let category = PluralCategory::for_count(lang, count);
+        self.plurals
+            .get(lang)
+            .and_then(|forms| {
+                forms
+                    .get(&category)
+                    .or_else(|| forms.get(&PluralCategory::Other))
+            })
+            .map(|value| &**value)
+            .or_else(|| self.get_value(lang))
     }
 
-    /// Returns the time this step takes
+    /// Renders the plural form for `count` things in the specified language, filling in any
+    /// placeholders
     ///
-    /// Units are in miniutes
-    pub fn get_time(&self) -> Rational32 {
-        self.time.to_rational()
+    /// The count itself is not added to the arguments, so pass it along under whichever name
+    /// the templates use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::food::plural::PluralCategory;
+    ///
+    /// # fn main() {
+    /// let mut is = IString::new("servings");
+    /// is.set_plural_value_for("en_US", PluralCategory::One, "{count} serving");
+    /// is.set_plural_value_for("en_US", PluralCategory::Other, "{count} servings");
+    ///
+    /// let one = is.render_plural("en_US", Rational32::from_integer(1), &[("count", "1")]);
+    /// let three = is.render_plural("en_US", Rational32::from_integer(3), &[("count", "3")]);
+    /// assert_eq!(one, Some("1 serving".to_string()));
+    /// assert_eq!(three, Some("3 servings".to_string()));
+    /// # }
+    /// ```
+    pub fn render_plural(
+        &self,
+        lang: &str,
+        count: Rational32,
+        args: &[(&str, &str)],
+    ) -> Option<String> {
+        self.get_plural_value(lang, count)
+            .map(|template| fill_placeholders(template, args))
     }
 
-    /// Sets the time the step takes
+    /// Sets the script hint for a given language, overwriting the existing hint if there is one
+    pub fn set_script_for(&mut self, lang: &str, hint: ScriptHint) {
+        self.scripts.insert(lang.to_string(), hint);
+    }
+
+    /// Gets the script hint for a given language, if one has been set
+    pub fn get_script(&self, lang: &str) -> Option<&ScriptHint> {
+        self.scripts.get(lang)
+    }
+
+    /// Gets the direction the value for a given language is written in
     ///
-    /// Units are in miniutes
-    pub fn set_time(&mut self, time: Rational32) {
-        self.time = Fraction::from_rational(time);
+    /// Uses the script hint if one is set, otherwise guesses from the language code.
+    pub fn get_direction(&self, lang: &str) -> TextDirection {
+        match self.scripts.get(lang) {
+            Some(hint) => hint.get_direction(),
+            None => TextDirection::guess_for(lang),
+        }
+    }
+
+    /// Renders the value for the specified language, along with its script and direction
+    ///
+    /// This is the same as `render`, but keeps the metadata exporters need to emit the
+    /// correct direction markers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use time_for_food::food::*;
+    /// use time_for_food::food::script::TextDirection;
+    ///
+    /// let mut is = IString::new("hummus");
+    /// is.set_value_for("en_US", "Hummus");
+    /// is.set_value_for("ar", "حمص");
+    ///
+    /// let arabic = is.render_text("ar", &[]).unwrap();
+    /// assert_eq!(arabic.get_direction(), TextDirection::RightToLeft);
+    /// assert_eq!(arabic.get_direction().html_dir(), "rtl");
+    /// assert_eq!(is.render_text("en_US", &[]).unwrap().get_direction(), TextDirection::LeftToRight);
+    /// ```
+    pub fn render_text(&self, lang: &str, args: &[(&str, &str)]) -> Option<RenderedText> {
+        self.render(lang, args)
+            .map(|text| self.wrap_rendered(lang, text))
+    }
+
+    /// Renders the plural form for `count` things in the specified language, along with its
+    /// script and direction
+    pub fn render_plural_text(
+        &self,
+        lang: &str,
+        count: Rational32,
+        args: &[(&str, &str)],
+    ) -> Option<RenderedText> {
+        self.render_plural(lang, count, args)
+            .map(|text| self.wrap_rendered(lang, text))
+    }
+
+    /// Attaches this IString's script metadata for a language to some rendered text
+    fn wrap_rendered(&self, lang: &str, text: String) -> RenderedText {
+        let script = self
+            .get_script(lang)
+            .and_then(|hint| hint.get_script())
+            .map(|x| x.to_string());
+        RenderedText::new(text, lang, script, self.get_direction(lang))
     }
 }
 
-/// Stub type, will be implemented later
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Nutrition;
+/// How much room a display has for text, used to pick between full and abbreviated values
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DisplayWidth {
+    /// Room for full values
+    Full,
+    /// Abbreviate wherever possible, such as on a watch or an e-ink kitchen display
+    Narrow,
+    /// Room for values up to the given number of characters, abbreviating longer ones
+    MaxChars(usize),
+}
 
-/// A single ingredient, no prepration food.
-/// The atomic building block of Recipes
-///
-/// A RawFood knows its name, its nutritonal value per serving size, its serving size,
+/// Two different values for the same language, found while merging IStrings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeConflict {
+    lang: String,
+    local: String,
+    remote: String,
+    local_updated: Option<DateTime<Utc>>,
+    remote_updated: Option<DateTime<Utc>>,
+}
+
+impl MergeConflict {
+    /// Returns the language code the values are for
+    pub fn get_lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Returns the value of the IString being merged into
+    pub fn get_local(&self) -> &str {
+        &self.local
+    }
+
+    /// Returns the value of the IString being merged in
+    pub fn get_remote(&self) -> &str {
+        &self.remote
+    }
+
+    /// Returns when the local value was set, if it was recorded
+    pub fn get_local_updated(&self) -> Option<DateTime<Utc>> {
+        self.local_updated
+    }
+
+    /// Returns when the remote value was set, if it was recorded
+    pub fn get_remote_updated(&self) -> Option<DateTime<Utc>> {
+        self.remote_updated
+    }
+}
+
+/// Which value a merge conflict is resolved with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// Keep the local value
+    Local,
+    /// Take the remote value
+    Remote,
+    /// Keep the local value, and keep the remote value as a variant
+    Both,
+}
+
+/// How conflicts are resolved when merging IStrings
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the local value
+    PreferLocal,
+    /// Take the remote value
+    PreferRemote,
+    /// Take whichever value was set most recently, keeping the local value on a tie
+    ///
+    /// Values without a recorded time count as older than any that have one.
+    PreferNewer,
+    /// Keep the local value, and keep the remote value as a variant
+    KeepBoth,
+}
+
+impl MergeStrategy {
+    /// Returns how the strategy resolves a conflict
+    pub fn resolve(self, conflict: &MergeConflict) -> Resolution {
+        match self {
+            MergeStrategy::PreferLocal => Resolution::Local,
+            MergeStrategy::PreferRemote => Resolution::Remote,
+            MergeStrategy::PreferNewer => {
+                if conflict.remote_updated > conflict.local_updated {
+                    Resolution::Remote
+                } else {
+                    Resolution::Local
+                }
+            }
+            MergeStrategy::KeepBoth => Resolution::Both,
+        }
+    }
+}
+
+/// Fills the `{name}` and `{index}` placeholders of a template from the given arguments
+fn fill_placeholders(template: &str, args: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                // Collect the placeholder name up to the closing brace
+                let mut name = String::new();
+                let mut closed = false;
+                for n in chars.by_ref() {
+                    if n == '}' {
+                        closed = true;
+                        break;
+                    }
+                    name.push(n);
+                }
+                // Look the argument up by name first, then by position
+                let value = args
+                    .iter()
+                    .find(|&&(key, _)| key == name)
+                    .or_else(|| name.parse::<usize>().ok().and_then(|i| args.get(i)))
+                    .map(|&(_, value)| value);
+                match value {
+                    Some(value) if closed => output.push_str(value),
+                    _ => {
+                        // Leave unknown or unterminated placeholders as they were
+                        output.push('{');
+                        output.push_str(&name);
+                        if closed {
+                            output.push('}');
+                        }
+                    }
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// How much of the cook's attention a step needs
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Attention {
+    /// The cook is busy for the whole step, such as chopping
+    Active,
+    /// The step needs nobody, such as resting dough
+    Passive,
+    /// The step needs a minute of the cook's time every so many minutes, such as stirring a
+    /// simmering sauce
+    Periodic { interval: u32 },
+}
+
+impl Default for Attention {
+    /// Steps are assumed to need the cook the whole time, unless stated otherwise
+    fn default() -> Attention {
+        Attention::Active
+    }
+}
+
+impl Attention {
+    /// Returns how many minutes of the cook's time a step of the given length needs
+    pub fn attended_minutes(self, time: Rational32) -> Rational32 {
+        match self {
+            Attention::Active => time,
+            Attention::Passive => Rational32::from_integer(0),
+            Attention::Periodic { interval: 0 } => time,
+            Attention::Periodic { interval } => {
+                let checks = (time / Rational32::from_integer(interval as i32)).ceil();
+                ::std::cmp::min(checks, time)
+            }
+        }
+    }
+}
+
+/// A step in making a recipe
+///
+/// Knows its text (encoded with an IString), and how long it takes to complete
+/// (encoded as a Rational32 describing miniutes)
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Step {
+    text: IString,
+    time: Fraction,
+    /// Indices of the steps, within the same recipe, that must be finished before this one
+    #[serde(default)]
+    depends_on: Vec<usize>,
+    /// How much of the cook's attention this step needs
+    #[serde(default)]
+    attention: Attention,
+    /// Short codes of the equipment this step uses, such as "large-pot" or "mixing-bowl"
+    #[serde(default)]
+    equipment: Vec<String>,
+    /// The oven temperature this step bakes at, in degrees Celsius, if it uses the oven
+    #[serde(default)]
+    oven_temperature: Option<u32>,
+    /// If true, the step's time grows and shrinks with the quantity being made, like chopping,
+    /// rather than staying fixed, like baking
+    #[serde(default)]
+    scales_with_quantity: bool,
+}
+
+impl Step {
+    /// Makes a new empty step, using a short-code name
+    /// and the time to completion
+    pub fn new(short_code: &str, time: Rational32) -> Step {
+        Step {
+            text: IString::new(short_code),
+            time: Fraction::from_rational(time),
+            depends_on: Vec::new(),
+            attention: Attention::default(),
+            equipment: Vec::new(),
+            oven_temperature: None,
+            scales_with_quantity: false,
+        }
+    }
+
+    /// Return an immutable refrence to the raw IString for the text
+    pub fn get_text(&self) -> &IString {
+        &self.text
+    }
+
+    /// Returns a mutable refrence to the raw IString
+    ///
+    /// For now, use this to add translations
+    pub fn get_mut_text(&mut self) -> &mut IString {
+        &mut self.text
+    }
+
+    /// Returns the time this step takes
+    ///
+    /// Units are in miniutes
+    pub fn get_time(&self) -> Rational32 {
+        self.time.to_rational()
+    }
+
+    /// Sets the time the step takes
+    ///
+    /// Units are in miniutes
+    pub fn set_time(&mut self, time: Rational32) {
+        self.time = Fraction::from_rational(time);
+    }
+
+    /// Returns how much of the cook's attention this step needs
+    pub fn get_attention(&self) -> Attention {
+        self.attention
+    }
+
+    /// Sets how much of the cook's attention this step needs
+    pub fn set_attention(&mut self, attention: Attention) {
+        self.attention = attention;
+    }
+
+    /// Returns how many minutes of the cook's time this step needs
+    pub fn get_attended_time(&self) -> Rational32 {
+        self.attention.attended_minutes(self.get_time())
+    }
+
+    /// Returns the short codes of the equipment this step uses
+    pub fn get_equipment(&self) -> &[String] {
+        &self.equipment
+    }
+
+    /// Records that this step uses a piece of equipment, doing nothing if it already does
+    pub fn add_equipment(&mut self, short_code: &str) {
+        if !self.equipment.iter().any(|x| x == short_code) {
+            self.equipment.push(short_code.to_string());
+        }
+    }
+
+    /// Removes a piece of equipment from this step, returning true if it was present
+    pub fn remove_equipment(&mut self, short_code: &str) -> bool {
+        let before = self.equipment.len();
+        self.equipment.retain(|x| x != short_code);
+        before != self.equipment.len()
+    }
+
+    /// Returns the oven temperature this step bakes at, in degrees Celsius, if it uses the oven
+    pub fn get_oven_temperature(&self) -> Option<u32> {
+        self.oven_temperature
+    }
+
+    /// Sets the oven temperature this step bakes at, in degrees Celsius, or None if it
+    /// doesn't use the oven
+    pub fn set_oven_temperature(&mut self, temperature: Option<u32>) {
+        self.oven_temperature = temperature;
+    }
+
+    /// Returns true if the step's time scales with the quantity being made
+    ///
+    /// Steps take a fixed time unless marked otherwise.
+    pub fn scales_with_quantity(&self) -> bool {
+        self.scales_with_quantity
+    }
+
+    /// Sets whether the step's time scales with the quantity being made
+    pub fn set_scales_with_quantity(&mut self, scales: bool) {
+        self.scales_with_quantity = scales;
+    }
+
+    /// Returns a copy of the step for making the given multiple of the quantity
+    fn scaled(&self, factor: Rational32) -> Step {
+        let mut step = self.clone();
+        if self.scales_with_quantity {
+            step.time = Fraction::from_rational(self.get_time() * factor);
+        }
+        step
+    }
+
+    /// Returns the indices of the steps that must be finished before this one
+    pub fn get_dependencies(&self) -> &[usize] {
+        &self.depends_on
+    }
+
+    /// Records that the step at the given index must be finished before this one
+    ///
+    /// Does nothing if the dependency is already recorded.
+    pub fn add_dependency(&mut self, index: usize) {
+        if !self.depends_on.contains(&index) {
+            self.depends_on.push(index);
+        }
+    }
+
+    /// Removes a dependency, returning true if it was present
+    pub fn remove_dependency(&mut self, index: usize) -> bool {
+        let before = self.depends_on.len();
+        self.depends_on.retain(|&x| x != index);
+        before != self.depends_on.len()
+    }
+
+    /// Rewrites every dependency through the given mapping, dropping any it maps to None
+    fn remap_dependencies<F: Fn(usize) -> Option<usize>>(&mut self, map: F) {
+        let mut remapped: Vec<usize> = Vec::new();
+        for index in self.depends_on.iter().filter_map(|&x| map(x)) {
+            if !remapped.contains(&index) {
+                remapped.push(index);
+            }
+        }
+        self.depends_on = remapped;
+    }
+}
+
+/// Returns the indices of the steps that must be finished before the step at the given index
+///
+/// Steps that declare dependencies are taken at their word, ignoring any that are out of
+/// range. If no step in the list declares any, the steps are taken to be made one after
+/// another, so each depends on the step before it.
+fn prerequisites(steps: &[Step], index: usize) -> Vec<usize> {
+    if steps.iter().any(|x| !x.depends_on.is_empty()) {
+        steps[index]
+            .depends_on
+            .iter()
+            .cloned()
+            .filter(|&x| x < steps.len() && x != index)
+            .collect()
+    } else if index > 0 {
+        vec![index - 1]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Orders the steps so that every step comes after the steps it depends on
+///
+/// Of the steps that are ready at any point, the one listed first goes first, so steps that
+/// are already in a workable order keep it. Returns None if the dependencies form a cycle.
+fn topological_order(steps: &[Step]) -> Option<Vec<usize>> {
+    let prerequisites: Vec<Vec<usize>> =
+        (0..steps.len()).map(|x| prerequisites(steps, x)).collect();
+    let mut placed = vec![false; steps.len()];
+    let mut order = Vec::with_capacity(steps.len());
+    while order.len() < steps.len() {
+        let next = (0..steps.len())
+            .find(|&x| !placed[x] && prerequisites[x].iter().all(|&y| placed[y]))?;
+        placed[next] = true;
+        order.push(next);
+    }
+    Some(order)
+}
+
+/// Finds the longest chain of dependent steps, and how long it takes
+///
+/// Returns None if the dependencies form a cycle.
+fn critical_path(steps: &[Step]) -> Option<(Vec<usize>, Rational32)> {
+    let order = topological_order(steps)?;
+    // The time each step can finish by, and the prerequisite that held it up longest
+    let mut finish = vec![Rational32::from_integer(0); steps.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; steps.len()];
+    for &index in &order {
+        let mut start = Rational32::from_integer(0);
+        for prerequisite in prerequisites(steps, index) {
+            if previous[index].is_none() || finish[prerequisite] > start {
+                start = finish[prerequisite];
+                previous[index] = Some(prerequisite);
+            }
+        }
+        finish[index] = start + steps[index].get_time();
+    }
+    let mut last = match (0..steps.len()).max_by_key(|&x| (finish[x], ::std::cmp::Reverse(x))) {
+        Some(x) => x,
+        None => return Some((Vec::new(), Rational32::from_integer(0))),
+    };
+    let time = finish[last];
+    let mut path = vec![last];
+    while let Some(x) = previous[last] {
+        path.push(x);
+        last = x;
+    }
+    path.reverse();
+    Some((path, time))
+}
+
+/// Returns how long the steps take to make, with independent steps done side by side
+///
+/// This is the length of the critical path, or the sum of the steps' times if their
+/// dependencies form a cycle.
+fn steps_time(steps: &[Step]) -> Rational32 {
+    match critical_path(steps) {
+        Some((_, time)) => time,
+        None => steps
+            .iter()
+            .fold(Rational32::from_integer(0), |total, x| total + x.get_time()),
+    }
+}
+
+/// Returns how long the steps take with the given number of cooks, or None if their
+/// dependencies form a cycle
+///
+/// Whenever a cook is free they start the ready step with the longest chain of steps still
+/// depending on it, ties going to the step listed first. Hands-off steps start as soon as they
+/// are ready, without needing a cook.
+fn parallel_time(steps: &[Step], workers: usize) -> Option<Rational32> {
+    let order = topological_order(steps)?;
+    let prerequisites: Vec<Vec<usize>> =
+        (0..steps.len()).map(|x| prerequisites(steps, x)).collect();
+    // The time from each step starting to the end of the longest chain depending on it
+    let mut tails = vec![Rational32::from_integer(0); steps.len()];
+    for &index in order.iter().rev() {
+        let longest = (0..steps.len())
+            .filter(|&x| prerequisites[x].contains(&index))
+            .map(|x| tails[x])
+            .max()
+            .unwrap_or_else(|| Rational32::from_integer(0));
+        tails[index] = steps[index].get_time() + longest;
+    }
+
+    let mut free = ::std::cmp::max(workers, 1);
+    let mut now = Rational32::from_integer(0);
+    let mut started = vec![false; steps.len()];
+    let mut finished = vec![false; steps.len()];
+    // The end time and index of each running step
+    let mut running: Vec<(Rational32, usize)> = Vec::new();
+    loop {
+        let mut ready: Vec<usize> = (0..steps.len())
+            .filter(|&x| !started[x] && prerequisites[x].iter().all(|&y| finished[y]))
+            .collect();
+        ready.sort_by_key(|&x| (::std::cmp::Reverse(tails[x]), x));
+        for index in ready {
+            let passive = steps[index].get_attention() == Attention::Passive;
+            if passive || free > 0 {
+                if !passive {
+                    free -= 1;
+                }
+                started[index] = true;
+                running.push((now + steps[index].get_time(), index));
+            }
+        }
+        now = match running.iter().map(|x| x.0).min() {
+            Some(x) => x,
+            None => return Some(now),
+        };
+        for &(end, index) in &running {
+            if end == now {
+                finished[index] = true;
+                if steps[index].get_attention() != Attention::Passive {
+                    free += 1;
+                }
+            }
+        }
+        running.retain(|x| x.0 != now);
+    }
+}
+
+/// An iterator over a recipe's steps, in an order that respects their dependencies
+///
+/// Created by `Recipe::get_step_order`, and yields each step along with its index.
+pub struct StepOrder<'a> {
+    steps: &'a [Step],
+    order: ::std::vec::IntoIter<usize>,
+}
+
+impl<'a> Iterator for StepOrder<'a> {
+    type Item = (usize, &'a Step);
+
+    fn next(&mut self) -> Option<(usize, &'a Step)> {
+        self.order.next().map(|x| (x, &self.steps[x]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.order.size_hint()
+    }
+}
+
+/// A way a child's portion of a recipe is adapted
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Adaptation {
+    /// Blended smooth, for babies starting on solids
+    Blended,
+    /// Mashed with a fork, leaving soft lumps
+    Mashed,
+    /// Cut into pieces small enough to pick up, for toddlers feeding themselves
+    FingerFood,
+    /// Set aside before any salt is added
+    Unsalted,
+    /// Set aside before any sugar, honey, or syrup is added
+    Unsweetened,
+}
+
+/// A version of a recipe adapted for young children, made alongside the adult version
+///
+/// The children's portion is set aside once a step of the adult version is done, such as
+/// before the sauce is seasoned, and then finished with the variant's own steps, such as
+/// blending. Dependencies between the variant's steps are indices into the variant's steps.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChildVariant {
+    adaptations: Vec<Adaptation>,
+    /// Index of the adult step after which the portion is set aside, or None for the last
+    set_aside_after: Option<usize>,
+    steps: Vec<Step>,
+}
+
+impl ChildVariant {
+    /// Creates a variant with the given adaptations, set aside once the adult version is done,
+    /// and with no steps of its own
+    pub fn new(adaptations: Vec<Adaptation>) -> ChildVariant {
+        ChildVariant {
+            adaptations,
+            set_aside_after: None,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Returns the ways the children's portion is adapted
+    pub fn get_adaptations(&self) -> &[Adaptation] {
+        &self.adaptations
+    }
+
+    /// Returns the index of the adult step after which the portion is set aside, or None if
+    /// it is set aside once the adult version is done
+    pub fn get_set_aside_after(&self) -> Option<usize> {
+        self.set_aside_after
+    }
+
+    /// Sets the index of the adult step after which the portion is set aside, or None to
+    /// set it aside once the adult version is done
+    pub fn set_set_aside_after(&mut self, index: Option<usize>) -> &mut Self {
+        self.set_aside_after = index;
+        self
+    }
+
+    /// Returns the steps that finish the children's portion, in order
+    pub fn get_steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Adds a step that finishes the children's portion, such as blending it
+    pub fn add_step(&mut self, step: Step) -> &mut Self {
+        self.steps.push(step);
+        self
+    }
+}
+
+/// One of the nutrients a Nutrition always has room for
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Nutrient {
+    Calories,
+    Protein,
+    Fat,
+    Carbohydrates,
+    Fiber,
+    Sugar,
+    Sodium,
+}
+
+impl Nutrient {
+    /// Returns the unit the nutrient is measured in: kilocalories for calories, milligrams for
+    /// sodium, and grams for everything else
+    pub fn get_unit(self) -> Unit {
+        match self {
+            Nutrient::Calories => Unit::Kilocalorie,
+            Nutrient::Sodium => Unit::Milligram,
+            _ => Unit::Gram,
+        }
+    }
+}
+
+/// The nutritional value of a food
+///
+/// Every value is optional, as a missing value (nobody has looked it up yet) is quite
+/// different from a value of zero. Values are stored as Amounts, keeping the exact fractions
+/// used everywhere else in the crate.
+///
+/// Beyond the common macronutrients, any number of micronutrients can be recorded, keyed by
+/// a short code such as "vitamin-c" or "iron".
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let mut nutrition = Nutrition::new();
+/// nutrition
+///     .set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(150)))
+///     .set_protein(Amount::new(Unit::Gram, Rational32::new(11, 2)))
+///     .set_micronutrient("iron", Amount::new(Unit::Milligram, Rational32::new(3, 2)));
+///
+/// assert_eq!(
+///     nutrition.get_protein().map(|x| x.get_amount()),
+///     Some(Rational32::new(11, 2))
+/// );
+/// assert!(nutrition.get_fat().is_none());
+/// # }
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Nutrition {
+    /// Energy, in kilocalories
+    calories: Option<Amount>,
+    /// Protein, in grams
+    protein: Option<Amount>,
+    /// Fat, in grams
+    fat: Option<Amount>,
+    /// Carbohydrates, in grams
+    carbohydrates: Option<Amount>,
+    /// Fiber, in grams
+    fiber: Option<Amount>,
+    /// Sugar, in grams
+    sugar: Option<Amount>,
+    /// Sodium, in milligrams
+    sodium: Option<Amount>,
+    /// Micronutrients, keyed by short code
+    #[serde(default)]
+    micronutrients: BTreeMap<String, Amount>,
+}
+
+impl Nutrition {
+    /// Creates an empty Nutrition, with nothing known
+    pub fn new() -> Nutrition {
+        Nutrition::default()
+    }
+
+    /// Returns true if nothing about the nutritional value is known
+    pub fn is_empty(&self) -> bool {
+        *self == Nutrition::default()
+    }
+
+    /// Returns the energy, in kilocalories, if known
+    pub fn get_calories(&self) -> Option<Amount> {
+        self.calories
+    }
+
+    /// Sets the energy, in kilocalories
+    pub fn set_calories(&mut self, amount: Amount) -> &mut Self {
+        self.calories = Some(amount);
+        self
+    }
+
+    /// Returns the protein, in grams, if known
+    pub fn get_protein(&self) -> Option<Amount> {
+        self.protein
+    }
+
+    /// Sets the protein, in grams
+    pub fn set_protein(&mut self, amount: Amount) -> &mut Self {
+        self.protein = Some(amount);
+        self
+    }
+
+    /// Returns the fat, in grams, if known
+    pub fn get_fat(&self) -> Option<Amount> {
+        self.fat
+    }
+
+    /// Sets the fat, in grams
+    pub fn set_fat(&mut self, amount: Amount) -> &mut Self {
+        self.fat = Some(amount);
+        self
+    }
+
+    /// Returns the carbohydrates, in grams, if known
+    pub fn get_carbohydrates(&self) -> Option<Amount> {
+        self.carbohydrates
+    }
+
+    /// Sets the carbohydrates, in grams
+    pub fn set_carbohydrates(&mut self, amount: Amount) -> &mut Self {
+        self.carbohydrates = Some(amount);
+        self
+    }
+
+    /// Returns the fiber, in grams, if known
+    pub fn get_fiber(&self) -> Option<Amount> {
+        self.fiber
+    }
+
+    /// Sets the fiber, in grams
+    pub fn set_fiber(&mut self, amount: Amount) -> &mut Self {
+        self.fiber = Some(amount);
+        self
+    }
+
+    /// Returns the sugar, in grams, if known
+    pub fn get_sugar(&self) -> Option<Amount> {
+        self.sugar
+    }
+
+    /// Sets the sugar, in grams
+    pub fn set_sugar(&mut self, amount: Amount) -> &mut Self {
+        self.sugar = Some(amount);
+        self
+    }
+
+    /// Returns the sodium, in milligrams, if known
+    pub fn get_sodium(&self) -> Option<Amount> {
+        self.sodium
+    }
+
+    /// Sets the sodium, in milligrams
+    pub fn set_sodium(&mut self, amount: Amount) -> &mut Self {
+        self.sodium = Some(amount);
+        self
+    }
+
+    /// Returns the amount of a nutrient in the nutrient's own unit, if known
+    ///
+    /// Returns None if the amount was recorded in a unit that can't be converted, such as a
+    /// volume of protein.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut nutrition = Nutrition::new();
+    /// nutrition.set_sodium(Amount::new(Unit::Gram, Rational32::new(1, 2)));
+    /// assert_eq!(
+    ///     nutrition.get_nutrient(Nutrient::Sodium),
+    ///     Some(Rational32::from_integer(500))
+    /// );
+    /// # }
+    /// ```
+    pub fn get_nutrient(&self, nutrient: Nutrient) -> Option<Rational32> {
+        let amount = match nutrient {
+            Nutrient::Calories => self.calories,
+            Nutrient::Protein => self.protein,
+            Nutrient::Fat => self.fat,
+            Nutrient::Carbohydrates => self.carbohydrates,
+            Nutrient::Fiber => self.fiber,
+            Nutrient::Sugar => self.sugar,
+            Nutrient::Sodium => self.sodium,
+        };
+        amount
+            .and_then(|x| x.convert_to(nutrient.get_unit()).ok())
+            .map(|x| x.get_amount())
+    }
+
+    /// Returns the amount of a micronutrient, if known
+    pub fn get_micronutrient(&self, short_code: &str) -> Option<Amount> {
+        self.micronutrients.get(short_code).cloned()
+    }
+
+    /// Sets the amount of a micronutrient
+    pub fn set_micronutrient(&mut self, short_code: &str, amount: Amount) -> &mut Self {
+        self.micronutrients.insert(short_code.to_string(), amount);
+        self
+    }
+
+    /// Returns every known micronutrient, in short code order
+    pub fn get_micronutrients(&self) -> Vec<(&str, Amount)> {
+        self.micronutrients
+            .iter()
+            .map(|(x, y)| (x.as_str(), *y))
+            .collect()
+    }
+
+    /// Returns a copy with every known amount multiplied by the given factor
+    pub fn scaled(&self, factor: Rational32) -> Nutrition {
+        let scale = |x: &Amount| Amount::new(x.get_unit(), x.get_amount() * factor);
+        Nutrition {
+            calories: self.calories.as_ref().map(scale),
+            protein: self.protein.as_ref().map(scale),
+            fat: self.fat.as_ref().map(scale),
+            carbohydrates: self.carbohydrates.as_ref().map(scale),
+            fiber: self.fiber.as_ref().map(scale),
+            sugar: self.sugar.as_ref().map(scale),
+            sodium: self.sodium.as_ref().map(scale),
+            micronutrients: self
+                .micronutrients
+                .iter()
+                .map(|(x, y)| (x.clone(), scale(y)))
+                .collect(),
+        }
+    }
+
+    /// Adds another Nutrition's amounts to this one
+    ///
+    /// Anything known by either side is known in the total, missing values count as zero.
+    /// Amounts are converted into the unit this one already has, and an amount that can't be
+    /// converted, such as a weight of sodium added to a count of it, is left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut total = Nutrition::new();
+    /// total.set_sodium(Amount::new(Unit::Milligram, Rational32::from_integer(200)));
+    /// let mut other = Nutrition::new();
+    /// other.set_sodium(Amount::new(Unit::Gram, Rational32::new(1, 2)));
+    ///
+    /// total.add(&other);
+    /// let sodium = total.get_sodium().unwrap();
+    /// assert_eq!(sodium.get_unit(), Unit::Milligram);
+    /// assert_eq!(sodium.get_amount(), Rational32::from_integer(700));
+    /// # }
+    /// ```
+    pub fn add(&mut self, other: &Nutrition) -> &mut Self {
+        fn sum(total: &mut Option<Amount>, other: Option<Amount>) {
+            *total = match (*total, other) {
+                (Some(x), Some(y)) => match y.convert_to(x.get_unit()) {
+                    Ok(y) => Some(Amount::new(x.get_unit(), x.get_amount() + y.get_amount())),
+                    Err(_) => Some(x),
+                },
+                (x, y) => x.or(y),
+            };
+        }
+        sum(&mut self.calories, other.calories);
+        sum(&mut self.protein, other.protein);
+        sum(&mut self.fat, other.fat);
+        sum(&mut self.carbohydrates, other.carbohydrates);
+        sum(&mut self.fiber, other.fiber);
+        sum(&mut self.sugar, other.sugar);
+        sum(&mut self.sodium, other.sodium);
+        for (short_code, amount) in &other.micronutrients {
+            let mut total = self.micronutrients.get(short_code).cloned();
+            sum(&mut total, Some(*amount));
+            if let Some(total) = total {
+                self.micronutrients.insert(short_code.clone(), total);
+            }
+        }
+        self
+    }
+}
+
+/// Where a food's nutritional information came from
+///
+/// Variants are ordered from least to most trustworthy, so the weakest of several sources is
+/// simply the minimum.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NutritionSource {
+    /// Guessed, such as for a meal eaten out
+    Estimated,
+    /// Entered by hand, from an unknown source
+    Manual,
+    /// Scanned from a nutrition label
+    LabelScan,
+    /// Taken from the USDA FoodData Central database
+    Usda,
+}
+
+impl Default for NutritionSource {
+    /// Nutrition is assumed to have been entered by hand, unless stated otherwise
+    fn default() -> NutritionSource {
+        NutritionSource::Manual
+    }
+}
+
+/// A single ingredient, no prepration food.
+/// The atomic building block of Recipes
+///
+/// A RawFood knows its name, its nutritonal value per serving size, its serving size,
 /// as well as what unit its serving size is in.
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RawFood {
@@ -294,104 +2166,1265 @@ pub struct RawFood {
     /// Repusents both the Unit and the actual value
     serving_size: Amount,
     /// The nutritional value of this food
+    #[serde(deserialize_with = "nutrition_or_empty")]
+    nutrition: Nutrition,
+    /// Where the nutritional value came from
+    #[serde(default)]
+    nutrition_source: NutritionSource,
+    /// Barcodes printed on packages of this food, normalized with `normalize_barcode`
+    #[serde(default)]
+    barcodes: Vec<String>,
+    /// The brand, for branded products
+    #[serde(default)]
+    brand: Option<String>,
+    /// The short code of the generic food this is a branded variant of
+    #[serde(default)]
+    generic: Option<String>,
+    /// The price of a serving size of this food, in the household's currency
+    #[serde(default)]
+    price: Option<Fraction>,
+    /// Free-form tags describing the food, such as "soft"
+    #[serde(default)]
+    tags: Vec<String>,
+    /// The grocery category the food is found under, such as "produce" or "dairy"
+    #[serde(default)]
+    category: Option<String>,
+    /// The diets the food is suitable for
+    #[serde(default)]
+    diet_tags: BTreeSet<DietTag>,
+    /// The allergens the food contains
+    #[serde(default)]
+    allergens: BTreeSet<Allergen>,
+}
+
+impl RawFood {
+    /// Constructs a new RawFood from its components
+    ///
+    /// While its probably not a horrible idea to call this directly,
+    /// Its probably a good idea to use a RawFoodBuilder instead.
+    pub fn new(name: IString, serving_size: Amount, nutrition: Nutrition) -> RawFood {
+        RawFood {
+            name,
+            serving_size,
+            nutrition,
+            nutrition_source: NutritionSource::default(),
+            barcodes: Vec::new(),
+            brand: None,
+            generic: None,
+            price: None,
+            tags: Vec::new(),
+            category: None,
+            diet_tags: BTreeSet::new(),
+            allergens: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the tags attached to this RawFood, such as "soft" or "spicy"
+    pub fn get_tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns true if this RawFood has the given tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|x| x == tag)
+    }
+
+    /// Tags this RawFood, doing nothing if it already has the tag
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.has_tag(tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    /// Removes a tag from this RawFood, returning true if the tag was present
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|x| x != tag);
+        before != self.tags.len()
+    }
+
+    /// Returns the diets this RawFood is suitable for
+    pub fn get_diet_tags(&self) -> &BTreeSet<DietTag> {
+        &self.diet_tags
+    }
+
+    /// Marks this RawFood as suitable for a diet
+    pub fn add_diet_tag(&mut self, tag: DietTag) {
+        self.diet_tags.insert(tag);
+    }
+
+    /// Marks this RawFood as unsuitable for a diet, returning true if it was marked suitable
+    pub fn remove_diet_tag(&mut self, tag: DietTag) -> bool {
+        self.diet_tags.remove(&tag)
+    }
+
+    /// Returns the allergens this RawFood contains
+    pub fn get_allergens(&self) -> &BTreeSet<Allergen> {
+        &self.allergens
+    }
+
+    /// Records that this RawFood contains an allergen
+    pub fn add_allergen(&mut self, allergen: Allergen) {
+        self.allergens.insert(allergen);
+    }
+
+    /// Records that this RawFood doesn't contain an allergen, returning true if it was
+    /// recorded as containing it
+    pub fn remove_allergen(&mut self, allergen: Allergen) -> bool {
+        self.allergens.remove(&allergen)
+    }
+
+    /// Returns the name (as an &IString) of this food
+    pub fn get_name(&self) -> &IString {
+        &self.name
+    }
+
+    /// Returns a mutable refrence to this foods name
+    pub fn get_mut_name(&mut self) -> &mut IString {
+        &mut self.name
+    }
+
+    /// Returns the serving size (as an amount) of this food
+    pub fn get_serving_size(&self) -> Amount {
+        self.serving_size
+    }
+
+    /// Returns the nutritional value of this food
+    pub fn get_nutrition(&self) -> &Nutrition {
+        &self.nutrition
+    }
+
+    /// Returns where the nutritional value of this food came from
+    pub fn get_nutrition_source(&self) -> NutritionSource {
+        self.nutrition_source
+    }
+
+    /// Records where the nutritional value of this food came from
+    pub fn set_nutrition_source(&mut self, source: NutritionSource) {
+        self.nutrition_source = source;
+    }
+
+    /// Returns the barcodes of this food, normalized
+    pub fn get_barcodes(&self) -> &[String] {
+        &self.barcodes
+    }
+
+    /// Returns true if the given barcode belongs to this food
+    pub fn has_barcode(&self, barcode: &str) -> bool {
+        let barcode = normalize_barcode(barcode);
+        self.barcodes.contains(&barcode)
+    }
+
+    /// Adds a barcode to this food, doing nothing if it already has it
+    ///
+    /// A food may have any number of barcodes, as the same product is often sold in
+    /// several package sizes, or under regional codes.
+    pub fn add_barcode(&mut self, barcode: &str) {
+        if !self.has_barcode(barcode) {
+            self.barcodes.push(normalize_barcode(barcode));
+        }
+    }
+
+    /// Removes a barcode from this food, returning true if it was present
+    pub fn remove_barcode(&mut self, barcode: &str) -> bool {
+        let barcode = normalize_barcode(barcode);
+        let before = self.barcodes.len();
+        self.barcodes.retain(|x| *x != barcode);
+        before != self.barcodes.len()
+    }
+
+    /// Returns the brand of this food, if it is a branded product
+    pub fn get_brand(&self) -> Option<&str> {
+        self.brand.as_deref()
+    }
+
+    /// Sets the brand of this food, or removes it if given None
+    pub fn set_brand(&mut self, brand: Option<&str>) {
+        self.brand = brand.map(|x| x.to_string());
+    }
+
+    /// Returns the short code of the generic food this is a variant of, if it is one
+    ///
+    /// Recipes should reference the generic food ("rolled-oats"), while the pantry tracks
+    /// the specific products actually bought.
+    pub fn get_generic(&self) -> Option<&str> {
+        self.generic.as_deref()
+    }
+
+    /// Marks this food as a variant of the generic food with the given short code, or as a
+    /// generic food itself if given None
+    pub fn set_generic(&mut self, short_code: Option<&str>) {
+        self.generic = short_code.map(|x| x.to_string());
+    }
+
+    /// Returns true if this food is a variant of some generic food
+    pub fn is_variant(&self) -> bool {
+        self.generic.is_some()
+    }
+
+    /// Returns the price of a serving size of this food, if known
+    pub fn get_price(&self) -> Option<Rational32> {
+        self.price.map(|x| x.to_rational())
+    }
+
+    /// Sets the price of a serving size of this food, or removes it if given None
+    pub fn set_price(&mut self, price: Option<Rational32>) {
+        self.price = price.map(Fraction::from_rational);
+    }
+
+    /// Returns the grocery category the food is found under, if it has one
+    pub fn get_category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    /// Sets the grocery category the food is found under, or removes it if given None
+    ///
+    /// Categories are short codes, such as "produce", matching those of a `StoreLayout`.
+    pub fn set_category(&mut self, category: Option<&str>) {
+        self.category = category.map(|x| x.to_string());
+    }
+}
+
+/// Normalizes a barcode, so the same code always compares equal however it was entered
+///
+/// Everything but the digits is dropped, and 12 digit UPC-A codes are widened to the
+/// equivalent 13 digit EAN-13 code.
+///
+/// # Examples
+///
+/// ```
+/// use time_for_food::food::normalize_barcode;
+///
+/// assert_eq!(normalize_barcode("0 12345 67890 5"), "0012345678905");
+/// assert_eq!(normalize_barcode("4006381333931"), "4006381333931");
+/// ```
+pub fn normalize_barcode(barcode: &str) -> String {
+    let digits: String = barcode.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.len() == 12 {
+        format!("0{}", digits)
+    } else {
+        digits
+    }
+}
+
+/// A composite Food, comprised of one or more other foods, as well as a set of
+/// directions for preparing the food.
+///
+/// A recipe knows its name, its components foods, the ammounts required, the steps
+/// required to produce the recipe, the nutritonal value of the resulting food,
+/// how many servings it produces, and how long the recipe takes to make.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Recipe {
+    /// The name of the Recipe as an encoded String
+    ///
+    /// The IString wrapper type is used to help with i18n
+    name: IString,
+    /// Contains the serving size and unit, encoded as an Amount
+    serving_size: Amount,
+    /// Number of servings the recipe makes.
+    ///
+    /// This is stored as a rational to prevent conflicts between the assumed
+    /// reality and actual reality. While it would be nice to have this as in integer
+    /// And force recipes to make a whole number of servings, some recipies, as input,
+    /// may make a fractional number of servings.
+    servings: Fraction,
+    /// Contains the component foods and ammounts there of
+    foods: Vec<(Food, Amount)>,
+    /// Contains the steps, in order, required to produce the recipe
+    steps: Vec<Step>,
+    /// How long the recipe takes to make, in miniutes
+    ///
+    /// Stored as  Fraction rather than directly as a Rational32 to allow serde derive
+    time: Fraction,
+    /// How long preparing the ingredients takes, in miniutes, if known
+    #[serde(default)]
+    prep_time: Option<Fraction>,
+    /// How long the cooking itself takes, in miniutes, if known
+    #[serde(default)]
+    cook_time: Option<Fraction>,
+    /// Nutritional value of a serving of this Recipe
+    #[serde(deserialize_with = "nutrition_or_empty")]
     nutrition: Nutrition,
+    /// Where the nutritional value of the Recipe itself came from
+    #[serde(default)]
+    nutrition_source: NutritionSource,
+    /// If true, the nutrition is derived from the component foods, and kept up to date as
+    /// the ingredients change
+    #[serde(default)]
+    nutrition_derived: bool,
+    /// The method (stovetop, pressure cooker, ...) the current steps are for, if named
+    #[serde(default)]
+    method: Option<String>,
+    /// Alternative steps for the other methods the recipe can be made with, keyed by method
+    #[serde(default)]
+    methods: BTreeMap<String, Vec<Step>>,
+    /// Free-form tags describing the recipe, such as "soft" or "spicy"
+    #[serde(default)]
+    tags: Vec<String>,
+    /// The version of the recipe made for young children, if it has one
+    #[serde(default)]
+    child_variant: Option<ChildVariant>,
 }
 
-impl RawFood {
-    /// Constructs a new RawFood from its components
+impl Recipe {
+    /// Constructs a new Recipe given the all the member values
     ///
-    /// While its probably not a horrible idea to call this directly,
-    /// Its probably a good idea to use a RawFoodBuilder instead.
-    pub fn new(name: IString, serving_size: Amount, nutrition: Nutrition) -> RawFood {
-        RawFood {
+    /// Probably not a good idea to use this directly,
+    /// a Recipe Builder is much better idea
+    pub fn new(
+        name: IString,
+        serving_size: Amount,
+        servings: Rational32,
+        foods: Vec<(Food, Amount)>,
+        steps: Vec<Step>,
+        time: Rational32,
+        nutrition: Nutrition,
+    ) -> Recipe {
+        Recipe {
             name,
             serving_size,
+            servings: Fraction::from_rational(servings),
+            foods,
+            steps,
+            time: Fraction::from_rational(time),
+            prep_time: None,
+            cook_time: None,
             nutrition,
+            nutrition_source: NutritionSource::default(),
+            nutrition_derived: false,
+            method: None,
+            methods: BTreeMap::new(),
+            tags: Vec::new(),
+            child_variant: None,
         }
     }
 
-    /// Returns the name (as an &IString) of this food
+    /// Returns the tags attached to this Recipe, such as "soft" or "spicy"
+    pub fn get_tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns true if this Recipe has the given tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|x| x == tag)
+    }
+
+    /// Tags this Recipe, doing nothing if it already has the tag
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.has_tag(tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    /// Removes a tag from this Recipe, returning true if the tag was present
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|x| x != tag);
+        before != self.tags.len()
+    }
+
+    /// Returns the diets this Recipe is suitable for, those every one of its component foods
+    /// suits
+    ///
+    /// A Recipe without any component foods isn't known to suit any diets.
+    pub fn get_diet_tags(&self) -> BTreeSet<DietTag> {
+        let mut foods = self.foods.iter().map(|(food, _)| food.get_diet_tags());
+        let first = foods.next().unwrap_or_default();
+        foods.fold(first, |tags, x| tags.intersection(&x).cloned().collect())
+    }
+
+    /// Returns the allergens this Recipe contains, those any of its component foods contain,
+    /// or None if they aren't known
+    ///
+    /// A Recipe without any component foods, or made with a food whose allergens aren't known,
+    /// isn't known to be free of any allergens.
+    pub fn get_allergens(&self) -> Option<BTreeSet<Allergen>> {
+        if self.foods.is_empty() {
+            return None;
+        }
+        let mut allergens = BTreeSet::new();
+        for (food, _) in &self.foods {
+            allergens.extend(food.get_allergens()?);
+        }
+        Some(allergens)
+    }
+
+    /// Returns the version of the recipe made for young children, if it has one
+    pub fn get_child_variant(&self) -> Option<&ChildVariant> {
+        self.child_variant.as_ref()
+    }
+
+    /// Sets the version of the recipe made for young children, or removes it if given None
+    pub fn set_child_variant(&mut self, variant: Option<ChildVariant>) {
+        self.child_variant = variant;
+    }
+
+    /// Returns a copy of the recipe that also makes its child variant, if it has one
+    ///
+    /// The variant's steps are added after the adult steps, starting once the portion has been
+    /// set aside, so they can be done while the adult version is finished. The adult steps are
+    /// given explicit dependencies to keep their order, and the recipe's time grows by however
+    /// much the added steps lengthen the critical path. The copy has no child variant, as it
+    /// already makes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut variant = ChildVariant::new(vec![Adaptation::Unsalted, Adaptation::Blended]);
+    /// variant
+    ///     .set_set_aside_after(Some(0))
+    ///     .add_step(Step::new("blend-portion", Rational32::from_integer(2)));
+    ///
+    /// let mut recipe = RecipeBuilder::new("lentil-soup")
+    ///     .add_step(Step::new("simmer-lentils", Rational32::from_integer(30)))
+    ///     .add_step(Step::new("season-soup", Rational32::from_integer(5)))
+    ///     .build_with_defaults();
+    /// recipe.set_child_variant(Some(variant));
+    ///
+    /// let both = recipe.with_child_variant().unwrap();
+    /// assert_eq!(both.get_steps().len(), 3);
+    /// // Blending happens while the adult soup is seasoned
+    /// assert_eq!(both.get_time(), Rational32::from_integer(35));
+    /// # }
+    /// ```
+    pub fn with_child_variant(&self) -> Option<Recipe> {
+        let variant = self.child_variant.as_ref()?;
+        let mut recipe = self.clone();
+        recipe.child_variant = None;
+        for (index, step) in recipe.steps.iter_mut().enumerate() {
+            for prerequisite in prerequisites(&self.steps, index) {
+                step.add_dependency(prerequisite);
+            }
+        }
+        let offset = self.steps.len();
+        let set_aside = variant
+            .set_aside_after
+            .filter(|&x| x < offset)
+            .or_else(|| offset.checked_sub(1));
+        for index in 0..variant.steps.len() {
+            let mut step = variant.steps[index].clone();
+            step.depends_on = prerequisites(&variant.steps, index)
+                .into_iter()
+                .map(|x| x + offset)
+                .collect();
+            if step.depends_on.is_empty() {
+                if let Some(x) = set_aside {
+                    step.add_dependency(x);
+                }
+            }
+            recipe.steps.push(step);
+        }
+        let time = self.get_time() - steps_time(&self.steps) + steps_time(&recipe.steps);
+        recipe.time = Fraction::from_rational(time);
+        Some(recipe)
+    }
+
+    /// Returns the time the recipe takes to make, as a fractional number of miniutes
+    ///
+    /// This is the total time, including any prep and cook time.
+    pub fn get_time(&self) -> Rational32 {
+        self.time.to_rational()
+    }
+
+    /// Returns the total time the recipe takes to make, the same as `get_time`
+    pub fn get_total_time(&self) -> Rational32 {
+        self.get_time()
+    }
+
+    /// Returns how long preparing the ingredients takes, in miniutes, if known
+    pub fn get_prep_time(&self) -> Option<Rational32> {
+        self.prep_time.as_ref().map(|x| x.to_rational())
+    }
+
+    /// Sets how long preparing the ingredients takes, in miniutes, or forgets it if given None
+    ///
+    /// The total time isn't changed.
+    pub fn set_prep_time(&mut self, time: Option<Rational32>) {
+        self.prep_time = time.map(Fraction::from_rational);
+    }
+
+    /// Returns how long the cooking itself takes, in miniutes, if known
+    pub fn get_cook_time(&self) -> Option<Rational32> {
+        self.cook_time.as_ref().map(|x| x.to_rational())
+    }
+
+    /// Sets how long the cooking itself takes, in miniutes, or forgets it if given None
+    ///
+    /// The total time isn't changed.
+    pub fn set_cook_time(&mut self, time: Option<Rational32>) {
+        self.cook_time = time.map(Fraction::from_rational);
+    }
+
+    /// Returns how many minutes of the recipe need the cook's attention
+    ///
+    /// Recipes without steps are assumed to need the cook for their whole time.
+    pub fn get_active_time(&self) -> Rational32 {
+        if self.steps.is_empty() {
+            self.get_time()
+        } else {
+            self.steps
+                .iter()
+                .fold(Rational32::from_integer(0), |total, x| {
+                    total + x.get_attended_time()
+                })
+        }
+    }
+
+    /// Returns how many minutes of the recipe are hands-off
+    ///
+    /// This is whatever part of the recipe's time doesn't need the cook's attention.
+    pub fn get_passive_time(&self) -> Rational32 {
+        ::std::cmp::max(
+            self.get_time() - self.get_active_time(),
+            Rational32::from_integer(0),
+        )
+    }
+
+    /// Returns the short codes of every piece of equipment the recipe uses, including the
+    /// equipment of any component recipes, sorted and without duplicates
+    pub fn get_equipment(&self) -> Vec<&str> {
+        let mut equipment: Vec<&str> = self
+            .steps
+            .iter()
+            .flat_map(|x| x.equipment.iter().map(|y| y.as_str()))
+            .collect();
+        for food in &self.foods {
+            if let Food::Recipe(ref recipe) = food.0 {
+                equipment.extend(recipe.get_equipment());
+            }
+        }
+        equipment.sort();
+        equipment.dedup();
+        equipment
+    }
+
+    /// Returns the steps required to produce the recipe, in order
+    pub fn get_steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns the steps in an order that respects their dependencies, or None if the
+    /// dependencies form a cycle
+    ///
+    /// Steps keep their listed order wherever their dependencies allow it. If no step declares
+    /// any dependencies, the steps are taken to be made one after another.
+    pub fn get_step_order(&self) -> Option<StepOrder<'_>> {
+        topological_order(&self.steps).map(|order| StepOrder {
+            steps: &self.steps,
+            order: order.into_iter(),
+        })
+    }
+
+    /// Returns the indices of the longest chain of dependent steps, in the order they're made
+    ///
+    /// However many hands are in the kitchen, the recipe can't be made faster than this chain.
+    /// Returns None if the dependencies form a cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let boil = Step::new("boil-pasta", Rational32::from_integer(12));
+    /// let sauce = Step::new("make-sauce", Rational32::from_integer(20));
+    /// let mut toss = Step::new("toss-in-sauce", Rational32::from_integer(2));
+    /// toss.add_dependency(0);
+    /// toss.add_dependency(1);
+    ///
+    /// let recipe = RecipeBuilder::new("pasta")
+    ///     .add_step(boil)
+    ///     .add_step(sauce)
+    ///     .add_step(toss)
+    ///     .build_with_defaults();
+    ///
+    /// // The pasta boils while the sauce is made
+    /// assert_eq!(recipe.get_critical_path(), Some(vec![1, 2]));
+    /// assert_eq!(recipe.get_critical_path_time(), Some(Rational32::from_integer(22)));
+    /// assert_eq!(recipe.get_time(), Rational32::from_integer(22));
+    ///
+    /// let order: Vec<usize> = recipe.get_step_order().unwrap().map(|(x, _)| x).collect();
+    /// assert_eq!(order, vec![0, 1, 2]);
+    /// # }
+    /// ```
+    pub fn get_critical_path(&self) -> Option<Vec<usize>> {
+        critical_path(&self.steps).map(|(path, _)| path)
+    }
+
+    /// Returns how long the longest chain of dependent steps takes, or None if the
+    /// dependencies form a cycle
+    pub fn get_critical_path_time(&self) -> Option<Rational32> {
+        critical_path(&self.steps).map(|(_, time)| time)
+    }
+
+    /// Returns how long the recipe takes to make with the given number of cooks, or None if
+    /// the dependencies of its steps form a cycle
+    ///
+    /// Unlike `get_time`, which assumes every step that can be done side by side is, this
+    /// only has as many steps needing a cook going at once as there are cooks. Hands-off steps
+    /// don't need a cook. Any time the recipe states beyond its steps is kept, and zero cooks
+    /// are treated as one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let boil = Step::new("boil-pasta", Rational32::from_integer(12));
+    /// let sauce = Step::new("make-sauce", Rational32::from_integer(20));
+    /// let mut toss = Step::new("toss-in-sauce", Rational32::from_integer(2));
+    /// toss.add_dependency(0);
+    /// toss.add_dependency(1);
+    ///
+    /// let recipe = RecipeBuilder::new("pasta")
+    ///     .add_step(boil)
+    ///     .add_step(sauce)
+    ///     .add_step(toss)
+    ///     .build_with_defaults();
+    ///
+    /// assert_eq!(recipe.get_parallel_time(1), Some(Rational32::from_integer(34)));
+    /// assert_eq!(recipe.get_parallel_time(2), Some(Rational32::from_integer(22)));
+    /// # }
+    /// ```
+    pub fn get_parallel_time(&self, workers: usize) -> Option<Rational32> {
+        let time = parallel_time(&self.steps, workers)?;
+        Some(self.get_time() - steps_time(&self.steps) + time)
+    }
+
+    /// Returns the method the current steps are for, if it has been named
+    pub fn get_method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
+    /// Names the method the current steps are for
+    pub fn set_method(&mut self, method: &str) {
+        self.method = Some(method.to_string());
+    }
+
+    /// Adds, or replaces, the steps for making the recipe with another method
+    pub fn add_method(&mut self, method: &str, steps: Vec<Step>) {
+        self.methods.insert(method.to_string(), steps);
+    }
+
+    /// Returns the methods the recipe can be made with, in order, including the current one
+    pub fn get_methods(&self) -> Vec<&str> {
+        let mut methods: Vec<&str> = self.methods.keys().map(|x| x.as_str()).collect();
+        if let Some(ref method) = self.method {
+            methods.push(method);
+            methods.sort();
+        }
+        methods
+    }
+
+    /// Switches the recipe to the steps for another method, returning false if the recipe
+    /// has no such method
+    ///
+    /// The current steps are kept as an alternative, under "default" if their method was
+    /// never named. The recipe's time changes by however much longer or shorter the new steps
+    /// take, so any time stated beyond the steps is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut chili = RecipeBuilder::new("chili")
+    ///     .add_step(Step::new("simmer-chili", Rational32::from_integer(120)))
+    ///     .build_with_defaults();
+    /// chili.set_method("stovetop");
+    /// chili.add_method(
+    ///     "pressure-cooker",
+    ///     vec![Step::new("pressure-cook-chili", Rational32::from_integer(35))],
+    /// );
+    ///
+    /// assert!(chili.select_method("pressure-cooker"));
+    /// assert_eq!(chili.get_time(), Rational32::from_integer(35));
+    /// assert_eq!(chili.get_methods(), vec!["pressure-cooker", "stovetop"]);
+    /// # }
+    /// ```
+    pub fn select_method(&mut self, method: &str) -> bool {
+        if self.method.as_deref() == Some(method) {
+            return true;
+        }
+        let steps = match self.methods.remove(method) {
+            Some(x) => x,
+            None => return false,
+        };
+        let time = self.get_time() - steps_time(&self.steps) + steps_time(&steps);
+        self.time = Fraction::from_rational(time);
+        let previous = self
+            .method
+            .replace(method.to_string())
+            .unwrap_or_else(|| "default".to_string());
+        let previous_steps = ::std::mem::replace(&mut self.steps, steps);
+        self.methods.insert(previous, previous_steps);
+        true
+    }
+
+    /// Returns a copy of the recipe made with the given method, if the recipe has it
+    ///
+    /// Intended for choosing a method at plan time, leaving the stored recipe untouched.
+    pub fn with_method(&self, method: &str) -> Option<Recipe> {
+        let mut recipe = self.clone();
+        if recipe.select_method(method) {
+            Some(recipe)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable refrence to the step at the given index, if there is one
+    pub fn get_mut_step(&mut self, index: usize) -> Option<&mut Step> {
+        self.steps.get_mut(index)
+    }
+
+    /// Inserts a step at the given index, shifting every step after it along by one
+    ///
+    /// Dependencies on the shifted steps are renumbered to match. The inserted step's own
+    /// dependencies are taken as already using the new numbering.
+    ///
+    /// # Panics
+    /// Panics if the index is greater than the number of steps.
+    pub fn insert_step(&mut self, index: usize, step: Step) {
+        for existing in &mut self.steps {
+            existing.remap_dependencies(|x| Some(if x >= index { x + 1 } else { x }));
+        }
+        self.steps.insert(index, step);
+    }
+
+    /// Removes the step at the given index
+    ///
+    /// Steps that depended on the removed step inherit its dependencies, so the ordering it
+    /// enforced is kept, and every dependency is renumbered to match.
+    ///
+    /// # Returns
+    /// The removed step, or None if there was no step at that index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut boil = Step::new("boil-pasta", Rational32::from_integer(10));
+    /// let mut sauce = Step::new("toss-in-sauce", Rational32::from_integer(2));
+    /// sauce.add_dependency(0);
+    /// let mut garnish = Step::new("garnish", Rational32::from_integer(1));
+    /// garnish.add_dependency(1);
+    ///
+    /// let mut recipe = RecipeBuilder::new("pasta")
+    ///     .set_serving_size(Unit::Count, Rational32::from_integer(1))
+    ///     .set_servings(Rational32::from_integer(2))
+    ///     .add_step(boil)
+    ///     .add_step(sauce)
+    ///     .add_step(garnish)
+    ///     .set_time(Rational32::from_integer(13))
+    ///     .set_nutrition(Nutrition::new())
+    ///     .build_recipe()
+    ///     .unwrap();
+    ///
+    /// recipe.remove_step(1);
+    /// // The garnish now waits on the pasta directly
+    /// assert_eq!(recipe.get_steps()[1].get_dependencies(), &[0]);
+    /// # }
+    /// ```
+    pub fn remove_step(&mut self, index: usize) -> Option<Step> {
+        if index >= self.steps.len() {
+            return None;
+        }
+        let removed = self.steps.remove(index);
+        for existing in &mut self.steps {
+            if existing.depends_on.contains(&index) {
+                for &inherited in &removed.depends_on {
+                    existing.add_dependency(inherited);
+                }
+            }
+            existing.remap_dependencies(|x| {
+                if x == index {
+                    None
+                } else if x > index {
+                    Some(x - 1)
+                } else {
+                    Some(x)
+                }
+            });
+        }
+        Some(removed)
+    }
+
+    /// Moves the step at one index to another, renumbering every dependency to match
+    ///
+    /// Moving a step doesn't change what it depends on, so it is up to the caller not to move
+    /// a step ahead of the steps it depends on.
+    ///
+    /// # Returns
+    /// False, without changing anything, if either index is out of range
+    pub fn move_step(&mut self, from: usize, to: usize) -> bool {
+        let len = self.steps.len();
+        if from >= len || to >= len {
+            return false;
+        }
+        let step = self.steps.remove(from);
+        self.steps.insert(to, step);
+        for existing in &mut self.steps {
+            existing.remap_dependencies(|x| {
+                Some(if x == from {
+                    to
+                } else if from < to && x > from && x <= to {
+                    x - 1
+                } else if to < from && x >= to && x < from {
+                    x + 1
+                } else {
+                    x
+                })
+            });
+        }
+        true
+    }
+
+    /// Returns the name (as an &IString) of this recipe
     pub fn get_name(&self) -> &IString {
         &self.name
     }
 
-    /// Returns a mutable refrence to this foods name
-    pub fn get_mut_name(&mut self) -> &mut IString {
-        &mut self.name
+    /// Returns the serving size (as an amount) of this recipe
+    pub fn get_serving_size(&self) -> Amount {
+        self.serving_size
+    }
+
+    /// Returns the number of servings the recipe makes
+    pub fn get_servings(&self) -> Rational32 {
+        self.servings.to_rational()
+    }
+
+    /// Returns the component foods, and the amount of each that is required
+    pub fn get_foods(&self) -> &[(Food, Amount)] {
+        &self.foods
+    }
+
+    /// Returns the nutritional value of a serving of this recipe
+    ///
+    /// The same as `get_nutrition_per_serving`, see `get_batch_nutrition` for the whole
+    /// recipe.
+    pub fn get_nutrition(&self) -> &Nutrition {
+        &self.nutrition
+    }
+
+    /// Returns the nutritional value of a single serving of this recipe
+    pub fn get_nutrition_per_serving(&self) -> &Nutrition {
+        &self.nutrition
+    }
+
+    /// Returns the nutritional value of everything the recipe makes, all of its servings
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut loaf = RecipeBuilder::new("banana-bread")
+    ///     .set_servings(Rational32::new(15, 2))
+    ///     .build_with_defaults();
+    /// let mut whole = Nutrition::new();
+    /// whole.set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(2250)));
+    /// loaf.set_batch_nutrition(whole);
+    ///
+    /// let calories = |x: &Nutrition| x.get_calories().map(|x| x.get_amount());
+    /// assert_eq!(
+    ///     calories(loaf.get_nutrition_per_serving()),
+    ///     Some(Rational32::from_integer(300))
+    /// );
+    /// assert_eq!(
+    ///     calories(&loaf.get_nutrition_for(Rational32::from_integer(2))),
+    ///     Some(Rational32::from_integer(600))
+    /// );
+    /// assert_eq!(
+    ///     calories(&loaf.get_batch_nutrition()),
+    ///     Some(Rational32::from_integer(2250))
+    /// );
+    /// # }
+    /// ```
+    pub fn get_batch_nutrition(&self) -> Nutrition {
+        self.nutrition.scaled(self.get_servings())
+    }
+
+    /// Returns the nutritional value of the given number of servings of this recipe
+    pub fn get_nutrition_for(&self, servings: Rational32) -> Nutrition {
+        self.nutrition.scaled(servings)
+    }
+
+    /// Sets the nutritional value of a single serving of this recipe
+    ///
+    /// The nutrition is no longer derived from the component foods afterwards.
+    pub fn set_nutrition_per_serving(&mut self, nutrition: Nutrition) {
+        self.nutrition_derived = false;
+        self.nutrition = nutrition;
+    }
+
+    /// Sets the nutritional value of everything the recipe makes, which is divided between
+    /// its servings
+    ///
+    /// A recipe making no servings has nothing to divide between, so the value is stored as
+    /// given. The nutrition is no longer derived from the component foods afterwards.
+    pub fn set_batch_nutrition(&mut self, nutrition: Nutrition) {
+        let servings = self.get_servings();
+        let per_serving = if servings == Rational32::from_integer(0) {
+            nutrition
+        } else {
+            nutrition.scaled(servings.recip())
+        };
+        self.set_nutrition_per_serving(per_serving);
+    }
+
+    /// Returns where the nutritional value of the recipe itself came from
+    ///
+    /// See `Food::get_nutrition_source` for the source after taking the component foods
+    /// into account.
+    pub fn get_nutrition_source(&self) -> NutritionSource {
+        self.nutrition_source
+    }
+
+    /// Records where the nutritional value of the recipe itself came from
+    pub fn set_nutrition_source(&mut self, source: NutritionSource) {
+        self.nutrition_source = source;
+    }
+
+    /// Adds an ingredient to the end of the list of ingredients
+    pub fn add_ingredient(&mut self, food: Food, amount: Amount) {
+        self.foods.push((food, amount));
+        self.ingredients_changed();
+    }
+
+    /// Removes the ingredient at the given index
+    ///
+    /// # Returns
+    /// The removed ingredient, or None if there was no ingredient at that index
+    pub fn remove_ingredient(&mut self, index: usize) -> Option<(Food, Amount)> {
+        if index < self.foods.len() {
+            let removed = self.foods.remove(index);
+            self.ingredients_changed();
+            Some(removed)
+        } else {
+            None
+        }
+    }
+
+    /// Swaps the food of the ingredient at the given index for another, keeping the amount
+    ///
+    /// # Returns
+    /// The replaced food, or None if there was no ingredient at that index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let butter = Food::new_from_raw_food(RawFood::new(
+    ///     IString::new("butter"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(14)),
+    ///     Nutrition::new(),
+    /// ));
+    /// let olive_oil = Food::new_from_raw_food(RawFood::new(
+    ///     IString::new("olive-oil"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(14)),
+    ///     Nutrition::new(),
+    /// ));
+    ///
+    /// let mut recipe = RecipeBuilder::new("fried-egg")
+    ///     .set_serving_size(Unit::Count, Rational32::from_integer(1))
+    ///     .set_servings(Rational32::from_integer(1))
+    ///     .add_food(butter, Unit::Gram, Rational32::from_integer(14))
+    ///     .add_step(Step::new("fry-egg", Rational32::from_integer(5)))
+    ///     .set_time(Rational32::from_integer(5))
+    ///     .set_nutrition(Nutrition::new())
+    ///     .build_recipe()
+    ///     .unwrap();
+    ///
+    /// recipe.replace_ingredient(0, olive_oil);
+    /// recipe.set_ingredient_amount(0, Rational32::from_integer(10));
+    /// let (ref food, amount) = recipe.get_foods()[0];
+    /// assert_eq!(food.get_name().get_short_code(), "olive-oil");
+    /// assert_eq!(amount.get_amount(), Rational32::from_integer(10));
+    /// # }
+    /// ```
+    pub fn replace_ingredient(&mut self, index: usize, food: Food) -> Option<Food> {
+        if index < self.foods.len() {
+            let replaced = ::std::mem::replace(&mut self.foods[index].0, food);
+            self.ingredients_changed();
+            Some(replaced)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the amount of the ingredient at the given index, keeping its unit
+    ///
+    /// # Returns
+    /// False, without changing anything, if there was no ingredient at that index
+    pub fn set_ingredient_amount(&mut self, index: usize, amount: Rational32) -> bool {
+        if index < self.foods.len() {
+            self.foods[index].1.set_amount(amount);
+            self.ingredients_changed();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns a copy of the recipe making the given number of servings
+    ///
+    /// Every ingredient amount is scaled to match, and so are the times of steps marked as
+    /// scaling with quantity, including those of alternative methods. The recipe's time
+    /// changes by however much its steps do. A serving stays the same size, and so does its
+    /// nutrition. A recipe making no servings can't be scaled, and is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let potatoes = Food::new_from_raw_food(RawFood::new(
+    ///     IString::new("potatoes"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(150)),
+    ///     Nutrition::new(),
+    /// ));
+    /// let mut peel = Step::new("peel-potatoes", Rational32::from_integer(10));
+    /// peel.set_scales_with_quantity(true);
+    /// let roast = Step::new("roast-potatoes", Rational32::from_integer(45));
+    ///
+    /// let recipe = RecipeBuilder::new("roast-potatoes")
+    ///     .set_servings(Rational32::from_integer(4))
+    ///     .add_food(potatoes, Unit::Gram, Rational32::from_integer(600))
+    ///     .add_step(peel)
+    ///     .add_step(roast)
+    ///     .build_with_defaults();
+    ///
+    /// let scaled = recipe.scale(Rational32::from_integer(6));
+    /// assert_eq!(scaled.get_servings(), Rational32::from_integer(6));
+    /// assert_eq!(scaled.get_foods()[0].1.get_amount(), Rational32::from_integer(900));
+    /// // Peeling takes half as long again, roasting doesn't change
+    /// assert_eq!(scaled.get_time(), Rational32::from_integer(60));
+    /// # }
+    /// ```
+    pub fn scale(&self, target_servings: Rational32) -> Recipe {
+        let servings = self.get_servings();
+        if servings == Rational32::from_integer(0) {
+            return self.clone();
+        }
+        let factor = target_servings / servings;
+        let mut recipe = self.clone();
+        recipe.servings = Fraction::from_rational(target_servings);
+        for (_, amount) in &mut recipe.foods {
+            amount.set_amount(amount.get_amount() * factor);
+        }
+        recipe.steps = self.steps.iter().map(|x| x.scaled(factor)).collect();
+        recipe.time = Fraction::from_rational(
+            self.get_time() - steps_time(&self.steps) + steps_time(&recipe.steps),
+        );
+        for steps in recipe.methods.values_mut() {
+            *steps = steps.iter().map(|x| x.scaled(factor)).collect();
+        }
+        recipe
+    }
+
+    /// Sets the time the recipe takes to the time its steps take
+    ///
+    /// Steps that don't depend on each other are counted as done side by side, so this is the
+    /// length of the critical path. The time isn't recomputed automatically when steps change,
+    /// as a stated time may deliberately differ from its steps, so call this when it should
+    /// track them.
+    pub fn recompute_time(&mut self) {
+        self.time = Fraction::from_rational(steps_time(&self.steps));
+    }
+
+    /// Computes the nutritional value of a serving from the component foods
+    ///
+    /// Each food's nutrition is scaled by how many of its servings the recipe uses, and the
+    /// total divided between the recipe's servings. External meals count as one serving each,
+    /// and amounts that can't be converted to a food's serving size, with the household's
+    /// unit sizes and densities, count as a number of servings. Foods with an empty serving
+    /// size contribute nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::settings::Settings;
+    ///
+    /// # fn main() {
+    /// let mut oats_nutrition = Nutrition::new();
+    /// oats_nutrition.set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(150)));
+    /// let oats = RawFood::new(
+    ///     IString::new("oats"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(40)),
+    ///     oats_nutrition,
+    /// );
+    ///
+    /// let mut porridge = RecipeBuilder::new("porridge")
+    ///     .set_servings(Rational32::from_integer(2))
+    ///     .add_food(Food::new_from_raw_food(oats), Unit::Gram, Rational32::from_integer(120))
+    ///     .build_with_defaults();
+    /// porridge.derive_nutrition();
+    /// assert!(porridge.get_nutrition() == &porridge.compute_nutrition(&Settings::new()));
+    ///
+    /// // Three servings of oats, split two ways
+    /// assert_eq!(
+    ///     porridge.get_nutrition().get_calories().map(|x| x.get_amount()),
+    ///     Some(Rational32::from_integer(225))
+    /// );
+    /// # }
+    /// ```
+    pub fn compute_nutrition(&self, settings: &Settings) -> Nutrition {
+        let mut total = Nutrition::new();
+        for (food, amount) in &self.foods {
+            if let Some(servings) = food.servings_in(*amount, settings.get_conversions()) {
+                total.add(&food.get_nutrition().scaled(servings));
+            }
+        }
+        let servings = self.get_servings();
+        if servings == Rational32::from_integer(0) {
+            total
+        } else {
+            total.scaled(servings.recip())
+        }
+    }
+
+    /// Adds the ingredients of the given multiple of this recipe to a flat list, merging
+    /// duplicates, see `Food::decompose`
+    fn decompose_into(
+        &self,
+        factor: Rational32,
+        conversions: &ConversionTable,
+        ingredients: &mut Vec<(Food, Amount)>,
+    ) {
+        for (food, amount) in &self.foods {
+            let scaled = Amount::new(amount.get_unit(), amount.get_amount() * factor);
+            if let Food::Recipe(ref recipe) = *food {
+                let servings = recipe.get_servings();
+                if let Some(used) = food.servings_in(scaled, conversions) {
+                    if servings != Rational32::from_integer(0) {
+                        recipe.decompose_into(used / servings, conversions, ingredients);
+                        continue;
+                    }
+                }
+            }
+            let id = FoodId::of(food);
+            let existing = ingredients.iter_mut().find(|(x, y)| {
+                x.get_name().get_short_code() == id.get_short_code()
+                    && conversions.convert_food(&id, &scaled, y.get_unit()).is_ok()
+            });
+            match existing {
+                Some((_, total)) => {
+                    match conversions.convert_food(&id, &scaled, total.get_unit()) {
+                        Ok(x) => total.set_amount(total.get_amount() + x.get_amount()),
+                        Err(_) => ingredients.push((food.clone(), scaled)),
+                    }
+                }
+                None => ingredients.push((food.clone(), scaled)),
+            }
+        }
+    }
+
+    /// Replaces the nutrition with that computed from the component foods, with the standard
+    /// unit sizes, and keeps it derived as the ingredients change
+    pub fn derive_nutrition(&mut self) {
+        self.nutrition_derived = true;
+        self.nutrition = self.compute_nutrition(&Settings::default());
     }
 
-    /// Returns the serving size (as an amount) of this food
-    pub fn get_serving_size(&self) -> Amount {
-        self.serving_size
+    /// Returns true if the nutrition is derived from the component foods
+    pub fn is_nutrition_derived(&self) -> bool {
+        self.nutrition_derived
     }
 
-    /// Returns the nutritional value of this food
-    pub fn get_nutrition(&self) -> &Nutrition {
-        &self.nutrition
+    /// Hook run after every change to the ingredients, keeping derived values up to date
+    fn ingredients_changed(&mut self) {
+        if self.nutrition_derived {
+            self.nutrition = self.compute_nutrition(&Settings::default());
+        }
     }
 }
 
-/// A composite Food, comprised of one or more other foods, as well as a set of
-/// directions for preparing the food.
-///
-/// A recipe knows its name, its components foods, the ammounts required, the steps
-/// required to produce the recipe, the nutritonal value of the resulting food,
-/// how many servings it produces, and how long the recipe takes to make.
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Recipe {
-    /// The name of the Recipe as an encoded String
-    ///
-    /// The IString wrapper type is used to help with i18n
-    name: IString,
-    /// Contains the serving size and unit, encoded as an Amount
-    serving_size: Amount,
-    /// Number of servings the recipe makes.
-    ///
-    /// This is stored as a rational to prevent conflicts between the assumed
-    /// reality and actual reality. While it would be nice to have this as in integer
-    /// And force recipes to make a whole number of servings, some recipies, as input,
-    /// may make a fractional number of servings.
-    servings: Fraction,
-    /// Contains the component foods and ammounts there of
-    foods: Vec<(Food, Amount)>,
-    /// Contains the steps, in order, required to produce the recipe
-    steps: Vec<Step>,
-    /// How long the recipe takes to make, in miniutes
-    ///
-    /// Stored as  Fraction rather than directly as a Rational32 to allow serde derive
-    time: Fraction,
-    /// Nutritional value of a serving of this Recipe
-    nutrition: Nutrition,
+/// Describes why a builder couldn't build its value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    MissingServingSize,
+    MissingServings,
+    MissingTime,
+    MissingNutrition,
+    /// A recipe needs at least one step
+    EmptySteps,
 }
 
-impl Recipe {
-    /// Constructs a new Recipe given the all the member values
-    ///
-    /// Probably not a good idea to use this directly,
-    /// a Recipe Builder is much better idea
-    pub fn new(
-        name: IString,
-        serving_size: Amount,
-        servings: Rational32,
-        foods: Vec<(Food, Amount)>,
-        steps: Vec<Step>,
-        time: Rational32,
-        nutrition: Nutrition,
-    ) -> Recipe {
-        Recipe {
-            name,
-            serving_size,
-            servings: Fraction::from_rational(servings),
-            foods,
-            steps,
-            time: Fraction::from_rational(time),
-            nutrition,
-        }
+impl ::std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        let message = match *self {
+            BuildError::MissingServingSize => "serving size not set",
+            BuildError::MissingServings => "servings not set",
+            BuildError::MissingTime => "time not set",
+            BuildError::MissingNutrition => "nutrition not set",
+            BuildError::EmptySteps => "no steps added",
+        };
+        write!(f, "{}", message)
     }
+}
 
-    /// Returns the time the recipe takes to make, as a fractional number of miniutes
-    pub fn get_time(&self) -> Rational32 {
-        self.time.to_rational()
+impl ::std::error::Error for BuildError {}
+
+/// Describes something suspicious about a builder's value that doesn't stop it being built
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildWarning {
+    /// The time set for a recipe differs from the time its steps take
+    TimeMismatch {
+        stated: Rational32,
+        steps: Rational32,
+    },
+}
+
+impl ::std::fmt::Display for BuildWarning {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            BuildWarning::TimeMismatch { stated, steps } => write!(
+                f,
+                "time is set to {} minutes, but the steps take {} minutes",
+                stated, steps
+            ),
+        }
     }
 }
 
@@ -406,7 +3439,12 @@ pub struct RecipeBuilder {
     foods: Vec<(Food, Amount)>,
     steps: Vec<Step>,
     time: Option<Rational32>,
+    prep_time: Option<Rational32>,
+    cook_time: Option<Rational32>,
     nutrition: Option<Nutrition>,
+    derive_nutrition: bool,
+    infer_time: bool,
+    tags: Vec<String>,
 }
 
 impl RecipeBuilder {
@@ -422,7 +3460,12 @@ impl RecipeBuilder {
             foods: Vec::new(),
             steps: Vec::new(),
             time: None,
+            prep_time: None,
+            cook_time: None,
             nutrition: None,
+            derive_nutrition: false,
+            infer_time: false,
+            tags: Vec::new(),
         }
     }
 
@@ -471,6 +3514,39 @@ impl RecipeBuilder {
         self
     }
 
+    /// Takes the time the recipe will take to make from its steps, when no time is set, nor
+    /// both a prep and cook time
+    ///
+    /// Steps that don't depend on each other are counted as done side by side, so the time
+    /// is the length of the steps' critical path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = RecipeBuilder::new("toast")
+    ///     .set_serving_size(Unit::Count, Rational32::from_integer(1))
+    ///     .set_servings(Rational32::from_integer(2))
+    ///     .add_step(Step::new("toast-bread", Rational32::from_integer(3)))
+    ///     .add_step(Step::new("butter-toast", Rational32::from_integer(1)))
+    ///     .set_nutrition(Nutrition::new())
+    ///     .infer_time()
+    ///     .build_recipe()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(recipe.get_time(), Rational32::from_integer(4));
+    /// # }
+    /// ```
+    pub fn infer_time(&mut self) -> &mut Self {
+        self.infer_time = true;
+        self
+    }
+
     /// Sets the ammount of time that the recipe will take to make
     ///
     /// Will overwrite the existing value if one exists
@@ -479,18 +3555,135 @@ impl RecipeBuilder {
         self
     }
 
-    /// Attaches a nutrition object to this recipe
+    /// Sets how long preparing the ingredients takes, in miniutes
+    ///
+    /// If both prep and cook time are set, but the total time isn't, the total is their sum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = RecipeBuilder::new("roast-vegetables")
+    ///     .set_prep_time(Rational32::from_integer(15))
+    ///     .set_cook_time(Rational32::from_integer(40))
+    ///     .build_with_defaults();
+    ///
+    /// assert_eq!(recipe.get_prep_time(), Some(Rational32::from_integer(15)));
+    /// assert_eq!(recipe.get_time(), Rational32::from_integer(55));
+    /// # }
+    /// ```
+    pub fn set_prep_time(&mut self, time: Rational32) -> &mut Self {
+        self.prep_time = Some(time);
+        self
+    }
+
+    /// Sets how long the cooking itself takes, in miniutes
+    ///
+    /// See `set_prep_time` for how this affects the total time.
+    pub fn set_cook_time(&mut self, time: Rational32) -> &mut Self {
+        self.cook_time = Some(time);
+        self
+    }
+
+    /// Returns the total time set, or the sum of the prep and cook time if both are set
+    fn stated_time(&self) -> Option<Rational32> {
+        match (self.time, self.prep_time, self.cook_time) {
+            (Some(x), _, _) => Some(x),
+            (None, Some(prep), Some(cook)) => Some(prep + cook),
+            _ => None,
+        }
+    }
+
+    /// Attaches a nutrition object, for a single serving, to this recipe
     ///
     /// Will overwrite the existing value if one exists
     pub fn set_nutrition(&mut self, nutrition: Nutrition) -> &mut Self {
         self.nutrition = Some(nutrition);
+        self.derive_nutrition = false;
+        self
+    }
+
+    /// Derives the recipe's nutrition from its component foods, instead of setting it
+    ///
+    /// See `Recipe::compute_nutrition`. Will overwrite a nutrition object if one was set.
+    pub fn derive_nutrition(&mut self) -> &mut Self {
+        self.nutrition = None;
+        self.derive_nutrition = true;
+        self
+    }
+
+    /// Adds a tag, see `Recipe::add_tag`
+    pub fn add_tag(&mut self, tag: &str) -> &mut Self {
+        self.tags.push(tag.to_string());
         self
     }
 
+    /// Checks the builder for anything suspicious that wouldn't stop it building
+    ///
+    /// Warns when the time set differs from the time the steps take, as the time may have
+    /// been set before a step was added or changed. Builders without steps, or without a time
+    /// set, aren't checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut builder = RecipeBuilder::new("toast");
+    /// builder
+    ///     .add_step(Step::new("toast-bread", Rational32::from_integer(3)))
+    ///     .set_time(Rational32::from_integer(10));
+    ///
+    /// assert_eq!(
+    ///     builder.validate(),
+    ///     vec![BuildWarning::TimeMismatch {
+    ///         stated: Rational32::from_integer(10),
+    ///         steps: Rational32::from_integer(3),
+    ///     }]
+    /// );
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Vec<BuildWarning> {
+        let mut warnings = Vec::new();
+        if let Some(stated) = self.time {
+            let steps = steps_time(&self.steps);
+            if !self.steps.is_empty() && stated != steps {
+                warnings.push(BuildWarning::TimeMismatch { stated, steps });
+            }
+        }
+        warnings
+    }
+
     /// Creates a Recipe from the given recipe builder
     ///
-    /// Will fail if any options are unset
-    pub fn build_recipe(&self) -> Result<Recipe, &str> {
+    /// Will fail if any options are unset, or there are no steps
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let result = RecipeBuilder::new("toast")
+    ///     .set_serving_size(Unit::Count, Rational32::from_integer(1))
+    ///     .set_servings(Rational32::from_integer(2))
+    ///     .build_recipe();
+    /// assert_eq!(result.err(), Some(BuildError::MissingTime));
+    /// # }
+    /// ```
+    pub fn build_recipe(&self) -> Result<Recipe, BuildError> {
         let serving_size: Amount;
         let servings: Rational32;
         let time: Rational32;
@@ -499,22 +3692,29 @@ impl RecipeBuilder {
         if let Some(x) = self.serving_size {
             serving_size = x;
         } else {
-            return Err("Serving Size not set");
+            return Err(BuildError::MissingServingSize);
         }
         if let Some(x) = self.servings {
             servings = x;
         } else {
-            return Err("Servings not set");
+            return Err(BuildError::MissingServings);
         }
-        if let Some(x) = self.time {
+        if let Some(x) = self.stated_time() {
             time = x;
+        } else if self.infer_time {
+            time = steps_time(&self.steps);
         } else {
-            return Err("Time not set");
+            return Err(BuildError::MissingTime);
         }
         if let Some(x) = &self.nutrition {
             nutrition = x.clone();
+        } else if self.derive_nutrition {
+            nutrition = Nutrition::new();
         } else {
-            return Err("Nutrition not set");
+            return Err(BuildError::MissingNutrition);
+        }
+        if self.steps.is_empty() {
+            return Err(BuildError::EmptySteps);
         }
 
         // Clone the other values
@@ -523,9 +3723,387 @@ impl RecipeBuilder {
         let steps = self.steps.clone();
 
         // Construct the Recipe!
-        let recipe = Recipe::new(name, serving_size, servings, foods, steps, time, nutrition);
+        let mut recipe = Recipe::new(name, serving_size, servings, foods, steps, time, nutrition);
+        recipe.set_prep_time(self.prep_time);
+        recipe.set_cook_time(self.cook_time);
+        if self.derive_nutrition {
+            recipe.derive_nutrition();
+        }
+        for tag in &self.tags {
+            recipe.add_tag(tag);
+        }
 
         // Return the recipe
         Ok(recipe)
     }
+
+    /// Creates a Recipe from the given recipe builder, filling in defaults for unset options
+    ///
+    /// Intended for quickly capturing a recipe that will be refined later. The defaults are:
+    ///
+    /// - Serving size: a count of one
+    /// - Servings: 1
+    /// - Time: the prep time plus the cook time, if both are set, otherwise the length of the
+    ///   steps' critical path
+    /// - Nutrition: empty, unless it is being derived
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = RecipeBuilder::new("toast")
+    ///     .add_step(Step::new("toast-bread", Rational32::from_integer(3)))
+    ///     .add_step(Step::new("butter-toast", Rational32::from_integer(1)))
+    ///     .build_with_defaults();
+    ///
+    /// assert_eq!(recipe.get_servings(), Rational32::from_integer(1));
+    /// assert_eq!(recipe.get_time(), Rational32::from_integer(4));
+    /// # }
+    /// ```
+    pub fn build_with_defaults(&self) -> Recipe {
+        let time = self
+            .stated_time()
+            .unwrap_or_else(|| steps_time(&self.steps));
+        let mut recipe = Recipe::new(
+            self.name.clone(),
+            self.serving_size
+                .unwrap_or_else(|| Amount::new(Unit::Count, Rational32::from_integer(1))),
+            self.servings.unwrap_or_else(|| Rational32::from_integer(1)),
+            self.foods.clone(),
+            self.steps.clone(),
+            time,
+            self.nutrition.clone().unwrap_or_default(),
+        );
+        recipe.set_prep_time(self.prep_time);
+        recipe.set_cook_time(self.cook_time);
+        if self.derive_nutrition {
+            recipe.derive_nutrition();
+        }
+        for tag in &self.tags {
+            recipe.add_tag(tag);
+        }
+        recipe
+    }
+}
+
+/// Provides a builder for RawFoods
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let oats = RawFoodBuilder::new("rolled-oats")
+///     .add_name("en_US", "Rolled oats")
+///     .set_serving_size(Unit::Gram, Rational32::from_integer(40))
+///     .set_nutrition(Nutrition::new())
+///     .add_barcode("0 12345 67890 5")
+///     .build_raw_food()
+///     .unwrap();
+/// assert!(oats.has_barcode("012345678905"));
+///
+/// let result = RawFoodBuilder::new("rolled-oats").build_raw_food();
+/// assert_eq!(result.err(), Some(BuildError::MissingServingSize));
+/// # }
+/// ```
+pub struct RawFoodBuilder {
+    name: IString,
+    serving_size: Option<Amount>,
+    nutrition: Option<Nutrition>,
+    nutrition_source: NutritionSource,
+    barcodes: Vec<String>,
+    brand: Option<String>,
+    generic: Option<String>,
+    price: Option<Rational32>,
+    tags: Vec<String>,
+    category: Option<String>,
+    diet_tags: BTreeSet<DietTag>,
+    allergens: BTreeSet<Allergen>,
+}
+
+impl RawFoodBuilder {
+    /// Creates a new RawFoodBuilder from a (short code) name
+    pub fn new(short_code: &str) -> RawFoodBuilder {
+        RawFoodBuilder {
+            name: IString::new(short_code),
+            serving_size: None,
+            nutrition: None,
+            nutrition_source: NutritionSource::default(),
+            barcodes: Vec::new(),
+            brand: None,
+            generic: None,
+            price: None,
+            tags: Vec::new(),
+            category: None,
+            diet_tags: BTreeSet::new(),
+            allergens: BTreeSet::new(),
+        }
+    }
+
+    /// Adds a name to the RawFood
+    ///
+    /// Will overwrite the exsiting name if given a name that already exists
+    pub fn add_name(&mut self, lang_code: &str, name: &str) -> &mut Self {
+        self.name.set_value_for(lang_code, name);
+        self
+    }
+
+    /// Sets a serving size, from a unit and an amount
+    pub fn set_serving_size(&mut self, unit: Unit, amount: Rational32) -> &mut Self {
+        self.serving_size = Some(Amount::new(unit, amount));
+        self
+    }
+
+    /// Attaches a nutrition object, for a single serving, to this food
+    pub fn set_nutrition(&mut self, nutrition: Nutrition) -> &mut Self {
+        self.nutrition = Some(nutrition);
+        self
+    }
+
+    /// Records where the nutritional value came from
+    pub fn set_nutrition_source(&mut self, source: NutritionSource) -> &mut Self {
+        self.nutrition_source = source;
+        self
+    }
+
+    /// Adds a barcode, see `RawFood::add_barcode`
+    pub fn add_barcode(&mut self, barcode: &str) -> &mut Self {
+        self.barcodes.push(barcode.to_string());
+        self
+    }
+
+    /// Adds a tag, see `RawFood::add_tag`
+    pub fn add_tag(&mut self, tag: &str) -> &mut Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// Sets the brand, making the food a branded product
+    pub fn set_brand(&mut self, brand: &str) -> &mut Self {
+        self.brand = Some(brand.to_string());
+        self
+    }
+
+    /// Sets the short code of the generic food this is a variant of
+    pub fn set_generic(&mut self, short_code: &str) -> &mut Self {
+        self.generic = Some(short_code.to_string());
+        self
+    }
+
+    /// Sets the price of a serving
+    pub fn set_price(&mut self, price: Rational32) -> &mut Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Sets the grocery category, see `RawFood::set_category`
+    pub fn set_category(&mut self, category: &str) -> &mut Self {
+        self.category = Some(category.to_string());
+        self
+    }
+
+    /// Marks the food as suitable for a diet
+    pub fn add_diet_tag(&mut self, tag: DietTag) -> &mut Self {
+        self.diet_tags.insert(tag);
+        self
+    }
+
+    /// Records that the food contains an allergen
+    pub fn add_allergen(&mut self, allergen: Allergen) -> &mut Self {
+        self.allergens.insert(allergen);
+        self
+    }
+
+    /// Creates a RawFood from the given builder
+    ///
+    /// Will fail if the serving size or nutrition are unset
+    pub fn build_raw_food(&self) -> Result<RawFood, BuildError> {
+        let serving_size = self.serving_size.ok_or(BuildError::MissingServingSize)?;
+        let nutrition = self.nutrition.clone().ok_or(BuildError::MissingNutrition)?;
+        let mut food = RawFood::new(self.name.clone(), serving_size, nutrition);
+        food.set_nutrition_source(self.nutrition_source);
+        for barcode in &self.barcodes {
+            food.add_barcode(barcode);
+        }
+        food.set_brand(self.brand.as_deref());
+        food.set_generic(self.generic.as_deref());
+        food.set_price(self.price);
+        food.set_category(self.category.as_deref());
+        for tag in &self.tags {
+            food.add_tag(tag);
+        }
+        food.diet_tags = self.diet_tags.clone();
+        food.allergens = self.allergens.clone();
+        Ok(food)
+    }
+}
+
+/// A drink, such as water or coffee, tracked by volume alongside meals
+///
+/// Beverages are deliberately lightweight, they know what they are and how much was drunk,
+/// and nothing else. Drinks with real nutritional value should be logged as foods instead.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Beverage {
+    /// The name of the drink, the short code is used to group drinks in reports
+    name: IString,
+    /// How much was drunk
+    volume: Amount,
+}
+
+impl Beverage {
+    /// Constructs a new Beverage from its name and volume
+    pub fn new(name: IString, volume: Amount) -> Beverage {
+        Beverage { name, volume }
+    }
+
+    /// Returns the name of the drink
+    pub fn get_name(&self) -> &IString {
+        &self.name
+    }
+
+    /// Returns how much was drunk
+    pub fn get_volume(&self) -> Amount {
+        self.volume
+    }
+}
+
+/// A placeholder for a meal someone else made, such as a restaurant meal or takeout
+///
+/// External meals keep plans and logs complete on days nobody cooks. Their nutrition can
+/// only ever be a rough estimate, so they store the handful of numbers a person can
+/// reasonably guess at, along with what the meal cost.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExternalMeal {
+    name: IString,
+    /// What the meal cost, in the household's currency
+    cost: Option<Fraction>,
+    /// The estimated nutritional value of the meal
+    nutrition: Nutrition,
+}
+
+impl ExternalMeal {
+    /// Constructs a new ExternalMeal, with no estimates
+    pub fn new(name: IString) -> ExternalMeal {
+        ExternalMeal {
+            name,
+            cost: None,
+            nutrition: Nutrition::new(),
+        }
+    }
+
+    /// Quickly captures a meal eaten out, with a name in a single language, a calorie
+    /// estimate, and a cost
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let pizza = ExternalMeal::quick(
+    ///     "friday-pizza",
+    ///     "en_US",
+    ///     "Pizza at Luigi's",
+    ///     Rational32::from_integer(900),
+    ///     Rational32::new(3150, 100),
+    /// );
+    /// assert_eq!(pizza.get_calories(), Some(Rational32::from_integer(900)));
+    /// let food = Food::new_from_external(pizza);
+    /// assert_eq!(food.get_name().get_value("en_US"), Some("Pizza at Luigi's"));
+    /// # }
+    /// ```
+    pub fn quick(
+        short_code: &str,
+        lang: &str,
+        name: &str,
+        calories: Rational32,
+        cost: Rational32,
+    ) -> ExternalMeal {
+        let mut string = IString::new(short_code);
+        string.set_value_for(lang, name);
+        string.set_default(lang);
+        let mut meal = ExternalMeal::new(string);
+        meal.set_calories(calories);
+        meal.set_cost(cost);
+        meal
+    }
+
+    /// Returns the name of the meal
+    pub fn get_name(&self) -> &IString {
+        &self.name
+    }
+
+    /// Returns a mutable refrence to the name of the meal
+    pub fn get_mut_name(&mut self) -> &mut IString {
+        &mut self.name
+    }
+
+    /// Returns the estimated energy, in kilocalories
+    pub fn get_calories(&self) -> Option<Rational32> {
+        self.nutrition.get_calories().map(|x| x.get_amount())
+    }
+
+    /// Sets the estimated energy, in kilocalories
+    pub fn set_calories(&mut self, calories: Rational32) -> &mut Self {
+        self.nutrition
+            .set_calories(Amount::new(Unit::Kilocalorie, calories));
+        self
+    }
+
+    /// Returns the estimated protein, fat, and carbohydrates, in grams
+    pub fn get_macros(&self) -> (Option<Rational32>, Option<Rational32>, Option<Rational32>) {
+        (
+            self.nutrition.get_protein().map(|x| x.get_amount()),
+            self.nutrition.get_fat().map(|x| x.get_amount()),
+            self.nutrition.get_carbohydrates().map(|x| x.get_amount()),
+        )
+    }
+
+    /// Sets the estimated protein, fat, and carbohydrates, in grams
+    pub fn set_macros(
+        &mut self,
+        protein: Rational32,
+        fat: Rational32,
+        carbohydrates: Rational32,
+    ) -> &mut Self {
+        self.nutrition
+            .set_protein(Amount::new(Unit::Gram, protein))
+            .set_fat(Amount::new(Unit::Gram, fat))
+            .set_carbohydrates(Amount::new(Unit::Gram, carbohydrates));
+        self
+    }
+
+    /// Returns what the meal cost, in the household's currency
+    pub fn get_cost(&self) -> Option<Rational32> {
+        self.cost.map(|x| x.to_rational())
+    }
+
+    /// Sets what the meal cost, in the household's currency
+    pub fn set_cost(&mut self, cost: Rational32) -> &mut Self {
+        self.cost = Some(Fraction::from_rational(cost));
+        self
+    }
+
+    /// Returns the estimated nutritional value of the meal
+    pub fn get_nutrition(&self) -> &Nutrition {
+        &self.nutrition
+    }
+
+    /// Returns a mutable refrence to the estimated nutritional value of the meal, for
+    /// estimates beyond calories and macros
+    pub fn get_mut_nutrition(&mut self) -> &mut Nutrition {
+        &mut self.nutrition
+    }
 }
@@ -0,0 +1,651 @@
+//! This module contains the optimizer, which searches for the best way to fill a meal plan
+//!
+//! The optimizer starts from a template plan, whose blocks without food are the slots to be
+//! filled, and generates candidate plans by assigning a recipe to each slot. Candidates
+//! breaking a hard constraint are discarded, and the rest are ranked by their penalty plus
+//! their score against the objectives, lower is better.
+//!
+//! With an `Objective::MeetNutritionGoals`, the optimizer chooses which recipes to cook, not
+//! just where they fit, picking the ones that bring each day closest to its `GoalPeriod`.
+//!
+//! Every candidate is generated from the seed and its own index alone, so candidates can be
+//! evaluated in any order. With the `rayon` feature they are evaluated in parallel, and the
+//! chosen plan is the same as when they are evaluated one after another.
+//!
+//! Optimizing many candidates can take a while, so `Optimizer::optimize_with_progress`
+//! reports how many candidates have been evaluated and can be cancelled part way through.
+//!
+//! To explain a plan to the user, `Optimizer::optimize_traced` also returns a `DecisionTrace`,
+//! recording which recipes were considered for each slot and how every candidate fared.
+//!
+//! With the `tracing` feature, every candidate is logged at the trace level, along with the
+//! hard constraints that ruled it out, and the chosen candidate at the debug level.
+use chrono::prelude::*;
+use food::constraint::{self, Constraint, Objective, Violation};
+use food::engine::{MealPlan, SlotId};
+use food::*;
+use progress::{CancelToken, Cancelled, Progress};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How many candidates are evaluated, unless told otherwise
+const DEFAULT_CANDIDATES: usize = 1000;
+
+/// A small, deterministic, source of pseudo-random numbers (splitmix64)
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates the generator for the candidate with the given index
+    fn for_candidate(seed: u64, index: usize) -> Rng {
+        let mut mixer = Rng {
+            state: index as u64,
+        };
+        Rng {
+            state: seed ^ mixer.next(),
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number less than the given bound, which must not be zero
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// The date, start, and end of a free block to be filled
+type Slot = (NaiveDate, NaiveTime, NaiveTime);
+
+/// Returns the date, start, and end of every free block in the template
+fn free_slots(template: &MealPlan) -> Vec<Slot> {
+    template
+        .get_days()
+        .iter()
+        .flat_map(|day| {
+            day.get_blocks()
+                .iter()
+                .filter(|x| !x.has_food())
+                .map(move |x| (day.get_date(), *x.get_start(), *x.get_end()))
+        })
+        .collect()
+}
+
+/// Returns the recipes that fit in the slot, leaving out any a hard constraint rules out on
+/// the slot's date, or as a meal ready at the end of the slot
+fn fitting<'a>(recipes: &'a [Food], constraints: &[Constraint], slot: Slot) -> Vec<&'a Food> {
+    let (date, start, end) = slot;
+    recipes
+        .iter()
+        // Compared as lengths, as a long recipe's end would wrap around midnight
+        .filter(|x| x.get_duration() <= end - start)
+        .filter(|x| constraints.iter().all(|c| c.permits_at(date, end, x)))
+        .collect()
+}
+
+/// A plan the optimizer generated, along with how it was ranked
+#[derive(Clone, PartialEq, Eq)]
+pub struct Candidate {
+    index: usize,
+    cost: Rational32,
+    plan: MealPlan,
+}
+
+impl Candidate {
+    /// Returns the index the candidate was generated with
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the candidate's penalty plus its score, lower is better
+    pub fn get_cost(&self) -> Rational32 {
+        self.cost
+    }
+
+    /// Returns the candidate plan
+    pub fn get_plan(&self) -> &MealPlan {
+        &self.plan
+    }
+
+    /// Consumes the candidate, returning its plan
+    pub fn into_plan(self) -> MealPlan {
+        self.plan
+    }
+}
+
+/// How a single candidate fared, as recorded in a DecisionTrace
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CandidateTrace {
+    index: usize,
+    penalty: Rational32,
+    score: Rational32,
+    violations: Vec<Violation>,
+}
+
+impl CandidateTrace {
+    /// Returns the index the candidate was generated with
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the candidate's total penalty for breaking soft constraints
+    pub fn get_penalty(&self) -> Rational32 {
+        self.penalty
+    }
+
+    /// Returns the candidate's total score against the objectives
+    pub fn get_score(&self) -> Rational32 {
+        self.score
+    }
+
+    /// Returns the candidate's penalty plus its score, lower is better
+    pub fn get_cost(&self) -> Rational32 {
+        self.penalty + self.score
+    }
+
+    /// Returns every constraint the candidate broke, hard and soft
+    pub fn get_violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Returns true if the candidate was rejected for breaking a hard constraint
+    pub fn is_rejected(&self) -> bool {
+        self.violations.iter().any(|x| x.is_hard())
+    }
+}
+
+/// The recipes considered for a single free block, as recorded in a DecisionTrace
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlotTrace {
+    date: NaiveDate,
+    start: NaiveTime,
+    considered: Vec<String>,
+    chosen: Option<String>,
+}
+
+impl SlotTrace {
+    /// Returns the date of the slot
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the start time of the slot
+    pub fn get_start(&self) -> NaiveTime {
+        self.start
+    }
+
+    /// Returns the short codes of the recipes that fit in the slot, and that no hard
+    /// constraint ruled out on their own
+    pub fn get_considered(&self) -> &[String] {
+        &self.considered
+    }
+
+    /// Returns the short code of the recipe the chosen plan put in the slot, if there is one
+    pub fn get_chosen(&self) -> Option<&str> {
+        self.chosen.as_deref()
+    }
+}
+
+/// A record of how the optimizer arrived at its plan, for explaining it to the user
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecisionTrace {
+    slots: Vec<SlotTrace>,
+    candidates: Vec<CandidateTrace>,
+    chosen: Option<usize>,
+}
+
+impl DecisionTrace {
+    /// Returns what was considered for each free block of the template, in order
+    pub fn get_slots(&self) -> &[SlotTrace] {
+        &self.slots
+    }
+
+    /// Returns how every candidate fared, in order of index
+    pub fn get_candidates(&self) -> &[CandidateTrace] {
+        &self.candidates
+    }
+
+    /// Returns the index of the chosen candidate, or None if every candidate was rejected
+    pub fn get_chosen(&self) -> Option<usize> {
+        self.chosen
+    }
+
+    /// Returns the candidates that were rejected for breaking a hard constraint
+    pub fn rejected(&self) -> Vec<&CandidateTrace> {
+        self.candidates.iter().filter(|x| x.is_rejected()).collect()
+    }
+}
+
+/// Searches for the lowest cost way to fill a plan's free blocks with recipes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Optimizer {
+    seed: u64,
+    candidates: usize,
+    constraints: Vec<Constraint>,
+    objectives: Vec<Objective>,
+}
+
+impl Optimizer {
+    /// Creates an optimizer with the given seed, no constraints, and no objectives
+    pub fn new(seed: u64) -> Optimizer {
+        Optimizer {
+            seed,
+            candidates: DEFAULT_CANDIDATES,
+            constraints: Vec::new(),
+            objectives: Vec::new(),
+        }
+    }
+
+    /// Returns the seed candidates are generated from
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns how many candidates are evaluated
+    pub fn get_candidates(&self) -> usize {
+        self.candidates
+    }
+
+    /// Sets how many candidates are evaluated
+    pub fn set_candidates(&mut self, candidates: usize) -> &mut Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Returns the constraints candidates are checked against
+    pub fn get_constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Adds a constraint candidates are checked against
+    pub fn add_constraint(&mut self, constraint: Constraint) -> &mut Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    /// Returns the objectives candidates are scored against
+    pub fn get_objectives(&self) -> &[Objective] {
+        &self.objectives
+    }
+
+    /// Adds an objective candidates are scored against
+    pub fn add_objective(&mut self, objective: Objective) -> &mut Self {
+        self.objectives.push(objective);
+        self
+    }
+
+    /// Fills the given slots of the template for the candidate with the given index
+    fn fill(
+        &self,
+        template: &MealPlan,
+        recipes: &[Food],
+        slots: &[Slot],
+        index: usize,
+    ) -> MealPlan {
+        let mut rng = Rng::for_candidate(self.seed, index);
+        let mut plan = template.clone();
+        for &(date, start, end) in slots {
+            let fitting = fitting(recipes, &self.constraints, (date, start, end));
+            if fitting.is_empty() {
+                continue;
+            }
+            let food = fitting[rng.below(fitting.len())].clone();
+            if let Some(block) = plan.get_mut_day(date).and_then(|x| x.get_mut_block(start)) {
+                block.add_food(food);
+            }
+        }
+        plan
+    }
+
+    /// Generates the candidate with the given index
+    ///
+    /// Each free block of the template is given a recipe chosen from those that fit in it and
+    /// that the constraints permit on its date and at its time, blocks no recipe fits in are
+    /// left free.
+    /// Returns None if the candidate breaks a hard constraint.
+    pub fn candidate(
+        &self,
+        template: &MealPlan,
+        recipes: &[Food],
+        index: usize,
+    ) -> Option<Candidate> {
+        self.candidate_in(template, recipes, &free_slots(template), index)
+    }
+
+    /// Generates the candidate with the given index, filling only the given slots
+    fn candidate_in(
+        &self,
+        template: &MealPlan,
+        recipes: &[Food],
+        slots: &[Slot],
+        index: usize,
+    ) -> Option<Candidate> {
+        let plan = self.fill(template, recipes, slots, index);
+        if !constraint::is_feasible(&plan, &self.constraints) {
+            #[cfg(feature = "tracing")]
+            for violation in constraint::check_plan(&plan, &self.constraints) {
+                if violation.is_hard() {
+                    ::tracing::trace!(
+                        index,
+                        date = %violation.get_date(),
+                        constraint = ?violation.get_constraint(),
+                        "rejected candidate"
+                    );
+                }
+            }
+            return None;
+        }
+        let cost = constraint::penalty(&plan, &self.constraints)
+            + constraint::score(&plan, &self.objectives);
+        #[cfg(feature = "tracing")]
+        ::tracing::trace!(index, %cost, "evaluated candidate");
+        Some(Candidate { index, cost, plan })
+    }
+
+    /// Evaluates every candidate, returning the one with the lowest cost
+    ///
+    /// Ties go to the candidate with the lowest index, so the same seed always picks the same
+    /// plan, whether or not the candidates were evaluated in parallel. Returns None if every
+    /// candidate breaks a hard constraint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::optimizer::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = |name: &str, minutes: i32| {
+    ///     Food::new_from_recipe(
+    ///         RecipeBuilder::new(name)
+    ///             .add_step(Step::new("cook", Rational32::from_integer(minutes)))
+    ///             .build_with_defaults(),
+    ///     )
+    /// };
+    /// let recipes = [recipe("salad", 15), recipe("curry", 45), recipe("roast", 90)];
+    ///
+    /// let mut template = MealPlan::new();
+    /// for day in 8..15 {
+    ///     template.insert_block(
+    ///         NaiveDate::from_ymd(2024, 1, day),
+    ///         Block::new(NaiveTime::from_hms(17, 0, 0), NaiveTime::from_hms(19, 0, 0), None),
+    ///     );
+    /// }
+    ///
+    /// let mut optimizer = Optimizer::new(42);
+    /// optimizer
+    ///     .set_candidates(200)
+    ///     .add_constraint(Constraint::max_active_minutes(60, Strength::Hard))
+    ///     .add_constraint(Constraint::max_active_minutes(15, Strength::Soft { weight: 1 }));
+    /// let best = optimizer.optimize(&template, &recipes).unwrap();
+    ///
+    /// // The roast is never chosen, as it breaks the hard constraint
+    /// assert!(best.get_plan().get_days().iter().all(|day| {
+    ///     day.get_blocks()[0].get_food().unwrap().get_name().get_short_code() != "roast"
+    /// }));
+    /// // The same seed always picks the same plan
+    /// assert_eq!(optimizer.optimize(&template, &recipes).unwrap().get_index(), best.get_index());
+    ///
+    /// // Nothing too long for a late slot is squeezed in by running past midnight
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut late = MealPlan::new();
+    /// late.insert_block(
+    ///     date,
+    ///     Block::new(NaiveTime::from_hms(23, 0, 0), NaiveTime::from_hms(23, 30, 0), None),
+    /// );
+    /// let plan = Optimizer::new(1).optimize(&late, &recipes[2..]).unwrap().into_plan();
+    /// assert!(!plan.get_days()[0].get_blocks()[0].has_food());
+    /// # }
+    /// ```
+    pub fn optimize(&self, template: &MealPlan, recipes: &[Food]) -> Option<Candidate> {
+        let token = CancelToken::new();
+        // The token is never cancelled, so this can't fail
+        self.optimize_with_progress(template, recipes, &|_| {}, &token)
+            .unwrap()
+    }
+
+    /// Evaluates every candidate like `optimize`, calling the callback after each one and
+    /// stopping early if the token is cancelled
+    ///
+    /// With the `rayon` feature, the callback is called from several threads at once, and
+    /// the reports may arrive out of order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::optimizer::*;
+    /// use time_for_food::food::*;
+    /// use time_for_food::progress::*;
+    ///
+    /// # fn main() {
+    /// let soup = RecipeBuilder::new("soup")
+    ///     .add_step(Step::new("simmer", Rational32::from_integer(30)))
+    ///     .build_with_defaults();
+    /// let mut template = MealPlan::new();
+    /// template.insert_block(
+    ///     NaiveDate::from_ymd(2024, 1, 8),
+    ///     Block::new(NaiveTime::from_hms(12, 0, 0), NaiveTime::from_hms(13, 0, 0), None),
+    /// );
+    ///
+    /// let mut optimizer = Optimizer::new(7);
+    /// optimizer.set_candidates(50);
+    /// let recipes = [Food::new_from_recipe(soup)];
+    ///
+    /// // Stop as soon as the first candidate is done
+    /// let token = CancelToken::new();
+    /// let stop = token.clone();
+    /// let result = optimizer.optimize_with_progress(&template, &recipes, &|_| stop.cancel(), &token);
+    /// assert_eq!(result.err(), Some(Cancelled));
+    /// # }
+    /// ```
+    pub fn optimize_with_progress(
+        &self,
+        template: &MealPlan,
+        recipes: &[Food],
+        progress: &(dyn Fn(Progress) + Sync),
+        token: &CancelToken,
+    ) -> Result<Option<Candidate>, Cancelled> {
+        self.search(template, recipes, &free_slots(template), progress, token)
+    }
+
+    /// Evaluates every candidate like `optimize`, but only fills the given slots, leaving the
+    /// template's other free blocks free
+    ///
+    /// Slots that aren't free blocks of the template are ignored. See `MealPlan::regenerate`
+    /// for replacing some of a plan's meals while keeping the rest.
+    pub fn optimize_slots(
+        &self,
+        template: &MealPlan,
+        recipes: &[Food],
+        slots: &[SlotId],
+    ) -> Option<Candidate> {
+        let slots: Vec<Slot> = free_slots(template)
+            .into_iter()
+            .filter(|&(date, start, _)| slots.contains(&SlotId::new(date, start)))
+            .collect();
+        let token = CancelToken::new();
+        // The token is never cancelled, so this can't fail
+        self.search(template, recipes, &slots, &|_| {}, &token)
+            .unwrap()
+    }
+
+    /// Evaluates every candidate filling the given slots, stopping early if the token is
+    /// cancelled
+    #[cfg_attr(
+        feature = "tracing",
+        ::tracing::instrument(skip_all, fields(seed = self.seed, candidates = self.candidates))
+    )]
+    fn search(
+        &self,
+        template: &MealPlan,
+        recipes: &[Food],
+        slots: &[Slot],
+        progress: &(dyn Fn(Progress) + Sync),
+        token: &CancelToken,
+    ) -> Result<Option<Candidate>, Cancelled> {
+        let done = AtomicUsize::new(0);
+        #[cfg(feature = "rayon")]
+        let candidates = (0..self.candidates).into_par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let candidates = 0..self.candidates;
+        let best = candidates
+            .filter_map(|x| {
+                if token.is_cancelled() {
+                    return None;
+                }
+                let candidate = self.candidate_in(template, recipes, slots, x);
+                let done = done.fetch_add(1, Ordering::SeqCst) + 1;
+                progress(Progress::new(done, self.candidates));
+                candidate
+            })
+            .min_by_key(|x| (x.cost, x.index));
+        if done.load(Ordering::SeqCst) < self.candidates {
+            #[cfg(feature = "tracing")]
+            ::tracing::debug!("cancelled");
+            Err(Cancelled)
+        } else {
+            #[cfg(feature = "tracing")]
+            match best {
+                Some(ref x) => {
+                    ::tracing::debug!(index = x.index, cost = %x.cost, "chose candidate")
+                }
+                None => ::tracing::debug!("every candidate was rejected"),
+            }
+            Ok(best)
+        }
+    }
+
+    /// Evaluates every candidate like `optimize`, also returning a trace of how the plan was
+    /// chosen
+    ///
+    /// The trace keeps a summary of every candidate, but not the candidates' plans, so it stays
+    /// small when there are many candidates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::*;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::optimizer::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = |name: &str, minutes: i32| {
+    ///     Food::new_from_recipe(
+    ///         RecipeBuilder::new(name)
+    ///             .add_step(Step::new("cook", Rational32::from_integer(minutes)))
+    ///             .build_with_defaults(),
+    ///     )
+    /// };
+    /// let recipes = [recipe("omelette", 10), recipe("risotto", 40), recipe("brisket", 300)];
+    ///
+    /// let mut template = MealPlan::new();
+    /// template.insert_block(
+    ///     NaiveDate::from_ymd(2024, 1, 8),
+    ///     Block::new(NaiveTime::from_hms(18, 0, 0), NaiveTime::from_hms(19, 0, 0), None),
+    /// );
+    ///
+    /// let mut optimizer = Optimizer::new(3);
+    /// optimizer
+    ///     .set_candidates(20)
+    ///     .add_constraint(Constraint::max_active_minutes(30, Strength::Hard));
+    /// let (best, trace) = optimizer.optimize_traced(&template, &recipes);
+    ///
+    /// // The brisket doesn't fit in the hour, so was never considered
+    /// let slot = &trace.get_slots()[0];
+    /// assert_eq!(slot.get_considered(), ["omelette", "risotto"]);
+    /// assert_eq!(slot.get_chosen(), Some("omelette"));
+    /// // Every candidate with the risotto broke the cap on active minutes
+    /// assert!(trace.rejected().iter().all(|x| x.get_violations()[0].is_hard()));
+    /// assert_eq!(trace.get_chosen(), best.map(|x| x.get_index()));
+    /// # }
+    /// ```
+    pub fn optimize_traced(
+        &self,
+        template: &MealPlan,
+        recipes: &[Food],
+    ) -> (Option<Candidate>, DecisionTrace) {
+        #[cfg(feature = "rayon")]
+        let indices = (0..self.candidates).into_par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let indices = 0..self.candidates;
+        let free = free_slots(template);
+        let candidates: Vec<CandidateTrace> = indices
+            .map(|index| {
+                let plan = self.fill(template, recipes, &free, index);
+                let violations = constraint::check_plan(&plan, &self.constraints);
+                let penalty = violations
+                    .iter()
+                    .fold(Rational32::from_integer(0), |total, x| {
+                        total + x.get_penalty()
+                    });
+                CandidateTrace {
+                    index,
+                    penalty,
+                    score: constraint::score(&plan, &self.objectives),
+                    violations,
+                }
+            })
+            .collect();
+
+        let chosen = candidates
+            .iter()
+            .filter(|x| !x.is_rejected())
+            .min_by_key(|x| (x.get_cost(), x.index))
+            .map(|x| x.index);
+        // Candidates depend only on their index, so the chosen one can be generated again
+        // rather than keeping every plan around
+        let best = chosen.and_then(|x| self.candidate(template, recipes, x));
+
+        let slots = free
+            .into_iter()
+            .map(|(date, start, end)| SlotTrace {
+                date,
+                start,
+                considered: fitting(recipes, &self.constraints, (date, start, end))
+                    .iter()
+                    .map(|x| x.get_name().get_short_code().to_string())
+                    .collect(),
+                chosen: best
+                    .as_ref()
+                    .and_then(|x| x.plan.get_day(date))
+                    .and_then(|x| x.get_blocks().iter().find(|x| *x.get_start() == start))
+                    .and_then(|x| x.get_food())
+                    .map(|x| x.get_name().get_short_code().to_string()),
+            })
+            .collect();
+
+        let trace = DecisionTrace {
+            slots,
+            candidates,
+            chosen,
+        };
+        (best, trace)
+    }
+}
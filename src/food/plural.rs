@@ -0,0 +1,130 @@
+//! This module contains the plural rules used when rendering IStrings
+//!
+//! The categories follow the CLDR naming (zero/one/two/few/many/other), but only the rules
+//! for a handful of common languages are implemented. Languages without a rule fall back to
+//! the English one/other split.
+use num_rational::*;
+
+/// A CLDR-style plural category
+///
+/// Not every language uses every category, `Other` is the one that all languages share,
+/// and is what the rendering code falls back to when a more specific form is missing.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// Selects the plural category a count falls in for the given language code
+    ///
+    /// Only the language portion of the code (the part before any `_` or `-`) is
+    /// considered, so "en_US" and "en-GB" share a rule.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::plural::PluralCategory;
+    ///
+    /// # fn main() {
+    /// let count = |n| Rational32::from_integer(n);
+    /// assert_eq!(PluralCategory::for_count("en_US", count(1)), PluralCategory::One);
+    /// assert_eq!(PluralCategory::for_count("en_US", count(3)), PluralCategory::Other);
+    /// assert_eq!(PluralCategory::for_count("ru_RU", count(3)), PluralCategory::Few);
+    /// assert_eq!(PluralCategory::for_count("ru_RU", count(5)), PluralCategory::Many);
+    /// # }
+    /// ```
+    pub fn for_count(lang: &str, count: Rational32) -> PluralCategory {
+        let language = lang.split(&['_', '-'][..]).next().unwrap_or("");
+        // CLDR rules are mostly written in terms of the integer digits, and whether or not
+        // the number has a visible fraction
+        let is_integer = count.is_integer();
+        let i = count.trunc().to_integer().abs();
+        match language {
+            // Languages without grammatical number
+            "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" => PluralCategory::Other,
+            // French and Portuguese treat everything below two as singular
+            "fr" | "pt" => {
+                if i == 0 || i == 1 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            }
+            "ru" | "uk" | "be" => {
+                if !is_integer {
+                    PluralCategory::Other
+                } else if i % 10 == 1 && i % 100 != 11 {
+                    PluralCategory::One
+                } else if is_few_slavic(i) {
+                    PluralCategory::Few
+                } else {
+                    PluralCategory::Many
+                }
+            }
+            "pl" => {
+                if !is_integer {
+                    PluralCategory::Other
+                } else if i == 1 {
+                    PluralCategory::One
+                } else if is_few_slavic(i) {
+                    PluralCategory::Few
+                } else {
+                    PluralCategory::Many
+                }
+            }
+            "cs" | "sk" => {
+                if !is_integer {
+                    PluralCategory::Many
+                } else if i == 1 {
+                    PluralCategory::One
+                } else if (2..=4).contains(&i) {
+                    PluralCategory::Few
+                } else {
+                    PluralCategory::Other
+                }
+            }
+            "ar" => {
+                if !is_integer {
+                    PluralCategory::Other
+                } else if i == 0 {
+                    PluralCategory::Zero
+                } else if i == 1 {
+                    PluralCategory::One
+                } else if i == 2 {
+                    PluralCategory::Two
+                } else if (3..=10).contains(&(i % 100)) {
+                    PluralCategory::Few
+                } else if i % 100 >= 11 {
+                    PluralCategory::Many
+                } else {
+                    PluralCategory::Other
+                }
+            }
+            // English, German, Spanish, and friends
+            _ => {
+                if is_integer && i == 1 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            }
+        }
+    }
+}
+
+/// The shared "few" rule for the east slavic languages and polish
+///
+/// Ends in 2-4, but not in 12-14
+fn is_few_slavic(i: i32) -> bool {
+    let last = i % 10;
+    let last_two = i % 100;
+    (2..=4).contains(&last) && !(12..=14).contains(&last_two)
+}
@@ -0,0 +1,1086 @@
+//! This module contains the scheduler, which places foods into a day's free time
+//!
+//! Free time is given as Blocks without food. Each food is placed at the start of a free
+//! block using `Block::split_at_start`, and whatever is left of the block stays free for the
+//! next food.
+//!
+//! How the foods are matched to free blocks is up to the `SchedulingStrategy`. The greedy
+//! strategy places foods longest first, as the long ones are the hardest to fit, each in the
+//! earliest block it fits in. It is quick, but can leave foods out on tight days where a
+//! different arrangement would fit them all. The exhaustive strategy searches every
+//! arrangement for one placing as many foods as possible, at a cost that grows exponentially
+//! with the number of foods.
+//!
+//! Rather than placing foods as early as possible, `Scheduler::schedule_for_mealtime` works
+//! backward from when a meal should be on the table, so it is ready just in time.
+//!
+//! Normally a recipe occupies a single block for its whole time. `Scheduler::schedule_interleaved`
+//! instead breaks recipes into their steps and only reserves the cook's time for the steps
+//! that need it, so other recipes can be made while the bread proves or the stew simmers.
+//!
+//! Given an `EatingWindow`, every strategy only places foods where they are ready to eat
+//! within it, starting them later than the start of a free block when that is what it takes.
+//! Given supplements, foods are likewise only placed where eating them breaks none of the
+//! supplements' timing rules.
+//!
+//! With the `tracing` feature, `Scheduler::schedule_partial` runs in a span for its date and
+//! strategy, emitting a debug event for every food placed or left out, for working out why a
+//! day was planned the way it was. Any `tracing` subscriber can collect them.
+//!
+//! ```
+//! extern crate chrono;
+//! extern crate num_rational;
+//! extern crate time_for_food;
+//! #[cfg(feature = "tracing")]
+//! extern crate tracing;
+//! use chrono::prelude::*;
+//! use num_rational::Rational32;
+//! use time_for_food::food::engine::*;
+//! use time_for_food::food::scheduler::*;
+//! use time_for_food::food::*;
+//!
+//! # #[cfg(feature = "tracing")]
+//! # fn main() {
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//! use tracing::span::{Attributes, Id, Record};
+//! use tracing::{Event, Metadata, Subscriber};
+//!
+//! // Counts the events emitted
+//! struct Counter(Arc<AtomicUsize>);
+//! impl Subscriber for Counter {
+//!     fn enabled(&self, _: &Metadata) -> bool {
+//!         true
+//!     }
+//!     fn new_span(&self, _: &Attributes) -> Id {
+//!         Id::from_u64(1)
+//!     }
+//!     fn record(&self, _: &Id, _: &Record) {}
+//!     fn record_follows_from(&self, _: &Id, _: &Id) {}
+//!     fn event(&self, _: &Event) {
+//!         self.0.fetch_add(1, Ordering::SeqCst);
+//!     }
+//!     fn enter(&self, _: &Id) {}
+//!     fn exit(&self, _: &Id) {}
+//! }
+//!
+//! let recipe = |name: &str, minutes: i32| {
+//!     Food::new_from_recipe(
+//!         RecipeBuilder::new(name)
+//!             .add_step(Step::new("cook", Rational32::from_integer(minutes)))
+//!             .build_with_defaults(),
+//!     )
+//! };
+//! let foods = [recipe("stew", 60), recipe("roast", 120)];
+//! let time = |h| NaiveTime::from_hms(h, 0, 0);
+//! let availability = [Block::new(time(17), time(18), None)];
+//! let scheduler = Scheduler::new(NaiveDate::from_ymd(2024, 1, 8));
+//!
+//! let events = Arc::new(AtomicUsize::new(0));
+//! tracing::subscriber::with_default(Counter(events.clone()), || {
+//!     scheduler.schedule_partial(&foods, &availability).unwrap();
+//! });
+//! // The stew was placed, and the roast left out
+//! assert_eq!(events.load(Ordering::SeqCst), 2);
+//! # }
+//! # #[cfg(not(feature = "tracing"))]
+//! # fn main() {}
+//! ```
+use chrono::prelude::*;
+use food::engine::{Block, EatingWindow, MealPlan, SplitBlock, Supplement};
+use food::*;
+use std::error::Error;
+use std::fmt;
+
+/// How the scheduler matches foods to free blocks
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SchedulingStrategy {
+    /// Place foods longest first, each in the earliest free block it fits in
+    #[default]
+    Greedy,
+    /// Search every arrangement for one placing as many foods as possible
+    Exhaustive,
+}
+
+/// Why a food couldn't be placed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnplacedReason {
+    /// There was no free time left at all
+    NoFreeTime,
+    /// No free block was long enough. Both lengths are in minutes.
+    TooLong {
+        needed: Rational32,
+        longest_free: Rational32,
+    },
+    /// The food had to be made between the given times, and no free block covered them
+    NotFree { start: NaiveTime, end: NaiveTime },
+    /// Each of the food's attended stretches fits in free time somewhere, but never with the
+    /// hands-off time between them lining up
+    Misaligned,
+    /// The food fits in free time, but nowhere it would be ready within the eating window
+    OutsideEatingWindow,
+    /// The food fits in free time, but nowhere it could be eaten without breaking one of a
+    /// supplement's timing rules
+    NearSupplement,
+}
+
+/// A food that couldn't be placed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unplaced {
+    index: usize,
+    reason: UnplacedReason,
+}
+
+impl Unplaced {
+    /// Returns the index of the food, within the foods given to the scheduler
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns why the food couldn't be placed
+    pub fn get_reason(&self) -> UnplacedReason {
+        self.reason
+    }
+}
+
+/// Describes why scheduling failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// Some of the available blocks overlap each other
+    OverlappingAvailability,
+    /// Some of the foods couldn't be placed, see `Scheduler::schedule_partial` to keep the
+    /// ones that could
+    Unplaced(Vec<Unplaced>),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ScheduleError::OverlappingAvailability => write!(f, "available blocks overlap"),
+            ScheduleError::Unplaced(ref x) => write!(f, "{} foods couldn't be placed", x.len()),
+        }
+    }
+}
+
+impl Error for ScheduleError {}
+
+/// Converts a number of minutes to a Duration, dropping any fraction of a second
+fn minutes_duration(minutes: Rational32) -> Duration {
+    let seconds = (minutes * Rational32::from_integer(60)).to_integer();
+    Duration::seconds(i64::from(seconds))
+}
+
+/// Works backward from the serving time through a recipe's steps, returning the time each
+/// step starts and ends, in the order of the steps
+///
+/// The last step finishes as the food is served, and every other step finishes as the next
+/// one starts. Foods other than recipes have no steps, so return nothing.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use num_rational::Rational32;
+/// use time_for_food::food::scheduler::*;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let lasagne = RecipeBuilder::new("lasagne")
+///     .add_step(Step::new("assemble", Rational32::from_integer(30)))
+///     .add_step(Step::new("bake", Rational32::from_integer(45)))
+///     .build_with_defaults();
+/// let times = step_times(&Food::new_from_recipe(lasagne), NaiveTime::from_hms(19, 0, 0));
+/// assert_eq!(times[0], (NaiveTime::from_hms(17, 45, 0), NaiveTime::from_hms(18, 15, 0)));
+/// assert_eq!(times[1], (NaiveTime::from_hms(18, 15, 0), NaiveTime::from_hms(19, 0, 0)));
+/// # }
+/// ```
+pub fn step_times(food: &Food, serve_at: NaiveTime) -> Vec<(NaiveTime, NaiveTime)> {
+    let steps = match *food {
+        Food::Recipe(ref recipe) => recipe.get_steps(),
+        _ => return Vec::new(),
+    };
+    let mut end = serve_at;
+    let mut times: Vec<(NaiveTime, NaiveTime)> = steps
+        .iter()
+        .rev()
+        .map(|step| {
+            let start = end - minutes_duration(step.get_time());
+            let time = (start, end);
+            end = start;
+            time
+        })
+        .collect();
+    times.reverse();
+    times
+}
+
+/// Time the cook is needed for, reserved while interleaving recipes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reservation {
+    food: usize,
+    steps: Vec<usize>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl Reservation {
+    /// Returns the index of the food, within the foods given to the scheduler
+    pub fn get_food(&self) -> usize {
+        self.food
+    }
+
+    /// Returns the indices of the recipe's steps done in this stretch, empty for a recipe
+    /// without steps
+    pub fn get_steps(&self) -> &[usize] {
+        &self.steps
+    }
+
+    /// Returns when the cook is needed from
+    pub fn get_start(&self) -> NaiveTime {
+        self.start
+    }
+
+    /// Returns when the cook is needed until
+    pub fn get_end(&self) -> NaiveTime {
+        self.end
+    }
+}
+
+/// The result of scheduling foods step by step, see `Scheduler::schedule_interleaved`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterleavedSchedule {
+    date: NaiveDate,
+    reservations: Vec<Reservation>,
+    ready: Vec<(usize, NaiveTime)>,
+}
+
+impl InterleavedSchedule {
+    /// Returns the date the foods are made on
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns every stretch of the cook's time that was reserved, in order of start time
+    pub fn get_reservations(&self) -> &[Reservation] {
+        &self.reservations
+    }
+
+    /// Returns when the food with the given index is ready, or None if it wasn't placed
+    pub fn ready_at(&self, food: usize) -> Option<NaiveTime> {
+        self.ready.iter().find(|x| x.0 == food).map(|x| x.1)
+    }
+}
+
+/// A stretch of consecutive steps needing the cook, in seconds from the start of the recipe
+struct Stretch {
+    steps: Vec<usize>,
+    offset: i64,
+    length: i64,
+}
+
+/// Breaks a food into the stretches needing the cook, returning them along with the food's
+/// total length in seconds
+///
+/// Consecutive attended steps are merged, and hands-off steps separate the stretches. A
+/// recipe without steps is attended for its whole time.
+fn stretches(food: &Food) -> (Vec<Stretch>, i64) {
+    let steps = match *food {
+        Food::Recipe(ref recipe) => recipe.get_steps(),
+        _ => &[],
+    };
+    if steps.is_empty() {
+        let length = food.get_duration().num_seconds();
+        let stretches = if length > 0 {
+            vec![Stretch {
+                steps: Vec::new(),
+                offset: 0,
+                length,
+            }]
+        } else {
+            Vec::new()
+        };
+        return (stretches, length);
+    }
+    let mut stretches: Vec<Stretch> = Vec::new();
+    let mut offset = 0;
+    for (i, step) in steps.iter().enumerate() {
+        let length = minutes_duration(step.get_time()).num_seconds();
+        if step.get_attention() != Attention::Passive && length > 0 {
+            match stretches.last_mut() {
+                Some(ref mut x) if x.offset + x.length == offset => {
+                    x.steps.push(i);
+                    x.length += length;
+                }
+                _ => stretches.push(Stretch {
+                    steps: vec![i],
+                    offset,
+                    length,
+                }),
+            }
+        }
+        offset += length;
+    }
+    (stretches, offset)
+}
+
+/// Converts a number of seconds since midnight to a time
+fn time_of(seconds: i64) -> NaiveTime {
+    NaiveTime::from_num_seconds_from_midnight_opt(seconds as u32, 0).unwrap_or(NaiveTime::MIN)
+}
+
+/// Returns the length of a block, in minutes
+fn block_minutes(block: &Block) -> Rational32 {
+    let seconds = (*block.get_end() - *block.get_start()).num_seconds();
+    Rational32::new(seconds as i32, 60)
+}
+
+/// Fails if any of the available blocks overlap each other
+fn check_overlaps(availability: &[Block]) -> Result<(), ScheduleError> {
+    for (i, x) in availability.iter().enumerate() {
+        if availability[i + 1..].iter().any(|y| x.overlaps(y)) {
+            #[cfg(feature = "tracing")]
+            ::tracing::warn!("available blocks overlap");
+            return Err(ScheduleError::OverlappingAvailability);
+        }
+    }
+    Ok(())
+}
+
+/// Places the food at the start of the free block with the given index, returning the block
+/// the food now occupies, or None if it doesn't fit
+///
+/// Given an eating window the food would be ready outside of, the food is instead started so
+/// it is ready as the window opens. Given supplements the food would be eaten too close to, the
+/// food is instead started so it is ready once they allow it. What is left of the free block
+/// stays in the list, unless nothing is left of it.
+fn place(
+    free: &mut Vec<Block>,
+    index: usize,
+    food: &Food,
+    window: Option<&EatingWindow>,
+    supplements: &[Supplement],
+) -> Option<Block> {
+    let mut start = *free[index].get_start();
+    if let Some(window) = window {
+        if !window.contains(start + food.get_duration()) {
+            start = *window.get_start() - food.get_duration();
+        }
+    }
+    if supplements
+        .iter()
+        .any(|x| x.forbids(food, start + food.get_duration()))
+    {
+        // Pushed back a minute at a time, until it is ready when the supplements allow it
+        let mut ready = start + food.get_duration();
+        while ready < NaiveTime::from_hms_opt(23, 59, 0)?
+            && supplements.iter().any(|x| x.forbids(food, ready))
+        {
+            ready += Duration::minutes(1);
+        }
+        start = ready - food.get_duration();
+        let allowed = window.is_none_or(|x| x.contains(ready))
+            && !supplements.iter().any(|x| x.forbids(food, ready));
+        if !allowed {
+            return None;
+        }
+    }
+    let block = match free[index].split_at(start, food) {
+        SplitBlock::Replace(block) => {
+            free.remove(index);
+            block
+        }
+        SplitBlock::Split(first, second) => {
+            if first.has_food() {
+                free[index] = second;
+                first
+            } else {
+                free[index] = first;
+                second
+            }
+        }
+        SplitBlock::SplitTwice(before, block, after) => {
+            free[index] = before;
+            free.insert(index + 1, after);
+            block
+        }
+        SplitBlock::Failure(_) => return None,
+    };
+    Some(block)
+}
+
+/// A way of placing some of the foods, found by the exhaustive search
+#[derive(Clone)]
+struct Arrangement {
+    placed: Vec<Block>,
+    free: Vec<Block>,
+    unplaced: Vec<usize>,
+}
+
+/// Searches every way of placing the foods, in the given order, into the free blocks,
+/// keeping the first arrangement found that leaves the fewest foods unplaced
+fn search(
+    foods: &[Food],
+    order: &[usize],
+    window: Option<&EatingWindow>,
+    supplements: &[Supplement],
+    current: &mut Arrangement,
+    best: &mut Option<Arrangement>,
+) {
+    if let Some(ref best) = *best {
+        if current.unplaced.len() >= best.unplaced.len() {
+            // Can't improve on the best arrangement from here
+            return;
+        }
+    }
+    let (&index, rest) = match order.split_first() {
+        Some(x) => x,
+        None => {
+            *best = Some(current.clone());
+            return;
+        }
+    };
+    for i in 0..current.free.len() {
+        let free = current.free.clone();
+        if let Some(block) = place(&mut current.free, i, &foods[index], window, supplements) {
+            current.placed.push(block);
+            search(foods, rest, window, supplements, current, best);
+            current.placed.pop();
+            current.free = free;
+            if best.as_ref().is_some_and(|x| x.unplaced.is_empty()) {
+                return;
+            }
+        }
+    }
+    current.unplaced.push(index);
+    search(foods, rest, window, supplements, current, best);
+    current.unplaced.pop();
+}
+
+/// Places foods into free time on a single date
+#[derive(Clone, PartialEq, Eq)]
+pub struct Scheduler {
+    date: NaiveDate,
+    strategy: SchedulingStrategy,
+    eating_window: Option<EatingWindow>,
+    supplements: Vec<Supplement>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler placing foods on the given date, with the greedy strategy, no
+    /// eating window, and no supplements
+    pub fn new(date: NaiveDate) -> Scheduler {
+        Scheduler {
+            date,
+            strategy: SchedulingStrategy::default(),
+            eating_window: None,
+            supplements: Vec::new(),
+        }
+    }
+
+    /// Returns the date foods are placed on
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns how foods are matched to free blocks
+    pub fn get_strategy(&self) -> SchedulingStrategy {
+        self.strategy
+    }
+
+    /// Sets how foods are matched to free blocks
+    pub fn set_strategy(&mut self, strategy: SchedulingStrategy) -> &mut Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Returns the window foods must be ready to eat within, if there is one
+    pub fn get_eating_window(&self) -> Option<&EatingWindow> {
+        self.eating_window.as_ref()
+    }
+
+    /// Sets the window foods must be ready to eat within, or None to place foods at any time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::scheduler::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let soup = RecipeBuilder::new("soup")
+    ///     .add_step(Step::new("simmer", Rational32::from_integer(30)))
+    ///     .build_with_defaults();
+    /// let soup = [Food::new_from_recipe(soup)];
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut scheduler = Scheduler::new(date);
+    /// scheduler.set_eating_window(Some(EatingWindow::new(time(12, 0), time(20, 0))));
+    ///
+    /// // Started so it is ready as the window opens, rather than first thing
+    /// let morning = [Block::new(time(8, 0), time(13, 0), None)];
+    /// let plan = scheduler.schedule(&soup, &morning).unwrap();
+    /// let block = plan.blocks_between(date, time(11, 30), time(11, 31))[0];
+    /// assert_eq!(*block.get_end(), time(12, 0));
+    ///
+    /// // Free time that is over before the window opens is refused
+    /// let early = [Block::new(time(8, 0), time(10, 0), None)];
+    /// match scheduler.schedule(&soup, &early) {
+    ///     Err(ScheduleError::Unplaced(unplaced)) => {
+    ///         assert_eq!(unplaced[0].get_reason(), UnplacedReason::OutsideEatingWindow)
+    ///     }
+    ///     _ => panic!("the soup shouldn't be placed"),
+    /// }
+    /// assert!(scheduler.schedule_for_mealtime(&soup[0], time(9, 30), &early).is_err());
+    /// # }
+    /// ```
+    pub fn set_eating_window(&mut self, window: Option<EatingWindow>) -> &mut Self {
+        self.eating_window = window;
+        self
+    }
+
+    /// Returns the supplements taken on the date, ordered as they were added
+    pub fn get_supplements(&self) -> &[Supplement] {
+        &self.supplements
+    }
+
+    /// Adds a supplement taken on the date
+    ///
+    /// Foods are only placed where eating them breaks none of the supplement's timing rules,
+    /// see `Supplement::forbids`, and the supplement is kept on the Day of the resulting plan.
+    /// Whether a WithFood rule is met depends on every meal of the day, so is left to
+    /// `Day::check_supplements`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::scheduler::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let porridge = RecipeBuilder::new("porridge")
+    ///     .add_step(Step::new("cook", Rational32::from_integer(15)))
+    ///     .build_with_defaults();
+    /// let porridge = [Food::new_from_recipe(porridge)];
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut thyroid = Supplement::new(IString::new("levothyroxine"), time(7, 0));
+    /// thyroid.add_rule(TimingRule::EmptyStomach { minutes: 60 });
+    /// let mut scheduler = Scheduler::new(date);
+    /// scheduler.add_supplement(thyroid);
+    ///
+    /// // Ready an hour after the pill, rather than first thing
+    /// let morning = [Block::new(time(6, 30), time(9, 0), None)];
+    /// let plan = scheduler.schedule(&porridge, &morning).unwrap();
+    /// let block = plan.blocks_between(date, time(7, 45), time(7, 46))[0];
+    /// assert_eq!(*block.get_end(), time(8, 0));
+    /// assert_eq!(plan.get_day(date).unwrap().get_supplements().len(), 1);
+    ///
+    /// // Free time that is over too soon after the pill is refused
+    /// let early = [Block::new(time(6, 30), time(7, 30), None)];
+    /// match scheduler.schedule(&porridge, &early) {
+    ///     Err(ScheduleError::Unplaced(unplaced)) => {
+    ///         assert_eq!(unplaced[0].get_reason(), UnplacedReason::NearSupplement)
+    ///     }
+    ///     _ => panic!("the porridge shouldn't be placed"),
+    /// }
+    /// # }
+    /// ```
+    pub fn add_supplement(&mut self, supplement: Supplement) -> &mut Self {
+        self.supplements.push(supplement);
+        self
+    }
+
+    /// Returns true if eating the food at the given time breaks none of the supplements' rules
+    fn supplements_allow(&self, food: &Food, eaten: NaiveTime) -> bool {
+        !self.supplements.iter().any(|x| x.forbids(food, eaten))
+    }
+
+    /// Builds a plan from the given blocks, keeping the supplements on the Day for the date
+    fn plan_from<I: IntoIterator<Item = Block>>(&self, blocks: I) -> MealPlan {
+        let mut plan = MealPlan::new();
+        for block in blocks {
+            plan.insert_block(self.date, block);
+        }
+        for supplement in &self.supplements {
+            plan.add_supplement(self.date, supplement.clone());
+        }
+        plan
+    }
+
+    /// Places a food so it is ready at the serving time, starting it as late as possible
+    ///
+    /// The food needs a single free block covering the whole time from when it has to be
+    /// started to when it is served, otherwise it is reported as unplaced with the reason
+    /// `NotFree`. As with `schedule`, blocks that already have food are kept as they are, and
+    /// free time left over is kept as blocks without food. See `step_times` for when each of a
+    /// recipe's steps happens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::scheduler::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let curry = RecipeBuilder::new("curry")
+    ///     .add_step(Step::new("simmer", Rational32::from_integer(50)))
+    ///     .build_with_defaults();
+    /// let curry = Food::new_from_recipe(curry);
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let availability = [Block::new(time(16, 0), time(20, 0), None)];
+    ///
+    /// // Dinner at seven
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let scheduler = Scheduler::new(date);
+    /// let plan = scheduler.schedule_for_mealtime(&curry, time(19, 0), &availability).unwrap();
+    /// let block = plan.blocks_between(date, time(18, 10), time(18, 11))[0];
+    /// assert_eq!(*block.get_start(), time(18, 10));
+    /// assert_eq!(*block.get_end(), time(19, 0));
+    ///
+    /// // Lunch at half past twelve is outside the free time
+    /// assert!(scheduler.schedule_for_mealtime(&curry, time(12, 30), &availability).is_err());
+    /// # }
+    /// ```
+    pub fn schedule_for_mealtime(
+        &self,
+        food: &Food,
+        serve_at: NaiveTime,
+        availability: &[Block],
+    ) -> Result<MealPlan, ScheduleError> {
+        check_overlaps(availability)?;
+        if self.eating_window.is_some_and(|x| !x.contains(serve_at)) {
+            let reason = UnplacedReason::OutsideEatingWindow;
+            return Err(ScheduleError::Unplaced(vec![Unplaced { index: 0, reason }]));
+        }
+        if !self.supplements_allow(food, serve_at) {
+            let reason = UnplacedReason::NearSupplement;
+            return Err(ScheduleError::Unplaced(vec![Unplaced { index: 0, reason }]));
+        }
+        let start = serve_at - food.get_duration();
+        let covering = availability.iter().position(|x| {
+            !x.has_food()
+                && *x.get_start() <= start
+                && start <= serve_at
+                && serve_at <= *x.get_end()
+        });
+        let index = match covering {
+            Some(x) => x,
+            None => {
+                let reason = UnplacedReason::NotFree {
+                    start,
+                    end: serve_at,
+                };
+                return Err(ScheduleError::Unplaced(vec![Unplaced { index: 0, reason }]));
+            }
+        };
+        let pieces = match availability[index].split_at(start, food) {
+            SplitBlock::Replace(block) => vec![block],
+            SplitBlock::Split(first, second) => vec![first, second],
+            SplitBlock::SplitTwice(first, second, third) => vec![first, second, third],
+            // The block was checked to cover the food, and has no food of its own
+            SplitBlock::Failure(_) => unreachable!(),
+        };
+
+        let others = availability
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != index)
+            .map(|(_, x)| x.clone());
+        Ok(self.plan_from(others.chain(pieces)))
+    }
+
+    /// Places foods step by step, reserving the cook's time only for the steps that need it
+    ///
+    /// Each recipe is broken into stretches of attended steps, separated by its hands-off
+    /// steps. Every stretch has to fit in free time, but hands-off steps can run through
+    /// anything, including the attended stretches of other recipes, so long as the recipe
+    /// finishes the same day. A hands-off step always lasts exactly its time, so the next
+    /// stretch starts the moment it finishes. Steps that need checking on now and then count
+    /// as attended.
+    ///
+    /// Foods are placed greedily, longest first, each at the earliest time it fits, whatever
+    /// the strategy. Returns the schedule along with the foods that couldn't be placed, in the
+    /// order they were given, and only fails if the available blocks overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::scheduler::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let mut bake = Step::new("bake", Rational32::from_integer(60));
+    /// bake.set_attention(Attention::Passive);
+    /// let bread = RecipeBuilder::new("bread")
+    ///     .add_step(Step::new("knead", Rational32::from_integer(15)))
+    ///     .add_step(bake)
+    ///     .add_step(Step::new("slice", Rational32::from_integer(5)))
+    ///     .build_with_defaults();
+    /// let salad = RecipeBuilder::new("salad")
+    ///     .add_step(Step::new("chop", Rational32::from_integer(30)))
+    ///     .build_with_defaults();
+    /// let foods = [Food::new_from_recipe(bread), Food::new_from_recipe(salad)];
+    ///
+    /// // Only an hour and twenty minutes, not enough to make them one after the other
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let availability = [Block::new(time(17, 0), time(18, 20), None)];
+    ///
+    /// let scheduler = Scheduler::new(NaiveDate::from_ymd(2024, 1, 8));
+    /// let (schedule, unplaced) = scheduler.schedule_interleaved(&foods, &availability).unwrap();
+    /// assert!(unplaced.is_empty());
+    /// // The salad is made while the bread bakes
+    /// let salad = schedule.get_reservations().iter().find(|x| x.get_food() == 1).unwrap();
+    /// assert_eq!(salad.get_start(), time(17, 15));
+    /// assert_eq!(schedule.ready_at(0), Some(time(18, 20)));
+    /// # }
+    /// ```
+    pub fn schedule_interleaved(
+        &self,
+        foods: &[Food],
+        availability: &[Block],
+    ) -> Result<(InterleavedSchedule, Vec<Unplaced>), ScheduleError> {
+        check_overlaps(availability)?;
+        let seconds = |x: &NaiveTime| i64::from(x.num_seconds_from_midnight());
+        let mut free: Vec<(i64, i64)> = availability
+            .iter()
+            .filter(|x| !x.has_food())
+            .map(|x| (seconds(x.get_start()), seconds(x.get_end())))
+            .collect();
+        free.sort();
+
+        let mut order: Vec<usize> = (0..foods.len()).collect();
+        order.sort_by_key(|&x| ::std::cmp::Reverse(foods[x].get_time()));
+        let opening = self.eating_window.map(|x| seconds(x.get_start()));
+
+        let mut reservations = Vec::new();
+        let mut ready = Vec::new();
+        let mut unplaced = Vec::new();
+        for index in order {
+            let (stretches, length) = stretches(&foods[index]);
+            let in_free = |start: i64| {
+                start >= 0
+                    && start + length < 24 * 60 * 60
+                    && stretches.iter().all(|x| {
+                        let from = start + x.offset;
+                        free.iter().any(|&(s, e)| s <= from && from + x.length <= e)
+                    })
+            };
+            let in_window = |start: i64| {
+                self.eating_window
+                    .is_none_or(|x| x.contains(time_of(start + length)))
+            };
+            let allowed = |start: i64| {
+                in_window(start) && self.supplements_allow(&foods[index], time_of(start + length))
+            };
+            // The earliest start always lines a stretch up with the start of some free time,
+            // or has the food ready just as the eating window opens
+            let mut starts: Vec<i64> = free
+                .iter()
+                .flat_map(|&(s, _)| stretches.iter().map(move |x| s - x.offset))
+                .chain(opening.map(|x| x - length))
+                .collect();
+            starts.sort();
+            let start = if stretches.is_empty() {
+                // Nothing needs the cook, so the food can be ready whenever free time starts
+                free.first()
+                    .map(|x| x.0)
+                    .into_iter()
+                    .chain(opening.map(|x| x - length))
+                    .find(|&x| x >= 0 && allowed(x))
+            } else {
+                starts.iter().cloned().find(|&x| in_free(x) && allowed(x))
+            };
+
+            let start = match start {
+                Some(x) => x,
+                None => {
+                    let longest_free = free.iter().map(|x| x.1 - x.0).max();
+                    let longest_stretch = stretches.iter().map(|x| x.length).max().unwrap_or(0);
+                    let reason = match longest_free {
+                        None => UnplacedReason::NoFreeTime,
+                        Some(free) if free < longest_stretch => UnplacedReason::TooLong {
+                            needed: Rational32::new(longest_stretch as i32, 60),
+                            longest_free: Rational32::new(free as i32, 60),
+                        },
+                        Some(_) if starts.iter().any(|&x| in_free(x) && in_window(x)) => {
+                            UnplacedReason::NearSupplement
+                        }
+                        Some(_) if stretches.is_empty() || starts.iter().any(|&x| in_free(x)) => {
+                            UnplacedReason::OutsideEatingWindow
+                        }
+                        Some(_) => UnplacedReason::Misaligned,
+                    };
+                    unplaced.push(Unplaced { index, reason });
+                    continue;
+                }
+            };
+
+            for stretch in stretches {
+                let from = start + stretch.offset;
+                let to = from + stretch.length;
+                // Take the stretch out of the free time it lies in
+                if let Some(i) = free.iter().position(|&(s, e)| s <= from && to <= e) {
+                    let (s, e) = free.remove(i);
+                    if to < e {
+                        free.insert(i, (to, e));
+                    }
+                    if s < from {
+                        free.insert(i, (s, from));
+                    }
+                }
+                reservations.push(Reservation {
+                    food: index,
+                    steps: stretch.steps,
+                    start: time_of(from),
+                    end: time_of(to),
+                });
+            }
+            ready.push((index, time_of(start + length)));
+        }
+        reservations.sort_by_key(|x| (x.start, x.food));
+        unplaced.sort_by_key(|x| x.index);
+
+        let schedule = InterleavedSchedule {
+            date: self.date,
+            reservations,
+            ready,
+        };
+        Ok((schedule, unplaced))
+    }
+
+    /// Places every food into the available time, failing unless all of them fit
+    ///
+    /// Blocks in the availability that already have food are kept in the plan as they are,
+    /// and nothing is placed in them. Free time left over is kept as blocks without food.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::scheduler::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = |name: &str, minutes: i32| {
+    ///     Food::new_from_recipe(
+    ///         RecipeBuilder::new(name)
+    ///             .add_step(Step::new("cook", Rational32::from_integer(minutes)))
+    ///             .build_with_defaults(),
+    ///     )
+    /// };
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let availability = [
+    ///     Block::new(time(7, 0), time(7, 30), None),
+    ///     Block::new(time(17, 0), time(18, 30), None),
+    /// ];
+    ///
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let scheduler = Scheduler::new(date);
+    /// let foods = [recipe("porridge", 10), recipe("stew", 60)];
+    /// let plan = scheduler.schedule(&foods, &availability).unwrap();
+    ///
+    /// let stew = plan.blocks_between(date, time(17, 0), time(17, 1))[0];
+    /// assert_eq!(stew.get_food().unwrap().get_name().get_short_code(), "stew");
+    ///
+    /// let roast = [recipe("roast", 120)];
+    /// match scheduler.schedule(&roast, &availability) {
+    ///     Err(ScheduleError::Unplaced(unplaced)) => assert_eq!(
+    ///         unplaced[0].get_reason(),
+    ///         UnplacedReason::TooLong {
+    ///             needed: Rational32::from_integer(120),
+    ///             longest_free: Rational32::from_integer(90),
+    ///         }
+    ///     ),
+    ///     _ => panic!("the roast shouldn't fit"),
+    /// }
+    /// # }
+    /// ```
+    pub fn schedule(
+        &self,
+        foods: &[Food],
+        availability: &[Block],
+    ) -> Result<MealPlan, ScheduleError> {
+        let (plan, unplaced) = self.schedule_partial(foods, availability)?;
+        if unplaced.is_empty() {
+            Ok(plan)
+        } else {
+            Err(ScheduleError::Unplaced(unplaced))
+        }
+    }
+
+    /// Places as many of the foods as possible into the available time, returning the plan
+    /// along with the foods that couldn't be placed, in the order they were given
+    ///
+    /// Only fails if the available blocks overlap.
+    ///
+    /// With the `tracing` feature, emits an event for every food placed or left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::scheduler::*;
+    /// use time_for_food::food::*;
+    ///
+    /// # fn main() {
+    /// let recipe = |name: &str, minutes: i32| {
+    ///     Food::new_from_recipe(
+    ///         RecipeBuilder::new(name)
+    ///             .add_step(Step::new("cook", Rational32::from_integer(minutes)))
+    ///             .build_with_defaults(),
+    ///     )
+    /// };
+    /// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+    /// let availability = [
+    ///     Block::new(time(12, 0), time(13, 0), None),
+    ///     Block::new(time(17, 0), time(17, 40), None),
+    /// ];
+    /// let foods = [recipe("curry", 40), recipe("rice", 30), recipe("dal", 30)];
+    ///
+    /// // Greedily, the curry takes the first block, leaving no room for the dal
+    /// let mut scheduler = Scheduler::new(NaiveDate::from_ymd(2024, 1, 8));
+    /// let (_, unplaced) = scheduler.schedule_partial(&foods, &availability).unwrap();
+    /// assert_eq!(unplaced[0].get_index(), 2);
+    ///
+    /// // Searching finds the curry fits the second block exactly
+    /// scheduler.set_strategy(SchedulingStrategy::Exhaustive);
+    /// let (_, unplaced) = scheduler.schedule_partial(&foods, &availability).unwrap();
+    /// assert!(unplaced.is_empty());
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        ::tracing::instrument(skip_all, fields(date = %self.date, strategy = ?self.strategy))
+    )]
+    pub fn schedule_partial(
+        &self,
+        foods: &[Food],
+        availability: &[Block],
+    ) -> Result<(MealPlan, Vec<Unplaced>), ScheduleError> {
+        check_overlaps(availability)?;
+        let mut placed: Vec<Block> = availability
+            .iter()
+            .filter(|x| x.has_food())
+            .cloned()
+            .collect();
+        let mut free: Vec<Block> = availability
+            .iter()
+            .filter(|x| !x.has_food())
+            .cloned()
+            .collect();
+        free.sort_by_key(|x| *x.get_start());
+        let window = self.eating_window.as_ref();
+
+        // Longest first, keeping the given order between foods of the same length
+        let mut order: Vec<usize> = (0..foods.len()).collect();
+        order.sort_by_key(|&x| ::std::cmp::Reverse(foods[x].get_time()));
+
+        let unplaced_indices = match self.strategy {
+            SchedulingStrategy::Greedy => {
+                let mut unplaced = Vec::new();
+                for index in order {
+                    let food = &foods[index];
+                    let block = (0..free.len())
+                        .find_map(|i| place(&mut free, i, food, window, &self.supplements));
+                    match block {
+                        Some(block) => {
+                            #[cfg(feature = "tracing")]
+                            ::tracing::debug!(index, start = %block.get_start(), "placed food");
+                            placed.push(block)
+                        }
+                        None => unplaced.push(index),
+                    }
+                }
+                unplaced
+            }
+            SchedulingStrategy::Exhaustive => {
+                let mut current = Arrangement {
+                    placed: Vec::new(),
+                    free,
+                    unplaced: Vec::new(),
+                };
+                let mut best = None;
+                search(
+                    foods,
+                    &order,
+                    window,
+                    &self.supplements,
+                    &mut current,
+                    &mut best,
+                );
+                // Leaving every food unplaced is always an arrangement, so one is found
+                let best = best.unwrap();
+                placed.extend(best.placed);
+                free = best.free;
+                best.unplaced
+            }
+        };
+
+        // Report against what was left free once everything that fit was placed
+        let mut unplaced: Vec<Unplaced> = unplaced_indices
+            .into_iter()
+            .map(|index| {
+                // Would have been placed if not for the supplements
+                let near_supplement = !self.supplements.is_empty()
+                    && (0..free.len())
+                        .any(|i| place(&mut free.clone(), i, &foods[index], window, &[]).is_some());
+                let reason = match free.iter().map(block_minutes).max() {
+                    Some(_) if near_supplement => UnplacedReason::NearSupplement,
+                    Some(longest_free)
+                        if window.is_some() && longest_free >= foods[index].get_time() =>
+                    {
+                        UnplacedReason::OutsideEatingWindow
+                    }
+                    Some(longest_free) => UnplacedReason::TooLong {
+                        needed: foods[index].get_time(),
+                        longest_free,
+                    },
+                    None => UnplacedReason::NoFreeTime,
+                };
+                #[cfg(feature = "tracing")]
+                ::tracing::debug!(index, ?reason, "couldn't place food");
+                Unplaced { index, reason }
+            })
+            .collect();
+        unplaced.sort_by_key(|x| x.index);
+
+        Ok((self.plan_from(placed.into_iter().chain(free)), unplaced))
+    }
+}
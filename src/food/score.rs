@@ -0,0 +1,219 @@
+//! This module contains computed scores for food servings, such as diabetic exchanges or
+//! points systems
+//!
+//! A score is worked out from the nutrition of a single serving. Anything implementing
+//! `ServingScore` can be used in reports, including closures wrapped in a `ScoreFn`, while
+//! `ScoreFormula`, a rounded weighted sum of nutrients, can also be saved and used in
+//! constraints, as most exchange lists and points systems are just that.
+use chrono::prelude::*;
+use food::engine::MealPlan;
+use food::{Food, Fraction, Nutrient, Nutrition};
+use log::FoodLog;
+use num_rational::*;
+
+/// Works out a score from the nutrition of a single serving
+pub trait ServingScore {
+    /// Returns the score of a serving with the given nutrition
+    fn score(&self, nutrition: &Nutrition) -> Rational32;
+
+    /// Returns the score of a single serving of the food
+    fn score_food(&self, food: &Food) -> Rational32 {
+        self.score(food.get_nutrition())
+    }
+}
+
+/// A score worked out by a closure, for formulas a `ScoreFormula` can't express
+pub struct ScoreFn<F>(pub F);
+
+impl<F: Fn(&Nutrition) -> Rational32> ServingScore for ScoreFn<F> {
+    fn score(&self, nutrition: &Nutrition) -> Rational32 {
+        (self.0)(nutrition)
+    }
+}
+
+/// Something a `ScoreFormula` can weigh
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScoreInput {
+    /// One of the nutrients every Nutrition has room for, in the nutrient's own unit
+    Nutrient(Nutrient),
+    /// A micronutrient, by short code, in whatever unit it was recorded in
+    Micronutrient(String),
+}
+
+/// A score made of a weighted sum of nutrients, rounded to a step
+///
+/// Nutrients a serving's nutrition doesn't record count as none, and scores are never
+/// negative.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::score::*;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let mut nutrition = Nutrition::new();
+/// nutrition.set_carbohydrates(Amount::new(Unit::Gram, Rational32::from_integer(40)));
+///
+/// // 40 g is 2 2/3 exchanges, rounded to the nearest half
+/// let exchanges = ScoreFormula::carbohydrate_exchanges();
+/// assert_eq!(exchanges.score(&nutrition), Rational32::new(5, 2));
+///
+/// // A made up points system, a point per 50 kcal and per 10 g of carbohydrates
+/// nutrition.set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(300)));
+/// let mut points = ScoreFormula::new("points");
+/// points
+///     .add_term(Nutrient::Calories, Rational32::new(1, 50))
+///     .add_term(Nutrient::Carbohydrates, Rational32::new(1, 10))
+///     .set_step(Rational32::from_integer(1));
+/// assert_eq!(points.score(&nutrition), Rational32::from_integer(10));
+/// # }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScoreFormula {
+    name: String,
+    terms: Vec<(ScoreInput, Fraction)>,
+    #[serde(default)]
+    step: Option<Fraction>,
+}
+
+impl ScoreFormula {
+    /// Creates a new formula, scoring everything zero until terms are added
+    pub fn new(name: &str) -> ScoreFormula {
+        ScoreFormula {
+            name: name.to_string(),
+            terms: Vec::new(),
+            step: None,
+        }
+    }
+
+    /// Carbohydrate exchanges, 15 g of carbohydrates each, to the nearest half
+    pub fn carbohydrate_exchanges() -> ScoreFormula {
+        let mut formula = ScoreFormula::new("carbohydrate-exchanges");
+        formula
+            .add_term(Nutrient::Carbohydrates, Rational32::new(1, 15))
+            .set_step(Rational32::new(1, 2));
+        formula
+    }
+
+    /// Protein exchanges, 7 g of protein each, to the nearest half
+    pub fn protein_exchanges() -> ScoreFormula {
+        let mut formula = ScoreFormula::new("protein-exchanges");
+        formula
+            .add_term(Nutrient::Protein, Rational32::new(1, 7))
+            .set_step(Rational32::new(1, 2));
+        formula
+    }
+
+    /// Fat exchanges, 5 g of fat each, to the nearest half
+    pub fn fat_exchanges() -> ScoreFormula {
+        let mut formula = ScoreFormula::new("fat-exchanges");
+        formula
+            .add_term(Nutrient::Fat, Rational32::new(1, 5))
+            .set_step(Rational32::new(1, 2));
+        formula
+    }
+
+    /// Returns the name of the formula
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the weight of each input
+    pub fn get_terms(&self) -> Vec<(&ScoreInput, Rational32)> {
+        self.terms
+            .iter()
+            .map(|(input, weight)| (input, weight.to_rational()))
+            .collect()
+    }
+
+    /// Adds a nutrient to the sum with the given weight, negative to lower the score
+    pub fn add_term(&mut self, nutrient: Nutrient, weight: Rational32) -> &mut Self {
+        self.add_input(ScoreInput::Nutrient(nutrient), weight)
+    }
+
+    /// Adds a micronutrient to the sum with the given weight, negative to lower the score
+    pub fn add_micronutrient_term(&mut self, short_code: &str, weight: Rational32) -> &mut Self {
+        self.add_input(ScoreInput::Micronutrient(short_code.to_string()), weight)
+    }
+
+    /// Adds any input to the sum with the given weight
+    pub fn add_input(&mut self, input: ScoreInput, weight: Rational32) -> &mut Self {
+        self.terms.push((input, Fraction::from_rational(weight)));
+        self
+    }
+
+    /// Returns the step scores are rounded to, if they are rounded at all
+    pub fn get_step(&self) -> Option<Rational32> {
+        self.step.map(|x| x.to_rational())
+    }
+
+    /// Rounds scores to the nearest multiple of the step, halves rounding up
+    pub fn set_step(&mut self, step: Rational32) -> &mut Self {
+        self.step = Some(Fraction::from_rational(step));
+        self
+    }
+}
+
+impl ServingScore for ScoreFormula {
+    fn score(&self, nutrition: &Nutrition) -> Rational32 {
+        let zero = Rational32::from_integer(0);
+        let total = self
+            .terms
+            .iter()
+            .map(|(input, weight)| {
+                let amount = match *input {
+                    ScoreInput::Nutrient(x) => nutrition.get_nutrient(x),
+                    ScoreInput::Micronutrient(ref x) => {
+                        nutrition.get_micronutrient(x).map(|y| y.get_amount())
+                    }
+                };
+                amount.unwrap_or(zero) * weight.to_rational()
+            })
+            .fold(zero, |total, x| total + x);
+        let total = match self.get_step() {
+            Some(step) if step > zero => (total / step).round() * step,
+            _ => total,
+        };
+        if total < zero {
+            zero
+        } else {
+            total
+        }
+    }
+}
+
+/// Totals the score of every day of a plan, in date order, each meal counting as a single
+/// serving of its food
+pub fn plan_scores<S: ServingScore>(plan: &MealPlan, score: &S) -> Vec<(NaiveDate, Rational32)> {
+    plan.get_days()
+        .iter()
+        .map(|day| {
+            let total = day
+                .get_blocks()
+                .iter()
+                .filter_map(|x| x.get_food())
+                .fold(Rational32::from_integer(0), |total, x| {
+                    total + score.score_food(x)
+                });
+            (day.get_date(), total)
+        })
+        .collect()
+}
+
+/// Totals the score of every day of a log, in date order, each entry counting for the servings
+/// eaten
+pub fn log_scores<S: ServingScore>(log: &FoodLog, score: &S) -> Vec<(NaiveDate, Rational32)> {
+    let mut totals: Vec<(NaiveDate, Rational32)> = Vec::new();
+    for entry in log.get_entries() {
+        let value = score.score_food(entry.get_food()) * entry.get_servings();
+        match totals.binary_search_by(|x| x.0.cmp(&entry.get_date())) {
+            Ok(index) => totals[index].1 += value,
+            Err(index) => totals.insert(index, (entry.get_date(), value)),
+        }
+    }
+    totals
+}
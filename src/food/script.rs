@@ -0,0 +1,144 @@
+//! This module contains the script and writing direction metadata attached to IString values
+//!
+//! The metadata is purely descriptive, the IString itself never reorders or otherwise touches
+//! the text. It exists so that exporters can emit the right direction markers for the output
+//! format they are producing.
+
+/// The direction a piece of text is written in
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+impl TextDirection {
+    /// Makes a best guess at the direction of a language from its language code
+    ///
+    /// Only the language portion of the code (the part before any `_` or `-`) is considered.
+    /// Anything not known to be right-to-left is assumed to be left-to-right.
+    pub fn guess_for(lang: &str) -> TextDirection {
+        let language = lang.split(&['_', '-'][..]).next().unwrap_or("");
+        match language {
+            "ar" | "he" | "fa" | "ur" | "yi" | "ps" | "sd" | "ug" | "dv" | "ckb" => {
+                TextDirection::RightToLeft
+            }
+            _ => TextDirection::LeftToRight,
+        }
+    }
+
+    /// Returns the value of the html `dir` attribute for this direction
+    pub fn html_dir(self) -> &'static str {
+        match self {
+            TextDirection::LeftToRight => "ltr",
+            TextDirection::RightToLeft => "rtl",
+        }
+    }
+
+    /// Wraps the text in the unicode isolate characters for this direction
+    ///
+    /// This is for plain text formats, like Markdown, that have no markup for direction.
+    /// Isolating the text keeps it from disturbing the layout of the text around it.
+    pub fn isolate(self, text: &str) -> String {
+        let opener = match self {
+            TextDirection::LeftToRight => '\u{2066}',
+            TextDirection::RightToLeft => '\u{2067}',
+        };
+        format!("{}{}\u{2069}", opener, text)
+    }
+}
+
+/// Describes how the value of an IString in a particular language is written
+///
+/// The script is an optional ISO 15924 code, such as "Arab" or "Hebr".
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScriptHint {
+    script: Option<String>,
+    direction: TextDirection,
+}
+
+impl ScriptHint {
+    /// Creates a new ScriptHint with the given direction, and no script code
+    pub fn new(direction: TextDirection) -> ScriptHint {
+        ScriptHint {
+            script: None,
+            direction,
+        }
+    }
+
+    /// Creates a new ScriptHint with both a script code and a direction
+    pub fn with_script(script: &str, direction: TextDirection) -> ScriptHint {
+        ScriptHint {
+            script: Some(script.to_string()),
+            direction,
+        }
+    }
+
+    /// Returns the ISO 15924 script code, if one was set
+    pub fn get_script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// Returns the direction the text is written in
+    pub fn get_direction(&self) -> TextDirection {
+        self.direction
+    }
+}
+
+/// The result of rendering an IString, along with the information needed to display it
+///
+/// Returned by `IString::render_text`, so that exporters can wrap the text in whatever
+/// direction markers their output format needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderedText {
+    text: String,
+    lang: String,
+    script: Option<String>,
+    direction: TextDirection,
+}
+
+impl RenderedText {
+    /// Constructs a RenderedText from its raw components
+    pub fn new(
+        text: String,
+        lang: &str,
+        script: Option<String>,
+        direction: TextDirection,
+    ) -> RenderedText {
+        RenderedText {
+            text,
+            lang: lang.to_string(),
+            script,
+            direction,
+        }
+    }
+
+    /// Returns the rendered text, without any direction markers
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the language code the text was rendered in
+    pub fn get_lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Returns the ISO 15924 script code of the text, if one is known
+    pub fn get_script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// Returns the direction the text is written in
+    pub fn get_direction(&self) -> TextDirection {
+        self.direction
+    }
+
+    /// Returns the text wrapped in unicode isolates if it is right-to-left
+    ///
+    /// Left-to-right text is returned as is, as that is what plain text formats already assume.
+    pub fn to_isolated(&self) -> String {
+        match self.direction {
+            TextDirection::LeftToRight => self.text.clone(),
+            TextDirection::RightToLeft => self.direction.isolate(&self.text),
+        }
+    }
+}
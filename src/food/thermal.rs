@@ -0,0 +1,159 @@
+//! This module contains thermal batching, grouping oven recipes so they can share the oven
+//!
+//! Preheating an oven takes a while, so baking several recipes in one go saves time and
+//! energy. Recipes whose oven temperatures are close enough are batched together, and baked at
+//! a compromise temperature, with their oven times adjusted to match.
+use food::*;
+
+/// The minutes an oven is assumed to take to preheat
+pub const PREHEAT_MINUTES: u32 = 15;
+
+/// A recipe's part in an oven batch
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchedRecipe {
+    short_code: String,
+    temperature: u32,
+    oven_time: Rational32,
+    adjusted_time: Rational32,
+}
+
+impl BatchedRecipe {
+    /// Returns the short code of the recipe
+    pub fn get_short_code(&self) -> &str {
+        &self.short_code
+    }
+
+    /// Returns the temperature the recipe asks for, in degrees Celsius
+    pub fn get_temperature(&self) -> u32 {
+        self.temperature
+    }
+
+    /// Returns the minutes the recipe spends in the oven at its own temperature
+    pub fn get_oven_time(&self) -> Rational32 {
+        self.oven_time
+    }
+
+    /// Returns the minutes the recipe spends in the oven at the batch's temperature
+    pub fn get_adjusted_time(&self) -> Rational32 {
+        self.adjusted_time
+    }
+}
+
+/// A group of recipes baked together at a compromise temperature
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OvenBatch {
+    temperature: u32,
+    recipes: Vec<BatchedRecipe>,
+}
+
+impl OvenBatch {
+    /// Returns the compromise temperature the batch bakes at, in degrees Celsius
+    pub fn get_temperature(&self) -> u32 {
+        self.temperature
+    }
+
+    /// Returns the recipes in the batch, coolest first
+    pub fn get_recipes(&self) -> &[BatchedRecipe] {
+        &self.recipes
+    }
+
+    /// Returns the minutes of preheating saved by baking the batch together
+    pub fn get_preheat_saved(&self) -> u32 {
+        (self.recipes.len() as u32).saturating_sub(1) * PREHEAT_MINUTES
+    }
+}
+
+/// Returns the oven temperature and oven minutes of a recipe, if it uses the oven
+///
+/// A recipe baking at several temperatures is treated as baking at its hottest.
+pub fn oven_use(recipe: &Recipe) -> Option<(u32, Rational32)> {
+    let oven_steps: Vec<&Step> = recipe
+        .get_steps()
+        .iter()
+        .filter(|x| x.get_oven_temperature().is_some())
+        .collect();
+    let temperature = oven_steps
+        .iter()
+        .filter_map(|x| x.get_oven_temperature())
+        .max()?;
+    let time = oven_steps
+        .iter()
+        .fold(Rational32::from_integer(0), |total, x| total + x.get_time());
+    Some((temperature, time))
+}
+
+/// Groups oven recipes whose temperatures are within the given tolerance of each other
+///
+/// Each batch bakes at the midpoint of its recipes' temperatures. Oven times are adjusted in
+/// proportion to the temperature change, so a recipe baked hotter than it asks for comes out
+/// sooner. That is only a rule of thumb, so keep the tolerance small. Foods that don't use the
+/// oven are ignored.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::thermal::*;
+/// use time_for_food::food::*;
+///
+/// # fn main() {
+/// let mut bake = Step::new("bake-potatoes", Rational32::from_integer(60));
+/// bake.set_oven_temperature(Some(190));
+/// let potatoes = RecipeBuilder::new("baked-potatoes").add_step(bake).build_with_defaults();
+///
+/// let mut roast = Step::new("roast-vegetables", Rational32::from_integer(42));
+/// roast.set_oven_temperature(Some(210));
+/// let vegetables = RecipeBuilder::new("roast-vegetables").add_step(roast).build_with_defaults();
+///
+/// let batches = batch_by_temperature(&[&potatoes, &vegetables], 20);
+/// assert_eq!(batches.len(), 1);
+/// assert_eq!(batches[0].get_temperature(), 200);
+/// // The potatoes bake 10 degrees hotter, so finish a little sooner
+/// assert_eq!(batches[0].get_recipes()[0].get_adjusted_time(), Rational32::from_integer(57));
+/// # }
+/// ```
+pub fn batch_by_temperature(recipes: &[&Recipe], tolerance: u32) -> Vec<OvenBatch> {
+    let mut ovened: Vec<(&Recipe, u32, Rational32)> = recipes
+        .iter()
+        .filter_map(|x| oven_use(x).map(|(temperature, time)| (*x, temperature, time)))
+        .collect();
+    ovened.sort_by_key(|x| x.1);
+
+    // Greedily grow each batch from its coolest recipe, for as long as the spread allows
+    let mut groups: Vec<Vec<(&Recipe, u32, Rational32)>> = Vec::new();
+    for entry in ovened {
+        let fits = groups
+            .last()
+            .map(|group| entry.1 - group[0].1 <= tolerance)
+            .unwrap_or(false);
+        if fits {
+            groups.last_mut().unwrap().push(entry);
+        } else {
+            groups.push(vec![entry]);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let coolest = group[0].1;
+            let hottest = group[group.len() - 1].1;
+            let temperature = (coolest + hottest) / 2;
+            let recipes = group
+                .iter()
+                .map(|&(recipe, own, time)| BatchedRecipe {
+                    short_code: recipe.get_name().get_short_code().to_string(),
+                    temperature: own,
+                    oven_time: time,
+                    adjusted_time: time * Rational32::new(own as i32, temperature.max(1) as i32),
+                })
+                .collect();
+            OvenBatch {
+                temperature,
+                recipes,
+            }
+        })
+        .collect()
+}
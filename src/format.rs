@@ -0,0 +1,580 @@
+//! This module contains the human-readable formatting of amounts and durations, such as
+//! "1 ½ cups" or "1 h 25 min"
+//!
+//! Unit names and duration labels are IStrings with plural forms, rendered with a `{count}`
+//! placeholder, so they are localized the same way as every other piece of text. A `Formatter`
+//! starts out knowing English, and other languages are added by setting their forms on its
+//! labels. Languages the formatter has no labels for fall back to the language portion of the
+//! code, so "en_US" uses the "en" labels, and then to the unit's symbol.
+//!
+//! Scaled recipes rarely come out to amounts anyone can measure, so `KitchenMeasures` snaps an
+//! exact amount to the nearest combination of practical measures, such as "2 tbsp + 1 ½ tsp"
+//! for 37 ml, keeping the exact amount alongside it.
+use food::plural::PluralCategory;
+use food::*;
+use num_rational::*;
+use parse::fraction_glyph;
+use settings::{RoundingPolicy, Settings, UnitSystem};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use units::ConversionTable;
+
+/// How the fractional part of a number is written
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FractionStyle {
+    /// Unicode vulgar fraction glyphs, such as "1 ½", where one exists for the fraction
+    Unicode,
+    /// Plain ASCII, such as "1 1/2"
+    Ascii,
+}
+
+/// Formats a number as a mixed number in the given style
+///
+/// Fractions without a unicode glyph, such as sixteenths, are written in ASCII even in the
+/// unicode style.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::format::*;
+///
+/// # fn main() {
+/// let value = Rational32::new(3, 2);
+/// assert_eq!(format_number(value, FractionStyle::Unicode), "1 ½");
+/// assert_eq!(format_number(value, FractionStyle::Ascii), "1 1/2");
+/// assert_eq!(format_number(Rational32::new(3, 4), FractionStyle::Unicode), "¾");
+/// assert_eq!(format_number(Rational32::new(5, 16), FractionStyle::Unicode), "5/16");
+/// # }
+/// ```
+pub fn format_number(value: Rational32, style: FractionStyle) -> String {
+    let zero = Rational32::from_integer(0);
+    let sign = if value < zero { "-" } else { "" };
+    let value = if value < zero { -value } else { value };
+    let whole = value.trunc().to_integer();
+    let fraction = value.fract();
+    if fraction == zero {
+        return format!("{}{}", sign, whole);
+    }
+    let fraction = match style {
+        FractionStyle::Unicode => fraction_to_glyph(fraction)
+            .map(|x| x.to_string())
+            .unwrap_or_else(|| fraction.to_string()),
+        FractionStyle::Ascii => fraction.to_string(),
+    };
+    if whole == 0 {
+        format!("{}{}", sign, fraction)
+    } else {
+        format!("{}{} {}", sign, whole, fraction)
+    }
+}
+
+/// Returns the unicode glyph for a fraction between zero and one, if there is one
+fn fraction_to_glyph(fraction: Rational32) -> Option<char> {
+    "½⅓⅔¼¾⅕⅖⅗⅘⅙⅚⅛⅜⅝⅞"
+        .chars()
+        .find(|&c| fraction_glyph(c) == Some(fraction))
+}
+
+/// Returns the language portion of a language code, such as "en" for "en_US"
+fn base_language(lang: &str) -> &str {
+    lang.split(['_', '-']).next().unwrap_or(lang)
+}
+
+/// Creates a label with English one and other forms
+fn english_label(short_code: &str, one: &str, other: &str) -> IString {
+    let mut label = IString::new(short_code);
+    label.set_plural_value_for("en", PluralCategory::One, &format!("{{count}} {}", one));
+    label.set_plural_value_for("en", PluralCategory::Other, &format!("{{count}} {}", other));
+    label
+}
+
+/// Formats amounts and durations for people, in a given language
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::plural::PluralCategory;
+/// use time_for_food::food::*;
+/// use time_for_food::format::*;
+///
+/// # fn main() {
+/// let mut formatter = Formatter::new("en_US");
+/// let cups = Amount::new(Unit::Cup, Rational32::new(3, 2));
+/// assert_eq!(formatter.format_amount(&cups), "1 ½ cups");
+/// assert_eq!(formatter.format_duration(Rational32::from_integer(85)), "1 h 25 min");
+///
+/// formatter.set_fraction_style(FractionStyle::Ascii);
+/// assert_eq!(formatter.format_amount(&cups), "1 1/2 cups");
+///
+/// // Other languages are added by giving their forms
+/// let mut formatter = Formatter::new("de_DE");
+/// let mut tasse = IString::new("cup");
+/// tasse.set_plural_value_for("de", PluralCategory::One, "{count} Tasse");
+/// tasse.set_plural_value_for("de", PluralCategory::Other, "{count} Tassen");
+/// formatter.set_unit_label(Unit::Cup, tasse);
+/// assert_eq!(formatter.format_amount(&cups), "1 ½ Tassen");
+/// // Units without German labels fall back to their symbols
+/// let flour = Amount::new(Unit::Gram, Rational32::from_integer(250));
+/// assert_eq!(formatter.format_amount(&flour), "250 g");
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Formatter {
+    lang: String,
+    fractions: FractionStyle,
+    rounding: RoundingPolicy,
+    unit_system: Option<UnitSystem>,
+    conversions: ConversionTable,
+    units: BTreeMap<Unit, IString>,
+    hours: IString,
+    minutes: IString,
+}
+
+impl Formatter {
+    /// Creates a new Formatter for the given language, with English labels, unicode fractions,
+    /// no rounding, and amounts left in their own units
+    pub fn new(lang: &str) -> Formatter {
+        let mut units = BTreeMap::new();
+        let mut add = |unit: Unit, one: &str, other: &str| {
+            units.insert(unit, english_label(unit.get_symbol(), one, other));
+        };
+        add(Unit::Milligram, "mg", "mg");
+        add(Unit::Gram, "g", "g");
+        add(Unit::Kilogram, "kg", "kg");
+        add(Unit::Ounce, "oz", "oz");
+        add(Unit::Pound, "lb", "lb");
+        add(Unit::Milliliter, "ml", "ml");
+        add(Unit::Liter, "L", "L");
+        add(Unit::Cup, "cup", "cups");
+        add(Unit::Tablespoon, "tbsp", "tbsp");
+        add(Unit::Teaspoon, "tsp", "tsp");
+        add(Unit::Kilocalorie, "kcal", "kcal");
+        Formatter {
+            lang: lang.to_string(),
+            fractions: FractionStyle::Unicode,
+            rounding: RoundingPolicy::Exact,
+            unit_system: None,
+            conversions: ConversionTable::new(),
+            units,
+            hours: english_label("hours", "h", "h"),
+            minutes: english_label("minutes", "min", "min"),
+        }
+    }
+
+    /// Creates a new Formatter using a household's language, rounding policy, and unit system,
+    /// converting with the household's unit sizes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::format::*;
+    /// use time_for_food::settings::*;
+    ///
+    /// # fn main() {
+    /// let mut settings = Settings::new();
+    /// settings
+    ///     .set_unit_system(UnitSystem::UsCustomary)
+    ///     .set_rounding(RoundingPolicy::Nearest(4));
+    /// let formatter = Formatter::from_settings(&settings);
+    /// let flour = Amount::new(Unit::Kilogram, Rational32::from_integer(1));
+    /// assert_eq!(formatter.format_amount(&flour), "2 ¼ lb");
+    /// let milk = Amount::new(Unit::Cup, Rational32::from_integer(2));
+    /// assert_eq!(formatter.format_amount(&milk), "2 cups");
+    /// # }
+    /// ```
+    pub fn from_settings(settings: &Settings) -> Formatter {
+        let mut formatter = Formatter::new(settings.get_language());
+        formatter
+            .set_rounding(settings.get_rounding())
+            .set_unit_system(Some(settings.get_unit_system()));
+        formatter.conversions = settings.get_conversions().clone();
+        formatter
+    }
+
+    /// Returns the language code the formatter renders in
+    pub fn get_lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Returns how fractions are written
+    pub fn get_fraction_style(&self) -> FractionStyle {
+        self.fractions
+    }
+
+    /// Sets how fractions are written
+    pub fn set_fraction_style(&mut self, style: FractionStyle) -> &mut Self {
+        self.fractions = style;
+        self
+    }
+
+    /// Returns how amounts are rounded before they are written
+    pub fn get_rounding(&self) -> RoundingPolicy {
+        self.rounding
+    }
+
+    /// Sets how amounts are rounded before they are written
+    pub fn set_rounding(&mut self, rounding: RoundingPolicy) -> &mut Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Returns the family of units amounts are presented in, if they are converted to one
+    pub fn get_unit_system(&self) -> Option<UnitSystem> {
+        self.unit_system
+    }
+
+    /// Sets the family of units amounts are presented in, see `UnitSystem::present`, or
+    /// leaves amounts in their own units if given None
+    pub fn set_unit_system(&mut self, unit_system: Option<UnitSystem>) -> &mut Self {
+        self.unit_system = unit_system;
+        self
+    }
+
+    /// Returns the label used for a unit, if it has one
+    pub fn get_unit_label(&self, unit: Unit) -> Option<&IString> {
+        self.units.get(&unit)
+    }
+
+    /// Sets the label used for a unit, replacing the existing one
+    ///
+    /// The plural forms of the label are rendered with the number as `{count}`.
+    pub fn set_unit_label(&mut self, unit: Unit, label: IString) -> &mut Self {
+        self.units.insert(unit, label);
+        self
+    }
+
+    /// Returns the label used for whole hours
+    pub fn get_hours_label(&self) -> &IString {
+        &self.hours
+    }
+
+    /// Sets the label used for whole hours, rendered with the number as `{count}`
+    pub fn set_hours_label(&mut self, label: IString) -> &mut Self {
+        self.hours = label;
+        self
+    }
+
+    /// Returns the label used for minutes
+    pub fn get_minutes_label(&self) -> &IString {
+        &self.minutes
+    }
+
+    /// Sets the label used for minutes, rendered with the number as `{count}`
+    pub fn set_minutes_label(&mut self, label: IString) -> &mut Self {
+        self.minutes = label;
+        self
+    }
+
+    /// Formats a number in the formatter's fraction style, without rounding it
+    pub fn format_number(&self, value: Rational32) -> String {
+        format_number(value, self.fractions)
+    }
+
+    /// Formats an amount, such as "1 ½ cups", presenting it in the formatter's unit system, if
+    /// it has one, and rounding it first
+    ///
+    /// Counts are written as the bare number. Amounts under one take the singular form, as
+    /// "½ cup" is read as half of a cup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::format::*;
+    /// use time_for_food::settings::RoundingPolicy;
+    ///
+    /// # fn main() {
+    /// let mut formatter = Formatter::new("en");
+    /// let amount = |unit, n, d| Amount::new(unit, Rational32::new(n, d));
+    /// assert_eq!(formatter.format_amount(&amount(Unit::Cup, 1, 2)), "½ cup");
+    /// assert_eq!(formatter.format_amount(&amount(Unit::Count, 3, 1)), "3");
+    ///
+    /// formatter.set_rounding(RoundingPolicy::Nearest(4));
+    /// assert_eq!(formatter.format_amount(&amount(Unit::Teaspoon, 7, 10)), "¾ tsp");
+    /// # }
+    /// ```
+    pub fn format_amount(&self, amount: &Amount) -> String {
+        let amount = match self.unit_system {
+            Some(system) => system.present(amount, &self.conversions),
+            None => *amount,
+        };
+        let value = self.rounding.apply(amount.get_amount());
+        let number = self.format_number(value);
+        let unit = amount.get_unit();
+        let zero = Rational32::from_integer(0);
+        let one = Rational32::from_integer(1);
+        let count = if value > zero && value < one {
+            one
+        } else {
+            value
+        };
+        self.units
+            .get(&unit)
+            .and_then(|label| self.render(label, count, &number))
+            .unwrap_or_else(|| {
+                if unit.get_symbol().is_empty() {
+                    number
+                } else {
+                    format!("{} {}", number, unit.get_symbol())
+                }
+            })
+    }
+
+    /// Formats a number of minutes, such as "1 h 25 min"
+    ///
+    /// Durations of an hour or more are rounded to the nearest minute, shorter ones keep their
+    /// fractions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::format::*;
+    ///
+    /// # fn main() {
+    /// let formatter = Formatter::new("en");
+    /// let minutes = |n| formatter.format_duration(Rational32::from_integer(n));
+    /// assert_eq!(minutes(45), "45 min");
+    /// assert_eq!(minutes(120), "2 h");
+    /// assert_eq!(formatter.format_duration(Rational32::new(1, 2)), "½ min");
+    /// # }
+    /// ```
+    pub fn format_duration(&self, minutes: Rational32) -> String {
+        let sixty = Rational32::from_integer(60);
+        if minutes < sixty {
+            return self.format_minutes(minutes);
+        }
+        let total = minutes.round().to_integer();
+        let hours = Rational32::from_integer(total / 60);
+        let rest = Rational32::from_integer(total % 60);
+        let hours = self
+            .render(&self.hours, hours, &self.format_number(hours))
+            .unwrap_or_else(|| format!("{} h", hours));
+        if rest == Rational32::from_integer(0) {
+            hours
+        } else {
+            format!("{} {}", hours, self.format_minutes(rest))
+        }
+    }
+
+    /// Formats a snapped amount as its measures joined with " + ", such as "2 tbsp + 1 ½ tsp"
+    ///
+    /// The measures are written without rounding, as they are already practical amounts.
+    pub fn format_snapped(&self, snapped: &SnappedAmount) -> String {
+        let mut formatter = self.clone();
+        formatter.set_rounding(RoundingPolicy::Exact);
+        snapped
+            .get_measures()
+            .iter()
+            .map(|x| formatter.format_amount(x))
+            .collect::<Vec<String>>()
+            .join(" + ")
+    }
+
+    fn format_minutes(&self, minutes: Rational32) -> String {
+        let number = self.format_number(minutes);
+        self.render(&self.minutes, minutes, &number)
+            .unwrap_or_else(|| format!("{} min", number))
+    }
+
+    /// Renders the plural form of a label, trying the full language code and then the
+    /// language portion of it
+    fn render(&self, label: &IString, count: Rational32, number: &str) -> Option<String> {
+        let args = [("count", number)];
+        label
+            .render_plural(&self.lang, count, &args)
+            .or_else(|| label.render_plural(base_language(&self.lang), count, &args))
+    }
+}
+
+/// An exact amount, along with the practical measures it snapped to
+#[derive(Clone, PartialEq, Eq)]
+pub struct SnappedAmount {
+    exact: Amount,
+    measures: Vec<Amount>,
+    total: Amount,
+}
+
+impl SnappedAmount {
+    /// Returns the amount before it was snapped
+    pub fn get_exact(&self) -> Amount {
+        self.exact
+    }
+
+    /// Returns the measures making up the snapped amount, largest first
+    pub fn get_measures(&self) -> &[Amount] {
+        &self.measures
+    }
+
+    /// Returns the total of the measures, in the unit of the exact amount, using the unit
+    /// sizes the amount was snapped with
+    pub fn get_total(&self) -> Amount {
+        self.total
+    }
+}
+
+/// The measures a kitchen has on hand, and the smallest step each is used in
+///
+/// The default set is cups in quarters, whole tablespoons, and teaspoons in quarters.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+/// use time_for_food::format::*;
+/// use time_for_food::settings::Settings;
+///
+/// # fn main() {
+/// let settings = Settings::new();
+/// let measures = KitchenMeasures::default();
+/// let vinegar = Amount::new(Unit::Milliliter, Rational32::from_integer(37));
+/// let snapped = measures.snap(&vinegar, &settings).unwrap();
+/// assert_eq!(Formatter::new("en").format_snapped(&snapped), "2 tbsp + 1 ½ tsp");
+/// // The exact amount is kept underneath
+/// assert!(snapped.get_exact() == vinegar);
+/// assert!(snapped.get_total() == Amount::new(Unit::Milliliter, Rational32::new(75, 2)));
+///
+/// // Amounts with no measures of their kind are left alone
+/// let flour = Amount::new(Unit::Gram, Rational32::from_integer(220));
+/// assert!(measures.snap(&flour, &settings).is_none());
+/// # }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KitchenMeasures {
+    measures: Vec<(Unit, Fraction)>,
+}
+
+impl Default for KitchenMeasures {
+    fn default() -> KitchenMeasures {
+        let mut measures = KitchenMeasures::new();
+        measures
+            .add_measure(Unit::Cup, Rational32::new(1, 4))
+            .add_measure(Unit::Tablespoon, Rational32::from_integer(1))
+            .add_measure(Unit::Teaspoon, Rational32::new(1, 4));
+        measures
+    }
+}
+
+impl KitchenMeasures {
+    /// Creates a new, empty, set of measures
+    pub fn new() -> KitchenMeasures {
+        KitchenMeasures {
+            measures: Vec::new(),
+        }
+    }
+
+    /// Returns the measures, largest first, along with the smallest step each is used in
+    pub fn get_measures(&self) -> Vec<(Unit, Rational32)> {
+        self.measures
+            .iter()
+            .map(|(unit, step)| (*unit, step.to_rational()))
+            .collect()
+    }
+
+    /// Adds a measure, used in multiples of the step, replacing any existing step for the unit
+    pub fn add_measure(&mut self, unit: Unit, step: Rational32) -> &mut Self {
+        self.measures.retain(|x| x.0 != unit);
+        self.measures.push((unit, Fraction::from_rational(step)));
+        self.measures.sort_by_key(|x| Reverse(x.0.base_factor()));
+        self
+    }
+
+    /// Removes a measure
+    pub fn remove_measure(&mut self, unit: Unit) -> &mut Self {
+        self.measures.retain(|x| x.0 != unit);
+        self
+    }
+
+    /// Snaps an amount to practical measures, using the household's unit sizes
+    ///
+    /// As much of the amount as possible is taken in each measure, largest first, and whatever
+    /// is left over is rounded to the nearest step of the smallest measure.
+    ///
+    /// # Returns
+    /// None if there are no measures of the amount's kind, or it is too small to measure with
+    /// them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::format::*;
+    /// use time_for_food::settings::Settings;
+    ///
+    /// # fn main() {
+    /// // A household using metric cups
+    /// let mut settings = Settings::new();
+    /// settings
+    ///     .get_mut_conversions()
+    ///     .set_unit_size(Unit::Cup, Amount::new(Unit::Milliliter, Rational32::from_integer(250)))
+    ///     .unwrap();
+    ///
+    /// let stock = Amount::new(Unit::Milliliter, Rational32::from_integer(250));
+    /// let snapped = KitchenMeasures::default().snap(&stock, &settings).unwrap();
+    /// assert_eq!(Formatter::new("en").format_snapped(&snapped), "1 cup");
+    /// assert!(snapped.get_total() == stock);
+    /// # }
+    /// ```
+    pub fn snap(&self, amount: &Amount, settings: &Settings) -> Option<SnappedAmount> {
+        let conversions = settings.get_conversions();
+        let unit = amount.get_unit();
+        let zero = Rational32::from_integer(0);
+        let compatible: Vec<(Unit, Rational32)> = self
+            .get_measures()
+            .into_iter()
+            .filter(|x| x.0.is_compatible(unit) && x.1 > zero)
+            .collect();
+        let mut remaining = *amount;
+        let mut total = zero;
+        let mut measures = Vec::new();
+        for (index, &(measure, step)) in compatible.iter().enumerate() {
+            let available = remaining
+                .convert_with(measure, conversions)
+                .ok()?
+                .get_amount();
+            let steps = available / step;
+            let steps = if index + 1 == compatible.len() {
+                steps.round()
+            } else {
+                steps.floor()
+            };
+            if steps <= zero {
+                continue;
+            }
+            let taken = Amount::new(measure, steps * step);
+            let used = taken.convert_with(unit, conversions).ok()?.get_amount();
+            remaining = Amount::new(unit, remaining.get_amount() - used);
+            total += used;
+            measures.push(taken);
+        }
+        if measures.is_empty() {
+            return None;
+        }
+        Some(SnappedAmount {
+            exact: *amount,
+            measures,
+            total: Amount::new(unit, total),
+        })
+    }
+}
@@ -0,0 +1,510 @@
+//! This module contains the people a household cooks for, and how much each of them eats
+//!
+//! Portions are measured in adult servings, and depend on each member's age through the
+//! household's `PortionProfile`. Babies and toddlers are served a recipe's child variant, made
+//! from the same cook block as everyone else's meal, when the recipe has one.
+//!
+//! Pets are kept apart from the people. Each has its own daily calorie target and its own
+//! pool of foods, and is scheduled as feedings alongside a plan's blocks rather than in them.
+//!
+//! Each member may keep diets and avoid allergens, so planners can leave out foods someone at
+//! the table can't eat.
+use chrono::prelude::*;
+use food::diet::DietProfile;
+use food::engine::*;
+use food::*;
+use num_rational::*;
+use std::collections::BTreeMap;
+
+/// A stage of life that portions are sized by
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AgeGroup {
+    /// Under a year old
+    Infant,
+    /// From one up to three years old
+    Toddler,
+    /// From three up to thirteen years old
+    Child,
+    /// Thirteen years old or more
+    Adult,
+}
+
+impl AgeGroup {
+    /// Returns the age group of someone the given number of months old
+    pub fn for_age_months(months: u32) -> AgeGroup {
+        match months {
+            0..=11 => AgeGroup::Infant,
+            12..=35 => AgeGroup::Toddler,
+            36..=155 => AgeGroup::Child,
+            _ => AgeGroup::Adult,
+        }
+    }
+
+    /// Returns true if the age group is served child variants of recipes
+    pub fn needs_child_variant(self) -> bool {
+        match self {
+            AgeGroup::Infant | AgeGroup::Toddler => true,
+            AgeGroup::Child | AgeGroup::Adult => false,
+        }
+    }
+}
+
+/// How much each age group eats, as a fraction of an adult serving
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PortionProfile {
+    portions: BTreeMap<AgeGroup, Fraction>,
+}
+
+impl Default for PortionProfile {
+    /// A quarter serving for infants, a third for toddlers, two thirds for children, and a
+    /// whole serving for adults
+    fn default() -> PortionProfile {
+        let mut portions = BTreeMap::new();
+        portions.insert(
+            AgeGroup::Infant,
+            Fraction::from_rational(Rational32::new(1, 4)),
+        );
+        portions.insert(
+            AgeGroup::Toddler,
+            Fraction::from_rational(Rational32::new(1, 3)),
+        );
+        portions.insert(
+            AgeGroup::Child,
+            Fraction::from_rational(Rational32::new(2, 3)),
+        );
+        portions.insert(
+            AgeGroup::Adult,
+            Fraction::from_rational(Rational32::from_integer(1)),
+        );
+        PortionProfile { portions }
+    }
+}
+
+impl PortionProfile {
+    /// Creates the default profile
+    pub fn new() -> PortionProfile {
+        PortionProfile::default()
+    }
+
+    /// Returns the portion an age group eats, in adult servings
+    ///
+    /// Age groups without a portion set eat a whole serving.
+    pub fn get_portion(&self, group: AgeGroup) -> Rational32 {
+        self.portions
+            .get(&group)
+            .map(|x| x.to_rational())
+            .unwrap_or_else(|| Rational32::from_integer(1))
+    }
+
+    /// Sets the portion an age group eats, in adult servings
+    pub fn set_portion(&mut self, group: AgeGroup, portion: Rational32) -> &mut Self {
+        self.portions
+            .insert(group, Fraction::from_rational(portion));
+        self
+    }
+}
+
+/// Someone a household cooks for
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Member {
+    name: String,
+    /// Age in months, or None for an adult whose age doesn't matter
+    age_months: Option<u32>,
+    /// The diets the member keeps and the allergens they avoid
+    #[serde(default)]
+    diet: DietProfile,
+}
+
+impl Member {
+    /// Creates an adult member
+    pub fn adult(name: &str) -> Member {
+        Member {
+            name: name.to_string(),
+            age_months: None,
+            diet: DietProfile::new(),
+        }
+    }
+
+    /// Creates a member of the given age, in months
+    pub fn aged(name: &str, months: u32) -> Member {
+        Member {
+            name: name.to_string(),
+            age_months: Some(months),
+            diet: DietProfile::new(),
+        }
+    }
+
+    /// Returns the member's name
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the member's age in months, if it was given
+    pub fn get_age_months(&self) -> Option<u32> {
+        self.age_months
+    }
+
+    /// Sets the member's age in months, or None for an adult whose age doesn't matter
+    pub fn set_age_months(&mut self, months: Option<u32>) {
+        self.age_months = months;
+    }
+
+    /// Returns the member's age group
+    pub fn get_age_group(&self) -> AgeGroup {
+        self.age_months
+            .map_or(AgeGroup::Adult, AgeGroup::for_age_months)
+    }
+
+    /// Returns the diets the member keeps and the allergens they avoid
+    pub fn get_diet(&self) -> &DietProfile {
+        &self.diet
+    }
+
+    /// Returns a mutable reference to the member's diets and allergens
+    pub fn get_mut_diet(&mut self) -> &mut DietProfile {
+        &mut self.diet
+    }
+
+    /// Returns true if the member can eat the food, see `Food::satisfies`
+    pub fn can_eat(&self, food: &Food) -> bool {
+        food.satisfies(&self.diet)
+    }
+}
+
+/// What one member is served from a recipe
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Serving {
+    member: String,
+    child_variant: bool,
+    servings: Rational32,
+}
+
+impl Serving {
+    /// Returns the name of the member being served
+    pub fn get_member(&self) -> &str {
+        &self.member
+    }
+
+    /// Returns true if the member is served the recipe's child variant
+    pub fn is_child_variant(&self) -> bool {
+        self.child_variant
+    }
+
+    /// Returns how many of the recipe's servings the member eats
+    pub fn get_servings(&self) -> Rational32 {
+        self.servings
+    }
+}
+
+/// A pet the household feeds
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Pet {
+    name: String,
+    /// What sort of animal the pet is, such as "dog"
+    kind: String,
+    /// Kilocalories the pet should eat each day
+    daily_calories: Fraction,
+    /// Short codes of the foods the pet eats
+    foods: Vec<String>,
+    /// Times of day the pet is fed, in order
+    feeding_times: Vec<NaiveTime>,
+}
+
+impl Pet {
+    /// Creates a pet that should eat the given number of kilocalories a day, with no foods or
+    /// feeding times yet
+    pub fn new(name: &str, kind: &str, daily_calories: Rational32) -> Pet {
+        Pet {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            daily_calories: Fraction::from_rational(daily_calories),
+            foods: Vec::new(),
+            feeding_times: Vec::new(),
+        }
+    }
+
+    /// Returns the pet's name
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns what sort of animal the pet is
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the kilocalories the pet should eat each day
+    pub fn get_daily_calories(&self) -> Rational32 {
+        self.daily_calories.to_rational()
+    }
+
+    /// Sets the kilocalories the pet should eat each day
+    pub fn set_daily_calories(&mut self, calories: Rational32) -> &mut Self {
+        self.daily_calories = Fraction::from_rational(calories);
+        self
+    }
+
+    /// Returns the short codes of the foods the pet eats
+    pub fn get_foods(&self) -> &[String] {
+        &self.foods
+    }
+
+    /// Adds a food to those the pet eats, by short code, doing nothing if it's already there
+    pub fn add_food(&mut self, short_code: &str) -> &mut Self {
+        if !self.foods.iter().any(|x| x == short_code) {
+            self.foods.push(short_code.to_string());
+        }
+        self
+    }
+
+    /// Returns the times of day the pet is fed, in order
+    pub fn get_feeding_times(&self) -> &[NaiveTime] {
+        &self.feeding_times
+    }
+
+    /// Adds a time of day the pet is fed, doing nothing if it's already there
+    pub fn add_feeding_time(&mut self, time: NaiveTime) -> &mut Self {
+        if let Err(index) = self.feeding_times.binary_search(&time) {
+            self.feeding_times.insert(index, time);
+        }
+        self
+    }
+
+    /// Returns how many servings of a food the pet is given at each feeding
+    ///
+    /// The daily calories are split evenly between the feedings. Foods without a known
+    /// calorie count are given a serving at a time.
+    pub fn get_servings_per_feeding(&self, food: &Food) -> Rational32 {
+        let calories = food.get_nutrition().get_nutrient(Nutrient::Calories);
+        match calories {
+            Some(x) if x > Rational32::from_integer(0) && !self.feeding_times.is_empty() => {
+                self.get_daily_calories()
+                    / x
+                    / Rational32::from_integer(self.feeding_times.len() as i32)
+            }
+            _ => Rational32::from_integer(1),
+        }
+    }
+}
+
+/// The people a household cooks for, and the pets it feeds
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Household {
+    members: Vec<Member>,
+    #[serde(default)]
+    portions: PortionProfile,
+    #[serde(default)]
+    pets: Vec<Pet>,
+}
+
+impl Household {
+    /// Creates a household with no members and the default portions
+    pub fn new() -> Household {
+        Household::default()
+    }
+
+    /// Returns the members of the household, in the order they were added
+    pub fn get_members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// Adds a member to the household
+    pub fn add_member(&mut self, member: Member) -> &mut Self {
+        self.members.push(member);
+        self
+    }
+
+    /// Removes every member with the given name, returning true if there were any
+    pub fn remove_member(&mut self, name: &str) -> bool {
+        let before = self.members.len();
+        self.members.retain(|x| x.name != name);
+        before != self.members.len()
+    }
+
+    /// Returns how much each age group eats
+    pub fn get_portions(&self) -> &PortionProfile {
+        &self.portions
+    }
+
+    /// Returns a mutable refrence to how much each age group eats
+    pub fn get_mut_portions(&mut self) -> &mut PortionProfile {
+        &mut self.portions
+    }
+
+    /// Returns the pets of the household, in the order they were added
+    pub fn get_pets(&self) -> &[Pet] {
+        &self.pets
+    }
+
+    /// Returns a mutable refrence to the pet with the given name, if there is one
+    pub fn get_mut_pet(&mut self, name: &str) -> Option<&mut Pet> {
+        self.pets.iter_mut().find(|x| x.name == name)
+    }
+
+    /// Adds a pet to the household
+    pub fn add_pet(&mut self, pet: Pet) -> &mut Self {
+        self.pets.push(pet);
+        self
+    }
+
+    /// Removes every pet with the given name, returning true if there were any
+    pub fn remove_pet(&mut self, name: &str) -> bool {
+        let before = self.pets.len();
+        self.pets.retain(|x| x.name != name);
+        before != self.pets.len()
+    }
+
+    /// Schedules the pets' feedings on every date from start up to, but not including, end,
+    /// returning how many feedings were added
+    ///
+    /// Each pet's foods are looked up by short code among the given foods, and rotated through
+    /// a day at a time. Any feedings already planned for a pet on those dates are replaced,
+    /// and dates the plan doesn't cover are added to it. Pets without feeding times, or without
+    /// any of their foods among those given, are left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    /// use time_for_food::household::*;
+    ///
+    /// # fn main() {
+    /// let mut nutrition = Nutrition::new();
+    /// nutrition.set_calories(Amount::new(Unit::Kilocalorie, Rational32::from_integer(100)));
+    /// let kibble = RawFoodBuilder::new("kibble")
+    ///     .set_serving_size(Unit::Gram, Rational32::from_integer(30))
+    ///     .set_nutrition(nutrition)
+    ///     .build_raw_food()
+    ///     .unwrap();
+    ///
+    /// let mut rex = Pet::new("Rex", "dog", Rational32::from_integer(800));
+    /// rex.add_food("kibble")
+    ///     .add_feeding_time(NaiveTime::from_hms(7, 0, 0))
+    ///     .add_feeding_time(NaiveTime::from_hms(18, 0, 0));
+    /// let mut household = Household::new();
+    /// household.add_pet(rex);
+    ///
+    /// let mut plan = MealPlan::new();
+    /// let start = NaiveDate::from_ymd(2024, 1, 8);
+    /// let added = household.schedule_pets(
+    ///     &mut plan,
+    ///     &[Food::new_from_raw_food(kibble)],
+    ///     start,
+    ///     start.succ(),
+    /// );
+    /// assert_eq!(added, 2);
+    /// let feedings = plan.get_day(start).unwrap().get_feedings();
+    /// assert_eq!(feedings[0].get_servings(), Rational32::from_integer(4));
+    /// # }
+    /// ```
+    pub fn schedule_pets(
+        &self,
+        plan: &mut MealPlan,
+        foods: &[Food],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> usize {
+        let mut count = 0;
+        for pet in &self.pets {
+            let pool: Vec<&Food> = pet
+                .foods
+                .iter()
+                .filter_map(|x| foods.iter().find(|y| y.get_name().get_short_code() == x))
+                .collect();
+            if pool.is_empty() || pet.feeding_times.is_empty() {
+                continue;
+            }
+            let mut date = start;
+            let mut offset = 0;
+            while date < end {
+                if plan.get_day(date).is_none() {
+                    plan.add_day(Day::new(date));
+                }
+                let day = plan.get_mut_day(date).unwrap();
+                day.remove_feedings(&pet.name);
+                let food = pool[offset % pool.len()];
+                let servings = pet.get_servings_per_feeding(food);
+                for &time in &pet.feeding_times {
+                    day.add_feeding(Feeding::new(&pet.name, time, food.clone(), servings));
+                    count += 1;
+                }
+                date = date.succ_opt().unwrap_or(end);
+                offset += 1;
+            }
+        }
+        count
+    }
+
+    /// Returns true if every member can eat the food, so it can be served to the whole
+    /// household
+    pub fn can_all_eat(&self, food: &Food) -> bool {
+        self.members.iter().all(|x| x.can_eat(food))
+    }
+
+    /// Returns true if any member is young enough to be served child variants
+    pub fn has_young_children(&self) -> bool {
+        self.members
+            .iter()
+            .any(|x| x.get_age_group().needs_child_variant())
+    }
+
+    /// Returns how many adult servings the whole household eats at a meal
+    pub fn get_servings(&self) -> Rational32 {
+        self.members
+            .iter()
+            .fold(Rational32::from_integer(0), |total, x| {
+                total + self.portions.get_portion(x.get_age_group())
+            })
+    }
+
+    /// Returns what each member is served from a recipe, in the order the members were added
+    ///
+    /// Young children are served the recipe's child variant if it has one, and the adult
+    /// version otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::household::*;
+    ///
+    /// # fn main() {
+    /// let mut household = Household::new();
+    /// household
+    ///     .add_member(Member::adult("Sam"))
+    ///     .add_member(Member::aged("Robin", 9));
+    ///
+    /// let mut recipe = RecipeBuilder::new("lentil-soup").build_with_defaults();
+    /// recipe.set_child_variant(Some(ChildVariant::new(vec![Adaptation::Blended])));
+    ///
+    /// let servings = household.serve(&recipe);
+    /// assert!(!servings[0].is_child_variant());
+    /// assert!(servings[1].is_child_variant());
+    /// assert_eq!(servings[1].get_servings(), Rational32::new(1, 4));
+    /// assert_eq!(household.get_servings(), Rational32::new(5, 4));
+    /// # }
+    /// ```
+    pub fn serve(&self, recipe: &Recipe) -> Vec<Serving> {
+        let has_variant = recipe.get_child_variant().is_some();
+        self.members
+            .iter()
+            .map(|x| {
+                let group = x.get_age_group();
+                Serving {
+                    member: x.name.clone(),
+                    child_variant: has_variant && group.needs_child_variant(),
+                    servings: self.portions.get_portion(group),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,561 @@
+//! This module contains the iCalendar (.ics) importer, for planning cooking around a user's
+//! real calendar
+//!
+//! Only the busy time is brought in. Every VEVENT becomes a `BusyEvent`, and the free time
+//! left between them on a day is handed back as Blocks without food, ready to be given to the
+//! `Scheduler` as availability.
+//!
+//! Events marked transparent (free) or cancelled aren't busy, and are skipped. Times in UTC
+//! are moved into the household's timezone, while floating times and times with a TZID are
+//! taken as already being local, as the crate has no timezone database. Recurring events are
+//! only brought in for their first occurrence, and are flagged in the report.
+//!
+//! Most people keep more than one calendar. `BusyCalendars` holds the events from each source,
+//! such as work and family, by name, and merges the overlapping events of every enabled source
+//! into single `BusyRegion`s, so a source can be left out without importing it again.
+use super::{ImportIssue, ImportReport, RecordReport};
+use chrono::prelude::*;
+use chrono::Duration;
+use food::engine::Block;
+use num_rational::Rational64;
+use parse::parse_duration;
+
+/// A stretch of time taken up by a calendar event, in local time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusyEvent {
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    summary: Option<String>,
+}
+
+impl BusyEvent {
+    /// Creates a new BusyEvent
+    pub fn new(start: NaiveDateTime, end: NaiveDateTime, summary: Option<&str>) -> BusyEvent {
+        BusyEvent {
+            start,
+            end,
+            summary: summary.map(|x| x.to_string()),
+        }
+    }
+
+    /// Returns when the event starts
+    pub fn get_start(&self) -> NaiveDateTime {
+        self.start
+    }
+
+    /// Returns when the event ends
+    pub fn get_end(&self) -> NaiveDateTime {
+        self.end
+    }
+
+    /// Returns the summary of the event, if it has one
+    pub fn get_summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+}
+
+/// Joins folded content lines back together
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(&line[1..]);
+                continue;
+            }
+        }
+        lines.push(line.to_string());
+    }
+    lines
+}
+
+/// Reverses the escaping of an iCalendar TEXT value
+fn unescape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => out.push('\n'),
+                Some(x) => out.push(x),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parses a DATE or DATE-TIME value into local time, along with whether it was a DATE
+fn parse_time(params: &str, value: &str, timezone: FixedOffset) -> Option<(NaiveDateTime, bool)> {
+    if params.to_uppercase().contains("VALUE=DATE") || !value.contains('T') {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.and_hms_opt(0, 0, 0)?, true));
+    }
+    if let Some(utc) = value.strip_suffix(['Z', 'z']) {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local = time + Duration::seconds(i64::from(timezone.local_minus_utc()));
+        return Some((local, false));
+    }
+    let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Some((time, false))
+}
+
+/// The properties of a single VEVENT that matter for busy time
+#[derive(Default)]
+struct RawEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    start: Option<(String, String)>,
+    end: Option<(String, String)>,
+    duration: Option<String>,
+    recurrence: Option<String>,
+    free: bool,
+}
+
+impl RawEvent {
+    /// Converts the raw properties into an event, recording anything that couldn't be
+    /// understood
+    fn into_event(self, timezone: FixedOffset, report: &mut RecordReport) -> Option<BusyEvent> {
+        let invalid = |field: &str, value: &str| ImportIssue::InvalidField {
+            field: field.to_string(),
+            value: value.to_string(),
+        };
+        if let Some(ref rule) = self.recurrence {
+            report.add_issue(invalid("RRULE", rule));
+        }
+        let (params, value) = match self.start {
+            Some(x) => x,
+            None => {
+                report.add_issue(ImportIssue::MissingField("DTSTART".to_string()));
+                return None;
+            }
+        };
+        let (start, is_date) = match parse_time(&params, &value, timezone) {
+            Some(x) => x,
+            None => {
+                report.add_issue(invalid("DTSTART", &value));
+                return None;
+            }
+        };
+        let end = match (self.end, self.duration) {
+            (Some((params, value)), _) => match parse_time(&params, &value, timezone) {
+                Some((end, _)) => end,
+                None => {
+                    report.add_issue(invalid("DTEND", &value));
+                    return None;
+                }
+            },
+            (None, Some(duration)) => {
+                let end = parse_duration(&duration).ok().and_then(|minutes| {
+                    // Worked in 64 bits, as long durations overflow 32 once in seconds
+                    let numer = i64::from(*minutes.numer());
+                    let minutes = Rational64::new(numer, i64::from(*minutes.denom()));
+                    let seconds = (minutes * Rational64::from_integer(60)).round();
+                    start.checked_add_signed(Duration::seconds(seconds.to_integer()))
+                });
+                match end {
+                    Some(x) => x,
+                    None => {
+                        report.add_issue(invalid("DURATION", &duration));
+                        return None;
+                    }
+                }
+            }
+            // Events with neither last a day if they are all day, and no time at all otherwise
+            (None, None) if is_date => start + Duration::days(1),
+            (None, None) => start,
+        };
+        Some(BusyEvent {
+            start,
+            end,
+            summary: self.summary,
+        })
+    }
+}
+
+/// Reads the busy events out of an iCalendar file
+///
+/// Times are converted into the given timezone, which should be the household's, see
+/// `Settings::get_timezone`. Events that can't be read are left out, and recorded in the
+/// report under their UID, or their position in the file if they have none.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use time_for_food::import::ical::*;
+///
+/// # fn main() {
+/// let ics = "BEGIN:VCALENDAR\r\n\
+///            BEGIN:VEVENT\r\n\
+///            UID:standup\r\n\
+///            SUMMARY:Stand-up\\, daily\r\n\
+///            DTSTART:20240108T090000Z\r\n\
+///            DURATION:PT15M\r\n\
+///            END:VEVENT\r\n\
+///            BEGIN:VEVENT\r\n\
+///            UID:lunch\r\n\
+///            TRANSP:TRANSPARENT\r\n\
+///            DTSTART:20240108T120000\r\n\
+///            DTEND:20240108T130000\r\n\
+///            END:VEVENT\r\n\
+///            END:VCALENDAR\r\n";
+///
+/// let (events, report) = import_busy(ics, FixedOffset::east(3600));
+/// assert!(report.is_clean());
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].get_summary(), Some("Stand-up, daily"));
+/// assert_eq!(events[0].get_start(), NaiveDate::from_ymd(2024, 1, 8).and_hms(10, 0, 0));
+/// assert_eq!(events[0].get_end(), NaiveDate::from_ymd(2024, 1, 8).and_hms(10, 15, 0));
+/// # }
+/// ```
+pub fn import_busy(ics: &str, timezone: FixedOffset) -> (Vec<BusyEvent>, ImportReport) {
+    let mut events = Vec::new();
+    let mut report = ImportReport::new();
+    let mut current: Option<RawEvent> = None;
+    let mut position = 0;
+    for line in unfold(ics) {
+        let (name, value) = match line.find(':') {
+            Some(x) => (&line[..x], &line[x + 1..]),
+            None => continue,
+        };
+        let (name, params) = match name.find(';') {
+            Some(x) => (&name[..x], &name[x + 1..]),
+            None => (name, ""),
+        };
+        let name = name.to_uppercase();
+        if name == "BEGIN" && value.eq_ignore_ascii_case("VEVENT") {
+            current = Some(RawEvent::default());
+            position += 1;
+            continue;
+        }
+        if name == "END" && value.eq_ignore_ascii_case("VEVENT") {
+            if let Some(event) = current.take() {
+                if event.free {
+                    continue;
+                }
+                let record = event
+                    .uid
+                    .clone()
+                    .unwrap_or_else(|| format!("event {}", position));
+                let mut record = RecordReport::new(&record);
+                events.extend(event.into_event(timezone, &mut record));
+                report.add_record(record);
+            }
+            continue;
+        }
+        let event = match current {
+            Some(ref mut x) => x,
+            None => continue,
+        };
+        let pair = || (params.to_string(), value.to_string());
+        match &*name {
+            "UID" => event.uid = Some(value.to_string()),
+            "SUMMARY" => event.summary = Some(unescape_text(value)),
+            "DTSTART" => event.start = Some(pair()),
+            "DTEND" => event.end = Some(pair()),
+            "DURATION" => event.duration = Some(value.to_string()),
+            "RRULE" => event.recurrence = Some(value.to_string()),
+            "TRANSP" => event.free |= value.eq_ignore_ascii_case("TRANSPARENT"),
+            "STATUS" => event.free |= value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+    (events, report)
+}
+
+/// Works out the free time on a day, between two times, around the busy events
+///
+/// # Returns
+/// Blocks without food covering every stretch between `from` and `until` that no event
+/// overlaps, in order. Events running over midnight take up time on both days.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use time_for_food::import::ical::*;
+///
+/// # fn main() {
+/// let date = NaiveDate::from_ymd(2024, 1, 8);
+/// let at = |h, m| date.and_hms(h, m, 0);
+/// let events = [
+///     BusyEvent::new(at(9, 0), at(17, 0), Some("Work")),
+///     BusyEvent::new(at(18, 30), at(19, 0), Some("Gym")),
+/// ];
+///
+/// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+/// let free = free_blocks(&events, date, time(7, 0), time(22, 0));
+/// let spans: Vec<(NaiveTime, NaiveTime)> =
+///     free.iter().map(|x| (*x.get_start(), *x.get_end())).collect();
+/// assert_eq!(
+///     spans,
+///     vec![
+///         (time(7, 0), time(9, 0)),
+///         (time(17, 0), time(18, 30)),
+///         (time(19, 0), time(22, 0)),
+///     ]
+/// );
+/// # }
+/// ```
+pub fn free_blocks(
+    events: &[BusyEvent],
+    date: NaiveDate,
+    from: NaiveTime,
+    until: NaiveTime,
+) -> Vec<Block> {
+    let window_start = date.and_time(from);
+    let window_end = date.and_time(until);
+    let mut busy: Vec<(NaiveDateTime, NaiveDateTime)> = events
+        .iter()
+        .filter(|x| x.start < window_end && x.end > window_start)
+        .map(|x| (x.start.max(window_start), x.end.min(window_end)))
+        .collect();
+    busy.sort();
+    let mut blocks = Vec::new();
+    let mut cursor = window_start;
+    for (start, end) in busy {
+        if start > cursor {
+            blocks.push(Block::new(cursor.time(), start.time(), None));
+        }
+        cursor = cursor.max(end);
+    }
+    if cursor < window_end {
+        blocks.push(Block::new(cursor.time(), window_end.time(), None));
+    }
+    blocks
+}
+
+/// Works out the free time on every day from `start` to `end`, inclusive, see `free_blocks`
+pub fn free_blocks_between(
+    events: &[BusyEvent],
+    start: NaiveDate,
+    end: NaiveDate,
+    from: NaiveTime,
+    until: NaiveTime,
+) -> Vec<(NaiveDate, Vec<Block>)> {
+    let mut days = Vec::new();
+    let mut date = start;
+    while date <= end {
+        days.push((date, free_blocks(events, date, from, until)));
+        date = match date.succ_opt() {
+            Some(x) => x,
+            None => break,
+        };
+    }
+    days
+}
+
+/// A stretch of time blocked by one or more overlapping events, from one or more calendars
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusyRegion {
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    sources: Vec<String>,
+}
+
+impl BusyRegion {
+    /// Returns when the region starts
+    pub fn get_start(&self) -> NaiveDateTime {
+        self.start
+    }
+
+    /// Returns when the region ends
+    pub fn get_end(&self) -> NaiveDateTime {
+        self.end
+    }
+
+    /// Returns the names of the calendars with events in the region, in the order they were
+    /// added
+    pub fn get_sources(&self) -> &[String] {
+        &self.sources
+    }
+}
+
+/// The busy events of several calendars, kept apart by source so each can be left out
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use time_for_food::import::ical::*;
+///
+/// # fn main() {
+/// let date = NaiveDate::from_ymd(2024, 1, 8);
+/// let at = |h, m| date.and_hms(h, m, 0);
+/// let mut calendars = BusyCalendars::new();
+/// calendars
+///     .add_source("work", vec![BusyEvent::new(at(9, 0), at(17, 0), Some("Work"))])
+///     .add_source(
+///         "family",
+///         vec![
+///             BusyEvent::new(at(16, 30), at(18, 0), Some("School run")),
+///             BusyEvent::new(at(20, 0), at(21, 0), Some("Choir")),
+///         ],
+///     );
+///
+/// // Work and the school run overlap, so block out a single stretch
+/// let busy = calendars.busy_on(date);
+/// assert_eq!(busy.len(), 2);
+/// assert_eq!((busy[0].get_start(), busy[0].get_end()), (at(9, 0), at(18, 0)));
+/// assert_eq!(busy[0].get_sources(), ["work", "family"]);
+///
+/// // Leave the family calendar out
+/// calendars.set_enabled("family", false);
+/// let time = |h, m| NaiveTime::from_hms(h, m, 0);
+/// let free = calendars.free_blocks(date, time(7, 0), time(22, 0));
+/// assert_eq!(*free[1].get_start(), time(17, 0));
+/// assert_eq!(*free[1].get_end(), time(22, 0));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BusyCalendars {
+    sources: Vec<(String, bool, Vec<BusyEvent>)>,
+}
+
+impl BusyCalendars {
+    /// Creates an empty set of calendars
+    pub fn new() -> BusyCalendars {
+        BusyCalendars {
+            sources: Vec::new(),
+        }
+    }
+
+    /// Adds a calendar's events under a name, enabled, replacing any calendar already added
+    /// under it
+    pub fn add_source(&mut self, name: &str, events: Vec<BusyEvent>) -> &mut Self {
+        match self.sources.iter_mut().find(|x| x.0 == name) {
+            Some(source) => {
+                source.1 = true;
+                source.2 = events;
+            }
+            None => self.sources.push((name.to_string(), true, events)),
+        }
+        self
+    }
+
+    /// Imports a calendar's events from an iCalendar file under a name, see `import_busy`
+    pub fn import_source(&mut self, name: &str, ics: &str, timezone: FixedOffset) -> ImportReport {
+        let (events, report) = import_busy(ics, timezone);
+        self.add_source(name, events);
+        report
+    }
+
+    /// Removes a calendar, returning its events if it was present
+    pub fn remove_source(&mut self, name: &str) -> Option<Vec<BusyEvent>> {
+        let index = self.sources.iter().position(|x| x.0 == name)?;
+        Some(self.sources.remove(index).2)
+    }
+
+    /// Returns the names of the calendars, in the order they were added
+    pub fn get_sources(&self) -> Vec<&str> {
+        self.sources.iter().map(|x| &*x.0).collect()
+    }
+
+    /// Returns the events of a calendar, if it is present
+    pub fn get_events(&self, name: &str) -> Option<&[BusyEvent]> {
+        self.sources.iter().find(|x| x.0 == name).map(|x| &*x.2)
+    }
+
+    /// Returns true if the calendar is present and its events are counted as busy
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.sources.iter().any(|x| x.0 == name && x.1)
+    }
+
+    /// Sets whether a calendar's events are counted as busy, returning false if it isn't
+    /// present
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.sources.iter_mut().find(|x| x.0 == name) {
+            Some(source) => {
+                source.1 = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Merges the events of every enabled calendar into regions, in order
+    ///
+    /// Events that overlap or touch end to end are merged into a single region.
+    pub fn merged(&self) -> Vec<BusyRegion> {
+        let mut events: Vec<(NaiveDateTime, NaiveDateTime, &str)> = self
+            .sources
+            .iter()
+            .filter(|x| x.1)
+            .flat_map(|(name, _, events)| events.iter().map(move |x| (x.start, x.end, &**name)))
+            .filter(|x| x.0 < x.1)
+            .collect();
+        events.sort_by_key(|x| (x.0, x.1));
+        let mut regions: Vec<BusyRegion> = Vec::new();
+        for (start, end, name) in events {
+            match regions.last_mut() {
+                Some(last) if start <= last.end => last.end = last.end.max(end),
+                _ => regions.push(BusyRegion {
+                    start,
+                    end,
+                    sources: Vec::new(),
+                }),
+            }
+            let last = regions.last_mut().unwrap();
+            if !last.sources.iter().any(|x| x == name) {
+                last.sources.push(name.to_string());
+            }
+        }
+        // List each region's sources in the order the calendars were added
+        for region in &mut regions {
+            region
+                .sources
+                .sort_by_key(|x| self.sources.iter().position(|y| y.0 == *x));
+        }
+        regions
+    }
+
+    /// Returns the merged regions on a day, cut off at the start and end of the day
+    pub fn busy_on(&self, date: NaiveDate) -> Vec<BusyRegion> {
+        let day_start = date.and_time(NaiveTime::MIN);
+        let day_end = day_start + Duration::days(1);
+        self.merged()
+            .into_iter()
+            .filter(|x| x.start < day_end && x.end > day_start)
+            .map(|x| BusyRegion {
+                start: x.start.max(day_start),
+                end: x.end.min(day_end),
+                sources: x.sources,
+            })
+            .collect()
+    }
+
+    /// Works out the free time on a day around the enabled calendars, see `free_blocks`
+    pub fn free_blocks(&self, date: NaiveDate, from: NaiveTime, until: NaiveTime) -> Vec<Block> {
+        free_blocks(&self.busy_events(), date, from, until)
+    }
+
+    /// Works out the free time on every day from `start` to `end`, inclusive, around the
+    /// enabled calendars, see `free_blocks`
+    pub fn free_blocks_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        from: NaiveTime,
+        until: NaiveTime,
+    ) -> Vec<(NaiveDate, Vec<Block>)> {
+        free_blocks_between(&self.busy_events(), start, end, from, until)
+    }
+
+    /// Returns the merged regions as events, for working out free time
+    fn busy_events(&self) -> Vec<BusyEvent> {
+        self.merged()
+            .into_iter()
+            .map(|x| BusyEvent::new(x.start, x.end, None))
+            .collect()
+    }
+}
@@ -0,0 +1,182 @@
+//! This module contains the code shared by the importers
+//!
+//! Imported data is rarely clean. Rather than failing outright, importers bring in as much as
+//! they can and record what went wrong in an `ImportReport`, which applications can use to walk
+//! the user through fixing up the imported foods afterwards.
+pub mod ical;
+pub mod schema_org;
+use parse::ingredient::ParsedIngredient;
+
+/// A single problem found while importing a record
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ImportIssue {
+    /// An ingredient line couldn't be parsed at all, includes the line
+    UnparsedLine(String),
+    /// An ingredient line was parsed, but the parser wasn't confident in the result
+    LowConfidence { line: String, confidence: u8 },
+    /// An ingredient had no amount, includes the line
+    MissingAmount(String),
+    /// An ingredient had an amount, but no unit that could be understood, includes the line
+    MissingUnit(String),
+    /// An ingredient couldn't be matched to any known food, includes the ingredient name
+    UnmatchedIngredient(String),
+    /// A required field was absent from the record, includes the name of the field
+    MissingField(String),
+    /// A field was present, but couldn't be understood, includes the field name and value
+    InvalidField { field: String, value: String },
+}
+
+impl ImportIssue {
+    /// Works out what, if anything, is wrong with a parsed ingredient line
+    pub fn from_parsed(parsed: &ParsedIngredient) -> Option<ImportIssue> {
+        match *parsed {
+            ParsedIngredient::Unparsed(ref line) => Some(ImportIssue::UnparsedLine(line.clone())),
+            ParsedIngredient::Parsed(ref line) => {
+                if line.needs_review() {
+                    Some(ImportIssue::LowConfidence {
+                        line: line.get_original().to_string(),
+                        confidence: line.get_confidence(),
+                    })
+                } else if line.get_amount().is_none() {
+                    Some(ImportIssue::MissingAmount(line.get_original().to_string()))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// The issues found while importing a single record
+///
+/// Records are identified by whatever the source format offers, a title, a URL, a line number,
+/// and so on.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordReport {
+    record: String,
+    issues: Vec<ImportIssue>,
+}
+
+impl RecordReport {
+    /// Creates a new, issue free, report for a record
+    pub fn new(record: &str) -> RecordReport {
+        RecordReport {
+            record: record.to_string(),
+            issues: Vec::new(),
+        }
+    }
+
+    /// Returns the identifier of the record
+    pub fn get_record(&self) -> &str {
+        &self.record
+    }
+
+    /// Adds an issue to the record
+    pub fn add_issue(&mut self, issue: ImportIssue) {
+        self.issues.push(issue);
+    }
+
+    /// Returns the unresolved issues for the record
+    pub fn get_issues(&self) -> &[ImportIssue] {
+        &self.issues
+    }
+
+    /// Marks the issue at the given index as resolved, removing it from the record
+    ///
+    /// # Returns
+    /// The resolved issue, or None if there was no issue at that index
+    pub fn resolve_issue(&mut self, index: usize) -> Option<ImportIssue> {
+        if index < self.issues.len() {
+            Some(self.issues.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the record has unresolved issues
+    pub fn needs_review(&self) -> bool {
+        !self.issues.is_empty()
+    }
+}
+
+/// The result of an import, with a report for every record that was imported
+///
+/// # Examples
+///
+/// ```
+/// extern crate time_for_food;
+/// use time_for_food::import::*;
+///
+/// # fn main() {
+/// let mut report = ImportReport::new();
+/// report.add_record(RecordReport::new("toast"));
+/// assert!(report.is_clean());
+///
+/// let mut soup = RecordReport::new("soup");
+/// soup.add_issue(ImportIssue::MissingField("name".to_string()));
+/// soup.add_issue(ImportIssue::UnmatchedIngredient("lovage".to_string()));
+/// report.add_record(soup);
+/// assert!(!report.is_clean());
+/// assert_eq!(report.issue_count(), 2);
+/// assert_eq!(report.pending_review()[0].get_record(), "soup");
+///
+/// // Resolving every issue leaves the report clean again
+/// let soup = report.get_mut_record("soup").unwrap();
+/// soup.resolve_issue(1);
+/// assert_eq!(
+///     soup.resolve_issue(0),
+///     Some(ImportIssue::MissingField("name".to_string()))
+/// );
+/// assert!(report.is_clean());
+/// assert_eq!(report.get_records().len(), 2);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportReport {
+    records: Vec<RecordReport>,
+}
+
+impl ImportReport {
+    /// Creates a new, empty, ImportReport
+    pub fn new() -> ImportReport {
+        ImportReport {
+            records: Vec::new(),
+        }
+    }
+
+    /// Adds the report for a record
+    pub fn add_record(&mut self, record: RecordReport) {
+        #[cfg(feature = "tracing")]
+        for issue in &record.issues {
+            ::tracing::warn!(record = %record.record, ?issue, "import issue");
+        }
+        self.records.push(record);
+    }
+
+    /// Returns the reports for all the records, including the clean ones
+    pub fn get_records(&self) -> &[RecordReport] {
+        &self.records
+    }
+
+    /// Returns a mutable refrence to the report for a record, if it exists
+    ///
+    /// Use this to resolve issues as the user works through them.
+    pub fn get_mut_record(&mut self, record: &str) -> Option<&mut RecordReport> {
+        self.records.iter_mut().find(|x| x.record == record)
+    }
+
+    /// Returns the records that still have unresolved issues
+    pub fn pending_review(&self) -> Vec<&RecordReport> {
+        self.records.iter().filter(|x| x.needs_review()).collect()
+    }
+
+    /// Returns the total number of unresolved issues across all records
+    pub fn issue_count(&self) -> usize {
+        self.records.iter().map(|x| x.issues.len()).sum()
+    }
+
+    /// Returns true if no record has any unresolved issues
+    pub fn is_clean(&self) -> bool {
+        self.issue_count() == 0
+    }
+}
@@ -0,0 +1,477 @@
+//! This module contains the importer for schema.org Recipe JSON-LD
+//!
+//! Most recipe web pages embed their recipe as a `<script type="application/ld+json">` block
+//! following the schema.org Recipe vocabulary, which makes it the easiest way to bring in a
+//! collection of recipes from around the web. The importer reads the name, ingredients,
+//! instructions, times, yield, keywords, and per serving nutrition, and reports anything else
+//! that looks off.
+//!
+//! schema.org only gives ingredients as free text lines, so turning a line into a food and an
+//! amount is left to an `IngredientParser`. `LineParser` is a reasonable default, and closures
+//! can be used for anything more involved.
+use food::*;
+use import::{ImportIssue, ImportReport, RecordReport};
+use num_rational::*;
+use parse::ingredient::{parse_ingredient, ParsedIngredient};
+use parse::{parse_duration, parse_number, to_short_code};
+use serde_json;
+use serde_json::Value;
+use store::{FoodId, FoodStore};
+
+/// Turns ingredient lines into the food they name and how much of it
+pub trait IngredientParser {
+    /// Parses a single ingredient line, adding anything worth a second look to the report
+    ///
+    /// Returning None leaves the line out of the recipe, and should always come with an issue
+    /// saying why.
+    fn parse_line(&mut self, line: &str, report: &mut RecordReport) -> Option<(Food, Amount)>;
+}
+
+impl<F> IngredientParser for F
+where
+    F: FnMut(&str, &mut RecordReport) -> Option<(Food, Amount)>,
+{
+    fn parse_line(&mut self, line: &str, report: &mut RecordReport) -> Option<(Food, Amount)> {
+        self(line, report)
+    }
+}
+
+/// The default ingredient parser, splitting lines with `parse_ingredient`
+///
+/// Ingredients are looked up in the store, if one is given, by the short code of their name,
+/// singular or plural. Anything else gets a placeholder raw food, named after the ingredient,
+/// with a serving of one of its unit and empty nutrition, ready to be filled in later.
+///
+/// Amounts in units the crate doesn't measure, such as cloves or pinches, are kept as a count
+/// and flagged for review.
+#[derive(Clone, Copy, Default)]
+pub struct LineParser<'a> {
+    store: Option<&'a FoodStore>,
+}
+
+impl<'a> LineParser<'a> {
+    /// Creates a new parser, creating a placeholder food for every ingredient
+    pub fn new() -> LineParser<'a> {
+        LineParser { store: None }
+    }
+
+    /// Creates a new parser, looking ingredients up in the store first
+    pub fn with_store(store: &'a FoodStore) -> LineParser<'a> {
+        LineParser { store: Some(store) }
+    }
+
+    /// Looks an ingredient up in the store by its short code, or by the short code with a
+    /// plural ending dropped
+    fn find(&self, short_code: &str) -> Option<Food> {
+        let store = self.store?;
+        let singular = [
+            Some(short_code),
+            short_code.strip_suffix("es"),
+            short_code.strip_suffix('s'),
+        ];
+        singular
+            .iter()
+            .filter_map(|x| *x)
+            .filter_map(|x| store.get(&FoodId::new(x)))
+            .next()
+            .cloned()
+    }
+}
+
+impl<'a> IngredientParser for LineParser<'a> {
+    fn parse_line(&mut self, line: &str, report: &mut RecordReport) -> Option<(Food, Amount)> {
+        let parsed = parse_ingredient(line);
+        if let Some(issue) = ImportIssue::from_parsed(&parsed) {
+            report.add_issue(issue);
+        }
+        let parsed = match parsed {
+            ParsedIngredient::Parsed(x) => x,
+            ParsedIngredient::Unparsed(_) => return None,
+        };
+        let unit = match parsed.get_unit() {
+            Some(symbol) => Unit::from_symbol(symbol).unwrap_or_else(|| {
+                report.add_issue(ImportIssue::MissingUnit(line.to_string()));
+                Unit::Count
+            }),
+            None => Unit::Count,
+        };
+        let amount = Amount::new(
+            unit,
+            parsed
+                .get_amount()
+                .unwrap_or_else(|| Rational32::from_integer(1)),
+        );
+        let short_code = to_short_code(parsed.get_name());
+        let food = match self.find(&short_code) {
+            Some(food) => food,
+            None => {
+                if self.store.is_some() {
+                    report.add_issue(ImportIssue::UnmatchedIngredient(
+                        parsed.get_name().to_string(),
+                    ));
+                }
+                let mut name = IString::new(&short_code);
+                name.set_value_for("en", parsed.get_name());
+                let serving = Amount::new(unit, Rational32::from_integer(1));
+                Food::new_from_raw_food(RawFood::new(name, serving, Nutrition::new()))
+            }
+        };
+        Some((food, amount))
+    }
+}
+
+/// Returns true if the JSON-LD node has the given type
+fn has_type(node: &Value, kind: &str) -> bool {
+    match node.get("@type") {
+        Some(Value::String(x)) => x == kind,
+        Some(Value::Array(x)) => x.iter().any(|y| y.as_str() == Some(kind)),
+        _ => false,
+    }
+}
+
+/// Collects every Recipe node in a JSON-LD document, looking through top level arrays and
+/// `@graph`s
+fn find_recipes<'v>(value: &'v Value, found: &mut Vec<&'v Value>) {
+    match *value {
+        Value::Array(ref items) => {
+            for item in items {
+                find_recipes(item, found);
+            }
+        }
+        Value::Object(ref object) => {
+            if has_type(value, "Recipe") {
+                found.push(value);
+            } else if let Some(graph) = object.get("@graph") {
+                find_recipes(graph, found);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Returns the strings in a field that may be given as a single string or a list of them
+fn strings(value: Option<&Value>) -> Vec<&str> {
+    match value {
+        Some(Value::String(x)) => vec![x.as_str()],
+        Some(Value::Array(x)) => x.iter().filter_map(|y| y.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Flattens recipeInstructions, which may be a single block of text, a list of strings, a
+/// list of HowToSteps, or a list of HowToSections holding HowToSteps
+fn instructions(value: &Value, found: &mut Vec<String>) {
+    match *value {
+        Value::String(ref text) => found.extend(
+            text.lines()
+                .map(|x| x.trim())
+                .filter(|x| !x.is_empty())
+                .map(|x| x.to_string()),
+        ),
+        Value::Array(ref items) => {
+            for item in items {
+                instructions(item, found);
+            }
+        }
+        Value::Object(ref object) => {
+            if let Some(steps) = object.get("itemListElement") {
+                instructions(steps, found);
+            } else if let Some(text) = object.get("text").or_else(|| object.get("name")) {
+                instructions(text, found);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Reads the leading number out of text such as "4 servings" or "240 kcal"
+fn leading_number(value: &Value) -> Option<Rational32> {
+    match *value {
+        Value::Number(ref x) => {
+            let x = x.as_f64()?;
+            Rational32::approximate_float(x)
+        }
+        Value::String(ref x) => x.split_whitespace().next().and_then(|y| {
+            let y = y.trim_end_matches(|c: char| c.is_alphabetic());
+            parse_number(y).ok()
+        }),
+        Value::Array(ref x) => x.iter().filter_map(leading_number).next(),
+        _ => None,
+    }
+}
+
+/// Reads schema.org NutritionInformation, assumed to be per serving
+fn nutrition(value: &Value, report: &mut RecordReport) -> Nutrition {
+    let mut nutrition = Nutrition::new();
+    let fields: [(&str, Nutrient); 7] = [
+        ("calories", Nutrient::Calories),
+        ("proteinContent", Nutrient::Protein),
+        ("fatContent", Nutrient::Fat),
+        ("carbohydrateContent", Nutrient::Carbohydrates),
+        ("fiberContent", Nutrient::Fiber),
+        ("sugarContent", Nutrient::Sugar),
+        ("sodiumContent", Nutrient::Sodium),
+    ];
+    for &(field, nutrient) in &fields {
+        let raw = match value.get(field) {
+            Some(x) => x,
+            None => continue,
+        };
+        let amount = match leading_number(raw) {
+            Some(x) => Amount::new(nutrient.get_unit(), x),
+            None => {
+                report.add_issue(ImportIssue::InvalidField {
+                    field: field.to_string(),
+                    value: raw.to_string(),
+                });
+                continue;
+            }
+        };
+        match nutrient {
+            Nutrient::Calories => nutrition.set_calories(amount),
+            Nutrient::Protein => nutrition.set_protein(amount),
+            Nutrient::Fat => nutrition.set_fat(amount),
+            Nutrient::Carbohydrates => nutrition.set_carbohydrates(amount),
+            Nutrient::Fiber => nutrition.set_fiber(amount),
+            Nutrient::Sugar => nutrition.set_sugar(amount),
+            Nutrient::Sodium => nutrition.set_sodium(amount),
+        };
+    }
+    nutrition
+}
+
+/// Imports a single schema.org Recipe node
+///
+/// The record is identified by the recipe's name, or its url if it has no name. Recipes
+/// without a name can't be given a short code, so aren't imported at all.
+pub fn import_recipe<P: IngredientParser>(
+    node: &Value,
+    parser: &mut P,
+) -> (Option<Recipe>, RecordReport) {
+    let name = node.get("name").and_then(|x| x.as_str()).map(|x| x.trim());
+    let record = name
+        .or_else(|| node.get("url").and_then(|x| x.as_str()))
+        .unwrap_or("Recipe");
+    let mut report = RecordReport::new(record);
+    let name = match name {
+        Some(x) if !x.is_empty() => x,
+        _ => {
+            report.add_issue(ImportIssue::MissingField("name".to_string()));
+            return (None, report);
+        }
+    };
+    let lang = node
+        .get("inLanguage")
+        .and_then(|x| x.as_str())
+        .unwrap_or("en");
+
+    let mut builder = RecipeBuilder::new(&to_short_code(name));
+    builder.add_name(lang, name);
+
+    let lines = strings(
+        node.get("recipeIngredient")
+            .or_else(|| node.get("ingredients")),
+    );
+    if lines.is_empty() {
+        report.add_issue(ImportIssue::MissingField("recipeIngredient".to_string()));
+    }
+    for line in lines {
+        if let Some((food, amount)) = parser.parse_line(line, &mut report) {
+            builder.add_food(food, amount.get_unit(), amount.get_amount());
+        }
+    }
+
+    let mut texts = Vec::new();
+    if let Some(x) = node.get("recipeInstructions") {
+        instructions(x, &mut texts);
+    }
+    if texts.is_empty() {
+        report.add_issue(ImportIssue::MissingField("recipeInstructions".to_string()));
+    }
+    for (index, text) in texts.iter().enumerate() {
+        let mut step = Step::new(&format!("step-{}", index + 1), Rational32::from_integer(0));
+        step.get_mut_text().set_value_for(lang, text);
+        builder.add_step(step);
+    }
+
+    let mut duration = |field: &str| -> Option<Rational32> {
+        let raw = node.get(field)?;
+        match raw.as_str().map(parse_duration) {
+            Some(Ok(x)) => Some(x),
+            _ => {
+                report.add_issue(ImportIssue::InvalidField {
+                    field: field.to_string(),
+                    value: raw.to_string(),
+                });
+                None
+            }
+        }
+    };
+    if let Some(x) = duration("prepTime") {
+        builder.set_prep_time(x);
+    }
+    if let Some(x) = duration("cookTime") {
+        builder.set_cook_time(x);
+    }
+    if let Some(x) = duration("totalTime") {
+        builder.set_time(x);
+    }
+
+    if let Some(raw) = node.get("recipeYield") {
+        match leading_number(raw) {
+            Some(x) if x > Rational32::from_integer(0) => {
+                builder.set_servings(x);
+            }
+            _ => report.add_issue(ImportIssue::InvalidField {
+                field: "recipeYield".to_string(),
+                value: raw.to_string(),
+            }),
+        }
+    }
+
+    if let Some(raw) = node.get("nutrition") {
+        builder.set_nutrition(nutrition(raw, &mut report));
+    }
+
+    let mut tags = Vec::new();
+    for field in &["keywords", "recipeCategory", "recipeCuisine"] {
+        for text in strings(node.get(*field)) {
+            tags.extend(text.split(',').map(to_short_code));
+        }
+    }
+    for tag in tags.iter().filter(|x| !x.is_empty()) {
+        builder.add_tag(tag);
+    }
+
+    (Some(builder.build_with_defaults()), report)
+}
+
+/// Imports every Recipe in a JSON-LD document
+///
+/// Fails only if the document isn't JSON at all, problems with individual recipes are left
+/// in the report.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+/// use time_for_food::import::schema_org::*;
+/// use time_for_food::import::ImportIssue;
+///
+/// # fn main() {
+/// let json = r#"{
+///     "@context": "https://schema.org",
+///     "@type": "Recipe",
+///     "name": "Pancakes",
+///     "recipeYield": "4 servings",
+///     "prepTime": "PT10M",
+///     "cookTime": "PT15M",
+///     "recipeIngredient": ["2 cups flour", "1 1/2 cups milk", "2 eggs"],
+///     "recipeInstructions": [
+///         {"@type": "HowToStep", "text": "Whisk everything together."},
+///         {"@type": "HowToStep", "text": "Fry in a hot pan."}
+///     ],
+///     "keywords": "breakfast, sweet"
+/// }"#;
+///
+/// let (recipes, report) = import_json_ld(json, &mut LineParser::new()).unwrap();
+/// let pancakes = &recipes[0];
+/// assert_eq!(pancakes.get_name().get_short_code(), "pancakes");
+/// assert_eq!(pancakes.get_servings(), Rational32::from_integer(4));
+/// assert_eq!(pancakes.get_time(), Rational32::from_integer(25));
+/// assert_eq!(pancakes.get_foods().len(), 3);
+/// assert_eq!(pancakes.get_steps().len(), 2);
+/// assert!(pancakes.has_tag("breakfast"));
+/// assert!(report.is_clean());
+///
+/// // Values too large to store are left out, and flagged for review
+/// let json = r#"{
+///     "@type": "Recipe",
+///     "name": "Stock",
+///     "totalTime": "PT99999999H",
+///     "nutrition": {"calories": "99999999999 kcal"}
+/// }"#;
+/// let (recipes, report) = import_json_ld(json, &mut LineParser::new()).unwrap();
+/// assert_eq!(recipes.len(), 1);
+/// assert!(recipes[0].get_nutrition().get_calories().is_none());
+/// let fields: Vec<_> = report.get_records()[0]
+///     .get_issues()
+///     .iter()
+///     .filter_map(|x| match *x {
+///         ImportIssue::InvalidField { ref field, .. } => Some(field.as_str()),
+///         _ => None,
+///     })
+///     .collect();
+/// assert_eq!(fields, ["totalTime", "calories"]);
+/// # }
+/// ```
+pub fn import_json_ld<P: IngredientParser>(
+    json: &str,
+    parser: &mut P,
+) -> Result<(Vec<Recipe>, ImportReport), serde_json::Error> {
+    let value: Value = serde_json::from_str(json)?;
+    let mut nodes = Vec::new();
+    find_recipes(&value, &mut nodes);
+    let mut recipes = Vec::new();
+    let mut report = ImportReport::new();
+    for node in nodes {
+        let (recipe, record) = import_recipe(node, parser);
+        recipes.extend(recipe);
+        report.add_record(record);
+    }
+    Ok((recipes, report))
+}
+
+/// Returns the contents of every `<script type="application/ld+json">` block in a page
+pub fn extract_json_ld(html: &str) -> Vec<&str> {
+    // Lowercasing ascii keeps every byte where it was, so offsets carry over to the original
+    let lower = html.to_ascii_lowercase();
+    let mut blocks = Vec::new();
+    let mut from = 0;
+    while let Some(open) = lower[from..].find("<script").map(|x| x + from) {
+        let tag_end = match lower[open..].find('>') {
+            Some(x) => open + x + 1,
+            None => break,
+        };
+        let close = match lower[tag_end..].find("</script") {
+            Some(x) => tag_end + x,
+            None => break,
+        };
+        if lower[open..tag_end].contains("application/ld+json") {
+            blocks.push(html[tag_end..close].trim());
+        }
+        from = close;
+    }
+    blocks
+}
+
+/// Imports every Recipe embedded in a web page
+///
+/// Blocks that aren't valid JSON are reported as a record of their own, named after their
+/// position on the page, rather than stopping the import.
+pub fn import_html<P: IngredientParser>(html: &str, parser: &mut P) -> (Vec<Recipe>, ImportReport) {
+    let mut recipes = Vec::new();
+    let mut report = ImportReport::new();
+    for (index, block) in extract_json_ld(html).into_iter().enumerate() {
+        match import_json_ld(block, parser) {
+            Ok((found, found_report)) => {
+                recipes.extend(found);
+                for record in found_report.get_records() {
+                    report.add_record(record.clone());
+                }
+            }
+            Err(e) => {
+                let mut record = RecordReport::new(&format!("JSON-LD block {}", index + 1));
+                record.add_issue(ImportIssue::InvalidField {
+                    field: "application/ld+json".to_string(),
+                    value: e.to_string(),
+                });
+                report.add_record(record);
+            }
+        }
+    }
+    (recipes, report)
+}
@@ -0,0 +1,299 @@
+//! This module contains the referential integrity checker
+//!
+//! Pantries, plans, and the store's own recipes and variants refer to foods by short code.
+//! After an import, or after foods are removed, some of those references may no longer match
+//! anything in the store. The checker finds these dangling references, and each one can be
+//! repaired by remapping it to another food, removing it, or adding a stub food to the store
+//! for it to refer to.
+//!
+//! Component recipes are embedded within the recipes using them, so only a recipe's own
+//! ingredients are checked, not those of its components.
+use chrono::prelude::*;
+use food::engine::MealPlan;
+use food::*;
+use num_rational::*;
+use pantry::Pantry;
+use store::{FoodId, FoodStore};
+
+/// Where a dangling reference was found
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Location {
+    /// An item on hand in the pantry
+    Pantry,
+    /// The food planned in the block starting at the given time on the given date
+    Plan { date: NaiveDate, start: NaiveTime },
+    /// The ingredient at the given index of a recipe in the store
+    Ingredient { recipe: FoodId, index: usize },
+    /// The generic food a branded variant in the store refers to
+    Generic { variant: FoodId },
+}
+
+/// A reference to a food the store doesn't have
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dangling {
+    id: FoodId,
+    location: Location,
+}
+
+impl Dangling {
+    /// Returns the id being referred to
+    pub fn get_id(&self) -> &FoodId {
+        &self.id
+    }
+
+    /// Returns where the reference is
+    pub fn get_location(&self) -> &Location {
+        &self.location
+    }
+}
+
+/// How to repair a dangling reference
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Repair {
+    /// Refer to the food with the given id instead, which the store must have
+    Remap(FoodId),
+    /// Remove the reference, along with what it is attached to (the pantry item, the planned
+    /// food, or the ingredient)
+    Remove,
+    /// Add a draft raw food with the referenced id to the store, to be filled in later
+    Stub,
+}
+
+/// Finds the dangling references among the store's own recipes and variants
+pub fn check_store(store: &FoodStore) -> Vec<Dangling> {
+    let mut dangling = Vec::new();
+    for id in store.ids() {
+        match store.get(id) {
+            Some(Food::Recipe(ref recipe)) => {
+                for (index, (food, _)) in recipe.get_foods().iter().enumerate() {
+                    let referenced = FoodId::of(food);
+                    if !store.contains(&referenced) {
+                        dangling.push(Dangling {
+                            id: referenced,
+                            location: Location::Ingredient {
+                                recipe: id.clone(),
+                                index,
+                            },
+                        });
+                    }
+                }
+            }
+            Some(Food::RawFood(ref raw)) => {
+                if let Some(generic) = raw.get_generic() {
+                    let referenced = FoodId::new(generic);
+                    if !store.contains(&referenced) {
+                        dangling.push(Dangling {
+                            id: referenced,
+                            location: Location::Generic {
+                                variant: id.clone(),
+                            },
+                        });
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    dangling
+}
+
+/// Finds the planned foods the store doesn't have
+///
+/// # Examples
+///
+/// ```
+/// extern crate chrono;
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use chrono::prelude::*;
+/// use num_rational::Rational32;
+/// use time_for_food::food::engine::*;
+/// use time_for_food::food::*;
+/// use time_for_food::integrity::*;
+/// use time_for_food::store::*;
+///
+/// # fn main() {
+/// let soup = RecipeBuilder::new("soup")
+///     .add_step(Step::new("simmer", Rational32::from_integer(30)))
+///     .build_with_defaults();
+/// let date = NaiveDate::from_ymd(2024, 1, 8);
+/// let mut plan = MealPlan::new();
+/// plan.insert_block(
+///     date,
+///     Block::new(
+///         NaiveTime::from_hms(18, 0, 0),
+///         NaiveTime::from_hms(18, 30, 0),
+///         Some(Food::new_from_recipe(soup)),
+///     ),
+/// );
+///
+/// let mut store = FoodStore::new();
+/// let dangling = check_plan(&store, &plan);
+/// assert_eq!(dangling[0].get_id(), &FoodId::new("soup"));
+///
+/// repair_plan(&mut store, &mut plan, &dangling[0], &Repair::Stub);
+/// assert!(check_plan(&store, &plan).is_empty());
+/// # }
+/// ```
+pub fn check_plan(store: &FoodStore, plan: &MealPlan) -> Vec<Dangling> {
+    let mut dangling = Vec::new();
+    for day in plan.get_days() {
+        for block in day.get_blocks() {
+            if let Some(food) = block.get_food() {
+                let referenced = FoodId::of(food);
+                if !store.contains(&referenced) {
+                    dangling.push(Dangling {
+                        id: referenced,
+                        location: Location::Plan {
+                            date: day.get_date(),
+                            start: *block.get_start(),
+                        },
+                    });
+                }
+            }
+        }
+    }
+    dangling
+}
+
+/// Finds the pantry items the store doesn't have
+pub fn check_pantry(store: &FoodStore, pantry: &Pantry) -> Vec<Dangling> {
+    pantry
+        .ids()
+        .into_iter()
+        .filter(|x| !store.contains(x))
+        .map(|x| Dangling {
+            id: x.clone(),
+            location: Location::Pantry,
+        })
+        .collect()
+}
+
+/// Adds a stub for the food to the store, unless it already has one
+fn add_stub(store: &mut FoodStore, id: &FoodId) {
+    if !store.contains(id) {
+        store.insert(Food::new_from_raw_food(RawFood::new(
+            IString::new(id.get_short_code()),
+            Amount::new(Unit::Count, Rational32::from_integer(1)),
+            Nutrition::new(),
+        )));
+    }
+}
+
+/// Repairs a dangling reference found by `check_store`
+///
+/// # Returns
+/// False, without changing anything, if the reference isn't in the store, or is remapped to
+/// a food the store doesn't have
+pub fn repair_store(store: &mut FoodStore, dangling: &Dangling, repair: &Repair) -> bool {
+    let replacement = match *repair {
+        Repair::Remap(ref id) => match store.get(id) {
+            Some(food) => Some(food.clone()),
+            None => return false,
+        },
+        _ => None,
+    };
+    if *repair == Repair::Stub {
+        add_stub(store, &dangling.id);
+        return true;
+    }
+    match dangling.location {
+        Location::Ingredient { ref recipe, index } => match store.get_mut(recipe) {
+            Some(Food::Recipe(ref mut recipe)) => match replacement {
+                Some(food) => recipe.replace_ingredient(index, food).is_some(),
+                None => recipe.remove_ingredient(index).is_some(),
+            },
+            _ => false,
+        },
+        Location::Generic { ref variant } => match store.get_mut(variant) {
+            Some(Food::RawFood(ref mut raw)) => {
+                raw.set_generic(replacement.as_ref().map(|x| x.get_name().get_short_code()));
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Repairs a dangling reference found by `check_plan`
+///
+/// Removing a planned food leaves its block in place, as free time.
+///
+/// # Returns
+/// False, without changing anything, if the reference isn't in the plan, or is remapped to a
+/// food the store doesn't have
+pub fn repair_plan(
+    store: &mut FoodStore,
+    plan: &mut MealPlan,
+    dangling: &Dangling,
+    repair: &Repair,
+) -> bool {
+    let (date, start) = match dangling.location {
+        Location::Plan { date, start } => (date, start),
+        _ => return false,
+    };
+    let replacement = match *repair {
+        Repair::Remap(ref id) => match store.get(id) {
+            Some(food) => Some(food.clone()),
+            None => return false,
+        },
+        _ => None,
+    };
+    let block = match plan.get_mut_day(date).and_then(|x| x.get_mut_block(start)) {
+        Some(block) => block,
+        None => return false,
+    };
+    match *repair {
+        Repair::Remap(_) => {
+            if let Some(food) = replacement {
+                block.add_food(food);
+            }
+        }
+        Repair::Remove => {
+            block.remove_food();
+        }
+        Repair::Stub => add_stub(store, &dangling.id),
+    }
+    true
+}
+
+/// Repairs a dangling reference found by `check_pantry`
+///
+/// Remapping moves the amount on hand to the other food, adding to anything already there,
+/// converted with the household's unit sizes.
+///
+/// # Returns
+/// False, without changing anything, if the reference isn't in the pantry, or is remapped to
+/// a food the store doesn't have, or to one on hand in a unit the amount can't be converted to
+pub fn repair_pantry(
+    store: &mut FoodStore,
+    pantry: &mut Pantry,
+    dangling: &Dangling,
+    repair: &Repair,
+) -> bool {
+    if dangling.location != Location::Pantry || pantry.get(&dangling.id).is_none() {
+        return false;
+    }
+    match *repair {
+        Repair::Remap(ref id) => {
+            if !store.contains(id) {
+                return false;
+            }
+            if let Some(amount) = pantry.remove(&dangling.id) {
+                if pantry
+                    .deposit(id.clone(), amount, store.get_settings())
+                    .is_err()
+                {
+                    pantry.set(dangling.id.clone(), amount);
+                    return false;
+                }
+            }
+        }
+        Repair::Remove => {
+            pantry.remove(&dangling.id);
+        }
+        Repair::Stub => add_stub(store, &dangling.id),
+    }
+    true
+}
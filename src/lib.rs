@@ -3,8 +3,35 @@ extern crate serde;
 extern crate serde_json;
 #[macro_use] extern crate serde_derive;
 extern crate chrono;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+pub mod export;
 pub mod food;
+pub mod format;
+pub mod household;
+pub mod import;
+pub mod integrity;
+pub mod log;
+pub mod pantry;
+pub mod parse;
+pub mod progress;
+pub mod report;
+pub mod schema;
+pub mod settings;
+pub mod shopping;
+pub mod storage;
+pub mod store;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod units;
+pub mod warning;
 
 
 
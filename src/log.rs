@@ -0,0 +1,423 @@
+//! This module contains the food log, a record of what was actually eaten
+//!
+//! Where the engine deals in plans, the log deals in what really happened. Logging an entry
+//! never fails, but the log checks each entry against the household's constraints and hands
+//! back warnings for anything that breaks them.
+use chrono::prelude::*;
+use food::constraint::Exclusion;
+use food::engine::{EatingWindow, MealKind, MealWindows};
+use food::*;
+use num_rational::*;
+use settings::Settings;
+
+/// A single logged meal or snack
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogEntry {
+    date: NaiveDate,
+    time: NaiveTime,
+    food: Food,
+    servings: Fraction,
+    /// Which meal the entry was, if known
+    #[serde(default)]
+    kind: Option<MealKind>,
+}
+
+impl LogEntry {
+    /// Constructs a new LogEntry from its components
+    pub fn new(date: NaiveDate, time: NaiveTime, food: Food, servings: Rational32) -> LogEntry {
+        LogEntry {
+            date,
+            time,
+            food,
+            servings: Fraction::from_rational(servings),
+            kind: None,
+        }
+    }
+
+    /// Returns the date the food was eaten on
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the time the food was eaten at
+    pub fn get_time(&self) -> NaiveTime {
+        self.time
+    }
+
+    /// Returns the food that was eaten
+    pub fn get_food(&self) -> &Food {
+        &self.food
+    }
+
+    /// Returns the number of servings that were eaten
+    pub fn get_servings(&self) -> Rational32 {
+        self.servings.to_rational()
+    }
+
+    /// Returns which meal the entry was, if known
+    pub fn get_kind(&self) -> Option<MealKind> {
+        self.kind
+    }
+
+    /// Sets which meal the entry was, or forgets it if given None
+    pub fn set_kind(&mut self, kind: Option<MealKind>) {
+        self.kind = kind;
+    }
+}
+
+/// A single logged drink
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BeverageEntry {
+    date: NaiveDate,
+    time: NaiveTime,
+    beverage: Beverage,
+}
+
+impl BeverageEntry {
+    /// Constructs a new BeverageEntry from its components
+    pub fn new(date: NaiveDate, time: NaiveTime, beverage: Beverage) -> BeverageEntry {
+        BeverageEntry {
+            date,
+            time,
+            beverage,
+        }
+    }
+
+    /// Returns the date the drink was drunk on
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the time the drink was drunk at
+    pub fn get_time(&self) -> NaiveTime {
+        self.time
+    }
+
+    /// Returns the drink
+    pub fn get_beverage(&self) -> &Beverage {
+        &self.beverage
+    }
+}
+
+/// A summary of everything logged on a single day
+pub struct DailyReport<'a> {
+    date: NaiveDate,
+    entries: Vec<&'a LogEntry>,
+    beverages: Vec<(String, Amount)>,
+    total_volume: Amount,
+}
+
+impl<'a> DailyReport<'a> {
+    /// Returns the date the report covers
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Returns the food entries for the day
+    pub fn get_entries(&self) -> &[&'a LogEntry] {
+        &self.entries
+    }
+
+    /// Returns the food entries for one meal of the day
+    ///
+    /// Entries that haven't been labelled with a meal aren't part of any, see
+    /// `FoodLog::label_meals`.
+    pub fn entries_for(&self, kind: MealKind) -> Vec<&'a LogEntry> {
+        self.entries
+            .iter()
+            .filter(|x| x.kind == Some(kind))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the total volume drunk of each beverage, keyed by the beverage's short code,
+    /// in the order each was first drunk
+    ///
+    /// Each total is in the unit the beverage was first drunk in. A beverage drunk in a unit
+    /// that can't be converted to that has a second total.
+    pub fn get_beverage_totals(&self) -> &[(String, Amount)] {
+        &self.beverages
+    }
+
+    /// Returns the total volume of all beverages drunk during the day, in milliliters
+    ///
+    /// Beverages logged in a unit that isn't a volume aren't counted.
+    pub fn get_total_volume(&self) -> Amount {
+        self.total_volume
+    }
+}
+
+/// Describes a constraint a logged entry broke
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogWarning {
+    /// The entry was eaten outside of the household's eating window
+    OutsideEatingWindow(EatingWindow),
+    /// The entry was made with an ingredient excluded on the day it was eaten
+    ExcludedIngredient(Exclusion),
+}
+
+/// A log of everything eaten, along with the constraints entries are checked against
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FoodLog {
+    entries: Vec<LogEntry>,
+    #[serde(default)]
+    beverages: Vec<BeverageEntry>,
+    #[serde(default)]
+    eating_window: Option<EatingWindow>,
+    #[serde(default)]
+    exclusions: Vec<Exclusion>,
+}
+
+impl FoodLog {
+    /// Creates a new, empty, FoodLog with no constraints
+    pub fn new() -> FoodLog {
+        FoodLog {
+            entries: Vec::new(),
+            beverages: Vec::new(),
+            eating_window: None,
+            exclusions: Vec::new(),
+        }
+    }
+
+    /// Sets the eating window entries are checked against, or removes it if given None
+    pub fn set_eating_window(&mut self, window: Option<EatingWindow>) {
+        self.eating_window = window;
+    }
+
+    /// Returns the eating window entries are checked against, if there is one
+    pub fn get_eating_window(&self) -> Option<&EatingWindow> {
+        self.eating_window.as_ref()
+    }
+
+    /// Returns the ingredient exclusions entries are checked against
+    pub fn get_exclusions(&self) -> &[Exclusion] {
+        &self.exclusions
+    }
+
+    /// Adds an ingredient exclusion entries are checked against
+    ///
+    /// Only entries eaten between the exclusion's dates are flagged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::constraint::Exclusion;
+    /// use time_for_food::food::*;
+    /// use time_for_food::log::*;
+    ///
+    /// # fn main() {
+    /// let mut cheese_toast = RecipeBuilder::new("cheese-toast");
+    /// cheese_toast.add_tag("dairy");
+    /// let cheese_toast = Food::new_from_recipe(cheese_toast.build_with_defaults());
+    /// let no_dairy = Exclusion::new(
+    ///     "dairy",
+    ///     NaiveDate::from_ymd(2024, 2, 1),
+    ///     NaiveDate::from_ymd(2024, 2, 29),
+    /// );
+    ///
+    /// let mut log = FoodLog::new();
+    /// log.add_exclusion(no_dairy.clone());
+    /// let entry = |date| {
+    ///     let time = NaiveTime::from_hms(8, 0, 0);
+    ///     LogEntry::new(date, time, cheese_toast.clone(), Rational32::from_integer(1))
+    /// };
+    /// let warnings = log.log(entry(NaiveDate::from_ymd(2024, 2, 10)));
+    /// assert_eq!(warnings, vec![LogWarning::ExcludedIngredient(no_dairy)]);
+    /// assert!(log.log(entry(NaiveDate::from_ymd(2024, 3, 10))).is_empty());
+    /// # }
+    /// ```
+    pub fn add_exclusion(&mut self, exclusion: Exclusion) {
+        self.exclusions.push(exclusion);
+    }
+
+    /// Removes the exclusion at the given index
+    ///
+    /// # Returns
+    /// The removed exclusion, or None if there was no exclusion at that index
+    pub fn remove_exclusion(&mut self, index: usize) -> Option<Exclusion> {
+        if index < self.exclusions.len() {
+            Some(self.exclusions.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Checks an entry against the log's constraints, without logging it
+    pub fn check(&self, entry: &LogEntry) -> Vec<LogWarning> {
+        let mut warnings = Vec::new();
+        if let Some(window) = self.eating_window {
+            if !window.contains(entry.time) {
+                warnings.push(LogWarning::OutsideEatingWindow(window));
+            }
+        }
+        for exclusion in &self.exclusions {
+            if exclusion.excludes(entry.date, &entry.food) {
+                warnings.push(LogWarning::ExcludedIngredient(exclusion.clone()));
+            }
+        }
+        warnings
+    }
+
+    /// Labels every entry that isn't already labelled with a meal, by the time it was eaten,
+    /// returning how many entries were labelled
+    ///
+    /// Intended for logs imported without labels, so reports can still be broken down by
+    /// meal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    /// use time_for_food::log::*;
+    /// use time_for_food::settings::Settings;
+    ///
+    /// # fn main() {
+    /// let toast = Food::new_from_recipe(RecipeBuilder::new("toast").build_with_defaults());
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut log = FoodLog::new();
+    /// for &hour in &[8, 15, 19] {
+    ///     let time = NaiveTime::from_hms(hour, 0, 0);
+    ///     log.log(LogEntry::new(date, time, toast.clone(), Rational32::from_integer(1)));
+    /// }
+    ///
+    /// assert_eq!(log.label_meals(&MealWindows::new()), 3);
+    /// let report = log.daily_report(date, &Settings::new());
+    /// assert_eq!(report.entries_for(MealKind::Breakfast).len(), 1);
+    /// assert_eq!(report.entries_for(MealKind::Snack).len(), 1);
+    /// assert_eq!(report.entries_for(MealKind::Lunch).len(), 0);
+    /// # }
+    /// ```
+    pub fn label_meals(&mut self, windows: &MealWindows) -> usize {
+        let mut count = 0;
+        for entry in self.entries.iter_mut().filter(|x| x.kind.is_none()) {
+            entry.kind = Some(windows.classify(entry.time));
+            count += 1;
+        }
+        count
+    }
+
+    /// Logs an entry
+    ///
+    /// # Returns
+    /// The warnings for any constraints the entry broke. The entry is logged regardless.
+    pub fn log(&mut self, entry: LogEntry) -> Vec<LogWarning> {
+        let warnings = self.check(&entry);
+        self.entries.push(entry);
+        warnings
+    }
+
+    /// Returns every entry in the log, in the order they were logged
+    pub fn get_entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+
+    /// Returns the entries eaten on the given date
+    pub fn entries_on(&self, date: NaiveDate) -> Vec<&LogEntry> {
+        self.entries.iter().filter(|x| x.date == date).collect()
+    }
+
+    /// Logs a drink
+    ///
+    /// Drinks aren't checked against the eating window, as water, black coffee, and the like
+    /// are generally allowed while fasting.
+    pub fn log_beverage(&mut self, entry: BeverageEntry) {
+        self.beverages.push(entry);
+    }
+
+    /// Returns every logged drink, in the order they were logged
+    pub fn get_beverages(&self) -> &[BeverageEntry] {
+        &self.beverages
+    }
+
+    /// Builds a summary of the given day, with beverage volumes rolled up per beverage
+    ///
+    /// Volumes are converted with the household's unit sizes before they are added up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::log::*;
+    /// use time_for_food::settings::Settings;
+    ///
+    /// # fn main() {
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let drink = |hour, name: &str, unit, amount| {
+    ///     let volume = Amount::new(unit, Rational32::from_integer(amount));
+    ///     let beverage = Beverage::new(IString::new(name), volume);
+    ///     BeverageEntry::new(date, NaiveTime::from_hms(hour, 0, 0), beverage)
+    /// };
+    /// let mut log = FoodLog::new();
+    /// log.log_beverage(drink(8, "water", Unit::Liter, 1));
+    /// log.log_beverage(drink(9, "coffee", Unit::Milliliter, 300));
+    /// log.log_beverage(drink(12, "water", Unit::Milliliter, 250));
+    ///
+    /// let report = log.daily_report(date, &Settings::new());
+    /// let water = &report.get_beverage_totals()[0];
+    /// assert_eq!(water.0, "water");
+    /// assert!(water.1 == Amount::new(Unit::Liter, Rational32::new(5, 4)));
+    /// let total = Amount::new(Unit::Milliliter, Rational32::from_integer(1550));
+    /// assert!(report.get_total_volume() == total);
+    /// # }
+    /// ```
+    pub fn daily_report(&self, date: NaiveDate, settings: &Settings) -> DailyReport<'_> {
+        let conversions = settings.get_conversions();
+        let mut beverages: Vec<(String, Amount)> = Vec::new();
+        let mut total_volume = Amount::new(Unit::Milliliter, Rational32::from_integer(0));
+        for entry in self.beverages.iter().filter(|x| x.date == date) {
+            let short_code = entry.beverage.get_name().get_short_code();
+            let volume = entry.beverage.get_volume();
+            if let Ok(x) = volume.convert_with(Unit::Milliliter, conversions) {
+                total_volume.set_amount(total_volume.get_amount() + x.get_amount());
+            }
+            let existing = beverages.iter_mut().find_map(|(code, total)| {
+                if code != short_code {
+                    return None;
+                }
+                let converted = volume.convert_with(total.get_unit(), conversions).ok()?;
+                Some((total, converted))
+            });
+            match existing {
+                Some((total, converted)) => {
+                    let sum = total.get_amount() + converted.get_amount();
+                    total.set_amount(sum);
+                }
+                None => beverages.push((short_code.to_string(), volume)),
+            }
+        }
+        DailyReport {
+            date,
+            entries: self.entries_on(date),
+            beverages,
+            total_volume,
+        }
+    }
+
+    /// Removes the entry at the given index
+    ///
+    /// # Returns
+    /// The removed entry, or None if there was no entry at that index
+    pub fn remove_entry(&mut self, index: usize) -> Option<LogEntry> {
+        if index < self.entries.len() {
+            Some(self.entries.remove(index))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! This module contains the pantry, a record of the food a household has on hand
+use food::engine::MealPlan;
+use food::*;
+use num_rational::*;
+use settings::Settings;
+use std::collections::BTreeMap;
+use store::{FoodId, FoodStore};
+
+/// The food a household has on hand, and how much of each
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Pantry {
+    items: BTreeMap<FoodId, Amount>,
+}
+
+impl Pantry {
+    /// Creates a new, empty, Pantry
+    pub fn new() -> Pantry {
+        Pantry {
+            items: BTreeMap::new(),
+        }
+    }
+
+    /// Returns how much of the given food is on hand, if there is any
+    pub fn get(&self, id: &FoodId) -> Option<Amount> {
+        self.items.get(id).cloned()
+    }
+
+    /// Returns how much of a generic food is on hand in the given unit, counting every branded
+    /// variant of it the store knows about
+    ///
+    /// Each variant is converted to the unit, with the store's unit sizes, and variants on hand
+    /// in a unit that can't be converted aren't counted.
+    pub fn on_hand_for(&self, store: &FoodStore, generic: &FoodId, unit: Unit) -> Rational32 {
+        let settings = store.get_settings();
+        store
+            .variants_of(generic)
+            .iter()
+            .map(|x| x.0)
+            .chain(Some(generic))
+            .filter_map(|x| self.available_in(x, unit, settings))
+            .fold(Rational32::from_integer(0), |total, x| total + x)
+    }
+
+    /// Adds an amount of a food to what is on hand
+    ///
+    /// The amount is added to any already on hand, converted to the unit that is in with the
+    /// household's unit sizes.
+    ///
+    /// # Errors
+    /// If the amount can't be converted to the unit already on hand, in which case nothing is
+    /// changed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::pantry::Pantry;
+    /// use time_for_food::settings::Settings;
+    /// use time_for_food::store::FoodId;
+    ///
+    /// # fn main() {
+    /// let settings = Settings::new();
+    /// let flour = FoodId::new("flour");
+    /// let mut pantry = Pantry::new();
+    /// pantry.set(flour.clone(), Amount::new(Unit::Gram, Rational32::from_integer(500)));
+    /// let kilogram = Amount::new(Unit::Kilogram, Rational32::from_integer(1));
+    /// pantry.deposit(flour.clone(), kilogram, &settings).unwrap();
+    /// assert_eq!(pantry.get(&flour).unwrap().get_amount(), Rational32::from_integer(1500));
+    ///
+    /// // Flour can't be counted out
+    /// let eggs = Amount::new(Unit::Count, Rational32::from_integer(2));
+    /// assert!(pantry.deposit(flour.clone(), eggs, &settings).is_err());
+    /// # }
+    /// ```
+    pub fn deposit(
+        &mut self,
+        id: FoodId,
+        amount: Amount,
+        settings: &Settings,
+    ) -> Result<(), ConversionError> {
+        let conversions = settings.get_conversions();
+        match self.items.get_mut(&id) {
+            Some(existing) => {
+                let converted = amount.convert_with(existing.get_unit(), conversions)?;
+                let sum = existing.get_amount() + converted.get_amount();
+                existing.set_amount(sum);
+            }
+            None => {
+                self.items.insert(id, amount);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets how much of a food is on hand, such as after a stock take
+    pub fn set(&mut self, id: FoodId, amount: Amount) {
+        self.items.insert(id, amount);
+    }
+
+    /// Returns how much of a food is on hand in the given unit, converted with the household's
+    /// unit sizes, or None if there is none, or what there is can't be converted to the unit
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::pantry::Pantry;
+    /// use time_for_food::settings::Settings;
+    /// use time_for_food::store::FoodId;
+    ///
+    /// # fn main() {
+    /// // A household using metric cups
+    /// let mut settings = Settings::new();
+    /// settings
+    ///     .get_mut_conversions()
+    ///     .set_unit_size(Unit::Cup, Amount::new(Unit::Milliliter, Rational32::from_integer(250)))
+    ///     .unwrap();
+    ///
+    /// let stock = FoodId::new("stock");
+    /// let mut pantry = Pantry::new();
+    /// pantry.set(stock.clone(), Amount::new(Unit::Liter, Rational32::from_integer(1)));
+    /// assert_eq!(
+    ///     pantry.available_in(&stock, Unit::Cup, &settings),
+    ///     Some(Rational32::from_integer(4))
+    /// );
+    /// let five_cups = Amount::new(Unit::Cup, Rational32::from_integer(5));
+    /// let missing = pantry.take(&stock, five_cups, &settings);
+    /// assert_eq!(missing.get_amount(), Rational32::from_integer(1));
+    /// # }
+    /// ```
+    pub fn available_in(&self, id: &FoodId, unit: Unit, settings: &Settings) -> Option<Rational32> {
+        let on_hand = self.items.get(id)?;
+        on_hand
+            .convert_with(unit, settings.get_conversions())
+            .ok()
+            .map(|x| x.get_amount())
+    }
+
+    /// Takes as much of an amount of food out of the pantry as is on hand, returning how much
+    /// was missing
+    ///
+    /// What is on hand is converted to the amount's unit where needed. Food on hand in a unit
+    /// that can't be converted is left alone, and the whole amount counts as missing. The
+    /// missing amount is in the amount's unit, and is zero if there was enough.
+    pub fn take(&mut self, id: &FoodId, amount: Amount, settings: &Settings) -> Amount {
+        let conversions = settings.get_conversions();
+        let wanted = amount.get_amount();
+        let available = self
+            .available_in(id, amount.get_unit(), settings)
+            .unwrap_or_else(|| Rational32::from_integer(0));
+        let taken = ::std::cmp::max(
+            ::std::cmp::min(wanted, available),
+            Rational32::from_integer(0),
+        );
+        if taken > Rational32::from_integer(0) {
+            let existing = self.items.get_mut(id).unwrap();
+            let unit = existing.get_unit();
+            let left =
+                Amount::new(amount.get_unit(), available - taken).convert_with(unit, conversions);
+            match left {
+                Ok(x) => existing.set_amount(x.get_amount()),
+                Err(_) => return amount,
+            }
+        }
+        Amount::new(amount.get_unit(), wanted - taken)
+    }
+
+    /// Takes everything needed to make a plan's food out of the pantry, returning what was
+    /// missing
+    ///
+    /// What's needed is worked out as by `MealPlan::shopping_list`, so run this once the
+    /// plan's food has been made, to keep the pantry up to date over the week. Foods left
+    /// with nothing on hand stay in the pantry, at zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    /// use time_for_food::pantry::Pantry;
+    /// use time_for_food::settings::Settings;
+    /// use time_for_food::store::FoodId;
+    ///
+    /// # fn main() {
+    /// let settings = Settings::new();
+    /// let milk = RawFoodBuilder::new("milk")
+    ///     .set_serving_size(Unit::Milliliter, Rational32::from_integer(250))
+    ///     .set_nutrition(Nutrition::new())
+    ///     .build_raw_food()
+    ///     .unwrap();
+    /// let porridge = RecipeBuilder::new("porridge")
+    ///     .add_food(Food::new_from_raw_food(milk), Unit::Milliliter, Rational32::from_integer(300))
+    ///     .build_with_defaults();
+    /// let mut plan = MealPlan::new();
+    /// plan.insert_block(
+    ///     NaiveDate::from_ymd(2024, 1, 8),
+    ///     Block::new(
+    ///         NaiveTime::from_hms(7, 0, 0),
+    ///         NaiveTime::from_hms(7, 30, 0),
+    ///         Some(Food::new_from_recipe(porridge)),
+    ///     ),
+    /// );
+    ///
+    /// let mut pantry = Pantry::new();
+    /// let liter = Amount::new(Unit::Liter, Rational32::from_integer(1));
+    /// pantry.deposit(FoodId::new("milk"), liter, &settings).unwrap();
+    /// assert!(pantry.consume(&plan, &settings).is_empty());
+    /// assert_eq!(
+    ///     pantry.get(&FoodId::new("milk")).unwrap().get_amount(),
+    ///     Rational32::new(7, 10)
+    /// );
+    /// # }
+    /// ```
+    pub fn consume(&mut self, plan: &MealPlan, settings: &Settings) -> Vec<(FoodId, Amount)> {
+        let mut missing = Vec::new();
+        for item in plan.shopping_list(settings).get_items() {
+            let id = FoodId::new(item.get_name().get_short_code());
+            let short = self.take(&id, item.get_amount(), settings);
+            if short.get_amount() > Rational32::from_integer(0) {
+                missing.push((id, short));
+            }
+        }
+        missing
+    }
+
+    /// Removes a food from the pantry entirely, returning how much there was
+    pub fn remove(&mut self, id: &FoodId) -> Option<Amount> {
+        self.items.remove(id)
+    }
+
+    /// Returns the ids of every food on hand, sorted
+    pub fn ids(&self) -> Vec<&FoodId> {
+        self.items.keys().collect()
+    }
+}
@@ -0,0 +1,329 @@
+//! This module contains the duration parser
+//!
+//! Durations are returned as exact rational minutes, matching how the rest of the crate
+//! stores times.
+use super::{checked_add, checked_mul, fraction_glyph, parse_number, ParseError};
+use num_rational::*;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// How long "overnight" is taken to be, in minutes
+pub const OVERNIGHT_MINUTES: i32 = 8 * 60;
+
+/// Parses a duration into rational minutes
+///
+/// Accepts:
+///
+/// * ISO 8601 durations, such as "PT30M", "PT1H15M", or "P1DT2H"
+/// * Natural language, such as "1 hour 15 minutes", "1h15m", "1 ½ hours", "an hour",
+///   or "half an hour"
+/// * Ranges, such as "10-15 minutes" or "10 to 15 minutes", which resolve to their upper bound,
+///   as it is better for a schedule to overestimate
+/// * "overnight", which is taken to be `OVERNIGHT_MINUTES`
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::parse::parse_duration;
+///
+/// # fn main() {
+/// let minutes = |n| Ok(Rational32::from_integer(n));
+/// assert_eq!(parse_duration("1 hour 15 minutes"), minutes(75));
+/// assert_eq!(parse_duration("PT30M"), minutes(30));
+/// assert_eq!(parse_duration("1 ½ hours"), minutes(90));
+/// assert_eq!(parse_duration("10-15 min"), minutes(15));
+/// assert_eq!(parse_duration("overnight"), minutes(480));
+/// // Too long to fit, rather than overflowing
+/// assert!(parse_duration("PT99999999H").is_err());
+/// assert!(parse_duration("99999999 weeks").is_err());
+/// # }
+/// ```
+pub fn parse_duration(text: &str) -> Result<Rational32, ParseError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let is_iso = text.starts_with('P') || text.starts_with('p');
+    if is_iso
+        && text.len() > 1
+        && text[1..].starts_with(|c: char| c.is_ascii_digit() || c == 'T' || c == 't')
+    {
+        parse_iso_duration(text)
+    } else {
+        parse_natural_duration(text)
+    }
+}
+
+/// Parses an ISO 8601 duration, such as "P1DT2H30M"
+///
+/// Years and months are rejected, as they have no fixed length in minutes.
+pub fn parse_iso_duration(text: &str) -> Result<Rational32, ParseError> {
+    let unrecognized = || ParseError::Unrecognized(text.to_string());
+    let invalid = || ParseError::InvalidNumber(text.to_string());
+    let upper = text.trim().to_uppercase();
+    let body = match upper.strip_prefix('P') {
+        Some(x) => x,
+        None => return Err(unrecognized()),
+    };
+    if body.is_empty() {
+        return Err(unrecognized());
+    }
+
+    let mut total = Rational32::from_integer(0);
+    let mut in_time = false;
+    let mut number = String::new();
+    for c in body.chars() {
+        match c {
+            'T' if !in_time && number.is_empty() => in_time = true,
+            '0'..='9' | '.' | ',' => number.push(if c == ',' { '.' } else { c }),
+            _ => {
+                let factor = match (c, in_time) {
+                    ('W', false) => Rational32::from_integer(7 * 24 * 60),
+                    ('D', false) => Rational32::from_integer(24 * 60),
+                    ('H', true) => Rational32::from_integer(60),
+                    ('M', true) => Rational32::from_integer(1),
+                    ('S', true) => Rational32::new(1, 60),
+                    _ => return Err(unrecognized()),
+                };
+                let minutes = checked_mul(parse_number(&number)?, factor).ok_or_else(invalid)?;
+                total = checked_add(total, minutes).ok_or_else(invalid)?;
+                number.clear();
+            }
+        }
+    }
+    if !number.is_empty() {
+        return Err(unrecognized());
+    }
+    Ok(total)
+}
+
+/// Formats rational minutes as an ISO 8601 duration, such as "PT1H15M"
+///
+/// Whole days are written as days, and everything else in hours, minutes, and seconds, with
+/// zero components left out. Seconds are written as a decimal, rounded to the millisecond,
+/// when the minutes don't divide into whole seconds. Negative durations are written as zero.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::parse::duration::*;
+///
+/// # fn main() {
+/// assert_eq!(format_iso_duration(Rational32::from_integer(75)), "PT1H15M");
+/// assert_eq!(format_iso_duration(Rational32::new(3, 2)), "PT1M30S");
+/// assert_eq!(format_iso_duration(Rational32::from_integer(26 * 60)), "P1DT2H");
+/// assert_eq!(format_iso_duration(Rational32::from_integer(0)), "PT0S");
+/// # }
+/// ```
+pub fn format_iso_duration(minutes: Rational32) -> String {
+    if minutes <= Rational32::from_integer(0) {
+        return "PT0S".to_string();
+    }
+    // Rounded to whole milliseconds, in 64 bits as long durations overflow 32
+    let numer = i64::from(*minutes.numer());
+    let denom = i64::from(*minutes.denom());
+    let total = (numer * 120_000 + denom) / (2 * denom);
+    let days = total / 86_400_000;
+    let hours = total % 86_400_000 / 3_600_000;
+    let mins = total % 3_600_000 / 60_000;
+    let millis = total % 60_000;
+
+    let mut output = "P".to_string();
+    if days > 0 {
+        output.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || mins > 0 || millis > 0 {
+        output.push('T');
+    }
+    if hours > 0 {
+        output.push_str(&format!("{}H", hours));
+    }
+    if mins > 0 {
+        output.push_str(&format!("{}M", mins));
+    }
+    if millis % 1000 == 0 && millis > 0 {
+        output.push_str(&format!("{}S", millis / 1000));
+    } else if millis > 0 {
+        let decimal = format!("{}.{:03}", millis / 1000, millis % 1000);
+        output.push_str(&format!("{}S", decimal.trim_end_matches('0')));
+    }
+    output
+}
+
+/// A number of minutes that serializes as an ISO 8601 duration string, such as "PT25M"
+///
+/// Intended for export formats that expect durations, such as schema.org recipes and iCalendar.
+/// Deserializing accepts anything `parse_duration` does, so imports can be lenient, and the
+/// minutes are kept as an exact rational either way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IsoDuration(pub Rational32);
+
+impl IsoDuration {
+    /// Returns the duration in minutes
+    pub fn get_minutes(&self) -> Rational32 {
+        self.0
+    }
+}
+
+impl fmt::Display for IsoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format_iso_duration(self.0))
+    }
+}
+
+impl FromStr for IsoDuration {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<IsoDuration, ParseError> {
+        parse_duration(text).map(IsoDuration)
+    }
+}
+
+impl Serialize for IsoDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IsoDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<IsoDuration, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Parses a natural language duration, such as "1 hour and 15 minutes"
+pub fn parse_natural_duration(text: &str) -> Result<Rational32, ParseError> {
+    let tokens = tokenize(text);
+    if tokens.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let invalid = || ParseError::InvalidNumber(text.to_string());
+
+    let mut total = Rational32::from_integer(0);
+    // The number waiting for its unit, and whether it came from an "a"/"an"
+    let mut pending: Option<Rational32> = None;
+    let mut article = false;
+    let mut last_unit: Option<Rational32> = None;
+    for token in &tokens {
+        let token = &**token;
+        match token {
+            "and" | "for" | "about" | "approximately" | "approx" | "around" | "~" => {}
+            "overnight" => {
+                total = checked_add(total, Rational32::from_integer(OVERNIGHT_MINUTES))
+                    .ok_or_else(invalid)?
+            }
+            // "an hour", and the "an" in "half an hour"
+            "a" | "an" => {
+                if pending.is_none() {
+                    pending = Some(Rational32::from_integer(1));
+                    article = true;
+                }
+            }
+            "half" => {
+                pending = match pending {
+                    // "1 and a half hours"
+                    Some(x) if !article => {
+                        Some(checked_add(x, Rational32::new(1, 2)).ok_or_else(invalid)?)
+                    }
+                    // "half an hour", "a half hour", and the end of "an hour and a half"
+                    _ => Some(Rational32::new(1, 2)),
+                };
+                article = false;
+            }
+            // Ranges, throw away the lower bound
+            "-" | "–" | "to" => pending = None,
+            _ => {
+                if let Some(factor) = unit_minutes(token) {
+                    let amount = pending
+                        .take()
+                        .ok_or_else(|| ParseError::Unrecognized(token.to_string()))?;
+                    let minutes = checked_mul(amount, factor).ok_or_else(invalid)?;
+                    total = checked_add(total, minutes).ok_or_else(invalid)?;
+                    last_unit = Some(factor);
+                } else if token
+                    .starts_with(|c: char| c.is_ascii_digit() || fraction_glyph(c).is_some())
+                {
+                    let value = parse_number(token)?;
+                    // Adding covers mixed numbers, as in "1 1/2"
+                    pending = Some(match pending {
+                        Some(x) if !article => checked_add(x, value).ok_or_else(invalid)?,
+                        _ => value,
+                    });
+                } else {
+                    return Err(ParseError::Unrecognized(token.to_string()));
+                }
+                article = false;
+            }
+        }
+    }
+
+    match (pending, last_unit) {
+        // "an hour and a half" leaves the half waiting after the unit it refers to
+        (Some(x), Some(factor)) if tokens.last().map(|x| &**x) == Some("half") => {
+            checked_mul(x, factor)
+                .and_then(|minutes| checked_add(total, minutes))
+                .ok_or_else(invalid)
+        }
+        (Some(_), _) => Err(ParseError::Unrecognized(text.to_string())),
+        (None, None) if !tokens.iter().any(|x| x == "overnight") => {
+            Err(ParseError::Unrecognized(text.to_string()))
+        }
+        (None, _) => Ok(total),
+    }
+}
+
+/// Returns the number of minutes a time unit is worth
+pub fn unit_minutes(unit: &str) -> Option<Rational32> {
+    let minutes = match unit {
+        "s" | "sec" | "secs" | "second" | "seconds" => return Some(Rational32::new(1, 60)),
+        "m" | "min" | "mins" | "minute" | "minutes" => 1,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60,
+        "d" | "day" | "days" => 24 * 60,
+        "w" | "wk" | "wks" | "week" | "weeks" => 7 * 24 * 60,
+        _ => return None,
+    };
+    Some(Rational32::from_integer(minutes))
+}
+
+/// Splits a duration into lowercase tokens
+///
+/// Numbers and letters are split apart even when written together ("1h15m"), and dashes and
+/// punctuation become their own tokens.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    // Whether the current token is numeric or alphabetic
+    let mut numeric = false;
+    for c in text.to_lowercase().chars() {
+        let is_numeric = c.is_ascii_digit() || c == '.' || c == '/' || fraction_glyph(c).is_some();
+        if c.is_alphabetic() || is_numeric {
+            if !current.is_empty() && numeric != is_numeric {
+                tokens.push(current.clone());
+                current.clear();
+            }
+            numeric = is_numeric;
+            current.push(c);
+        } else {
+            if !current.is_empty() {
+                tokens.push(current.clone());
+                current.clear();
+            }
+            if c == '-' || c == '–' || c == '~' {
+                tokens.push(c.to_string());
+            }
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
@@ -0,0 +1,414 @@
+//! This module contains the ingredient line parser
+//!
+//! Splits free text lines, like "2 cups chopped onions, divided", into an amount, a unit, the
+//! name of the food, and a preparation note. Ingredient lines in the wild are very loosely
+//! formatted, so every parse comes with a confidence score, and lines that can't be made sense
+//! of at all are handed back as unparsed so importers can flag them for review.
+use super::{checked_add, fraction_glyph, parse_number, ParseError};
+use food::{Amount, Unit};
+use num_rational::*;
+
+/// Parses below this confidence should be looked at by a human
+pub const REVIEW_THRESHOLD: u8 = 60;
+
+/// Unit spellings, and the name they are normalized to
+///
+/// The single letter "T" and "t" forms are handled separately, as they are case sensitive.
+const UNIT_ALIASES: &[(&str, &str)] = &[
+    ("g", "g"),
+    ("gram", "g"),
+    ("grams", "g"),
+    ("kg", "kg"),
+    ("kilogram", "kg"),
+    ("kilograms", "kg"),
+    ("oz", "oz"),
+    ("ounce", "oz"),
+    ("ounces", "oz"),
+    ("lb", "lb"),
+    ("lbs", "lb"),
+    ("pound", "lb"),
+    ("pounds", "lb"),
+    ("ml", "ml"),
+    ("milliliter", "ml"),
+    ("milliliters", "ml"),
+    ("millilitre", "ml"),
+    ("millilitres", "ml"),
+    ("l", "l"),
+    ("liter", "l"),
+    ("liters", "l"),
+    ("litre", "l"),
+    ("litres", "l"),
+    ("c", "cup"),
+    ("cup", "cup"),
+    ("cups", "cup"),
+    ("tbsp", "tbsp"),
+    ("tbs", "tbsp"),
+    ("tablespoon", "tbsp"),
+    ("tablespoons", "tbsp"),
+    ("tsp", "tsp"),
+    ("teaspoon", "tsp"),
+    ("teaspoons", "tsp"),
+    ("pinch", "pinch"),
+    ("pinches", "pinch"),
+    ("dash", "dash"),
+    ("dashes", "dash"),
+    ("clove", "clove"),
+    ("cloves", "clove"),
+    ("can", "can"),
+    ("cans", "can"),
+    ("slice", "slice"),
+    ("slices", "slice"),
+    ("bunch", "bunch"),
+    ("bunches", "bunch"),
+];
+
+/// Words describing how an ingredient is prepared, which belong in the note rather than the
+/// name when they lead the name ("chopped onions")
+const PREPARATION_WORDS: &[&str] = &[
+    "chopped",
+    "diced",
+    "minced",
+    "sliced",
+    "grated",
+    "shredded",
+    "crushed",
+    "melted",
+    "softened",
+    "beaten",
+    "peeled",
+    "cubed",
+    "julienned",
+    "toasted",
+    "cooked",
+    "drained",
+    "rinsed",
+    "sifted",
+    "finely",
+    "roughly",
+    "thinly",
+    "coarsely",
+    "freshly",
+];
+
+/// The pieces of a successfully parsed ingredient line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IngredientLine {
+    original: String,
+    amount: Option<Rational32>,
+    unit: Option<String>,
+    name: String,
+    note: Option<String>,
+    confidence: u8,
+}
+
+impl IngredientLine {
+    /// Returns the line as it was originally given
+    pub fn get_original(&self) -> &str {
+        &self.original
+    }
+
+    /// Returns the amount, if the line had one
+    pub fn get_amount(&self) -> Option<Rational32> {
+        self.amount
+    }
+
+    /// Returns the normalized unit name, such as "cup" or "tbsp", if the line had one
+    ///
+    /// Lines with an amount but no unit are counts of whole items ("2 eggs").
+    pub fn get_unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    /// Returns the name of the food
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the preparation note, if there was one
+    pub fn get_note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    /// Returns how confident the parser is in the result, from 0 to 100
+    pub fn get_confidence(&self) -> u8 {
+        self.confidence
+    }
+
+    /// Returns true if the parse is shaky enough that a human should look at it
+    pub fn needs_review(&self) -> bool {
+        self.confidence < REVIEW_THRESHOLD
+    }
+}
+
+/// The result of parsing an ingredient line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsedIngredient {
+    /// The line was split into its pieces
+    Parsed(IngredientLine),
+    /// The line couldn't be made sense of, includes the original text
+    Unparsed(String),
+}
+
+impl ParsedIngredient {
+    /// Returns true if the line should be looked at by a human
+    ///
+    /// Always true for unparsed lines.
+    pub fn needs_review(&self) -> bool {
+        match *self {
+            ParsedIngredient::Parsed(ref x) => x.needs_review(),
+            ParsedIngredient::Unparsed(_) => true,
+        }
+    }
+}
+
+/// Parses a free text ingredient line
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::parse::ingredient::*;
+///
+/// # fn main() {
+/// match parse_ingredient("2 cups chopped onions, divided") {
+///     ParsedIngredient::Parsed(line) => {
+///         assert_eq!(line.get_amount(), Some(Rational32::from_integer(2)));
+///         assert_eq!(line.get_unit(), Some("cup"));
+///         assert_eq!(line.get_name(), "onions");
+///         assert_eq!(line.get_note(), Some("chopped, divided"));
+///         assert!(!line.needs_review());
+///     }
+///     ParsedIngredient::Unparsed(_) => panic!("Should have parsed"),
+/// }
+/// # }
+/// ```
+pub fn parse_ingredient(line: &str) -> ParsedIngredient {
+    let unparsed = || ParsedIngredient::Unparsed(line.to_string());
+    let text = line.trim().trim_start_matches(&['-', '*', '•'][..]).trim();
+    if text.is_empty() {
+        return unparsed();
+    }
+
+    // Everything after the first comma, or inside parentheses, is a note
+    let mut notes = Vec::new();
+    let (body, trailing) = match text.find(',') {
+        Some(i) => (&text[..i], Some(text[i + 1..].trim())),
+        None => (text, None),
+    };
+    let mut body = body.to_string();
+    while let (Some(open), Some(close)) = (body.find('('), body.find(')')) {
+        if close < open {
+            break;
+        }
+        let inner = body[open + 1..close].trim().to_string();
+        body = format!("{} {}", &body[..open], &body[close + 1..]);
+        if !inner.is_empty() {
+            notes.push(inner);
+        }
+    }
+
+    let tokens: Vec<&str> = body.split_whitespace().collect();
+    let mut index = 0;
+    let mut confidence: i32 = 100;
+
+    // Amount, summing mixed numbers and keeping the top of ranges
+    let mut amount: Option<Rational32> = None;
+    while index < tokens.len() {
+        let token = tokens[index];
+        if let Some(upper) = parse_range(token) {
+            amount = Some(upper);
+        } else if (token == "a" || token == "an") && amount.is_none() {
+            amount = Some(Rational32::from_integer(1));
+        } else if let Some(value) = parse_amount_token(token) {
+            amount = match amount {
+                Some(x) => match checked_add(x, value) {
+                    Some(sum) => Some(sum),
+                    None => return unparsed(),
+                },
+                None => Some(value),
+            };
+        } else if (token == "-" || token == "to") && amount.is_some() {
+            // The top of the range replaces the bottom
+            if let Some(value) = tokens.get(index + 1).and_then(|x| parse_amount_token(x)) {
+                amount = Some(value);
+                index += 1;
+            }
+        } else {
+            break;
+        }
+        index += 1;
+    }
+
+    // Unit
+    let mut unit = None;
+    if let Some(token) = tokens.get(index) {
+        if let Some(normalized) = normalize_unit(token) {
+            unit = Some(normalized.to_string());
+            index += 1;
+            if tokens.get(index) == Some(&"of") {
+                index += 1;
+            }
+        }
+    }
+
+    // Leading preparation words go to the note
+    let mut preparation = Vec::new();
+    while let Some(token) = tokens.get(index) {
+        if PREPARATION_WORDS.contains(&&*token.to_lowercase()) {
+            preparation.push(*token);
+            index += 1;
+        } else {
+            break;
+        }
+    }
+
+    let name_words = &tokens[index..];
+    if name_words.is_empty() {
+        return unparsed();
+    }
+    let name = name_words.join(" ");
+
+    // Score the parse
+    if amount.is_none() {
+        confidence -= 30;
+        if unit.is_some() {
+            confidence -= 20;
+        }
+    }
+    if name_words.len() > 5 {
+        confidence -= 20;
+    }
+    if name
+        .chars()
+        .any(|c| c.is_ascii_digit() || fraction_glyph(c).is_some())
+    {
+        confidence -= 30;
+    }
+    if confidence <= 0 {
+        return unparsed();
+    }
+
+    if !preparation.is_empty() {
+        notes.insert(0, preparation.join(" "));
+    }
+    if let Some(trailing) = trailing {
+        if !trailing.is_empty() {
+            notes.push(trailing.to_string());
+        }
+    }
+    let note = if notes.is_empty() {
+        None
+    } else {
+        Some(notes.join(", "))
+    };
+
+    ParsedIngredient::Parsed(IngredientLine {
+        original: line.to_string(),
+        amount,
+        unit,
+        name,
+        note,
+        confidence: confidence as u8,
+    })
+}
+
+/// Parses a free text ingredient line into the name of the food, its amount, and any
+/// preparation note
+///
+/// A stricter form of `parse_ingredient` for callers that need a usable amount: lines without
+/// a number are refused. Lines without a unit are counts, so "2 eggs" is a count of two, but
+/// units the crate doesn't measure, such as cloves or cans, are refused with the unit, as
+/// counting them would lose what is being counted. See `parse_ingredient` to keep them.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::Unit;
+/// use time_for_food::parse::{parse_ingredient_line, ParseError};
+///
+/// # fn main() {
+/// let (name, amount, note) = parse_ingredient_line("2 1/2 cups flour, sifted").unwrap();
+/// assert_eq!(name, "flour");
+/// assert_eq!(amount.get_unit(), Unit::Cup);
+/// assert_eq!(amount.get_amount(), Rational32::new(5, 2));
+/// assert_eq!(note.as_deref(), Some("sifted"));
+///
+/// let (_, amount, _) = parse_ingredient_line("¾ tsp salt").unwrap();
+/// assert_eq!(amount.get_amount(), Rational32::new(3, 4));
+///
+/// let (_, amount, _) = parse_ingredient_line("2 eggs").unwrap();
+/// assert_eq!(amount.get_unit(), Unit::Count);
+///
+/// assert!(parse_ingredient_line("salt to taste").is_err());
+/// assert_eq!(
+///     parse_ingredient_line("3 cloves garlic").err(),
+///     Some(ParseError::UnsupportedUnit("clove".to_string()))
+/// );
+/// # }
+/// ```
+pub fn parse_ingredient_line(line: &str) -> Result<(String, Amount, Option<String>), ParseError> {
+    if line.trim().is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let parsed = match parse_ingredient(line) {
+        ParsedIngredient::Parsed(x) => x,
+        ParsedIngredient::Unparsed(x) => return Err(ParseError::Unrecognized(x)),
+    };
+    let amount = match parsed.amount {
+        Some(x) => x,
+        None => return Err(ParseError::Unrecognized(line.to_string())),
+    };
+    let unit = match parsed.unit {
+        Some(unit) => match Unit::from_symbol(&unit) {
+            Some(x) => x,
+            None => return Err(ParseError::UnsupportedUnit(unit)),
+        },
+        None => Unit::Count,
+    };
+    Ok((parsed.name, Amount::new(unit, amount), parsed.note))
+}
+
+/// Normalizes a unit spelling, such as "Tablespoons" or "tsp.", to its short name
+pub fn normalize_unit(token: &str) -> Option<&'static str> {
+    let token = token.trim_end_matches('.');
+    match token {
+        "T" | "Tb" => return Some("tbsp"),
+        "t" => return Some("tsp"),
+        _ => {}
+    }
+    let lower = token.to_lowercase();
+    UNIT_ALIASES
+        .iter()
+        .find(|&&(alias, _)| alias == lower)
+        .map(|&(_, normalized)| normalized)
+}
+
+/// Parses a token that is all number, such as "2", "1/2", "½", or "1½"
+fn parse_amount_token(token: &str) -> Option<Rational32> {
+    let starts_numeric = token
+        .chars()
+        .next()
+        .map(|c| c.is_ascii_digit() || c == '.' || fraction_glyph(c).is_some())
+        .unwrap_or(false);
+    if starts_numeric {
+        parse_number(token).ok()
+    } else {
+        None
+    }
+}
+
+/// Parses a range written as a single token, such as "2-3", returning the top of the range
+fn parse_range(token: &str) -> Option<Rational32> {
+    let mut parts = token.split(&['-', '–'][..]);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(low), Some(high), None) => {
+            parse_amount_token(low).and_then(|_| parse_amount_token(high))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,180 @@
+//! This module contains the parsers shared by the importers
+//!
+//! Everything in here works on free text, and produces the crate's exact rational types.
+//! Importers should always go through these, rather than doing their own ad-hoc parsing,
+//! so that every import format agrees on what "1 ½ hours" means.
+pub mod duration;
+pub mod ingredient;
+use num_rational::*;
+use std::error::Error;
+use std::fmt;
+
+pub use self::duration::{parse_duration, IsoDuration};
+pub use self::ingredient::parse_ingredient_line;
+
+/// Describes why a piece of text could not be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// There was nothing to parse
+    Empty,
+    /// A number was malformed, includes the offending text
+    InvalidNumber(String),
+    /// A piece of the text didn't fit what the parser expected, includes the offending text
+    Unrecognized(String),
+    /// The text gave a unit the crate doesn't measure in, such as cans, includes the unit
+    UnsupportedUnit(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Empty => write!(f, "Nothing to parse"),
+            ParseError::InvalidNumber(ref x) => write!(f, "Invalid number: {}", x),
+            ParseError::Unrecognized(ref x) => write!(f, "Unrecognized text: {}", x),
+            ParseError::UnsupportedUnit(ref x) => write!(f, "Unsupported unit: {}", x),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Returns the value of a unicode vulgar fraction glyph, such as '½'
+pub fn fraction_glyph(c: char) -> Option<Rational32> {
+    let (n, d) = match c {
+        '½' => (1, 2),
+        '⅓' => (1, 3),
+        '⅔' => (2, 3),
+        '¼' => (1, 4),
+        '¾' => (3, 4),
+        '⅕' => (1, 5),
+        '⅖' => (2, 5),
+        '⅗' => (3, 5),
+        '⅘' => (4, 5),
+        '⅙' => (1, 6),
+        '⅚' => (5, 6),
+        '⅛' => (1, 8),
+        '⅜' => (3, 8),
+        '⅝' => (5, 8),
+        '⅞' => (7, 8),
+        _ => return None,
+    };
+    Some(Rational32::new(n, d))
+}
+
+/// Parses a single number into an exact rational
+///
+/// Accepts integers ("2"), decimals ("1.25"), simple fractions ("3/4"), fraction glyphs ("½"),
+/// and integers with a trailing glyph ("1½"). Mixed numbers written with a space ("1 1/2") are
+/// two numbers as far as this function is concerned, callers are expected to add them up.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::parse::parse_number;
+///
+/// # fn main() {
+/// assert_eq!(parse_number("1.25"), Ok(Rational32::new(5, 4)));
+/// assert_eq!(parse_number("3/4"), Ok(Rational32::new(3, 4)));
+/// assert_eq!(parse_number("1½"), Ok(Rational32::new(3, 2)));
+/// // Too large to fit, rather than overflowing
+/// assert!(parse_number("2147483647½").is_err());
+/// # }
+/// ```
+pub fn parse_number(text: &str) -> Result<Rational32, ParseError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let invalid = || ParseError::InvalidNumber(text.to_string());
+
+    // Split off a trailing fraction glyph, if there is one
+    let (whole, glyph) = match text.chars().last().and_then(fraction_glyph) {
+        Some(value) => {
+            let glyph_len = text.chars().last().map(|c| c.len_utf8()).unwrap_or(0);
+            (&text[..text.len() - glyph_len], value)
+        }
+        None => (text, Rational32::from_integer(0)),
+    };
+    if whole.is_empty() {
+        return Ok(glyph);
+    }
+
+    let value = if let Some(slash) = whole.find('/') {
+        let numerator = parse_integer(&whole[..slash]).ok_or_else(invalid)?;
+        let denominator = parse_integer(&whole[slash + 1..]).ok_or_else(invalid)?;
+        if denominator == 0 {
+            return Err(invalid());
+        }
+        Rational32::new(numerator, denominator)
+    } else if let Some(point) = whole.find('.') {
+        let integer = if point == 0 {
+            0
+        } else {
+            parse_integer(&whole[..point]).ok_or_else(invalid)?
+        };
+        let decimals = &whole[point + 1..];
+        let fraction = parse_integer(decimals).ok_or_else(invalid)?;
+        let scale = 10i32
+            .checked_pow(decimals.len() as u32)
+            .ok_or_else(invalid)?;
+        checked_add(
+            Rational32::from_integer(integer),
+            Rational32::new(fraction, scale),
+        )
+        .ok_or_else(invalid)?
+    } else {
+        Rational32::from_integer(parse_integer(whole).ok_or_else(invalid)?)
+    };
+
+    checked_add(value, glyph).ok_or_else(invalid)
+}
+
+/// Adds two rationals, returning `None` instead of overflowing
+pub(crate) fn checked_add(x: Rational32, y: Rational32) -> Option<Rational32> {
+    let numer = x
+        .numer()
+        .checked_mul(*y.denom())?
+        .checked_add(y.numer().checked_mul(*x.denom())?)?;
+    let denom = x.denom().checked_mul(*y.denom())?;
+    Some(Rational32::new(numer, denom))
+}
+
+/// Multiplies two rationals, returning `None` instead of overflowing
+pub(crate) fn checked_mul(x: Rational32, y: Rational32) -> Option<Rational32> {
+    let numer = x.numer().checked_mul(*y.numer())?;
+    let denom = x.denom().checked_mul(*y.denom())?;
+    Some(Rational32::new(numer, denom))
+}
+
+/// Makes a short code out of a name, lowercased with runs of anything other than letters and
+/// digits replaced by a single dash
+///
+/// # Examples
+///
+/// ```
+/// extern crate time_for_food;
+/// use time_for_food::parse::to_short_code;
+///
+/// # fn main() {
+/// assert_eq!(to_short_code("Ground Black Pepper"), "ground-black-pepper");
+/// assert_eq!(to_short_code("Mac 'n' Cheese!"), "mac-n-cheese");
+/// # }
+/// ```
+pub fn to_short_code(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// Parses a run of ascii digits, rejecting signs and anything else `str::parse` would accept
+fn parse_integer(text: &str) -> Option<i32> {
+    if text.is_empty() || !text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
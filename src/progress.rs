@@ -0,0 +1,77 @@
+//! This module contains progress reporting and cancellation for long running operations
+//!
+//! Operations that can take a while, such as optimizing a plan, accept a callback that is told
+//! how far along they are and a `CancelToken` that is checked between units of work. A GUI can
+//! drive a progress bar from the callback, and cancel the token from another thread to abort
+//! the operation cleanly.
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// How far along an operation is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    done: usize,
+    total: usize,
+}
+
+impl Progress {
+    /// Creates a progress report of the given number of units of work done, out of a total
+    pub fn new(done: usize, total: usize) -> Progress {
+        Progress { done, total }
+    }
+
+    /// Returns the number of units of work done
+    pub fn get_done(&self) -> usize {
+        self.done
+    }
+
+    /// Returns the total number of units of work
+    pub fn get_total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns true if every unit of work is done
+    pub fn is_complete(&self) -> bool {
+        self.done >= self.total
+    }
+}
+
+/// A flag shared between an operation and whatever may want to cancel it
+///
+/// Clones share the same flag, so one clone can be handed to the operation and another kept
+/// to cancel it with.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a token that hasn't been cancelled
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Asks every operation holding the token to stop
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true if the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Returned by operations that stopped because their token was cancelled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the operation was cancelled")
+    }
+}
+
+impl Error for Cancelled {}
@@ -0,0 +1,198 @@
+//! This module contains reports summarizing plans and logs
+//!
+//! The time use report shows where kitchen hours go, splitting time into active cooking,
+//! passive (hands-off) cooking, and eating, per day and per recipe.
+use chrono::prelude::*;
+use food::engine::MealPlan;
+use food::*;
+use log::FoodLog;
+use num_rational::*;
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+
+/// Time spent, in minutes, split by what it was spent on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeUse {
+    active: Rational32,
+    passive: Rational32,
+    eating: Rational32,
+}
+
+impl Default for TimeUse {
+    fn default() -> TimeUse {
+        TimeUse::new()
+    }
+}
+
+impl AddAssign for TimeUse {
+    fn add_assign(&mut self, other: TimeUse) {
+        self.active += other.active;
+        self.passive += other.passive;
+        self.eating += other.eating;
+    }
+}
+
+impl TimeUse {
+    /// Creates a TimeUse with no time spent on anything
+    pub fn new() -> TimeUse {
+        TimeUse {
+            active: Rational32::from_integer(0),
+            passive: Rational32::from_integer(0),
+            eating: Rational32::from_integer(0),
+        }
+    }
+
+    /// Returns the minutes spent actively cooking
+    pub fn get_active(&self) -> Rational32 {
+        self.active
+    }
+
+    /// Returns the minutes food spent cooking without needing anyone
+    pub fn get_passive(&self) -> Rational32 {
+        self.passive
+    }
+
+    /// Returns the minutes spent eating
+    pub fn get_eating(&self) -> Rational32 {
+        self.eating
+    }
+
+    /// Returns the minutes of a person's time taken up, that is active cooking and eating
+    pub fn get_occupied(&self) -> Rational32 {
+        self.active + self.eating
+    }
+
+    /// The time use of making and eating a food once
+    fn of_food(food: &Food, eating_minutes: Rational32) -> TimeUse {
+        let mut time_use = TimeUse::new();
+        if let Food::Recipe(ref recipe) = *food {
+            time_use.active = recipe.get_active_time();
+            time_use.passive = recipe.get_passive_time();
+        }
+        time_use.eating = eating_minutes;
+        time_use
+    }
+}
+
+/// A breakdown of time use per day and per recipe
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimeUseReport {
+    days: BTreeMap<NaiveDate, TimeUse>,
+    foods: BTreeMap<String, TimeUse>,
+}
+
+impl TimeUseReport {
+    /// Creates an empty report
+    pub fn new() -> TimeUseReport {
+        TimeUseReport {
+            days: BTreeMap::new(),
+            foods: BTreeMap::new(),
+        }
+    }
+
+    /// Builds the report for a meal plan
+    ///
+    /// Blocks only record when food is ready, not how long it takes to eat, so each meal is
+    /// assumed to take the given number of minutes to eat.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::engine::*;
+    /// use time_for_food::food::*;
+    /// use time_for_food::report::TimeUseReport;
+    ///
+    /// # fn main() {
+    /// let mut simmer = Step::new("simmer", Rational32::from_integer(30));
+    /// simmer.set_attention(Attention::Passive);
+    /// let soup = RecipeBuilder::new("soup")
+    ///     .add_step(Step::new("chop", Rational32::from_integer(10)))
+    ///     .add_step(simmer)
+    ///     .build_with_defaults();
+    ///
+    /// let date = NaiveDate::from_ymd(2024, 1, 8);
+    /// let mut day = Day::new(date);
+    /// day.add_block(Block::new(
+    ///     NaiveTime::from_hms(18, 0, 0),
+    ///     NaiveTime::from_hms(18, 40, 0),
+    ///     Some(Food::new_from_recipe(soup)),
+    /// ));
+    /// let mut plan = MealPlan::new();
+    /// plan.add_day(day);
+    ///
+    /// let report = TimeUseReport::from_plan(&plan, Rational32::from_integer(20));
+    /// let monday = report.get_day(date);
+    /// assert_eq!(monday.get_active(), Rational32::from_integer(10));
+    /// assert_eq!(monday.get_passive(), Rational32::from_integer(30));
+    /// assert_eq!(monday.get_eating(), Rational32::from_integer(20));
+    /// # }
+    /// ```
+    pub fn from_plan(plan: &MealPlan, eating_minutes: Rational32) -> TimeUseReport {
+        let mut report = TimeUseReport::new();
+        for day in plan.get_days() {
+            for food in day.get_blocks().iter().filter_map(|x| x.get_food()) {
+                report.add(day.get_date(), food, eating_minutes);
+            }
+        }
+        report
+    }
+
+    /// Builds the report for a food log
+    ///
+    /// Every logged recipe is assumed to have been cooked for that meal, and every entry is
+    /// assumed to take the given number of minutes to eat.
+    pub fn from_log(log: &FoodLog, eating_minutes: Rational32) -> TimeUseReport {
+        let mut report = TimeUseReport::new();
+        for entry in log.get_entries() {
+            report.add(entry.get_date(), entry.get_food(), eating_minutes);
+        }
+        report
+    }
+
+    fn add(&mut self, date: NaiveDate, food: &Food, eating_minutes: Rational32) {
+        let time_use = TimeUse::of_food(food, eating_minutes);
+        *self.days.entry(date).or_default() += time_use;
+        *self
+            .foods
+            .entry(food.get_name().get_short_code().to_string())
+            .or_default() += time_use;
+    }
+
+    /// Returns the time use on the given date
+    pub fn get_day(&self, date: NaiveDate) -> TimeUse {
+        self.days.get(&date).cloned().unwrap_or_default()
+    }
+
+    /// Returns the time use over the seven days starting on the given date
+    pub fn get_week(&self, start: NaiveDate) -> TimeUse {
+        let mut total = TimeUse::new();
+        for (_, time_use) in self.days.range(start..start + ::chrono::Duration::days(7)) {
+            total += *time_use;
+        }
+        total
+    }
+
+    /// Returns the time use of every day in the report, in date order
+    pub fn get_days(&self) -> Vec<(NaiveDate, TimeUse)> {
+        self.days.iter().map(|(x, y)| (*x, *y)).collect()
+    }
+
+    /// Returns the time use of each food, keyed by short code, in short code order
+    pub fn get_foods(&self) -> Vec<(&str, TimeUse)> {
+        self.foods.iter().map(|(x, y)| (x.as_str(), *y)).collect()
+    }
+
+    /// Returns the time use over the whole report
+    pub fn get_total(&self) -> TimeUse {
+        let mut total = TimeUse::new();
+        for time_use in self.days.values() {
+            total += *time_use;
+        }
+        total
+    }
+}
@@ -0,0 +1,162 @@
+//! This module documents the crate's serialization schema, and provides round trip checks
+//! for verifying storage backends against it
+//!
+//! # Schema
+//!
+//! Every public data structure serializes through serde, and the field names used are part of
+//! the crate's stable interface. In JSON terms:
+//!
+//! - Rationals are stored as `{"numerator": 3, "denominator": 2}`, never as floats, so amounts
+//!   survive any number of round trips exactly.
+//! - An `Amount` is `{"unit": "<Unit>", "amount": <rational>}`, where the unit is a variant
+//!   name such as `"Gram"` or `"Cup"`. A null unit, as written by older versions, reads as
+//!   `"Count"`.
+//! - An `IString` is `{"short_code": "...", "names": {"<lang>": "..."}, "default": "<lang>",
+//!   "plurals": {...}, "scripts": {...}, "updated": {...}, "variants": {...}, "short": {...}}`,
+//!   with every map keyed in sorted order.
+//! - A `Food` is externally tagged, `{"RawFood": {...}}`, `{"Recipe": {...}}`, or
+//!   `{"External": {...}}`.
+//! - Times are `"HH:MM:SS"` strings, dates are `"YYYY-MM-DD"` strings.
+//!
+//! Fields added after the first release are always optional when reading, so files written by
+//! older versions keep loading. `SCHEMA_VERSION` is bumped whenever a change would stop older
+//! versions reading newer files.
+//!
+//! # Examples
+//!
+//! A recipe as written by the first release, before units or nutrition were tracked:
+//!
+//! ```
+//! extern crate num_rational;
+//! extern crate serde_json;
+//! extern crate time_for_food;
+//! use num_rational::Rational32;
+//! use time_for_food::food::*;
+//!
+//! # fn main() {
+//! let v1 = r#"{"Recipe": {
+//!     "name": {"short_code": "toast", "names": {}, "default": ""},
+//!     "serving_size": {"unit": null, "amount": {"numerator": 1, "denominator": 1}},
+//!     "servings": {"numerator": 2, "denominator": 1},
+//!     "foods": [[
+//!         {"RawFood": {
+//!             "name": {"short_code": "bread", "names": {}, "default": ""},
+//!             "serving_size": {"unit": null, "amount": {"numerator": 1, "denominator": 1}},
+//!             "nutrition": null
+//!         }},
+//!         {"unit": null, "amount": {"numerator": 2, "denominator": 1}}
+//!     ]],
+//!     "steps": [{
+//!         "text": {"short_code": "toast", "names": {}, "default": ""},
+//!         "time": {"numerator": 3, "denominator": 1}
+//!     }],
+//!     "time": {"numerator": 3, "denominator": 1},
+//!     "nutrition": null
+//! }}"#;
+//! let food: Food = serde_json::from_str(v1).unwrap();
+//! assert!(food.get_nutrition().is_empty());
+//! assert_eq!(food.get_time(), Rational32::from_integer(3));
+//! match food {
+//!     Food::Recipe(ref toast) => {
+//!         assert_eq!(toast.get_serving_size().get_unit(), Unit::Count);
+//!         assert_eq!(toast.get_foods()[0].0.get_name().get_short_code(), "bread");
+//!     }
+//!     _ => panic!("Should have loaded a recipe"),
+//! }
+//! # }
+//! ```
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json;
+use std::error::Error;
+use std::fmt;
+
+/// The version of the serialization schema described by this module
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Describes how a value failed to survive a round trip
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoundtripError {
+    /// The value could not be encoded
+    Encode(String),
+    /// The encoded value could not be decoded
+    Decode(String),
+    /// The decoded value differs from the original, both are given as JSON
+    Mismatch { expected: String, found: String },
+}
+
+impl fmt::Display for RoundtripError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RoundtripError::Encode(ref x) => write!(f, "failed to encode value: {}", x),
+            RoundtripError::Decode(ref x) => write!(f, "failed to decode value: {}", x),
+            RoundtripError::Mismatch {
+                ref expected,
+                ref found,
+            } => write!(
+                f,
+                "value changed in round trip, expected {} but found {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl Error for RoundtripError {}
+
+/// Checks that a value survives a round trip through the crate's JSON representation
+///
+/// Both the decoded value and its re-encoding must match the original exactly, so this also
+/// checks that serialization is deterministic.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate serde_json;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+/// use time_for_food::schema::roundtrip_check;
+///
+/// # fn main() {
+/// let amount = Amount::new(Unit::Cup, Rational32::new(3, 2));
+/// assert_eq!(roundtrip_check(&amount), Ok(()));
+///
+/// // The golden representation of an amount
+/// assert_eq!(
+///     serde_json::to_string(&amount).unwrap(),
+///     r#"{"unit":"Cup","amount":{"numerator":3,"denominator":2}}"#
+/// );
+/// # }
+/// ```
+pub fn roundtrip_check<T: Serialize + DeserializeOwned + PartialEq>(
+    value: &T,
+) -> Result<(), RoundtripError> {
+    roundtrip_check_with(value, |x| {
+        let encoded = serde_json::to_string(x).map_err(|e| e.to_string())?;
+        serde_json::from_str(&encoded).map_err(|e| e.to_string())
+    })
+}
+
+/// Checks that a value survives a round trip through a custom storage backend
+///
+/// The backend is given the value, and should store it and load it back again. Any error it
+/// reports is treated as a failure to decode. The loaded value is compared to the original
+/// through the crate's JSON representation, which is also what mismatches are reported in.
+pub fn roundtrip_check_with<T, F>(value: &T, backend: F) -> Result<(), RoundtripError>
+where
+    T: Serialize + PartialEq,
+    F: FnOnce(&T) -> Result<T, String>,
+{
+    let expected =
+        serde_json::to_string(value).map_err(|e| RoundtripError::Encode(e.to_string()))?;
+    let decoded = backend(value).map_err(RoundtripError::Decode)?;
+    let found =
+        serde_json::to_string(&decoded).map_err(|e| RoundtripError::Encode(e.to_string()))?;
+    if decoded == *value && found == expected {
+        Ok(())
+    } else {
+        Err(RoundtripError::Mismatch { expected, found })
+    }
+}
@@ -0,0 +1,351 @@
+//! This module contains the household settings, the preferences shared by every part of the
+//! crate
+//!
+//! Rather than passing a language here and a unit system there, a household's preferences are
+//! gathered into one `Settings`, stored alongside its foods on the `FoodStore`.
+use chrono::{FixedOffset, NaiveDate, Offset, Utc};
+use food::appliance::Appliances;
+use food::engine::MealWindows;
+use food::goal::{GoalPeriod, GoalSchedule};
+use food::{Amount, Dimension, Unit};
+use household::Household;
+use num_rational::*;
+use units::ConversionTable;
+
+/// The family of units amounts should be presented in
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UnitSystem {
+    /// Grams, litres, and so on
+    Metric,
+    /// Ounces, cups, and so on, as used in the United States
+    UsCustomary,
+    /// Ounces, pints, and so on, as used in the United Kingdom
+    Imperial,
+}
+
+impl UnitSystem {
+    /// Returns the system's units for a dimension, largest first, empty for dimensions every
+    /// system shares
+    ///
+    /// There are no pints among the units, so imperial amounts use the same units as US
+    /// customary ones.
+    pub fn get_units(self, dimension: Dimension) -> &'static [Unit] {
+        match (self, dimension) {
+            (UnitSystem::Metric, Dimension::Mass) => &[Unit::Kilogram, Unit::Gram, Unit::Milligram],
+            (UnitSystem::Metric, Dimension::Volume) => &[Unit::Liter, Unit::Milliliter],
+            (_, Dimension::Mass) => &[Unit::Pound, Unit::Ounce],
+            (_, Dimension::Volume) => &[Unit::Cup, Unit::Tablespoon, Unit::Teaspoon],
+            _ => &[],
+        }
+    }
+
+    /// Converts an amount into the system's units, if it isn't in one already
+    ///
+    /// The amount is put in the largest of the system's units it is at least one of, or the
+    /// smallest if there is none. Amounts that can't be converted are returned as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::settings::UnitSystem;
+    /// use time_for_food::units::ConversionTable;
+    ///
+    /// # fn main() {
+    /// let conversions = ConversionTable::new();
+    /// let butter = Amount::new(Unit::Gram, Rational32::from_integer(1500));
+    /// let presented = UnitSystem::UsCustomary.present(&butter, &conversions);
+    /// assert_eq!(presented.get_unit(), Unit::Pound);
+    /// let presented = UnitSystem::Metric.present(&butter, &conversions);
+    /// assert_eq!(presented.get_unit(), Unit::Gram);
+    ///
+    /// // Less than a teaspoon is still measured in teaspoons
+    /// let vanilla = Amount::new(Unit::Milliliter, Rational32::from_integer(2));
+    /// let presented = UnitSystem::UsCustomary.present(&vanilla, &conversions);
+    /// assert_eq!(presented.get_unit(), Unit::Teaspoon);
+    /// # }
+    /// ```
+    pub fn present(self, amount: &Amount, conversions: &ConversionTable) -> Amount {
+        let units = self.get_units(amount.get_unit().get_dimension());
+        if units.is_empty() || units.contains(&amount.get_unit()) {
+            return *amount;
+        }
+        let one = Rational32::from_integer(1);
+        let converted: Vec<Amount> = units
+            .iter()
+            .filter_map(|x| amount.convert_with(*x, conversions).ok())
+            .collect();
+        converted
+            .iter()
+            .find(|x| x.get_amount() >= one)
+            .or_else(|| converted.last())
+            .cloned()
+            .unwrap_or(*amount)
+    }
+}
+
+/// How amounts should be rounded for display
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RoundingPolicy {
+    /// Show amounts exactly as computed
+    Exact,
+    /// Round to the nearest multiple of one over the given denominator, such as quarters
+    Nearest(u8),
+}
+
+impl RoundingPolicy {
+    /// Rounds a value according to the policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::settings::RoundingPolicy;
+    ///
+    /// # fn main() {
+    /// let quarters = RoundingPolicy::Nearest(4);
+    /// assert_eq!(quarters.apply(Rational32::new(7, 10)), Rational32::new(3, 4));
+    /// # }
+    /// ```
+    pub fn apply(self, value: Rational32) -> Rational32 {
+        match self {
+            RoundingPolicy::Exact | RoundingPolicy::Nearest(0) => value,
+            RoundingPolicy::Nearest(denominator) => {
+                let step = Rational32::new(1, i32::from(denominator));
+                (value / step).round() * step
+            }
+        }
+    }
+}
+
+/// A household's preferences
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Settings {
+    /// Language code used when rendering IStrings
+    language: String,
+    /// Family of units amounts are presented in, see `Formatter::from_settings`
+    unit_system: UnitSystem,
+    rounding: RoundingPolicy,
+    /// Name of the goal period nutrition is measured against, in place of whichever is in
+    /// effect, if there is one
+    #[serde(default)]
+    nutrition_profile: Option<String>,
+    /// Offset from UTC, in minutes, that exported times are in
+    utc_offset_minutes: i32,
+    /// How the household's kitchen differs from the one recipes are written for
+    #[serde(default)]
+    appliances: Appliances,
+    /// The people the household cooks for
+    #[serde(default)]
+    household: Household,
+    /// The times of day each meal is eaten in
+    #[serde(default)]
+    meal_windows: MealWindows,
+    /// The household's nutrition goals, phase by phase
+    #[serde(default)]
+    goals: GoalSchedule,
+    /// The household's own unit sizes and food densities
+    #[serde(default)]
+    conversions: ConversionTable,
+}
+
+impl Default for Settings {
+    /// English, metric, exact amounts, no nutrition profile, UTC, a kitchen that behaves as
+    /// recipes are written, nobody to cook for yet, the default meal windows, no nutrition
+    /// goals, and the standard unit conversions
+    fn default() -> Settings {
+        Settings {
+            language: "en_US".to_string(),
+            unit_system: UnitSystem::Metric,
+            rounding: RoundingPolicy::Exact,
+            nutrition_profile: None,
+            utc_offset_minutes: 0,
+            appliances: Appliances::new(),
+            household: Household::new(),
+            meal_windows: MealWindows::new(),
+            goals: GoalSchedule::new(),
+            conversions: ConversionTable::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Creates the default Settings
+    pub fn new() -> Settings {
+        Settings::default()
+    }
+
+    /// Returns the language code IStrings are rendered in
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+
+    /// Sets the language code IStrings are rendered in
+    pub fn set_language(&mut self, language: &str) -> &mut Self {
+        self.language = language.to_string();
+        self
+    }
+
+    /// Returns the family of units amounts are presented in
+    pub fn get_unit_system(&self) -> UnitSystem {
+        self.unit_system
+    }
+
+    /// Sets the family of units amounts are presented in
+    pub fn set_unit_system(&mut self, unit_system: UnitSystem) -> &mut Self {
+        self.unit_system = unit_system;
+        self
+    }
+
+    /// Returns how amounts are rounded for display
+    pub fn get_rounding(&self) -> RoundingPolicy {
+        self.rounding
+    }
+
+    /// Sets how amounts are rounded for display
+    pub fn set_rounding(&mut self, rounding: RoundingPolicy) -> &mut Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Returns the name of the goal period nutrition is measured against, if there is one
+    pub fn get_nutrition_profile(&self) -> Option<&str> {
+        self.nutrition_profile.as_deref()
+    }
+
+    /// Sets the name of the goal period nutrition is measured against, or goes back to the
+    /// period in effect on each day if given None
+    pub fn set_nutrition_profile(&mut self, profile: Option<&str>) -> &mut Self {
+        self.nutrition_profile = profile.map(|x| x.to_string());
+        self
+    }
+
+    /// Returns the goal period nutrition on the given date is measured against
+    ///
+    /// This is the period named by the nutrition profile, if there is one and the goals have
+    /// a period by that name, and otherwise the period in effect on the date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// extern crate time_for_food;
+    /// use chrono::prelude::*;
+    /// use time_for_food::food::goal::*;
+    /// use time_for_food::settings::Settings;
+    ///
+    /// # fn main() {
+    /// let january = NaiveDate::from_ymd(2024, 1, 1);
+    /// let mut settings = Settings::new();
+    /// settings
+    ///     .get_mut_goals()
+    ///     .add_period(GoalPeriod::new("maintenance", january))
+    ///     .add_period(GoalPeriod::new("cutting", NaiveDate::from_ymd(2024, 3, 1)));
+    ///
+    /// let april = NaiveDate::from_ymd(2024, 4, 1);
+    /// assert_eq!(settings.nutrition_goals_on(april).unwrap().get_name(), "cutting");
+    /// settings.set_nutrition_profile(Some("maintenance"));
+    /// assert_eq!(settings.nutrition_goals_on(april).unwrap().get_name(), "maintenance");
+    /// # }
+    /// ```
+    pub fn nutrition_goals_on(&self, date: NaiveDate) -> Option<&GoalPeriod> {
+        let profile = self.nutrition_profile.as_ref().and_then(|name| {
+            self.goals
+                .get_periods()
+                .iter()
+                .find(|x| x.get_name() == name)
+        });
+        profile.or_else(|| self.goals.period_on(date))
+    }
+
+    /// Returns the timezone exported times are in
+    ///
+    /// Offsets further than a day from UTC, which only a hand-edited file can hold, are
+    /// clamped to within a day of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate serde_json;
+    /// extern crate time_for_food;
+    /// use time_for_food::settings::Settings;
+    ///
+    /// # fn main() {
+    /// let mut json = serde_json::to_value(Settings::new()).unwrap();
+    /// json["utc_offset_minutes"] = serde_json::Value::from(100000);
+    /// let settings: Settings = serde_json::from_value(json).unwrap();
+    /// assert_eq!(settings.get_timezone().local_minus_utc(), 1439 * 60);
+    /// # }
+    /// ```
+    pub fn get_timezone(&self) -> FixedOffset {
+        let minutes = self.utc_offset_minutes.clamp(-1439, 1439);
+        FixedOffset::east_opt(minutes * 60).unwrap_or_else(|| Utc.fix())
+    }
+
+    /// Sets the timezone exported times are in, as an offset from UTC in minutes
+    ///
+    /// Offsets are clamped to within a day either side of UTC.
+    pub fn set_utc_offset(&mut self, minutes: i32) -> &mut Self {
+        self.utc_offset_minutes = minutes.clamp(-1439, 1439);
+        self
+    }
+
+    /// Returns the household's appliance profiles
+    pub fn get_appliances(&self) -> &Appliances {
+        &self.appliances
+    }
+
+    /// Returns a mutable refrence to the household's appliance profiles
+    pub fn get_mut_appliances(&mut self) -> &mut Appliances {
+        &mut self.appliances
+    }
+
+    /// Returns the people the household cooks for
+    pub fn get_household(&self) -> &Household {
+        &self.household
+    }
+
+    /// Returns a mutable refrence to the people the household cooks for
+    pub fn get_mut_household(&mut self) -> &mut Household {
+        &mut self.household
+    }
+
+    /// Returns the times of day each meal is eaten in
+    pub fn get_meal_windows(&self) -> &MealWindows {
+        &self.meal_windows
+    }
+
+    /// Returns a mutable refrence to the times of day each meal is eaten in
+    pub fn get_mut_meal_windows(&mut self) -> &mut MealWindows {
+        &mut self.meal_windows
+    }
+
+    /// Returns the household's nutrition goals
+    pub fn get_goals(&self) -> &GoalSchedule {
+        &self.goals
+    }
+
+    /// Returns a mutable refrence to the household's nutrition goals
+    pub fn get_mut_goals(&mut self) -> &mut GoalSchedule {
+        &mut self.goals
+    }
+
+    /// Returns the household's own unit sizes and food densities
+    ///
+    /// These are used wherever the settings are passed, such as `Food::decompose`, and can be
+    /// given to `Amount::convert_with` directly, so every conversion agrees on what the
+    /// household's cup holds.
+    pub fn get_conversions(&self) -> &ConversionTable {
+        &self.conversions
+    }
+
+    /// Returns the household's own unit sizes and food densities, for changing
+    pub fn get_mut_conversions(&mut self) -> &mut ConversionTable {
+        &mut self.conversions
+    }
+}
@@ -0,0 +1,499 @@
+//! This module contains shopping lists, and the store layouts used to sort them
+//!
+//! Items on a list carry an optional category ("produce", "dairy"), which a `StoreLayout`
+//! orders to match the aisles of a particular physical store, so the list can be walked
+//! front to back rather than read alphabetically.
+use food::*;
+use num_rational::*;
+use pantry::Pantry;
+use settings::Settings;
+use store::FoodId;
+use warning::{Warning, WithWarnings};
+
+/// A single item on a shopping list
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ShoppingItem {
+    name: IString,
+    amount: Amount,
+    category: Option<String>,
+    /// How much of the item comes in a single package, if it's sold in packages
+    #[serde(default)]
+    package_size: Option<Fraction>,
+    #[serde(default)]
+    checked: bool,
+}
+
+impl ShoppingItem {
+    /// Creates a new, uncategorized, ShoppingItem
+    pub fn new(name: IString, amount: Amount) -> ShoppingItem {
+        ShoppingItem {
+            name,
+            amount,
+            category: None,
+            package_size: None,
+            checked: false,
+        }
+    }
+
+    /// Returns the name of the item
+    pub fn get_name(&self) -> &IString {
+        &self.name
+    }
+
+    /// Returns how much of the item to buy
+    pub fn get_amount(&self) -> Amount {
+        self.amount
+    }
+
+    /// Sets how much of the item to buy
+    pub fn set_amount(&mut self, amount: Rational32) {
+        self.amount.set_amount(amount);
+    }
+
+    /// Returns the category of the item, if it has one
+    pub fn get_category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    /// Sets the category of the item, or removes it if given None
+    pub fn set_category(&mut self, category: Option<&str>) {
+        self.category = category.map(|x| x.to_string());
+    }
+
+    /// Returns how much of the item comes in a single package, if it's sold in packages
+    pub fn get_package_size(&self) -> Option<Rational32> {
+        self.package_size.map(|x| x.to_rational())
+    }
+
+    /// Sets how much of the item comes in a single package, or None if it's sold loose
+    pub fn set_package_size(&mut self, size: Option<Rational32>) {
+        self.package_size = size.map(Fraction::from_rational);
+    }
+
+    /// Returns how much of the item is actually bought
+    ///
+    /// For items sold in packages, this is the amount needed rounded up to whole packages.
+    pub fn get_purchased_amount(&self) -> Amount {
+        let mut amount = self.amount;
+        if let Some(size) = self.get_package_size() {
+            if size > Rational32::from_integer(0) {
+                amount.set_amount((amount.get_amount() / size).ceil() * size);
+            }
+        }
+        amount
+    }
+
+    /// Returns how much of the item is actually bought, as `get_purchased_amount` does,
+    /// warning when it was rounded up to whole packages
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::shopping::ShoppingItem;
+    /// use time_for_food::warning::Warning;
+    ///
+    /// # fn main() {
+    /// let needed = Amount::new(Unit::Gram, Rational32::from_integer(300));
+    /// let mut butter = ShoppingItem::new(IString::new("butter"), needed);
+    /// butter.set_package_size(Some(Rational32::from_integer(250)));
+    ///
+    /// let purchased = butter.get_purchased_amount_with_warnings();
+    /// assert_eq!(purchased.get_value().get_amount(), Rational32::from_integer(500));
+    /// assert_eq!(
+    ///     purchased.get_warnings(),
+    ///     [Warning::PackageRounding {
+    ///         needed: Rational32::from_integer(300),
+    ///         purchased: Rational32::from_integer(500),
+    ///     }]
+    /// );
+    /// # }
+    /// ```
+    pub fn get_purchased_amount_with_warnings(&self) -> WithWarnings<Amount> {
+        let purchased = self.get_purchased_amount();
+        let needed = self.amount.get_amount();
+        let mut result = WithWarnings::new(purchased);
+        if purchased.get_amount() != needed {
+            result.add_warning(Warning::PackageRounding {
+                needed,
+                purchased: purchased.get_amount(),
+            });
+        }
+        result
+    }
+
+    /// Returns true if the item has been checked off
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Checks off the item, or unchecks it
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+}
+
+/// A list of things to buy
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ShoppingList {
+    items: Vec<ShoppingItem>,
+}
+
+impl ShoppingList {
+    /// Creates a new, empty, ShoppingList
+    pub fn new() -> ShoppingList {
+        ShoppingList { items: Vec::new() }
+    }
+
+    /// Adds an item to the end of the list
+    pub fn add_item(&mut self, item: ShoppingItem) {
+        self.items.push(item);
+    }
+
+    /// Removes the item at the given index
+    ///
+    /// # Returns
+    /// The removed item, or None if there was no item at that index
+    pub fn remove_item(&mut self, index: usize) -> Option<ShoppingItem> {
+        if index < self.items.len() {
+            Some(self.items.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the items on the list, in the order they were added
+    pub fn get_items(&self) -> &[ShoppingItem] {
+        &self.items
+    }
+
+    /// Returns a mutable refrence to the item at the given index, if there is one
+    pub fn get_mut_item(&mut self, index: usize) -> Option<&mut ShoppingItem> {
+        self.items.get_mut(index)
+    }
+
+    /// Checks off, or unchecks, the item at the given index
+    ///
+    /// # Returns
+    /// False, without changing anything, if there was no item at that index
+    pub fn set_checked(&mut self, index: usize, checked: bool) -> bool {
+        match self.items.get_mut(index) {
+            Some(item) => {
+                item.checked = checked;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the items that have been checked off
+    pub fn checked_items(&self) -> Vec<&ShoppingItem> {
+        self.items.iter().filter(|x| x.checked).collect()
+    }
+
+    /// Moves every checked off item into the pantry, removing it from the list
+    ///
+    /// Items sold in packages are deposited as whole packages, see
+    /// `ShoppingItem::get_purchased_amount`. Items in a unit that can't be converted to the
+    /// unit already in the pantry, with the household's unit sizes, are left on the list, still
+    /// checked off.
+    ///
+    /// # Returns
+    /// The number of items posted
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::pantry::Pantry;
+    /// use time_for_food::settings::Settings;
+    /// use time_for_food::shopping::*;
+    /// use time_for_food::store::FoodId;
+    ///
+    /// # fn main() {
+    /// let mut flour = ShoppingItem::new(
+    ///     IString::new("flour"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(1200)),
+    /// );
+    /// // Flour comes in 1 kg bags
+    /// flour.set_package_size(Some(Rational32::from_integer(1000)));
+    /// let mut list = ShoppingList::new();
+    /// list.add_item(flour);
+    /// list.set_checked(0, true);
+    ///
+    /// let mut pantry = Pantry::new();
+    /// assert_eq!(list.post_to_pantry(&mut pantry, &Settings::new()), 1);
+    /// assert!(list.get_items().is_empty());
+    /// let on_hand = pantry.get(&FoodId::new("flour")).unwrap();
+    /// assert_eq!(on_hand.get_amount(), Rational32::from_integer(2000));
+    /// # }
+    /// ```
+    pub fn post_to_pantry(&mut self, pantry: &mut Pantry, settings: &Settings) -> usize {
+        let (checked, unchecked): (Vec<ShoppingItem>, Vec<ShoppingItem>) =
+            self.items.drain(..).partition(|x| x.checked);
+        self.items = unchecked;
+        let mut posted = 0;
+        for item in checked {
+            let id = FoodId::new(item.name.get_short_code());
+            match pantry.deposit(id, item.get_purchased_amount(), settings) {
+                Ok(()) => posted += 1,
+                Err(_) => self.items.push(item),
+            }
+        }
+        posted
+    }
+
+    /// Takes what is already on hand off the list, returning how many items were removed
+    ///
+    /// Each item's amount is reduced by how much of it the pantry has, converted to the
+    /// item's unit with the household's unit sizes where possible, and items the pantry has
+    /// enough of are removed. The pantry itself is left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::pantry::Pantry;
+    /// use time_for_food::settings::Settings;
+    /// use time_for_food::shopping::*;
+    /// use time_for_food::store::FoodId;
+    ///
+    /// # fn main() {
+    /// let mut list = ShoppingList::new();
+    /// for &(name, grams) in &[("flour", 500), ("sugar", 200)] {
+    ///     list.add_item(ShoppingItem::new(
+    ///         IString::new(name),
+    ///         Amount::new(Unit::Gram, Rational32::from_integer(grams)),
+    ///     ));
+    /// }
+    /// let mut pantry = Pantry::new();
+    /// pantry.set(FoodId::new("flour"), Amount::new(Unit::Kilogram, Rational32::new(1, 5)));
+    /// pantry.set(FoodId::new("sugar"), Amount::new(Unit::Gram, Rational32::from_integer(250)));
+    ///
+    /// assert_eq!(list.subtract_pantry(&pantry, &Settings::new()), 1);
+    /// let flour = &list.get_items()[0];
+    /// assert_eq!(flour.get_amount().get_amount(), Rational32::from_integer(300));
+    /// # }
+    /// ```
+    pub fn subtract_pantry(&mut self, pantry: &Pantry, settings: &Settings) -> usize {
+        let before = self.items.len();
+        let zero = Rational32::from_integer(0);
+        for item in &mut self.items {
+            let id = FoodId::new(item.name.get_short_code());
+            let unit = item.amount.get_unit();
+            if let Some(on_hand) = pantry.available_in(&id, unit, settings) {
+                let left = item.amount.get_amount() - ::std::cmp::max(on_hand, zero);
+                item.amount.set_amount(::std::cmp::max(left, zero));
+            }
+        }
+        self.items.retain(|x| x.amount.get_amount() > zero);
+        before - self.items.len()
+    }
+
+    /// Returns the items on the list, sorted by their short codes
+    pub fn sorted(&self) -> Vec<&ShoppingItem> {
+        let mut items: Vec<&ShoppingItem> = self.items.iter().collect();
+        items.sort_by(|a, b| a.name.get_short_code().cmp(b.name.get_short_code()));
+        items
+    }
+
+    /// Returns the items on the list, in the order they are found walking the given store
+    ///
+    /// Items are sorted by where their category falls in the layout, and by short code within
+    /// a category. Items without a category, or with one the layout doesn't know, come last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::shopping::*;
+    ///
+    /// # fn main() {
+    /// let mut list = ShoppingList::new();
+    /// for &(name, category) in &[("apples", "produce"), ("milk", "dairy"), ("bread", "bakery")] {
+    ///     let mut item = ShoppingItem::new(
+    ///         IString::new(name),
+    ///         Amount::new(Unit::Count, Rational32::from_integer(1)),
+    ///     );
+    ///     item.set_category(Some(category));
+    ///     list.add_item(item);
+    /// }
+    ///
+    /// let mut corner_shop = StoreLayout::new("corner-shop");
+    /// corner_shop.add_category("bakery").add_category("dairy").add_category("produce");
+    ///
+    /// let order: Vec<&str> = list
+    ///     .sorted_for(&corner_shop)
+    ///     .iter()
+    ///     .map(|x| x.get_name().get_short_code())
+    ///     .collect();
+    /// assert_eq!(order, vec!["bread", "milk", "apples"]);
+    /// # }
+    /// ```
+    pub fn sorted_for(&self, layout: &StoreLayout) -> Vec<&ShoppingItem> {
+        let mut items = self.sorted();
+        // The sort is stable, so items keep their short code order within a category
+        items.sort_by_key(|x| layout.position_of(x.get_category()));
+        items
+    }
+
+    /// Groups the items by category, in the order of a typical store, see
+    /// `StoreLayout::typical`
+    pub fn group_by_category(&self) -> Vec<(Option<&str>, Vec<&ShoppingItem>)> {
+        self.group_by_category_for(&StoreLayout::typical())
+    }
+
+    /// Groups the items by category, in the order the categories are found walking the given
+    /// store
+    ///
+    /// Categories the layout doesn't know come after those it does, in alphabetical order,
+    /// and items without a category come last. Items are sorted by short code within a group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::shopping::*;
+    ///
+    /// # fn main() {
+    /// let mut list = ShoppingList::new();
+    /// let items = [
+    ///     ("milk", Some("dairy")),
+    ///     ("foil", None),
+    ///     ("pears", Some("produce")),
+    ///     ("apples", Some("produce")),
+    /// ];
+    /// for &(name, category) in &items {
+    ///     let mut item = ShoppingItem::new(
+    ///         IString::new(name),
+    ///         Amount::new(Unit::Count, Rational32::from_integer(1)),
+    ///     );
+    ///     item.set_category(category);
+    ///     list.add_item(item);
+    /// }
+    ///
+    /// let groups: Vec<(Option<&str>, Vec<&str>)> = list
+    ///     .group_by_category()
+    ///     .into_iter()
+    ///     .map(|(category, items)| {
+    ///         (category, items.iter().map(|x| x.get_name().get_short_code()).collect())
+    ///     })
+    ///     .collect();
+    /// assert_eq!(
+    ///     groups,
+    ///     vec![
+    ///         (Some("produce"), vec!["apples", "pears"]),
+    ///         (Some("dairy"), vec!["milk"]),
+    ///         (None, vec!["foil"]),
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    pub fn group_by_category_for(
+        &self,
+        layout: &StoreLayout,
+    ) -> Vec<(Option<&str>, Vec<&ShoppingItem>)> {
+        let mut items = self.sorted();
+        // The sort is stable, so items keep their short code order within a category
+        items.sort_by_key(|x| {
+            let category = x.get_category();
+            (
+                category.is_none(),
+                layout.position_of(category),
+                category.map(|x| x.to_string()),
+            )
+        });
+        let mut groups: Vec<(Option<&str>, Vec<&ShoppingItem>)> = Vec::new();
+        for item in items {
+            match groups.last_mut() {
+                Some(group) if group.0 == item.get_category() => group.1.push(item),
+                _ => groups.push((item.get_category(), vec![item])),
+            }
+        }
+        groups
+    }
+}
+
+/// The order categories are found in when walking a particular physical store
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StoreLayout {
+    name: String,
+    categories: Vec<String>,
+}
+
+impl StoreLayout {
+    /// Creates a new StoreLayout, with no categories
+    pub fn new(name: &str) -> StoreLayout {
+        StoreLayout {
+            name: name.to_string(),
+            categories: Vec::new(),
+        }
+    }
+
+    /// Returns the layout of a typical supermarket, used when no particular store is given
+    ///
+    /// The categories are, in order: produce, bakery, meat, seafood, deli, dairy, frozen,
+    /// pantry, spices, snacks, beverages, and household.
+    pub fn typical() -> StoreLayout {
+        let mut layout = StoreLayout::new("typical");
+        for category in &[
+            "produce",
+            "bakery",
+            "meat",
+            "seafood",
+            "deli",
+            "dairy",
+            "frozen",
+            "pantry",
+            "spices",
+            "snacks",
+            "beverages",
+            "household",
+        ] {
+            layout.add_category(category);
+        }
+        layout
+    }
+
+    /// Returns the name of the store
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Adds a category after all the existing ones, doing nothing if it is already present
+    pub fn add_category(&mut self, category: &str) -> &mut Self {
+        if !self.categories.iter().any(|x| x == category) {
+            self.categories.push(category.to_string());
+        }
+        self
+    }
+
+    /// Returns the categories, in walking order
+    pub fn get_categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    /// Returns where a category falls in walking order
+    ///
+    /// Missing and unknown categories sort after every known one.
+    pub fn position_of(&self, category: Option<&str>) -> usize {
+        category
+            .and_then(|c| self.categories.iter().position(|x| x == c))
+            .unwrap_or(self.categories.len())
+    }
+}
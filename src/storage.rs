@@ -0,0 +1,126 @@
+//! This module contains helpers for saving foods to, and loading them from, files
+//!
+//! Foods are written as pretty printed JSON, wrapped with the `SCHEMA_VERSION` they were written
+//! with, `{"version": 2, "foods": [...]}`. Files written by a newer version of the schema are
+//! refused rather than partially read, and a bare list of foods, as written before the wrapper
+//! was introduced, is read as version 1.
+use food::*;
+use schema::SCHEMA_VERSION;
+use serde_json;
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Describes why foods could not be saved or loaded
+#[derive(Debug)]
+pub enum StorageError {
+    /// The file could not be read or written
+    Io(io::Error),
+    /// The file was read, but its contents could not be understood
+    Parse(serde_json::Error),
+    /// The file was written with a newer version of the schema, includes that version
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StorageError::Io(ref x) => write!(f, "failed to access file: {}", x),
+            StorageError::Parse(ref x) => write!(f, "failed to parse file: {}", x),
+            StorageError::UnsupportedVersion(x) => write!(
+                f,
+                "file uses schema version {}, but only up to {} is supported",
+                x, SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(error: io::Error) -> StorageError {
+        StorageError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(error: serde_json::Error) -> StorageError {
+        StorageError::Parse(error)
+    }
+}
+
+#[derive(Serialize)]
+struct VersionedFoods<'a> {
+    version: u32,
+    foods: &'a [Food],
+}
+
+#[derive(Deserialize)]
+struct StoredFoods {
+    version: u32,
+    foods: Value,
+}
+
+/// Saves foods to a file, replacing anything already there
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+/// use time_for_food::storage::{load_foods, save_foods};
+///
+/// # fn main() {
+/// let milk = RawFoodBuilder::new("milk")
+///     .set_serving_size(Unit::Milliliter, Rational32::from_integer(250))
+///     .set_nutrition(Nutrition::new())
+///     .build_raw_food()
+///     .unwrap();
+/// let path = std::env::temp_dir().join("time_for_food_storage_doctest.json");
+///
+/// save_foods(&path, &[Food::new_from_raw_food(milk)]).unwrap();
+/// let foods = load_foods(&path).unwrap();
+/// assert_eq!(foods.len(), 1);
+/// assert_eq!(foods[0].get_name().get_short_code(), "milk");
+/// # std::fs::remove_file(&path).unwrap();
+/// # }
+/// ```
+pub fn save_foods<P: AsRef<Path>>(path: P, foods: &[Food]) -> Result<(), StorageError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(
+        &mut writer,
+        &VersionedFoods {
+            version: SCHEMA_VERSION,
+            foods,
+        },
+    )?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Loads the foods saved in a file
+///
+/// Returns `StorageError::UnsupportedVersion` if the file was written with a newer schema than
+/// this version of the crate understands.
+pub fn load_foods<P: AsRef<Path>>(path: P) -> Result<Vec<Food>, StorageError> {
+    let reader = BufReader::new(File::open(path)?);
+    let value: Value = serde_json::from_reader(reader)?;
+    let foods = if value.is_array() {
+        value
+    } else {
+        let stored: StoredFoods = serde_json::from_value(value)?;
+        if stored.version > SCHEMA_VERSION {
+            return Err(StorageError::UnsupportedVersion(stored.version));
+        }
+        stored.foods
+    };
+    Ok(serde_json::from_value(foods)?)
+}
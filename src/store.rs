@@ -0,0 +1,1105 @@
+//! This module contains the food store, the collection of every food a household knows about
+//!
+//! Foods are keyed by a `FoodId`, which is simply the short code of the food's name. Alongside
+//! each food the store keeps bookkeeping that isn't part of the food itself, such as how
+//! complete its data is. The store also carries the household's `Settings`.
+//!
+//! Foods are kept in id order, so saved stores serialize identically from one save to the
+//! next and diff cleanly under version control.
+//!
+//! Deleting a food recipes still use would leave them referring to nothing, so foods are
+//! normally moved to the trash, which refuses foods still in use, rather than removed
+//! outright. Trashed foods can be restored until the trash is purged.
+use food::*;
+use num_rational::*;
+use settings::Settings;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::ops::Bound;
+
+/// How many recipes `StoreStats` lists as the largest
+pub const LARGEST_RECIPES: usize = 5;
+
+/// Identifies a food within a FoodStore, by the short code of its name
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FoodId(String);
+
+impl FoodId {
+    /// Creates a FoodId from a short code
+    pub fn new(short_code: &str) -> FoodId {
+        FoodId(short_code.to_string())
+    }
+
+    /// Returns the FoodId of a food
+    pub fn of(food: &Food) -> FoodId {
+        FoodId::new(food.get_name().get_short_code())
+    }
+
+    /// Returns the short code the FoodId refers to
+    pub fn get_short_code(&self) -> &str {
+        &self.0
+    }
+}
+
+/// How complete a food's data is
+///
+/// Only verified foods are trusted in nutrition reports, as quickly captured foods typically
+/// have empty nutrition that would otherwise be counted as zeros. See
+/// `GoalSchedule::report_trusted`, which leaves the rest out.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum FoodStatus {
+    /// Quickly captured, and not yet reviewed
+    Draft,
+    /// Reviewed, but the nutrition still needs filling in
+    NeedsNutrition,
+    /// Complete, and checked by a person
+    Verified,
+}
+
+impl FoodStatus {
+    /// Returns true if foods with this status have nutrition fit for reports
+    pub fn has_trusted_nutrition(self) -> bool {
+        self == FoodStatus::Verified
+    }
+}
+
+/// What to do when inserting a food whose id the store already has
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Replace the existing food, keeping its tags
+    Replace,
+    /// Keep the existing food, and drop the new one
+    Skip,
+    /// Insert nothing at all
+    Fail,
+}
+
+/// Describes why a batch operation on the store failed, leaving the store unchanged
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchError {
+    /// A food's id was already taken, either in the store or earlier in the batch
+    Conflict(FoodId),
+    /// An amount couldn't be converted
+    Conversion { id: FoodId, error: ConversionError },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BatchError::Conflict(ref id) => {
+                write!(f, "a food with id {} already exists", id.get_short_code())
+            }
+            BatchError::Conversion { ref id, ref error } => {
+                write!(f, "failed to convert {}: {}", id.get_short_code(), error)
+            }
+        }
+    }
+}
+
+impl Error for BatchError {}
+
+/// Describes why a food couldn't be moved into or out of the trash
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrashError {
+    /// There is no such food to move
+    NotFound(FoodId),
+    /// Recipes in the store still use the food
+    InUse { id: FoodId, used_by: Vec<FoodId> },
+    /// A food with the same id has been added since it was trashed
+    Conflict(FoodId),
+}
+
+impl fmt::Display for TrashError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TrashError::NotFound(ref id) => write!(f, "no food with id {}", id.get_short_code()),
+            TrashError::InUse {
+                ref id,
+                ref used_by,
+            } => write!(
+                f,
+                "{} is used by {} {}",
+                id.get_short_code(),
+                used_by.len(),
+                if used_by.len() == 1 {
+                    "recipe"
+                } else {
+                    "recipes"
+                }
+            ),
+            TrashError::Conflict(ref id) => {
+                write!(f, "a food with id {} already exists", id.get_short_code())
+            }
+        }
+    }
+}
+
+impl Error for TrashError {}
+
+/// An overview of a store's contents and health, for maintenance dashboards
+///
+/// Lists of foods are sorted by id, except for the largest recipes, which are largest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StoreStats {
+    raw_foods: usize,
+    recipes: usize,
+    external_meals: usize,
+    missing_nutrition: Vec<FoodId>,
+    missing_translations: Vec<FoodId>,
+    orphaned_raw_foods: Vec<FoodId>,
+    average_recipe_time: Option<Rational32>,
+    largest_recipes: Vec<(FoodId, usize)>,
+}
+
+impl StoreStats {
+    /// Returns the number of raw foods in the store
+    pub fn get_raw_foods(&self) -> usize {
+        self.raw_foods
+    }
+
+    /// Returns the number of recipes in the store
+    pub fn get_recipes(&self) -> usize {
+        self.recipes
+    }
+
+    /// Returns the number of external meals in the store
+    pub fn get_external_meals(&self) -> usize {
+        self.external_meals
+    }
+
+    /// Returns the recipes with no nutritional information at all
+    pub fn get_missing_nutrition(&self) -> &[FoodId] {
+        &self.missing_nutrition
+    }
+
+    /// Returns the recipes without a name in the household's language
+    pub fn get_missing_translations(&self) -> &[FoodId] {
+        &self.missing_translations
+    }
+
+    /// Returns the raw foods no recipe in the store uses, directly or through a component
+    /// recipe
+    pub fn get_orphaned_raw_foods(&self) -> &[FoodId] {
+        &self.orphaned_raw_foods
+    }
+
+    /// Returns the average time a recipe takes, in minutes, or None if there are no recipes
+    pub fn get_average_recipe_time(&self) -> Option<Rational32> {
+        self.average_recipe_time
+    }
+
+    /// Returns up to `LARGEST_RECIPES` recipes with the most ingredients, and how many
+    /// ingredients they have, largest first
+    pub fn get_largest_recipes(&self) -> &[(FoodId, usize)] {
+        &self.largest_recipes
+    }
+}
+
+/// A single page of the foods matching a query, see `FoodStore::query_page`
+pub struct QueryPage<'a> {
+    items: Vec<(&'a FoodId, &'a Food)>,
+    next: Option<FoodId>,
+}
+
+impl<'a> QueryPage<'a> {
+    /// Returns the foods on this page, sorted by id
+    pub fn get_items(&self) -> &[(&'a FoodId, &'a Food)] {
+        &self.items
+    }
+
+    /// Returns the cursor to pass to `FoodStore::query_page` for the following page
+    ///
+    /// None if this is the last page.
+    pub fn get_next(&self) -> Option<&FoodId> {
+        self.next.as_ref()
+    }
+}
+
+/// A description of which foods to pick out of a store, which can be saved as a smart collection
+///
+/// A food matches when it meets every condition set on the query, so an empty query matches
+/// everything. Tags match whether they are on the food itself or only in the store.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Query {
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    max_time: Option<Fraction>,
+    #[serde(default)]
+    status: Option<FoodStatus>,
+    #[serde(default)]
+    recipes_only: bool,
+}
+
+impl Query {
+    /// Creates a new query, matching every food
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    /// Returns the tags a food must have, all of them, to match
+    pub fn get_tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Requires matching foods to have the given tag
+    pub fn add_tag(&mut self, tag: &str) -> &mut Self {
+        if !self.tags.iter().any(|x| x == tag) {
+            self.tags.push(tag.to_string());
+        }
+        self
+    }
+
+    /// Returns the longest a matching food may take, in miniutes
+    pub fn get_max_time(&self) -> Option<Rational32> {
+        self.max_time.map(|x| x.to_rational())
+    }
+
+    /// Sets the longest a matching food may take, in miniutes
+    pub fn set_max_time(&mut self, max_time: Rational32) -> &mut Self {
+        self.max_time = Some(Fraction::from_rational(max_time));
+        self
+    }
+
+    /// Returns the status a food must have to match, if any
+    pub fn get_status(&self) -> Option<FoodStatus> {
+        self.status
+    }
+
+    /// Requires matching foods to have the given status
+    pub fn set_status(&mut self, status: FoodStatus) -> &mut Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Returns true if only recipes can match
+    pub fn is_recipes_only(&self) -> bool {
+        self.recipes_only
+    }
+
+    /// Sets whether only recipes can match
+    pub fn set_recipes_only(&mut self, recipes_only: bool) -> &mut Self {
+        self.recipes_only = recipes_only;
+        self
+    }
+
+    /// Returns true if the food with the given id in the store matches the query
+    ///
+    /// Returns false if the store doesn't have the food. This can be handed to the store's own
+    /// queries, `|id, _| query.matches(&store, id)`, to page through the matching foods.
+    pub fn matches(&self, store: &FoodStore, id: &FoodId) -> bool {
+        match store.foods.get(id) {
+            Some(entry) => self.matches_entry(entry),
+            None => false,
+        }
+    }
+
+    fn matches_entry(&self, entry: &StoreEntry) -> bool {
+        let food = &entry.food;
+        if self.recipes_only && !matches!(*food, Food::Recipe(_)) {
+            return false;
+        }
+        if self.status.is_some_and(|x| x != entry.status) {
+            return false;
+        }
+        if self
+            .max_time
+            .is_some_and(|x| food.get_time() > x.to_rational())
+        {
+            return false;
+        }
+        self.tags
+            .iter()
+            .all(|tag| food.has_tag(tag) || entry.tags.iter().any(|x| x == tag))
+    }
+}
+
+/// Adds the short codes of every food a recipe uses, at any depth, to the set
+fn collect_used<'a>(recipe: &'a Recipe, used: &mut BTreeSet<&'a str>) {
+    for (food, _) in recipe.get_foods() {
+        used.insert(food.get_name().get_short_code());
+        if let Food::Recipe(ref x) = *food {
+            collect_used(x, used);
+        }
+    }
+}
+
+/// A food, along with the store's bookkeeping for it
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct StoreEntry {
+    food: Food,
+    status: FoodStatus,
+    /// Free-form labels for organizing the store, sorted and without duplicates
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// The collection of every food a household knows about
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+/// use time_for_food::store::*;
+///
+/// # fn main() {
+/// let toast = RecipeBuilder::new("toast")
+///     .add_step(Step::new("toast-bread", Rational32::from_integer(3)))
+///     .build_with_defaults();
+///
+/// let mut store = FoodStore::new();
+/// let id = store.insert(Food::new_from_recipe(toast));
+/// assert_eq!(store.get_status(&id), Some(FoodStatus::Draft));
+///
+/// store.set_status(&id, FoodStatus::Verified);
+/// assert_eq!(store.with_status(FoodStatus::Draft).len(), 0);
+/// assert_eq!(store.with_status(FoodStatus::Verified).len(), 1);
+/// # }
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FoodStore {
+    foods: BTreeMap<FoodId, StoreEntry>,
+    #[serde(default)]
+    settings: Settings,
+    /// Foods deleted, but not yet purged
+    #[serde(default)]
+    trash: BTreeMap<FoodId, StoreEntry>,
+    /// Saved queries, by name
+    #[serde(default)]
+    collections: BTreeMap<String, Query>,
+}
+
+impl FoodStore {
+    /// Creates a new, empty, FoodStore
+    pub fn new() -> FoodStore {
+        FoodStore {
+            foods: BTreeMap::new(),
+            settings: Settings::default(),
+            trash: BTreeMap::new(),
+            collections: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the household's settings
+    pub fn get_settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Returns a mutable refrence to the household's settings
+    pub fn get_mut_settings(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Replaces the household's settings
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+    }
+
+    /// Adds a food to the store as a draft, replacing any food with the same short code
+    pub fn insert(&mut self, food: Food) -> FoodId {
+        self.insert_with_status(food, FoodStatus::Draft)
+    }
+
+    /// Adds a food to the store with the given status, replacing any food with the same
+    /// short code
+    pub fn insert_with_status(&mut self, food: Food, status: FoodStatus) -> FoodId {
+        let id = FoodId::of(&food);
+        let tags = self.foods.remove(&id).map(|x| x.tags).unwrap_or_default();
+        self.foods
+            .insert(id.clone(), StoreEntry { food, status, tags });
+        id
+    }
+
+    /// Adds many foods to the store as drafts, deciding what to do with ids already taken
+    /// by the given policy
+    ///
+    /// Under `ConflictPolicy::Fail` the batch is all or nothing, including foods that share
+    /// an id within the batch. Under the other policies, later foods in the batch conflict
+    /// with earlier ones just as with foods already in the store.
+    ///
+    /// # Returns
+    /// The ids of the foods actually inserted, in batch order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::*;
+    ///
+    /// # fn main() {
+    /// let recipe = |name: &str| {
+    ///     Food::new_from_recipe(
+    ///         RecipeBuilder::new(name)
+    ///             .add_step(Step::new("cook", Rational32::from_integer(10)))
+    ///             .build_with_defaults(),
+    ///     )
+    /// };
+    ///
+    /// let mut store = FoodStore::new();
+    /// store.insert(recipe("soup"));
+    ///
+    /// let batch = vec![recipe("stew"), recipe("soup")];
+    /// assert_eq!(
+    ///     store.insert_many(batch.clone(), ConflictPolicy::Fail),
+    ///     Err(BatchError::Conflict(FoodId::new("soup")))
+    /// );
+    /// assert_eq!(store.len(), 1);
+    ///
+    /// let inserted = store.insert_many(batch, ConflictPolicy::Skip).unwrap();
+    /// assert_eq!(inserted, vec![FoodId::new("stew")]);
+    /// assert_eq!(store.len(), 2);
+    /// # }
+    /// ```
+    pub fn insert_many(
+        &mut self,
+        foods: Vec<Food>,
+        policy: ConflictPolicy,
+    ) -> Result<Vec<FoodId>, BatchError> {
+        if policy == ConflictPolicy::Fail {
+            let mut seen = BTreeSet::new();
+            for food in &foods {
+                let id = FoodId::of(food);
+                if self.contains(&id) || !seen.insert(id.clone()) {
+                    return Err(BatchError::Conflict(id));
+                }
+            }
+        }
+        let mut inserted = Vec::new();
+        for food in foods {
+            if policy == ConflictPolicy::Skip && self.contains(&FoodId::of(&food)) {
+                continue;
+            }
+            inserted.push(self.insert(food));
+        }
+        Ok(inserted)
+    }
+
+    /// Removes every food matching the query, returning them in id order
+    pub fn remove_where<F: Fn(&FoodId, &Food) -> bool>(&mut self, query: F) -> Vec<(FoodId, Food)> {
+        let ids: Vec<FoodId> = self
+            .foods
+            .iter()
+            .filter(|&(id, x)| query(id, &x.food))
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.into_iter()
+            .filter_map(|id| self.foods.remove(&id).map(|x| (id, x.food)))
+            .collect()
+    }
+
+    /// Returns the tags of the food with the given id, sorted, if the store has it
+    pub fn get_tags(&self, id: &FoodId) -> Option<&[String]> {
+        self.foods.get(id).map(|x| x.tags.as_slice())
+    }
+
+    /// Tags the food with the given id, doing nothing if it already has the tag
+    ///
+    /// # Returns
+    /// False, without changing anything, if the store doesn't have the food
+    pub fn add_tag(&mut self, id: &FoodId, tag: &str) -> bool {
+        match self.foods.get_mut(id) {
+            Some(entry) => {
+                if let Err(index) = entry.tags.binary_search_by(|x| x.as_str().cmp(tag)) {
+                    entry.tags.insert(index, tag.to_string());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a tag from the food with the given id, returning false if it didn't have it
+    pub fn remove_tag(&mut self, id: &FoodId, tag: &str) -> bool {
+        match self.foods.get_mut(id) {
+            Some(entry) => match entry.tags.binary_search_by(|x| x.as_str().cmp(tag)) {
+                Ok(index) => {
+                    entry.tags.remove(index);
+                    true
+                }
+                Err(_) => false,
+            },
+            None => false,
+        }
+    }
+
+    /// Returns every food with the given tag, sorted by id
+    pub fn with_tag(&self, tag: &str) -> Vec<(&FoodId, &Food)> {
+        self.foods
+            .iter()
+            .filter(|&(_, x)| x.tags.iter().any(|y| y == tag))
+            .map(|(id, x)| (id, &x.food))
+            .collect()
+    }
+
+    /// Adds and removes tags on every food matching the query, returning how many matched
+    pub fn retag<F: Fn(&FoodId, &Food) -> bool>(
+        &mut self,
+        query: F,
+        add: &[&str],
+        remove: &[&str],
+    ) -> usize {
+        let ids: Vec<FoodId> = self
+            .foods
+            .iter()
+            .filter(|&(id, x)| query(id, &x.food))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &ids {
+            for tag in add {
+                self.add_tag(id, tag);
+            }
+            for tag in remove {
+                self.remove_tag(id, tag);
+            }
+        }
+        ids.len()
+    }
+
+    /// Converts every serving size and ingredient amount in one unit to another, across the
+    /// whole store, returning how many amounts were converted
+    ///
+    /// See `Food::convert_units`, with the store's own settings. The conversion is all or
+    /// nothing, if any amount fails to convert the store is left unchanged.
+    pub fn convert_units(&mut self, from: Unit, to: Unit) -> Result<usize, BatchError> {
+        let mut foods = self.foods.clone();
+        let mut count = 0;
+        for (id, entry) in &mut foods {
+            count += entry
+                .food
+                .convert_units(from, to, &self.settings)
+                .map_err(|error| BatchError::Conversion {
+                    id: id.clone(),
+                    error,
+                })?;
+        }
+        self.foods = foods;
+        Ok(count)
+    }
+
+    /// Returns the food with the given id, if the store has it
+    pub fn get(&self, id: &FoodId) -> Option<&Food> {
+        self.foods.get(id).map(|x| &x.food)
+    }
+
+    /// Returns a mutable refrence to the food with the given id, if the store has it
+    pub fn get_mut(&mut self, id: &FoodId) -> Option<&mut Food> {
+        self.foods.get_mut(id).map(|x| &mut x.food)
+    }
+
+    /// Returns true if the store has a food with the given id
+    pub fn contains(&self, id: &FoodId) -> bool {
+        self.foods.contains_key(id)
+    }
+
+    /// Removes the food with the given id from the store, returning it
+    ///
+    /// This is permanent, and doesn't check whether recipes still use the food, see `trash`
+    /// for a safer alternative.
+    pub fn remove(&mut self, id: &FoodId) -> Option<Food> {
+        self.foods.remove(id).map(|x| x.food)
+    }
+
+    /// Returns the number of foods in the store
+    pub fn len(&self) -> usize {
+        self.foods.len()
+    }
+
+    /// Returns true if the store has no foods
+    pub fn is_empty(&self) -> bool {
+        self.foods.is_empty()
+    }
+
+    /// Returns the ids of every food in the store, sorted
+    pub fn ids(&self) -> Vec<&FoodId> {
+        self.foods.keys().collect()
+    }
+
+    /// Returns how many foods match the query
+    pub fn count_where<F: Fn(&FoodId, &Food) -> bool>(&self, query: F) -> usize {
+        self.foods
+            .iter()
+            .filter(|&(id, x)| query(id, &x.food))
+            .count()
+    }
+
+    /// Returns at most `limit` of the foods matching the query, skipping the first `offset`
+    ///
+    /// Foods are matched in id order, so the same offset always gives the same page as long as
+    /// the store is unchanged. Only the foods up to the end of the page are tested against the
+    /// query.
+    pub fn query_range<F: Fn(&FoodId, &Food) -> bool>(
+        &self,
+        query: F,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<(&FoodId, &Food)> {
+        self.foods
+            .iter()
+            .filter(|&(id, x)| query(id, &x.food))
+            .skip(offset)
+            .take(limit)
+            .map(|(id, x)| (id, &x.food))
+            .collect()
+    }
+
+    /// Returns a page of at most `limit` of the foods matching the query, starting after the
+    /// cursor
+    ///
+    /// Pass None for the first page, and the page's `get_next` for each page after it. Unlike
+    /// offsets, cursors stay stable while foods are inserted or removed, and each page starts
+    /// straight at the cursor rather than walking past every earlier food.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::*;
+    ///
+    /// # fn main() {
+    /// let mut store = FoodStore::new();
+    /// for name in &["apple", "banana", "cherry", "date", "elderberry"] {
+    ///     store.insert(Food::new_from_raw_food(RawFood::new(
+    ///         IString::new(name),
+    ///         Amount::new(Unit::Gram, Rational32::from_integer(100)),
+    ///         Nutrition::new(),
+    ///     )));
+    /// }
+    ///
+    /// let first = store.query_page(|_, _| true, None, 2);
+    /// assert_eq!(first.get_items().len(), 2);
+    /// assert_eq!(first.get_next().unwrap().get_short_code(), "banana");
+    ///
+    /// let second = store.query_page(|_, _| true, first.get_next(), 2);
+    /// assert_eq!(second.get_items()[0].0.get_short_code(), "cherry");
+    ///
+    /// let last = store.query_page(|_, _| true, second.get_next(), 2);
+    /// assert_eq!(last.get_items().len(), 1);
+    /// assert!(last.get_next().is_none());
+    /// # }
+    /// ```
+    pub fn query_page<F: Fn(&FoodId, &Food) -> bool>(
+        &self,
+        query: F,
+        after: Option<&FoodId>,
+        limit: usize,
+    ) -> QueryPage<'_> {
+        let start = match after {
+            Some(id) => Bound::Excluded(id),
+            None => Bound::Unbounded,
+        };
+        let mut matching = self
+            .foods
+            .range::<FoodId, _>((start, Bound::Unbounded))
+            .filter(|&(id, x)| query(id, &x.food))
+            .map(|(id, x)| (id, &x.food));
+        let items: Vec<(&FoodId, &Food)> = matching.by_ref().take(limit).collect();
+        let next = match (items.last(), matching.next()) {
+            (Some(&(id, _)), Some(_)) => Some(id.clone()),
+            _ => None,
+        };
+        QueryPage { items, next }
+    }
+
+    /// Returns every food matching the query, sorted by id
+    pub fn query(&self, query: &Query) -> Vec<(&FoodId, &Food)> {
+        self.foods
+            .iter()
+            .filter(|&(_, x)| query.matches_entry(x))
+            .map(|(id, x)| (id, &x.food))
+            .collect()
+    }
+
+    /// Saves a query as a smart collection, replacing any collection with the same name
+    ///
+    /// Only the query is saved, so the collection always reflects the store as it is when the
+    /// collection is read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::*;
+    ///
+    /// # fn main() {
+    /// let salad = RecipeBuilder::new("salad")
+    ///     .add_step(Step::new("chop", Rational32::from_integer(10)))
+    ///     .add_tag("vegetarian")
+    ///     .build_with_defaults();
+    /// let roast = RecipeBuilder::new("roast")
+    ///     .add_step(Step::new("roast", Rational32::from_integer(90)))
+    ///     .add_tag("vegetarian")
+    ///     .build_with_defaults();
+    ///
+    /// let mut store = FoodStore::new();
+    /// let salad = store.insert(Food::new_from_recipe(salad));
+    /// store.insert(Food::new_from_recipe(roast));
+    /// store.add_tag(&salad, "favorite");
+    ///
+    /// let mut query = Query::new();
+    /// query
+    ///     .set_max_time(Rational32::from_integer(30))
+    ///     .add_tag("vegetarian")
+    ///     .add_tag("favorite");
+    /// store.save_collection("quick favorites", query);
+    ///
+    /// let pool = store.collection_pool("quick favorites").unwrap();
+    /// assert_eq!(pool.len(), 1);
+    /// assert_eq!(pool[0].get_name().get_short_code(), "salad");
+    /// # }
+    /// ```
+    pub fn save_collection(&mut self, name: &str, query: Query) {
+        self.collections.insert(name.to_string(), query);
+    }
+
+    /// Returns the query behind the named collection, if there is one
+    pub fn get_collection(&self, name: &str) -> Option<&Query> {
+        self.collections.get(name)
+    }
+
+    /// Removes the named collection, returning its query
+    pub fn remove_collection(&mut self, name: &str) -> Option<Query> {
+        self.collections.remove(name)
+    }
+
+    /// Returns the names of every saved collection, sorted
+    pub fn collection_names(&self) -> Vec<&str> {
+        self.collections.keys().map(|x| x.as_str()).collect()
+    }
+
+    /// Returns the foods currently in the named collection, sorted by id
+    ///
+    /// Returns None if there is no collection with that name.
+    pub fn collection(&self, name: &str) -> Option<Vec<(&FoodId, &Food)>> {
+        self.collections.get(name).map(|x| self.query(x))
+    }
+
+    /// Returns copies of the foods currently in the named collection, sorted by id, ready to
+    /// hand to the optimizer as its candidate recipes
+    ///
+    /// Returns None if there is no collection with that name.
+    pub fn collection_pool(&self, name: &str) -> Option<Vec<Food>> {
+        self.collection(name)
+            .map(|x| x.into_iter().map(|(_, food)| food.clone()).collect())
+    }
+
+    /// Finds the raw food a scanned barcode belongs to, if the store has it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::*;
+    ///
+    /// # fn main() {
+    /// let mut oats = RawFood::new(
+    ///     IString::new("rolled-oats"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(40)),
+    ///     Nutrition::new(),
+    /// );
+    /// oats.add_barcode("5000128104517");
+    ///
+    /// let mut store = FoodStore::new();
+    /// store.insert(Food::new_from_raw_food(oats));
+    ///
+    /// let (id, _) = store.find_by_barcode("5 000128 104517").unwrap();
+    /// assert_eq!(id.get_short_code(), "rolled-oats");
+    /// # }
+    /// ```
+    pub fn find_by_barcode(&self, barcode: &str) -> Option<(&FoodId, &Food)> {
+        self.foods
+            .iter()
+            .find(|&(_, x)| match x.food {
+                Food::RawFood(ref raw) => raw.has_barcode(barcode),
+                _ => false,
+            })
+            .map(|(id, x)| (id, &x.food))
+    }
+
+    /// Returns every branded variant of the generic food with the given id, sorted by id
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::*;
+    ///
+    /// # fn main() {
+    /// let serving = Amount::new(Unit::Gram, Rational32::from_integer(40));
+    /// let oats = RawFood::new(IString::new("rolled-oats"), serving, Nutrition::new());
+    /// let mut quaker = RawFood::new(IString::new("quaker-rolled-oats"), serving, Nutrition::new());
+    /// quaker.set_brand(Some("Quaker"));
+    /// quaker.set_generic(Some("rolled-oats"));
+    ///
+    /// let mut store = FoodStore::new();
+    /// let generic = store.insert(Food::new_from_raw_food(oats));
+    /// let variant = store.insert(Food::new_from_raw_food(quaker));
+    ///
+    /// assert_eq!(store.variants_of(&generic)[0].0, &variant);
+    /// assert_eq!(FoodId::of(store.generic_of(&variant).unwrap()), generic);
+    /// # }
+    /// ```
+    pub fn variants_of(&self, generic: &FoodId) -> Vec<(&FoodId, &Food)> {
+        self.foods
+            .iter()
+            .filter(|&(_, x)| match x.food {
+                Food::RawFood(ref raw) => raw.get_generic() == Some(generic.get_short_code()),
+                _ => false,
+            })
+            .map(|(id, x)| (id, &x.food))
+            .collect()
+    }
+
+    /// Returns the generic food the food with the given id is a variant of, if the store has
+    /// both
+    pub fn generic_of(&self, variant: &FoodId) -> Option<&Food> {
+        match self.get(variant) {
+            Some(Food::RawFood(raw)) => raw.get_generic().and_then(|x| self.get(&FoodId::new(x))),
+            _ => None,
+        }
+    }
+
+    /// Returns the status of the food with the given id, if the store has it
+    pub fn get_status(&self, id: &FoodId) -> Option<FoodStatus> {
+        self.foods.get(id).map(|x| x.status)
+    }
+
+    /// Sets the status of the food with the given id
+    ///
+    /// # Returns
+    /// False, without changing anything, if the store doesn't have the food
+    pub fn set_status(&mut self, id: &FoodId, status: FoodStatus) -> bool {
+        match self.foods.get_mut(id) {
+            Some(entry) => {
+                entry.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns every food with the given status, sorted by id
+    pub fn with_status(&self, status: FoodStatus) -> Vec<(&FoodId, &Food)> {
+        self.foods
+            .iter()
+            .filter(|&(_, x)| x.status == status)
+            .map(|(id, x)| (id, &x.food))
+            .collect()
+    }
+
+    /// Returns every food whose nutrition isn't yet fit for reports, sorted by id
+    pub fn untrusted_nutrition(&self) -> Vec<(&FoodId, &Food)> {
+        self.foods
+            .iter()
+            .filter(|&(_, x)| !x.status.has_trusted_nutrition())
+            .map(|(id, x)| (id, &x.food))
+            .collect()
+    }
+
+    /// Summarizes the store's contents and health
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::*;
+    ///
+    /// # fn main() {
+    /// let bread = RawFood::new(
+    ///     IString::new("bread"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(40)),
+    ///     Nutrition::new(),
+    /// );
+    /// let caviar = RawFood::new(
+    ///     IString::new("caviar"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(10)),
+    ///     Nutrition::new(),
+    /// );
+    /// let toast = RecipeBuilder::new("toast")
+    ///     .add_food(Food::new_from_raw_food(bread.clone()), Unit::Gram, Rational32::from_integer(40))
+    ///     .add_step(Step::new("toast-bread", Rational32::from_integer(3)))
+    ///     .build_with_defaults();
+    ///
+    /// let mut store = FoodStore::new();
+    /// store.insert(Food::new_from_raw_food(bread));
+    /// store.insert(Food::new_from_raw_food(caviar));
+    /// store.insert(Food::new_from_recipe(toast));
+    ///
+    /// let stats = store.stats();
+    /// assert_eq!(stats.get_raw_foods(), 2);
+    /// assert_eq!(stats.get_orphaned_raw_foods(), &[FoodId::new("caviar")]);
+    /// assert_eq!(stats.get_missing_nutrition(), &[FoodId::new("toast")]);
+    /// assert_eq!(stats.get_average_recipe_time(), Some(Rational32::from_integer(3)));
+    /// # }
+    /// ```
+    pub fn stats(&self) -> StoreStats {
+        let mut stats = StoreStats::default();
+        let mut used = BTreeSet::new();
+        let mut total_time = Rational32::from_integer(0);
+        let language = self.settings.get_language();
+        for (id, entry) in &self.foods {
+            match entry.food {
+                Food::RawFood(_) => stats.raw_foods += 1,
+                Food::External(_) => stats.external_meals += 1,
+                Food::Recipe(ref recipe) => {
+                    stats.recipes += 1;
+                    total_time += recipe.get_time();
+                    collect_used(recipe, &mut used);
+                    if recipe.get_nutrition().is_empty() {
+                        stats.missing_nutrition.push(id.clone());
+                    }
+                    if recipe.get_name().get_value(language).is_none() {
+                        stats.missing_translations.push(id.clone());
+                    }
+                    stats
+                        .largest_recipes
+                        .push((id.clone(), recipe.get_foods().len()));
+                }
+            }
+        }
+        stats.orphaned_raw_foods = self
+            .foods
+            .iter()
+            .filter(|&(id, x)| match x.food {
+                Food::RawFood(_) => !used.contains(id.get_short_code()),
+                _ => false,
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        if stats.recipes > 0 {
+            stats.average_recipe_time =
+                Some(total_time / Rational32::from_integer(stats.recipes as i32));
+        }
+        // Stable, so recipes of the same size stay in id order
+        stats
+            .largest_recipes
+            .sort_by_key(|x| ::std::cmp::Reverse(x.1));
+        stats.largest_recipes.truncate(LARGEST_RECIPES);
+        stats
+    }
+
+    /// Returns the ids of every recipe in the store using the food with the given id,
+    /// directly or through a component recipe, sorted
+    pub fn used_by(&self, id: &FoodId) -> Vec<&FoodId> {
+        self.foods
+            .iter()
+            .filter(|&(_, x)| match x.food {
+                Food::Recipe(ref recipe) => {
+                    let mut used = BTreeSet::new();
+                    collect_used(recipe, &mut used);
+                    used.contains(id.get_short_code())
+                }
+                _ => false,
+            })
+            .map(|(x, _)| x)
+            .collect()
+    }
+
+    /// Moves the food with the given id to the trash, refusing if recipes still use it
+    ///
+    /// A food already in the trash with the same id is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::*;
+    ///
+    /// # fn main() {
+    /// let flour = Food::new_from_raw_food(RawFood::new(
+    ///     IString::new("flour"),
+    ///     Amount::new(Unit::Gram, Rational32::from_integer(30)),
+    ///     Nutrition::new(),
+    /// ));
+    /// let bread = RecipeBuilder::new("bread")
+    ///     .add_food(flour.clone(), Unit::Gram, Rational32::from_integer(500))
+    ///     .build_with_defaults();
+    ///
+    /// let mut store = FoodStore::new();
+    /// let flour = store.insert(flour);
+    /// let bread = store.insert(Food::new_from_recipe(bread));
+    ///
+    /// let refused = store.trash(&flour).unwrap_err();
+    /// assert_eq!(refused.to_string(), "flour is used by 1 recipe");
+    ///
+    /// store.trash(&bread).unwrap();
+    /// store.trash(&flour).unwrap();
+    /// assert!(store.is_empty());
+    ///
+    /// store.restore(&flour).unwrap();
+    /// assert!(store.contains(&flour));
+    /// assert_eq!(store.purge_trash(), 1);
+    /// # }
+    /// ```
+    pub fn trash(&mut self, id: &FoodId) -> Result<(), TrashError> {
+        if !self.contains(id) {
+            return Err(TrashError::NotFound(id.clone()));
+        }
+        let used_by: Vec<FoodId> = self.used_by(id).into_iter().cloned().collect();
+        if !used_by.is_empty() {
+            return Err(TrashError::InUse {
+                id: id.clone(),
+                used_by,
+            });
+        }
+        if let Some(entry) = self.foods.remove(id) {
+            self.trash.insert(id.clone(), entry);
+        }
+        Ok(())
+    }
+
+    /// Moves the food with the given id back out of the trash, with its status and tags
+    pub fn restore(&mut self, id: &FoodId) -> Result<(), TrashError> {
+        if self.contains(id) {
+            return Err(TrashError::Conflict(id.clone()));
+        }
+        match self.trash.remove(id) {
+            Some(entry) => {
+                self.foods.insert(id.clone(), entry);
+                Ok(())
+            }
+            None => Err(TrashError::NotFound(id.clone())),
+        }
+    }
+
+    /// Returns every food in the trash, sorted by id
+    pub fn get_trash(&self) -> Vec<(&FoodId, &Food)> {
+        self.trash.iter().map(|(id, x)| (id, &x.food)).collect()
+    }
+
+    /// Permanently deletes the food with the given id from the trash, returning it
+    pub fn purge(&mut self, id: &FoodId) -> Option<Food> {
+        self.trash.remove(id).map(|x| x.food)
+    }
+
+    /// Permanently deletes everything in the trash, returning how many foods were deleted
+    pub fn purge_trash(&mut self) -> usize {
+        let count = self.trash.len();
+        self.trash.clear();
+        count
+    }
+}
@@ -0,0 +1,286 @@
+//! This module contains fixtures, realistic sample data for writing tests against
+//!
+//! Only available with the `testing` feature. Every factory builds its value fresh, so tests
+//! are free to modify what they are given. The foods are small but complete, with names,
+//! serving sizes, nutrition, steps, and equipment, so they exercise the same paths real data
+//! does.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate chrono;
+//! extern crate time_for_food;
+//! use chrono::prelude::*;
+//! use time_for_food::testing;
+//!
+//! # fn main() {
+//! let plan = testing::plan(NaiveDate::from_ymd(2024, 1, 8), 7);
+//! assert_eq!(plan.get_days().len(), 7);
+//! assert!(testing::store().len() >= 5);
+//! # }
+//! ```
+use chrono::prelude::*;
+use food::engine::{Block, Day, MealPlan};
+use food::*;
+use num_rational::*;
+use pantry::Pantry;
+use store::{FoodId, FoodStatus, FoodStore};
+
+fn grams(amount: i32) -> Amount {
+    Amount::new(Unit::Gram, Rational32::from_integer(amount))
+}
+
+fn name(short_code: &str, english: &str) -> IString {
+    let mut name = IString::new(short_code);
+    name.set_value_for("en_US", english);
+    name
+}
+
+fn nutrition(calories: i32, protein: i32, fat: i32, carbohydrates: i32) -> Nutrition {
+    let mut nutrition = Nutrition::new();
+    nutrition
+        .set_calories(Amount::new(
+            Unit::Kilocalorie,
+            Rational32::from_integer(calories),
+        ))
+        .set_protein(grams(protein))
+        .set_fat(grams(fat))
+        .set_carbohydrates(grams(carbohydrates));
+    nutrition
+}
+
+fn raw_food(
+    short_code: &str,
+    english: &str,
+    serving_size: Amount,
+    nutrition: Nutrition,
+) -> RawFood {
+    let mut food = RawFood::new(name(short_code, english), serving_size, nutrition);
+    food.set_nutrition_source(NutritionSource::Usda);
+    food
+}
+
+fn step(text: &str, minutes: i32, attention: Attention, equipment: &[&str]) -> Step {
+    let mut step = Step::new(text, Rational32::from_integer(minutes));
+    step.set_attention(attention);
+    for x in equipment {
+        step.add_equipment(x);
+    }
+    step
+}
+
+/// Rolled oats, 40 g a serving
+pub fn oats() -> RawFood {
+    raw_food(
+        "rolled-oats",
+        "Rolled oats",
+        grams(40),
+        nutrition(150, 5, 3, 27),
+    )
+}
+
+/// Whole milk, 240 ml a serving
+pub fn milk() -> RawFood {
+    raw_food(
+        "whole-milk",
+        "Whole milk",
+        Amount::new(Unit::Milliliter, Rational32::from_integer(240)),
+        nutrition(150, 8, 8, 12),
+    )
+}
+
+/// Eggs, one a serving
+pub fn eggs() -> RawFood {
+    raw_food(
+        "egg",
+        "Egg",
+        Amount::new(Unit::Count, Rational32::from_integer(1)),
+        nutrition(70, 6, 5, 0),
+    )
+}
+
+/// Plain flour, 30 g a serving
+pub fn flour() -> RawFood {
+    raw_food(
+        "plain-flour",
+        "Plain flour",
+        grams(30),
+        nutrition(110, 3, 0, 23),
+    )
+}
+
+/// Unsalted butter, 14 g a serving
+pub fn butter() -> RawFood {
+    raw_food("butter", "Butter", grams(14), nutrition(100, 0, 11, 0))
+}
+
+/// Every raw food fixture
+pub fn raw_foods() -> Vec<RawFood> {
+    vec![oats(), milk(), eggs(), flour(), butter()]
+}
+
+/// Porridge for two, a quick breakfast simmered in a saucepan
+pub fn porridge() -> Recipe {
+    let mut recipe = RecipeBuilder::new("porridge")
+        .add_name("en_US", "Porridge")
+        .set_serving_size(Unit::Count, Rational32::from_integer(1))
+        .set_servings(Rational32::from_integer(2))
+        .add_food(
+            Food::new_from_raw_food(oats()),
+            Unit::Gram,
+            Rational32::from_integer(80),
+        )
+        .add_food(
+            Food::new_from_raw_food(milk()),
+            Unit::Milliliter,
+            Rational32::from_integer(480),
+        )
+        .add_step(step("bring-to-boil", 5, Attention::Active, &["saucepan"]))
+        .add_step(step(
+            "simmer-oats",
+            5,
+            Attention::Periodic { interval: 1 },
+            &["saucepan"],
+        ))
+        .build_with_defaults();
+    recipe.derive_nutrition();
+    recipe
+}
+
+/// Pancakes for four, with mostly hands-on cooking
+pub fn pancakes() -> Recipe {
+    let mut recipe = RecipeBuilder::new("pancakes")
+        .add_name("en_US", "Pancakes")
+        .set_serving_size(Unit::Count, Rational32::from_integer(1))
+        .set_servings(Rational32::from_integer(4))
+        .add_food(
+            Food::new_from_raw_food(flour()),
+            Unit::Gram,
+            Rational32::from_integer(150),
+        )
+        .add_food(
+            Food::new_from_raw_food(milk()),
+            Unit::Milliliter,
+            Rational32::from_integer(300),
+        )
+        .add_food(
+            Food::new_from_raw_food(eggs()),
+            Unit::Count,
+            Rational32::from_integer(2),
+        )
+        .add_food(
+            Food::new_from_raw_food(butter()),
+            Unit::Gram,
+            Rational32::from_integer(14),
+        )
+        .add_step(step(
+            "mix-batter",
+            5,
+            Attention::Active,
+            &["mixing-bowl", "whisk"],
+        ))
+        .add_step(step("rest-batter", 20, Attention::Passive, &[]))
+        .add_step(step("fry-pancakes", 20, Attention::Active, &["frying-pan"]))
+        .build_with_defaults();
+    recipe.derive_nutrition();
+    recipe
+}
+
+/// A baked oat and egg traybake for six, mostly passive time in the oven
+pub fn traybake() -> Recipe {
+    let mut bake = step("bake-traybake", 35, Attention::Passive, &["baking-tray"]);
+    bake.set_oven_temperature(Some(180));
+    let mut recipe = RecipeBuilder::new("oat-traybake")
+        .add_name("en_US", "Oat traybake")
+        .set_serving_size(Unit::Count, Rational32::from_integer(1))
+        .set_servings(Rational32::from_integer(6))
+        .add_food(
+            Food::new_from_raw_food(oats()),
+            Unit::Gram,
+            Rational32::from_integer(240),
+        )
+        .add_food(
+            Food::new_from_raw_food(eggs()),
+            Unit::Count,
+            Rational32::from_integer(3),
+        )
+        .add_food(
+            Food::new_from_raw_food(butter()),
+            Unit::Gram,
+            Rational32::from_integer(56),
+        )
+        .add_step(step(
+            "mix-traybake",
+            10,
+            Attention::Active,
+            &["mixing-bowl"],
+        ))
+        .add_step(bake)
+        .build_with_defaults();
+    recipe.derive_nutrition();
+    recipe
+}
+
+/// Every recipe fixture
+pub fn recipes() -> Vec<Recipe> {
+    vec![porridge(), pancakes(), traybake()]
+}
+
+/// A store holding every raw food and recipe fixture, all verified
+pub fn store() -> FoodStore {
+    let mut store = FoodStore::new();
+    for food in raw_foods() {
+        store.insert_with_status(Food::new_from_raw_food(food), FoodStatus::Verified);
+    }
+    for recipe in recipes() {
+        store.insert_with_status(Food::new_from_recipe(recipe), FoodStatus::Verified);
+    }
+    store
+}
+
+/// A pantry with a week's worth of the basics
+pub fn pantry() -> Pantry {
+    let mut pantry = Pantry::new();
+    pantry.set(FoodId::new("rolled-oats"), grams(1000));
+    pantry.set(
+        FoodId::new("whole-milk"),
+        Amount::new(Unit::Milliliter, Rational32::from_integer(2000)),
+    );
+    pantry.set(
+        FoodId::new("egg"),
+        Amount::new(Unit::Count, Rational32::from_integer(12)),
+    );
+    pantry.set(FoodId::new("plain-flour"), grams(1500));
+    pantry.set(FoodId::new("butter"), grams(250));
+    pantry
+}
+
+fn block(hour: u32, minutes: u32, food: Recipe) -> Option<Block> {
+    let start = NaiveTime::from_hms_opt(hour, 0, 0)?;
+    let end = start + ::chrono::Duration::minutes(i64::from(minutes));
+    Some(Block::new(start, end, Some(Food::new_from_recipe(food))))
+}
+
+/// A day with breakfast at 8:00 and a traybake lunch at 12:00, with pancakes instead of
+/// porridge at weekends
+pub fn day(date: NaiveDate) -> Day {
+    let mut day = Day::new(date);
+    let breakfast = match date.weekday() {
+        Weekday::Sat | Weekday::Sun => pancakes(),
+        _ => porridge(),
+    };
+    let blocks = vec![block(8, 30, breakfast), block(12, 45, traybake())];
+    for x in blocks.into_iter().flatten() {
+        day.add_block(x);
+    }
+    day
+}
+
+/// A plan of consecutive fixture days, starting on the given date
+pub fn plan(start: NaiveDate, days: u32) -> MealPlan {
+    let mut plan = MealPlan::new();
+    for x in start.iter_days().take(days as usize) {
+        plan.add_day(day(x));
+    }
+    plan
+}
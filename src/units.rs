@@ -0,0 +1,336 @@
+//! This module contains conversions that depend on the food being measured
+//!
+//! Converting between units of the same dimension only needs the units, see
+//! `Amount::convert_to`. Converting between volume and mass also needs to know how dense the
+//! food is, a cup of flour weighs far less than a cup of honey, so densities are recorded per
+//! food in a `DensityTable`.
+//!
+//! A household's `ConversionTable`, kept in its `Settings`, brings the two together, along
+//! with its own sizes for units where they differ from the standard ones, such as a 250 ml
+//! metric cup. Anything converting or decomposing amounts for a household takes the
+//! household's `Settings`, such as `Food::decompose`, so every conversion goes through it.
+use food::*;
+use num_rational::*;
+use std::collections::BTreeMap;
+use store::FoodId;
+use warning::{Warning, WithWarnings};
+
+/// The densities of foods, for converting between volume and mass
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DensityTable {
+    /// Densities, in grams per milliliter
+    densities: BTreeMap<FoodId, Fraction>,
+}
+
+impl DensityTable {
+    /// Creates a new, empty, DensityTable
+    pub fn new() -> DensityTable {
+        DensityTable {
+            densities: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the density of a food, in grams per milliliter, if it is known
+    pub fn get_density(&self, id: &FoodId) -> Option<Rational32> {
+        self.densities.get(id).map(|x| x.to_rational())
+    }
+
+    /// Sets the density of a food, in grams per milliliter, replacing any existing density
+    pub fn set_density(&mut self, id: FoodId, grams_per_milliliter: Rational32) -> &mut Self {
+        self.densities
+            .insert(id, Fraction::from_rational(grams_per_milliliter));
+        self
+    }
+
+    /// Sets the density of a food from a measurement of it, such as a label giving both
+    /// "1 cup" and "120 g"
+    ///
+    /// Returns an error, changing nothing, unless one amount is a volume and the other a mass.
+    pub fn set_density_from(
+        &mut self,
+        id: FoodId,
+        volume: Amount,
+        mass: Amount,
+    ) -> Result<&mut Self, ConversionError> {
+        let milliliters = volume.convert_to(Unit::Milliliter)?.get_amount();
+        let grams = mass.convert_to(Unit::Gram)?.get_amount();
+        if milliliters == Rational32::from_integer(0) {
+            return Err(ConversionError::UnknownDensity);
+        }
+        Ok(self.set_density(id, grams / milliliters))
+    }
+
+    /// Removes the density of a food, returning it
+    pub fn remove_density(&mut self, id: &FoodId) -> Option<Rational32> {
+        self.densities.remove(id).map(|x| x.to_rational())
+    }
+
+    /// Returns the ids of every food with a known density, in order
+    pub fn ids(&self) -> Vec<&FoodId> {
+        self.densities.keys().collect()
+    }
+
+    /// Converts an amount of a food to another unit, going between volume and mass using the
+    /// food's density where needed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::FoodId;
+    /// use time_for_food::units::DensityTable;
+    ///
+    /// # fn main() {
+    /// let flour = FoodId::new("flour");
+    /// let mut densities = DensityTable::new();
+    /// densities
+    ///     .set_density_from(
+    ///         flour.clone(),
+    ///         Amount::new(Unit::Cup, Rational32::from_integer(1)),
+    ///         Amount::new(Unit::Gram, Rational32::from_integer(120)),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let needed = Amount::new(Unit::Cup, Rational32::new(5, 2));
+    /// let grams = densities.convert(&flour, &needed, Unit::Gram).unwrap();
+    /// assert_eq!(grams.get_amount(), Rational32::from_integer(300));
+    ///
+    /// let sugar = FoodId::new("sugar");
+    /// assert_eq!(
+    ///     densities.convert(&sugar, &needed, Unit::Gram).err(),
+    ///     Some(ConversionError::UnknownDensity)
+    /// );
+    /// # }
+    /// ```
+    pub fn convert(
+        &self,
+        id: &FoodId,
+        amount: &Amount,
+        target: Unit,
+    ) -> Result<Amount, ConversionError> {
+        let from = amount.get_unit().get_dimension();
+        let to = target.get_dimension();
+        match (from, to) {
+            (Dimension::Volume, Dimension::Mass) => {
+                let density = self
+                    .get_density(id)
+                    .ok_or(ConversionError::UnknownDensity)?;
+                let milliliters = amount.convert_to(Unit::Milliliter)?.get_amount();
+                Amount::new(Unit::Gram, milliliters * density).convert_to(target)
+            }
+            (Dimension::Mass, Dimension::Volume) => {
+                let density = self
+                    .get_density(id)
+                    .ok_or(ConversionError::UnknownDensity)?;
+                if density == Rational32::from_integer(0) {
+                    return Err(ConversionError::UnknownDensity);
+                }
+                let grams = amount.convert_to(Unit::Gram)?.get_amount();
+                Amount::new(Unit::Milliliter, grams / density).convert_to(target)
+            }
+            _ => amount.convert_to(target),
+        }
+    }
+
+    /// Converts an amount of a food to another unit, as `convert` does, warning when the
+    /// food's density was used
+    ///
+    /// Densities are measured, and so only approximate, while conversions within a dimension
+    /// are exact.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num_rational;
+    /// extern crate time_for_food;
+    /// use num_rational::Rational32;
+    /// use time_for_food::food::*;
+    /// use time_for_food::store::FoodId;
+    /// use time_for_food::units::DensityTable;
+    /// use time_for_food::warning::Warning;
+    ///
+    /// # fn main() {
+    /// let honey = FoodId::new("honey");
+    /// let mut densities = DensityTable::new();
+    /// densities.set_density(honey.clone(), Rational32::new(7, 5));
+    ///
+    /// let needed = Amount::new(Unit::Tablespoon, Rational32::from_integer(2));
+    /// let grams = densities.convert_with_warnings(&honey, &needed, Unit::Gram).unwrap();
+    /// assert_eq!(grams.get_value().get_amount(), Rational32::from_integer(42));
+    /// assert_eq!(grams.get_warnings(), [Warning::DensityConversion { food: honey.clone() }]);
+    ///
+    /// let teaspoons = densities.convert_with_warnings(&honey, &needed, Unit::Teaspoon).unwrap();
+    /// assert!(teaspoons.is_exact());
+    /// # }
+    /// ```
+    pub fn convert_with_warnings(
+        &self,
+        id: &FoodId,
+        amount: &Amount,
+        target: Unit,
+    ) -> Result<WithWarnings<Amount>, ConversionError> {
+        let mut converted = WithWarnings::new(self.convert(id, amount, target)?);
+        if amount.get_unit().get_dimension() != target.get_dimension() {
+            converted.add_warning(Warning::DensityConversion { food: id.clone() });
+        }
+        Ok(converted)
+    }
+}
+
+/// A household's unit sizes and food densities, overriding the standard conversions
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::food::*;
+/// use time_for_food::store::FoodId;
+/// use time_for_food::units::ConversionTable;
+///
+/// # fn main() {
+/// let flour = FoodId::new("flour");
+/// let mut conversions = ConversionTable::new();
+/// conversions
+///     .set_unit_size(Unit::Cup, Amount::new(Unit::Milliliter, Rational32::from_integer(250)))
+///     .unwrap();
+/// // The household sifts its flour, so it is lighter than usual
+/// conversions
+///     .get_mut_densities()
+///     .set_density(flour.clone(), Rational32::new(2, 5));
+///
+/// let needed = Amount::new(Unit::Cup, Rational32::from_integer(2));
+/// let grams = conversions.convert_food(&flour, &needed, Unit::Gram).unwrap();
+/// assert_eq!(grams.get_amount(), Rational32::from_integer(200));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConversionTable {
+    /// Sizes of units, in grams for mass and milliliters for volume
+    unit_sizes: BTreeMap<Unit, Fraction>,
+    densities: DensityTable,
+}
+
+impl ConversionTable {
+    /// Creates a new ConversionTable, with the standard unit sizes and no densities
+    pub fn new() -> ConversionTable {
+        ConversionTable {
+            unit_sizes: BTreeMap::new(),
+            densities: DensityTable::new(),
+        }
+    }
+
+    /// Returns the household's size for a unit, if it overrides the standard one
+    pub fn get_unit_size(&self, unit: Unit) -> Option<Amount> {
+        let base = match unit.get_dimension() {
+            Dimension::Mass => Unit::Gram,
+            Dimension::Volume => Unit::Milliliter,
+            _ => return None,
+        };
+        self.unit_sizes
+            .get(&unit)
+            .map(|x| Amount::new(base, x.to_rational()))
+    }
+
+    /// Overrides the size of a unit, such as a cup being 250 ml
+    ///
+    /// Returns an error, changing nothing, unless the size measures the same thing as the
+    /// unit, and the unit is one of mass or volume.
+    pub fn set_unit_size(
+        &mut self,
+        unit: Unit,
+        size: Amount,
+    ) -> Result<&mut Self, ConversionError> {
+        let base = match unit.get_dimension() {
+            Dimension::Mass => Unit::Gram,
+            Dimension::Volume => Unit::Milliliter,
+            _ => {
+                return Err(ConversionError::Incompatible {
+                    from: unit,
+                    to: size.get_unit(),
+                })
+            }
+        };
+        if !unit.is_compatible(size.get_unit()) {
+            return Err(ConversionError::Incompatible {
+                from: unit,
+                to: size.get_unit(),
+            });
+        }
+        let size = size.convert_with(base, self)?.get_amount();
+        self.unit_sizes.insert(unit, Fraction::from_rational(size));
+        Ok(self)
+    }
+
+    /// Removes the household's size for a unit, going back to the standard one
+    pub fn remove_unit_size(&mut self, unit: Unit) -> Option<Amount> {
+        let size = self.get_unit_size(unit);
+        self.unit_sizes.remove(&unit);
+        size
+    }
+
+    /// Returns the household's food densities
+    pub fn get_densities(&self) -> &DensityTable {
+        &self.densities
+    }
+
+    /// Returns the household's food densities, for changing
+    pub fn get_mut_densities(&mut self) -> &mut DensityTable {
+        &mut self.densities
+    }
+
+    /// Returns the size of a unit in its dimension's base unit
+    pub(crate) fn base_factor(&self, unit: Unit) -> Rational64 {
+        match self.unit_sizes.get(&unit) {
+            Some(size) => {
+                let size = size.to_rational();
+                Rational64::new(i64::from(*size.numer()), i64::from(*size.denom()))
+            }
+            None => unit.base_factor(),
+        }
+    }
+
+    /// Converts an amount to another unit of the same dimension, see `Amount::convert_with`
+    pub fn convert(&self, amount: &Amount, target: Unit) -> Result<Amount, ConversionError> {
+        amount.convert_with(target, self)
+    }
+
+    /// Converts an amount of a food to another unit, going between volume and mass using the
+    /// food's density where needed, as `DensityTable::convert` does, with the household's unit
+    /// sizes
+    pub fn convert_food(
+        &self,
+        id: &FoodId,
+        amount: &Amount,
+        target: Unit,
+    ) -> Result<Amount, ConversionError> {
+        let from = amount.get_unit().get_dimension();
+        let to = target.get_dimension();
+        match (from, to) {
+            (Dimension::Volume, Dimension::Mass) => {
+                let density = self
+                    .densities
+                    .get_density(id)
+                    .ok_or(ConversionError::UnknownDensity)?;
+                let milliliters = self.convert(amount, Unit::Milliliter)?.get_amount();
+                self.convert(&Amount::new(Unit::Gram, milliliters * density), target)
+            }
+            (Dimension::Mass, Dimension::Volume) => {
+                let density = self
+                    .densities
+                    .get_density(id)
+                    .ok_or(ConversionError::UnknownDensity)?;
+                if density == Rational32::from_integer(0) {
+                    return Err(ConversionError::UnknownDensity);
+                }
+                let grams = self.convert(amount, Unit::Gram)?.get_amount();
+                self.convert(&Amount::new(Unit::Milliliter, grams / density), target)
+            }
+            _ => self.convert(amount, target),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! This module contains `WithWarnings`, the result of operations that can be approximate
+//!
+//! Most numbers in the crate are exact rationals, but some are only as good as the data behind
+//! them: a density recorded from a label, an amount rounded up to whole packages, a step time
+//! guessed from its text. Operations like these hand back their value alongside a list of
+//! `Warning`s, so callers can always tell an estimate from an exact figure, and pass the
+//! warnings along when building on the value.
+use food::estimate::EstimateBasis;
+use num_rational::*;
+use store::FoodId;
+
+/// Describes why a value is approximate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// An amount was converted between volume and mass using the food's recorded density
+    DensityConversion { food: FoodId },
+    /// An amount was rounded up to whole packages, so more is bought than needed
+    PackageRounding {
+        needed: Rational32,
+        purchased: Rational32,
+    },
+    /// A time was estimated, rather than stated
+    EstimatedTime(EstimateBasis),
+}
+
+/// A value, along with the warnings describing how it is approximate
+///
+/// A value with no warnings is exact.
+///
+/// # Examples
+///
+/// ```
+/// extern crate num_rational;
+/// extern crate time_for_food;
+/// use num_rational::Rational32;
+/// use time_for_food::warning::*;
+///
+/// # fn main() {
+/// let needed = WithWarnings::new(Rational32::from_integer(3));
+/// assert!(needed.is_exact());
+///
+/// let mut purchased = WithWarnings::new(Rational32::from_integer(4));
+/// purchased.add_warning(Warning::PackageRounding {
+///     needed: Rational32::from_integer(3),
+///     purchased: Rational32::from_integer(4),
+/// });
+///
+/// // Warnings carry through anything built on the value
+/// let total = needed.combine(purchased, |a, b| a + b);
+/// assert_eq!(*total.get_value(), Rational32::from_integer(7));
+/// assert!(!total.is_exact());
+/// assert_eq!(total.get_warnings().len(), 1);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WithWarnings<T> {
+    value: T,
+    warnings: Vec<Warning>,
+}
+
+impl<T> WithWarnings<T> {
+    /// Wraps an exact value, with no warnings
+    pub fn new(value: T) -> WithWarnings<T> {
+        WithWarnings {
+            value,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Wraps a value along with the warnings describing it
+    pub fn from_parts(value: T, warnings: Vec<Warning>) -> WithWarnings<T> {
+        WithWarnings { value, warnings }
+    }
+
+    /// Returns the value
+    pub fn get_value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the warnings describing how the value is approximate
+    pub fn get_warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Adds a warning
+    pub fn add_warning(&mut self, warning: Warning) -> &mut Self {
+        self.warnings.push(warning);
+        self
+    }
+
+    /// Returns true if there are no warnings, and the value is exact
+    pub fn is_exact(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Returns the value, discarding the warnings
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Returns the value and the warnings
+    pub fn into_parts(self) -> (T, Vec<Warning>) {
+        (self.value, self.warnings)
+    }
+
+    /// Transforms the value, keeping the warnings
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> WithWarnings<U> {
+        WithWarnings {
+            value: f(self.value),
+            warnings: self.warnings,
+        }
+    }
+
+    /// Transforms the value with an operation that may add warnings of its own, keeping the
+    /// warnings of both
+    pub fn and_then<U, F: FnOnce(T) -> WithWarnings<U>>(self, f: F) -> WithWarnings<U> {
+        let mut next = f(self.value);
+        let mut warnings = self.warnings;
+        warnings.append(&mut next.warnings);
+        WithWarnings {
+            value: next.value,
+            warnings,
+        }
+    }
+
+    /// Combines the value with another, keeping the warnings of both
+    pub fn combine<U, V, F: FnOnce(T, U) -> V>(
+        self,
+        other: WithWarnings<U>,
+        f: F,
+    ) -> WithWarnings<V> {
+        let mut warnings = self.warnings;
+        warnings.extend(other.warnings);
+        WithWarnings {
+            value: f(self.value, other.value),
+            warnings,
+        }
+    }
+
+    /// Takes the value out of another WithWarnings, moving its warnings onto this one
+    ///
+    /// Useful when building up a value from several approximate parts.
+    pub fn absorb<U>(&mut self, other: WithWarnings<U>) -> U {
+        self.warnings.extend(other.warnings);
+        other.value
+    }
+}